@@ -1,7 +1,7 @@
 use ark_ec::bls12::Bls12;
 use ark_ec::{pairing::Pairing, AffineRepr};
 use ark_gemini::iterable::dummy::{dummy_r1cs_stream, DummyStreamer};
-use ark_gemini::kzg::{Commitment, CommitterKeyStream};
+use ark_gemini::kzg::{Commitment, CommitterKeyStream, SpaceProverConfig};
 use ark_serialize::CanonicalSerialize;
 use ark_std::rand::Rng;
 use ark_std::test_rng;
@@ -61,6 +61,8 @@ fn elastic_snark_main(rng: &mut impl Rng, instance_logsize: usize) -> Proof {
     let ck = CommitterKeyStream {
         powers_of_g: DummyStreamer::new(g1, instance_size * 3 + 1),
         powers_of_g2: vec![g2; 4],
+        powers_of_gamma_g: vec![g1; 2],
+        config: SpaceProverConfig::default(),
     };
 
     let index = vec![Commitment(g1.into_group()); 5];
@@ -0,0 +1,306 @@
+//! The `gemini` command line tool, proving and verifying R1CS instances over BLS12-381 or BN254 from files.
+//!
+//! ```bash
+//! gemini prove --circom --circuit circuit.r1cs --witness witness.wtns --srs srs.bin \
+//!     --proof proof.bin --statement statement.bin
+//! gemini verify --circom --circuit circuit.r1cs --statement statement.bin --srs srs.bin --proof proof.bin
+//! ```
+//!
+//! The files are:
+//! - the circuit, either in the compact format of [`R1cs::read_compact`],
+//!   or, with `--circom`, a Circom `.r1cs` file;
+//! - the witness, either the pair of statement and witness vectors serialized with
+//!   [`CanonicalSerialize`] in compressed form, or, with `--circom`, a Circom `.wtns` file;
+//! - the statement, a vector serialized with [`CanonicalSerialize`] in compressed form,
+//!   including the constant one and the padding; the prover writes it, the verifier reads it;
+//! - the SRS, either a [`CommitterKey`] serialized with [`CanonicalSerialize`],
+//!   or, with `--ptau-degree`, a snarkjs `.ptau` transcript;
+//! - the proof, serialized with [`CanonicalSerialize`] in compressed form.
+//!
+//! All files are over the curve selected with `--curve`, BLS12-381 by default;
+//! Circom circuits compiled for the default prime of Circom are over BN254.
+//! The command exits with a non-zero status if any file is invalid or the proof does not verify.
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use ark_bn254::Bn254;
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+use ark_gemini::circuit::circom::{read_witness, CircomCircuit};
+use ark_gemini::circuit::{matrix_into_colmaj, matrix_into_rowmaj, R1cs, R1csStream};
+use ark_gemini::iterable::Reverse;
+use ark_gemini::kzg::{CommitterKey, CommitterKeyStream, VerifierKey};
+use ark_gemini::misc::product_matrix_vector;
+use ark_gemini::snark::{CircuitDigest, Proof};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_test_curves::bls12_381::Bls12_381;
+use clap::{ArgEnum, Args, Parser, Subcommand};
+
+/// The size of the buffer of the multi-scalar multiplications of the elastic prover.
+const MAX_MSM_BUFFER: usize = 1 << 20;
+/// The number of evaluation points supported by the keys read from `.ptau` transcripts.
+const MAX_EVAL_POINTS: usize = 5;
+
+/// Prove and verify R1CS instances with the Gemini SNARK.
+#[derive(Parser, Debug)]
+#[clap(name = "gemini")]
+struct Cli {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Prove that a witness satisfies a circuit, writing the proof.
+    Prove {
+        #[clap(flatten)]
+        files: CommonFiles,
+        /// The witness file.
+        #[clap(long)]
+        witness: PathBuf,
+        /// The output file for the proof.
+        #[clap(long)]
+        proof: PathBuf,
+        /// The output file for the statement.
+        #[clap(long)]
+        statement: Option<PathBuf>,
+        /// The prover to run.
+        #[clap(long, arg_enum, default_value = "time")]
+        prover: ProverKind,
+    },
+    /// Verify a proof for a circuit and a statement.
+    Verify {
+        #[clap(flatten)]
+        files: CommonFiles,
+        /// The statement file.
+        #[clap(long)]
+        statement: PathBuf,
+        /// The proof file.
+        #[clap(long)]
+        proof: PathBuf,
+    },
+}
+
+#[derive(Args, Debug)]
+struct CommonFiles {
+    /// The circuit file.
+    #[clap(long)]
+    circuit: PathBuf,
+    /// Read the circuit and the witness in the Circom formats.
+    #[clap(long)]
+    circom: bool,
+    /// The curve of the circuit, the SRS and the proof.
+    #[clap(long, arg_enum, default_value = "bls12-381")]
+    curve: Curve,
+    /// The SRS file.
+    #[clap(long)]
+    srs: PathBuf,
+    /// Read the SRS from a snarkjs `.ptau` transcript, taking the first powers up to this degree.
+    ///
+    /// The prover and the verifier must use the same degree.
+    #[clap(long)]
+    ptau_degree: Option<usize>,
+}
+
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum ProverKind {
+    /// The time-efficient prover.
+    Time,
+    /// The elastic, space-efficient prover.
+    Elastic,
+}
+
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum Curve {
+    /// BLS12-381.
+    #[clap(name = "bls12-381")]
+    Bls12_381,
+    /// BN254, also known as BN128 or alt_bn128.
+    Bn254,
+}
+
+/// A circuit read from file, either native or from Circom.
+enum Circuit<F: PrimeField> {
+    Native(R1cs<F>),
+    Circom(CircomCircuit<F>),
+}
+
+impl<F: PrimeField> Circuit<F> {
+    fn read(files: &CommonFiles) -> Result<Self, String> {
+        let reader = open(&files.circuit)?;
+        if files.circom {
+            CircomCircuit::read(reader)
+                .map(Circuit::Circom)
+                .map_err(|e| format!("invalid Circom circuit: {}", e))
+        } else {
+            R1cs::read_compact(reader)
+                .map(Circuit::Native)
+                .map_err(|e| format!("invalid circuit: {}", e))
+        }
+    }
+
+    /// Return the circuit matrices.
+    fn matrices(&self) -> &R1cs<F> {
+        match self {
+            Circuit::Native(r1cs) => r1cs,
+            Circuit::Circom(circuit) => circuit.circuit(),
+        }
+    }
+
+    /// Return the instance assigned with the witness file `path`.
+    fn assign(self, path: &Path) -> Result<R1cs<F>, String> {
+        let reader = open(path)?;
+        match self {
+            Circuit::Native(r1cs) => {
+                let (x, w) = <(Vec<F>, Vec<F>)>::deserialize_compressed(reader)
+                    .map_err(|e| format!("invalid witness: {}", e))?;
+                Ok(R1cs {
+                    z: [x.as_slice(), w.as_slice()].concat(),
+                    x,
+                    w,
+                    ..r1cs
+                })
+            }
+            Circuit::Circom(circuit) => read_witness(reader)
+                .and_then(|witness| circuit.assign(&witness))
+                .map_err(|e| format!("invalid Circom witness: {}", e)),
+        }
+    }
+}
+
+fn open(path: &Path) -> Result<BufReader<File>, String> {
+    File::open(path)
+        .map(BufReader::new)
+        .map_err(|e| format!("cannot open {}: {}", path.display(), e))
+}
+
+fn create(path: &Path) -> Result<BufWriter<File>, String> {
+    File::create(path)
+        .map(BufWriter::new)
+        .map_err(|e| format!("cannot create {}: {}", path.display(), e))
+}
+
+fn read_committer_key<E: Pairing>(files: &CommonFiles) -> Result<CommitterKey<E>, String> {
+    let reader = open(&files.srs)?;
+    if let Some(max_degree) = files.ptau_degree {
+        CommitterKey::from_ptau(reader, max_degree, MAX_EVAL_POINTS)
+            .map_err(|e| format!("invalid SRS: {}", e))
+    } else {
+        CommitterKey::deserialize_compressed(reader).map_err(|e| format!("invalid SRS: {}", e))
+    }
+}
+
+fn prove<E: Pairing>(
+    files: CommonFiles,
+    witness: &Path,
+    proof_path: &Path,
+    statement: Option<&Path>,
+    prover: ProverKind,
+) -> Result<(), String> {
+    let r1cs = Circuit::<E::ScalarField>::read(&files)?.assign(witness)?;
+    if let Err(unsatisfied) = r1cs.is_satisfied() {
+        return Err(format!(
+            "the witness does not satisfy the circuit: {}",
+            unsatisfied
+        ));
+    }
+    let ck = read_committer_key::<E>(&files)?;
+
+    let proof = match prover {
+        ProverKind::Time => Proof::new_time(&r1cs, &ck),
+        ProverKind::Elastic => {
+            let z_a = product_matrix_vector(&r1cs.a, &r1cs.z);
+            let z_b = product_matrix_vector(&r1cs.b, &r1cs.z);
+            let z_c = product_matrix_vector(&r1cs.c, &r1cs.z);
+            let num_columns = r1cs.z.len();
+            let [a_colmaj, b_colmaj, c_colmaj] =
+                [&r1cs.a, &r1cs.b, &r1cs.c].map(|matrix| matrix_into_colmaj(matrix, num_columns));
+            let [a_rowmaj, b_rowmaj, c_rowmaj] =
+                [&r1cs.a, &r1cs.b, &r1cs.c].map(|matrix| matrix_into_rowmaj(matrix));
+            let r1cs_stream = R1csStream {
+                z: Reverse(r1cs.z.as_slice()),
+                a_colmaj: a_colmaj.as_slice(),
+                b_colmaj: b_colmaj.as_slice(),
+                c_colmaj: c_colmaj.as_slice(),
+                a_rowmaj: a_rowmaj.as_slice(),
+                b_rowmaj: b_rowmaj.as_slice(),
+                c_rowmaj: c_rowmaj.as_slice(),
+                witness: Reverse(r1cs.w.as_slice()),
+                z_a: Reverse(z_a.as_slice()),
+                z_b: Reverse(z_b.as_slice()),
+                z_c: Reverse(z_c.as_slice()),
+                nonzero: r1cs.a.len(),
+                joint_len: r1cs.a.len(),
+            };
+            let digest = CircuitDigest::new(&r1cs, &r1cs.x, &VerifierKey::from(&ck));
+            Proof::new_elastic_with_digest(
+                r1cs_stream,
+                CommitterKeyStream::from(&ck),
+                &digest,
+                MAX_MSM_BUFFER,
+            )
+        }
+    };
+
+    proof
+        .serialize_compressed(create(proof_path)?)
+        .map_err(|e| format!("cannot write the proof: {}", e))?;
+    if let Some(statement) = statement {
+        r1cs.x
+            .serialize_compressed(create(statement)?)
+            .map_err(|e| format!("cannot write the statement: {}", e))?;
+    }
+    Ok(())
+}
+
+fn verify<E: Pairing>(
+    files: CommonFiles,
+    statement: &Path,
+    proof_path: &Path,
+) -> Result<(), String> {
+    let circuit = Circuit::<E::ScalarField>::read(&files)?;
+    let r1cs = circuit.matrices();
+    let x = Vec::<E::ScalarField>::deserialize_compressed(open(statement)?)
+        .map_err(|e| format!("invalid statement: {}", e))?;
+    let proof = Proof::<E>::deserialize_compressed(open(proof_path)?)
+        .map_err(|e| format!("invalid proof: {}", e))?;
+    let vk = VerifierKey::from(&read_committer_key::<E>(&files)?);
+    proof
+        .verify(r1cs, &x, &vk)
+        .map_err(|e| format!("verification failed: {}", e))
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Command::Prove {
+            files,
+            witness,
+            proof,
+            statement,
+            prover,
+        } => match files.curve {
+            Curve::Bls12_381 => {
+                prove::<Bls12_381>(files, &witness, &proof, statement.as_deref(), prover)
+            }
+            Curve::Bn254 => prove::<Bn254>(files, &witness, &proof, statement.as_deref(), prover),
+        },
+        Command::Verify {
+            files,
+            statement,
+            proof,
+        } => match files.curve {
+            Curve::Bls12_381 => verify::<Bls12_381>(files, &statement, &proof),
+            Curve::Bn254 => verify::<Bn254>(files, &statement, &proof),
+        },
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("gemini: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! Execution of the witness generators compiled by Circom to WebAssembly.
+use ark_ff::{BigInteger, PrimeField};
+use ark_serialize::CanonicalDeserialize;
+use ark_std::vec::Vec;
+use wasmer::{imports, Function, Instance, Module, NativeFunc, Store};
+
+use super::CircomError;
+
+/// The version of the Circom witness generators supported.
+const CIRCOM_VERSION: i32 = 2;
+
+/// Return the 64-bit FNV-1a hash of the signal `name`, split in its most and least significant halves,
+/// which Circom uses to identify the input signals.
+fn fnv1a(name: &str) -> (i32, i32) {
+    let mut hash = 0xcbf29ce484222325u64;
+    for byte in name.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    ((hash >> 32) as i32, hash as i32)
+}
+
+fn wasm_error<E>(_: E) -> CircomError {
+    CircomError::WitnessCalculation("the witness generator failed")
+}
+
+/// A Circom witness generator, i.e. the `.wasm` file produced by `circom --wasm`.
+///
+/// Field elements are exchanged with the generator through a shared memory of 32-bit limbs.
+pub struct WitnessCalculator {
+    instance: Instance,
+    /// The number of 32-bit limbs of a field element.
+    n32: usize,
+}
+
+impl WitnessCalculator {
+    /// Load the witness generator from the WebAssembly module `wasm`.
+    pub fn new(wasm: &[u8]) -> Result<Self, CircomError> {
+        let store = Store::default();
+        let module = Module::new(&store, wasm).map_err(wasm_error)?;
+        // the runtime only reports errors and logs, which surface as traps anyway.
+        let import_object = imports! {
+            "runtime" => {
+                "exceptionHandler" => Function::new_native(&store, |_code: i32| {}),
+                "printErrorMessage" => Function::new_native(&store, || {}),
+                "writeBufferMessage" => Function::new_native(&store, || {}),
+                "showSharedRWMemory" => Function::new_native(&store, || {}),
+            }
+        };
+        let instance = Instance::new(&module, &import_object).map_err(wasm_error)?;
+
+        let mut calculator = WitnessCalculator { instance, n32: 0 };
+        if calculator
+            .function::<(), i32>("getVersion")?
+            .call()
+            .map_err(wasm_error)?
+            != CIRCOM_VERSION
+        {
+            return Err(CircomError::WitnessCalculation(
+                "unsupported circom version",
+            ));
+        }
+        calculator.n32 = calculator
+            .function::<(), i32>("getFieldNumLen32")?
+            .call()
+            .map_err(wasm_error)? as usize;
+        Ok(calculator)
+    }
+
+    fn function<Args, Rets>(&self, name: &str) -> Result<NativeFunc<Args, Rets>, CircomError>
+    where
+        Args: wasmer::WasmTypeList,
+        Rets: wasmer::WasmTypeList,
+    {
+        self.instance
+            .exports
+            .get_native_function(name)
+            .map_err(|_| CircomError::WitnessCalculation("missing export"))
+    }
+
+    /// Read a field element from the shared memory.
+    fn read_element<F: PrimeField>(&self) -> Result<F, CircomError> {
+        let read = self.function::<i32, i32>("readSharedRWMemory")?;
+        let mut bytes = Vec::with_capacity(4 * self.n32);
+        for i in 0..self.n32 {
+            bytes.extend((read.call(i as i32).map_err(wasm_error)? as u32).to_le_bytes());
+        }
+        let size = F::zero().uncompressed_size();
+        if bytes.len() > size && bytes[size..].iter().any(|&byte| byte != 0) {
+            return Err(CircomError::InvalidFieldElement);
+        }
+        bytes.resize(size, 0);
+        F::deserialize_uncompressed(&bytes[..]).map_err(|_| CircomError::InvalidFieldElement)
+    }
+
+    /// Write a field element into the shared memory.
+    fn write_element<F: PrimeField>(&self, element: &F) -> Result<(), CircomError> {
+        let write = self.function::<(i32, i32), ()>("writeSharedRWMemory")?;
+        let mut bytes = element.into_bigint().to_bytes_le();
+        bytes.resize(4 * self.n32, 0);
+        for (i, limb) in bytes.chunks_exact(4).enumerate() {
+            let limb = u32::from_le_bytes([limb[0], limb[1], limb[2], limb[3]]);
+            write.call(i as i32, limb as i32).map_err(wasm_error)?;
+        }
+        Ok(())
+    }
+
+    /// Compute the assignment of all wires, given the values of the input signals `inputs`,
+    /// as pairs of signal names and (possibly multi-dimensional, flattened) values.
+    ///
+    /// The result can be fed to [`CircomCircuit::assign`](super::CircomCircuit::assign).
+    pub fn calculate<F: PrimeField>(
+        &mut self,
+        inputs: &[(&str, Vec<F>)],
+    ) -> Result<Vec<F>, CircomError> {
+        // the generator must be defined over F.
+        self.function::<(), ()>("getRawPrime")?
+            .call()
+            .map_err(wasm_error)?;
+        let read = self.function::<i32, i32>("readSharedRWMemory")?;
+        let mut prime = Vec::with_capacity(4 * self.n32);
+        for i in 0..self.n32 {
+            prime.extend((read.call(i as i32).map_err(wasm_error)? as u32).to_le_bytes());
+        }
+        let mut modulus = F::MODULUS.to_bytes_le();
+        modulus.resize(prime.len(), 0);
+        if prime != modulus {
+            return Err(CircomError::UnsupportedField);
+        }
+
+        self.function::<i32, ()>("init")?
+            .call(1)
+            .map_err(wasm_error)?;
+        let input_size = self.function::<(i32, i32), i32>("getInputSignalSize")?;
+        let set_input = self.function::<(i32, i32, i32), ()>("setInputSignal")?;
+        for (name, values) in inputs {
+            let (msb, lsb) = fnv1a(name);
+            let size = input_size.call(msb, lsb).map_err(wasm_error)?;
+            if size < 0 || size as usize != values.len() {
+                return Err(CircomError::WitnessCalculation("invalid input signal"));
+            }
+            for (i, value) in values.iter().enumerate() {
+                self.write_element(value)?;
+                set_input.call(msb, lsb, i as i32).map_err(wasm_error)?;
+            }
+        }
+
+        let witness_size = self
+            .function::<(), i32>("getWitnessSize")?
+            .call()
+            .map_err(wasm_error)?;
+        let get_witness = self.function::<i32, ()>("getWitness")?;
+        (0..witness_size)
+            .map(|i| {
+                get_witness.call(i).map_err(wasm_error)?;
+                self.read_element()
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_witness_calculator() {
+    use ark_ff::One;
+    use ark_std::string::String;
+    use ark_test_curves::bls12_381::Fr;
+
+    use crate::circuit::circom::CircomCircuit;
+
+    // a mock witness generator for the circuit `out <== in * 1`, with wires [1, out, in]:
+    // the shared memory lies at offset 0, the input signal at offset 64, and the prime at offset 128.
+    let (msb, lsb) = fnv1a("in");
+    let prime = Fr::MODULUS
+        .to_bytes_le()
+        .iter()
+        .map(|byte| format!("\\{:02x}", byte))
+        .collect::<String>();
+    let wat = format!(
+        r#"(module
+        (import "runtime" "exceptionHandler" (func (param i32)))
+        (import "runtime" "printErrorMessage" (func))
+        (import "runtime" "writeBufferMessage" (func))
+        (import "runtime" "showSharedRWMemory" (func))
+        (memory 1)
+        (data (i32.const 128) "{prime}")
+        (func $copy (param $dst i32) (param $src i32)
+            (i64.store (local.get $dst) (i64.load (local.get $src)))
+            (i64.store offset=8 (local.get $dst) (i64.load offset=8 (local.get $src)))
+            (i64.store offset=16 (local.get $dst) (i64.load offset=16 (local.get $src)))
+            (i64.store offset=24 (local.get $dst) (i64.load offset=24 (local.get $src))))
+        (func (export "getVersion") (result i32) (i32.const 2))
+        (func (export "getFieldNumLen32") (result i32) (i32.const 8))
+        (func (export "init") (param i32))
+        (func (export "getRawPrime") (call $copy (i32.const 0) (i32.const 128)))
+        (func (export "readSharedRWMemory") (param i32) (result i32)
+            (i32.load (i32.mul (local.get 0) (i32.const 4))))
+        (func (export "writeSharedRWMemory") (param i32 i32)
+            (i32.store (i32.mul (local.get 0) (i32.const 4)) (local.get 1)))
+        (func (export "getInputSignalSize") (param i32 i32) (result i32)
+            (select (i32.const 1) (i32.const -1)
+                (i32.and (i32.eq (local.get 0) (i32.const {msb})) (i32.eq (local.get 1) (i32.const {lsb})))))
+        (func (export "setInputSignal") (param i32 i32 i32) (call $copy (i32.const 64) (i32.const 0)))
+        (func (export "getWitnessSize") (result i32) (i32.const 3))
+        (func (export "getWitness") (param i32)
+            (if (i32.eqz (local.get 0))
+                (then
+                    (i64.store (i32.const 0) (i64.const 1))
+                    (i64.store (i32.const 8) (i64.const 0))
+                    (i64.store (i32.const 16) (i64.const 0))
+                    (i64.store (i32.const 24) (i64.const 0)))
+                (else (call $copy (i32.const 0) (i32.const 64))))))"#,
+    );
+
+    let input = Fr::from(42u64);
+    let mut calculator = WitnessCalculator::new(wat.as_bytes()).unwrap();
+    let witness = calculator.calculate(&[("in", vec![input])]).unwrap();
+    assert_eq!(witness, [Fr::one(), input, input]);
+    assert!(calculator.calculate(&[("out", vec![input])]).is_err());
+
+    // the full pipeline, from the inputs to the relation.
+    let circuit = CircomCircuit::<Fr>::from_wires(
+        vec![vec![(Fr::one(), 2)]],
+        vec![vec![(Fr::one(), 0)]],
+        vec![vec![(Fr::one(), 1)]],
+        3,
+        2,
+    )
+    .unwrap();
+    let r1cs = circuit.assign(&witness).unwrap();
+    assert_eq!(r1cs.is_satisfied(), Ok(()));
+}
@@ -0,0 +1,506 @@
+//! Import of [Circom](https://docs.circom.io/) circuits.
+//!
+//! Circom compiles a circuit into a binary `.r1cs` file, describing the constraints,
+//! and computes the assignment into a binary `.wtns` file.
+//! Both formats are organized in sections, and store field elements in little-endian standard form.
+//!
+//! Circom numbers the wires as: the constant \\(1\\), the public outputs, the public inputs,
+//! the private inputs, and the internal wires.
+//! With the `circom-witness` feature, the witness can be computed directly
+//! from the WebAssembly witness generator of the circuit, via [`WitnessCalculator`].
+//!
+//! The public wires form the statement, which is padded with zeros to a power of two
+//! as for circuits synthesized with arkworks, see [`try_generate_relation`](super::try_generate_relation).
+use ark_ff::{BigInteger, PrimeField};
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+use ark_serialize::CanonicalDeserialize;
+use ark_std::fmt;
+use ark_std::io::Read;
+use ark_std::vec::Vec;
+
+use super::{Matrix, R1cs};
+
+#[cfg(feature = "circom-witness")]
+mod calculator;
+#[cfg(feature = "circom-witness")]
+pub use calculator::WitnessCalculator;
+
+/// The magic string at the beginning of a Circom `.r1cs` file.
+const R1CS_MAGIC: &[u8; 4] = b"r1cs";
+/// The magic string at the beginning of a Circom `.wtns` file.
+const WTNS_MAGIC: &[u8; 4] = b"wtns";
+/// The `.r1cs` section identifiers we are interested in.
+const R1CS_SECTION_HEADER: u32 = 1;
+const R1CS_SECTION_CONSTRAINTS: u32 = 2;
+/// The `.wtns` section identifiers.
+const WTNS_SECTION_HEADER: u32 = 1;
+const WTNS_SECTION_WITNESS: u32 = 2;
+/// The largest size of the field elements, in bytes.
+const MAX_FIELD_BYTES: usize = 64;
+
+/// Error identifying malformed Circom files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CircomError {
+    /// The underlying reader failed.
+    Io,
+    /// The file is malformed.
+    InvalidFormat(&'static str),
+    /// The file is defined over a different prime field.
+    UnsupportedField,
+    /// Some field element is not canonically encoded.
+    InvalidFieldElement,
+    /// The witness does not match the wires of the circuit.
+    WitnessMismatch,
+    /// The witness generator failed.
+    WitnessCalculation(&'static str),
+}
+
+impl fmt::Display for CircomError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io => write!(f, "I/O error while reading the Circom file."),
+            Self::InvalidFormat(reason) => write!(f, "Invalid Circom format: {}.", reason),
+            Self::UnsupportedField => write!(f, "The circuit is defined over a different field."),
+            Self::InvalidFieldElement => write!(f, "Invalid field element encoding."),
+            Self::WitnessMismatch => write!(f, "The witness does not match the circuit."),
+            Self::WitnessCalculation(reason) => {
+                write!(f, "Witness calculation failed: {}.", reason)
+            }
+        }
+    }
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32, CircomError> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf).map_err(|_| CircomError::Io)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> Result<u64, CircomError> {
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf).map_err(|_| CircomError::Io)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+/// Read the next `len` bytes of `reader` in chunks, passing each to `f`.
+fn read_chunks<R: Read>(
+    reader: &mut R,
+    mut len: u64,
+    mut f: impl FnMut(&[u8]),
+) -> Result<(), CircomError> {
+    let mut buf = [0u8; 1 << 12];
+    while len > 0 {
+        let step = u64::min(len, buf.len() as u64) as usize;
+        reader
+            .read_exact(&mut buf[..step])
+            .map_err(|_| CircomError::Io)?;
+        f(&buf[..step]);
+        len -= step as u64;
+    }
+    Ok(())
+}
+
+/// Discard the next `len` bytes of `reader`.
+fn skip<R: Read>(reader: &mut R, len: u64) -> Result<(), CircomError> {
+    read_chunks(reader, len, |_| ())
+}
+
+/// Return the next `len` bytes of `reader`.
+///
+/// The buffer grows with the bytes actually read, so that a forged `len` cannot exhaust the memory.
+fn read_bytes<R: Read>(reader: &mut R, len: u64) -> Result<Vec<u8>, CircomError> {
+    let mut bytes = Vec::new();
+    read_chunks(reader, len, |chunk| bytes.extend_from_slice(chunk))?;
+    Ok(bytes)
+}
+
+/// Return the size of the rest of a section of `size` bytes, once `read` bytes have been read.
+fn remaining(size: u64, read: u64) -> Result<u64, CircomError> {
+    size.checked_sub(read)
+        .ok_or(CircomError::InvalidFormat("section too short"))
+}
+
+/// Check the magic string `magic` and return the number of sections.
+fn read_preamble<R: Read>(reader: &mut R, magic: &[u8; 4]) -> Result<u32, CircomError> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf).map_err(|_| CircomError::Io)?;
+    if &buf != magic {
+        return Err(CircomError::InvalidFormat("invalid magic string"));
+    }
+    let _version = read_u32(reader)?;
+    read_u32(reader)
+}
+
+/// Read the size of the field elements and the prime,
+/// checking that the prime is the modulus of `F`.
+fn read_field_header<F: PrimeField, R: Read>(reader: &mut R) -> Result<usize, CircomError> {
+    let n8 = read_u32(reader)? as usize;
+    if n8 > MAX_FIELD_BYTES {
+        return Err(CircomError::InvalidFormat("field elements too large"));
+    }
+    let mut modulus = vec![0u8; n8];
+    reader
+        .read_exact(&mut modulus)
+        .map_err(|_| CircomError::Io)?;
+    if modulus != F::MODULUS.to_bytes_le() {
+        return Err(CircomError::UnsupportedField);
+    }
+    Ok(n8)
+}
+
+/// Read a single field element of `n8` bytes, in little-endian standard form.
+fn read_field_element<F: PrimeField, R: Read>(reader: &mut R, n8: usize) -> Result<F, CircomError> {
+    let mut bytes = vec![0u8; n8];
+    reader.read_exact(&mut bytes).map_err(|_| CircomError::Io)?;
+    F::deserialize_uncompressed(&bytes[..]).map_err(|_| CircomError::InvalidFieldElement)
+}
+
+/// Read the matrices of `num_constraints` constraints over `num_wires` wires,
+/// with field elements of `n8` bytes.
+fn read_constraints<F: PrimeField, R: Read>(
+    reader: &mut R,
+    n8: usize,
+    num_wires: usize,
+    num_constraints: usize,
+) -> Result<[Matrix<F>; 3], CircomError> {
+    let mut matrices = [Vec::new(), Vec::new(), Vec::new()];
+    for _ in 0..num_constraints {
+        for matrix in matrices.iter_mut() {
+            let num_terms = read_u32(reader)?;
+            let mut row = (0..num_terms)
+                .map(|_| {
+                    let wire = read_u32(reader)? as usize;
+                    if wire >= num_wires {
+                        return Err(CircomError::InvalidFormat("wire out of range"));
+                    }
+                    Ok((read_field_element::<F, _>(reader, n8)?, wire))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            row.sort_by_key(|&(_, wire)| wire);
+            matrix.push(row);
+        }
+    }
+    Ok(matrices)
+}
+
+/// Read the `len` field elements of `n8` bytes of the witness section.
+fn read_values<F: PrimeField, R: Read>(
+    reader: &mut R,
+    n8: usize,
+    len: usize,
+) -> Result<Vec<F>, CircomError> {
+    (0..len).map(|_| read_field_element(reader, n8)).collect()
+}
+
+/// A circuit read from a Circom `.r1cs` file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CircomCircuit<F: PrimeField> {
+    /// The circuit description, with columns already arranged as the padded statement followed by the witness.
+    circuit: R1cs<F>,
+    /// The number of wires.
+    num_wires: usize,
+    /// The number of public wires, including the constant.
+    num_public: usize,
+    /// The length of the padded statement.
+    num_instance_variables: usize,
+}
+
+impl<F: PrimeField> CircomCircuit<F> {
+    /// Read the circuit from the `.r1cs` file `reader`.
+    pub fn read<R: Read>(mut reader: R) -> Result<Self, CircomError> {
+        let sections = read_preamble(&mut reader, R1CS_MAGIC)?;
+
+        // sections may come in any order:
+        // the constraints are buffered until the header is read.
+        let mut header = None;
+        let mut constraints = None;
+        let mut pending_constraints = None;
+        for _ in 0..sections {
+            let section = read_u32(&mut reader)?;
+            let size = read_u64(&mut reader)?;
+            match (section, header) {
+                (R1CS_SECTION_HEADER, _) => {
+                    let n8 = read_field_header::<F, _>(&mut reader)?;
+                    let num_wires = read_u32(&mut reader)? as usize;
+                    let num_outputs = read_u32(&mut reader)? as usize;
+                    let num_public_inputs = read_u32(&mut reader)? as usize;
+                    let _num_private_inputs = read_u32(&mut reader)?;
+                    let _num_labels = read_u64(&mut reader)?;
+                    let num_constraints = read_u32(&mut reader)? as usize;
+                    let num_public = 1 + num_outputs + num_public_inputs;
+                    if num_public > num_wires {
+                        return Err(CircomError::InvalidFormat("too many public wires"));
+                    }
+                    skip(&mut reader, remaining(size, n8 as u64 + 32)?)?;
+                    header = Some((n8, num_wires, num_public, num_constraints));
+                }
+                (R1CS_SECTION_CONSTRAINTS, Some((n8, num_wires, _, num_constraints))) => {
+                    constraints = Some(read_constraints(
+                        &mut reader,
+                        n8,
+                        num_wires,
+                        num_constraints,
+                    )?);
+                }
+                (R1CS_SECTION_CONSTRAINTS, None) => {
+                    pending_constraints = Some(read_bytes(&mut reader, size)?);
+                }
+                _ => skip(&mut reader, size)?,
+            }
+        }
+
+        let (n8, num_wires, num_public, num_constraints) =
+            header.ok_or(CircomError::InvalidFormat("missing section"))?;
+        if let Some(bytes) = pending_constraints {
+            constraints = Some(read_constraints(
+                &mut &bytes[..],
+                n8,
+                num_wires,
+                num_constraints,
+            )?);
+        }
+        let [a, b, c] = constraints.ok_or(CircomError::InvalidFormat("missing section"))?;
+        Self::from_wires(a, b, c, num_wires, num_public)
+    }
+
+    /// Return the circuit with matrices `a`, `b`, `c` over `num_wires` wires in Circom order,
+    /// the first `num_public` of which are public.
+    pub(crate) fn from_wires(
+        a: Matrix<F>,
+        b: Matrix<F>,
+        c: Matrix<F>,
+        num_wires: usize,
+        num_public: usize,
+    ) -> Result<Self, CircomError> {
+        let num_instance_variables = GeneralEvaluationDomain::<F>::new(num_public)
+            .ok_or(CircomError::InvalidFormat("too many public wires"))?
+            .size();
+        let padding = num_instance_variables - num_public;
+        let rearrange = |matrix: Matrix<F>| {
+            matrix
+                .into_iter()
+                .map(|row| {
+                    row.into_iter()
+                        .map(|(value, wire)| {
+                            (
+                                value,
+                                if wire < num_public {
+                                    wire
+                                } else {
+                                    wire + padding
+                                },
+                            )
+                        })
+                        .collect()
+                })
+                .collect()
+        };
+        Ok(CircomCircuit {
+            circuit: R1cs::from_matrices(rearrange(a), rearrange(b), rearrange(c)),
+            num_wires,
+            num_public,
+            num_instance_variables,
+        })
+    }
+
+    /// Return the circuit description, without any assignment.
+    pub fn circuit(&self) -> &R1cs<F> {
+        &self.circuit
+    }
+
+    /// Return the number of public wires, including the constant.
+    pub fn num_public(&self) -> usize {
+        self.num_public
+    }
+
+    /// Return the statement for the public wires of the assignment `witness`, padded with zeros.
+    pub fn statement(&self, witness: &[F]) -> Result<Vec<F>, CircomError> {
+        if witness.len() != self.num_wires || witness.first() != Some(&F::one()) {
+            return Err(CircomError::WitnessMismatch);
+        }
+        let mut x = witness[..self.num_public].to_vec();
+        x.resize(self.num_instance_variables, F::zero());
+        Ok(x)
+    }
+
+    /// Return the R1CS relation for the assignment of all wires `witness`,
+    /// as read from a `.wtns` file with [`read_witness`].
+    pub fn assign(&self, witness: &[F]) -> Result<R1cs<F>, CircomError> {
+        let x = self.statement(witness)?;
+        let w = witness[self.num_public..].to_vec();
+        Ok(R1cs {
+            z: [x.as_slice(), w.as_slice()].concat(),
+            x,
+            w,
+            ..self.circuit.clone()
+        })
+    }
+}
+
+/// Read the assignment of all wires from the `.wtns` file `reader`.
+pub fn read_witness<F: PrimeField, R: Read>(mut reader: R) -> Result<Vec<F>, CircomError> {
+    let sections = read_preamble(&mut reader, WTNS_MAGIC)?;
+
+    // as in `.r1cs` files, the witness is buffered until the header is read.
+    let mut header = None;
+    let mut witness = None;
+    let mut pending_witness = None;
+    for _ in 0..sections {
+        let section = read_u32(&mut reader)?;
+        let size = read_u64(&mut reader)?;
+        match (section, header) {
+            (WTNS_SECTION_HEADER, _) => {
+                let n8 = read_field_header::<F, _>(&mut reader)?;
+                let len = read_u32(&mut reader)? as usize;
+                skip(&mut reader, remaining(size, n8 as u64 + 8)?)?;
+                header = Some((n8, len));
+            }
+            (WTNS_SECTION_WITNESS, Some((n8, len))) => {
+                if (n8 as u64).checked_mul(len as u64) != Some(size) {
+                    return Err(CircomError::InvalidFormat("invalid witness size"));
+                }
+                witness = Some(read_values(&mut reader, n8, len)?);
+            }
+            (WTNS_SECTION_WITNESS, None) => {
+                pending_witness = Some(read_bytes(&mut reader, size)?);
+            }
+            _ => skip(&mut reader, size)?,
+        }
+    }
+
+    let (n8, len) = header.ok_or(CircomError::InvalidFormat("missing section"))?;
+    if let Some(bytes) = pending_witness {
+        if (n8 as u64).checked_mul(len as u64) != Some(bytes.len() as u64) {
+            return Err(CircomError::InvalidFormat("invalid witness size"));
+        }
+        witness = Some(read_values(&mut &bytes[..], n8, len)?);
+    }
+    witness.ok_or(CircomError::InvalidFormat("missing section"))
+}
+
+#[test]
+fn test_circom_import() {
+    use ark_ff::One;
+    use ark_test_curves::bls12_381::Fr;
+
+    fn write_section(out: &mut Vec<u8>, section: u32, body: &[u8]) {
+        out.extend(section.to_le_bytes());
+        out.extend((body.len() as u64).to_le_bytes());
+        out.extend(body);
+    }
+
+    fn write_element(out: &mut Vec<u8>, element: Fr) {
+        out.extend(element.into_bigint().to_bytes_le());
+    }
+
+    fn write_lc(out: &mut Vec<u8>, terms: &[(u32, Fr)]) {
+        out.extend((terms.len() as u32).to_le_bytes());
+        for &(wire, value) in terms {
+            out.extend(wire.to_le_bytes());
+            write_element(out, value);
+        }
+    }
+
+    // the circuit proving knowledge of a, b such that a * b = out, and (a + 1) * b = out + in.
+    // wires: [1, out, in, a, b].
+    let mut header_r1cs = Vec::new();
+    header_r1cs.extend(32u32.to_le_bytes());
+    header_r1cs.extend(Fr::MODULUS.to_bytes_le());
+    for value in [5u32, 1, 1, 2] {
+        header_r1cs.extend(value.to_le_bytes());
+    }
+    header_r1cs.extend(5u64.to_le_bytes());
+    header_r1cs.extend(2u32.to_le_bytes());
+    let mut constraints = Vec::new();
+    write_lc(&mut constraints, &[(3, Fr::one())]);
+    write_lc(&mut constraints, &[(4, Fr::one())]);
+    write_lc(&mut constraints, &[(1, Fr::one())]);
+    write_lc(&mut constraints, &[(3, Fr::one()), (0, Fr::one())]);
+    write_lc(&mut constraints, &[(4, Fr::one())]);
+    write_lc(&mut constraints, &[(2, Fr::one()), (1, Fr::one())]);
+    let mut r1cs_file = R1CS_MAGIC.to_vec();
+    r1cs_file.extend(1u32.to_le_bytes());
+    r1cs_file.extend(2u32.to_le_bytes());
+    write_section(&mut r1cs_file, R1CS_SECTION_HEADER, &header_r1cs);
+    write_section(&mut r1cs_file, R1CS_SECTION_CONSTRAINTS, &constraints);
+
+    let assignment = [1u64, 6, 3, 2, 3].map(Fr::from);
+    let mut header = Vec::new();
+    header.extend(32u32.to_le_bytes());
+    header.extend(Fr::MODULUS.to_bytes_le());
+    header.extend((assignment.len() as u32).to_le_bytes());
+    let mut values = Vec::new();
+    assignment
+        .iter()
+        .for_each(|&e| write_element(&mut values, e));
+    let mut wtns_file = WTNS_MAGIC.to_vec();
+    wtns_file.extend(2u32.to_le_bytes());
+    wtns_file.extend(2u32.to_le_bytes());
+    write_section(&mut wtns_file, WTNS_SECTION_HEADER, &header);
+    write_section(&mut wtns_file, WTNS_SECTION_WITNESS, &values);
+
+    let circuit = CircomCircuit::<Fr>::read(&r1cs_file[..]).unwrap();
+    let witness = read_witness::<Fr, _>(&wtns_file[..]).unwrap();
+    assert_eq!(witness, assignment);
+    assert_eq!(circuit.num_public(), 3);
+
+    let r1cs = circuit.assign(&witness).unwrap();
+    assert_eq!(r1cs.x, [1u64, 6, 3, 0].map(Fr::from));
+    assert_eq!(r1cs.w, [2u64, 3].map(Fr::from));
+    assert_eq!(r1cs.is_satisfied(), Ok(()));
+
+    let mut wrong_witness = witness.clone();
+    wrong_witness[2] = Fr::from(4u64);
+    assert!(circuit
+        .assign(&wrong_witness)
+        .unwrap()
+        .is_satisfied()
+        .is_err());
+    assert_eq!(
+        circuit.assign(&witness[1..]),
+        Err(CircomError::WitnessMismatch)
+    );
+    assert_eq!(
+        CircomCircuit::<Fr>::read(&wtns_file[..]),
+        Err(CircomError::InvalidFormat("invalid magic string"))
+    );
+
+    // sections are found by their identifier, in any order.
+    let mut swapped_r1cs_file = r1cs_file[..12].to_vec();
+    write_section(
+        &mut swapped_r1cs_file,
+        R1CS_SECTION_CONSTRAINTS,
+        &constraints,
+    );
+    write_section(&mut swapped_r1cs_file, R1CS_SECTION_HEADER, &header_r1cs);
+    assert_eq!(
+        CircomCircuit::<Fr>::read(&swapped_r1cs_file[..]),
+        Ok(circuit)
+    );
+    let mut swapped_wtns_file = wtns_file[..12].to_vec();
+    write_section(&mut swapped_wtns_file, WTNS_SECTION_WITNESS, &values);
+    write_section(&mut swapped_wtns_file, WTNS_SECTION_HEADER, &header);
+    assert_eq!(read_witness::<Fr, _>(&swapped_wtns_file[..]), Ok(witness));
+
+    // a header section shorter than its content.
+    let mut short_wtns_file = wtns_file[..12].to_vec();
+    short_wtns_file.extend(WTNS_SECTION_HEADER.to_le_bytes());
+    short_wtns_file.extend(8u64.to_le_bytes());
+    short_wtns_file.extend(&header);
+    assert_eq!(
+        read_witness::<Fr, _>(&short_wtns_file[..]),
+        Err(CircomError::InvalidFormat("section too short"))
+    );
+
+    // a forged size of the field elements.
+    let mut large_wtns_file = wtns_file[..12].to_vec();
+    write_section(
+        &mut large_wtns_file,
+        WTNS_SECTION_HEADER,
+        &u32::MAX.to_le_bytes(),
+    );
+    assert_eq!(
+        read_witness::<Fr, _>(&large_wtns_file[..]),
+        Err(CircomError::InvalidFormat("field elements too large"))
+    );
+}
@@ -0,0 +1,198 @@
+//! A compact binary format for R1CS instances.
+//!
+//! [`R1cs`] implements [`CanonicalSerialize`], storing each column index as a `u64`.
+//! The compact format stores instead the column indices as LEB128 varints,
+//! and omits the coefficients equal to one, which are the vast majority in practice:
+//! - the magic string `gr1cs` and the version byte;
+//! - for each of the matrices \\(A, B, C\\), the number of rows, and for each row
+//!   the number of entries followed by the entries, each as the varint `column << 1 | is_one`
+//!   followed by the compressed coefficient if it is not one;
+//! - the statement \\(\vec x\\) and the witness \\(\vec w\\), as varint lengths followed by compressed elements.
+//!
+//! The assignment \\(\vec z\\) is the concatenation of the statement and the witness.
+use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use ark_std::vec::Vec;
+
+use super::{Matrix, R1cs};
+
+/// The magic string at the beginning of the compact format.
+const COMPACT_MAGIC: &[u8; 5] = b"gr1cs";
+/// The version of the compact format.
+const COMPACT_VERSION: u8 = 1;
+
+fn write_varint<W: Write>(writer: &mut W, mut value: u64) -> Result<(), SerializationError> {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            writer.write_all(&[byte])?;
+            return Ok(());
+        }
+        writer.write_all(&[byte | 0x80])?;
+    }
+}
+
+fn read_varint<R: Read>(reader: &mut R) -> Result<u64, SerializationError> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let mut byte = [0u8];
+        reader.read_exact(&mut byte)?;
+        value |= u64::from(byte[0] & 0x7f) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(SerializationError::InvalidData)
+}
+
+fn read_len<R: Read>(reader: &mut R) -> Result<usize, SerializationError> {
+    usize::try_from(read_varint(reader)?).map_err(|_| SerializationError::InvalidData)
+}
+
+fn write_vector<F: Field, W: Write>(
+    writer: &mut W,
+    vector: &[F],
+) -> Result<(), SerializationError> {
+    write_varint(writer, vector.len() as u64)?;
+    vector
+        .iter()
+        .try_for_each(|e| e.serialize_compressed(&mut *writer))
+}
+
+fn read_vector<F: Field, R: Read>(reader: &mut R) -> Result<Vec<F>, SerializationError> {
+    let len = read_len(reader)?;
+    (0..len)
+        .map(|_| F::deserialize_compressed(&mut *reader))
+        .collect()
+}
+
+fn write_matrix<F: Field, W: Write>(
+    writer: &mut W,
+    matrix: &Matrix<F>,
+) -> Result<(), SerializationError> {
+    write_varint(writer, matrix.len() as u64)?;
+    for row in matrix {
+        write_varint(writer, row.len() as u64)?;
+        for (coefficient, column) in row {
+            let is_one = coefficient.is_one();
+            // the column index is packed together with the flag `is_one`.
+            let column = u64::try_from(*column)
+                .ok()
+                .filter(|&column| column < 1 << 63)
+                .ok_or(SerializationError::InvalidData)?;
+            write_varint(writer, (column << 1) | is_one as u64)?;
+            if !is_one {
+                coefficient.serialize_compressed(&mut *writer)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn read_matrix<F: Field, R: Read>(reader: &mut R) -> Result<Matrix<F>, SerializationError> {
+    let num_rows = read_len(reader)?;
+    (0..num_rows)
+        .map(|_| {
+            let len = read_len(reader)?;
+            (0..len)
+                .map(|_| {
+                    let entry = read_varint(reader)?;
+                    let column =
+                        usize::try_from(entry >> 1).map_err(|_| SerializationError::InvalidData)?;
+                    let coefficient = if entry & 1 == 1 {
+                        F::one()
+                    } else {
+                        F::deserialize_compressed(&mut *reader)?
+                    };
+                    Ok((coefficient, column))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+impl<F: Field> R1cs<F> {
+    /// Write the instance into `writer` in the compact binary format.
+    ///
+    /// Fails if the assignment `z` is not the concatenation of the statement `x` and the witness `w`.
+    pub fn write_compact<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        if self.z.len() != self.x.len() + self.w.len()
+            || self.z[..self.x.len()] != self.x[..]
+            || self.z[self.x.len()..] != self.w[..]
+        {
+            return Err(SerializationError::InvalidData);
+        }
+        writer.write_all(COMPACT_MAGIC)?;
+        writer.write_all(&[COMPACT_VERSION])?;
+        for matrix in [&self.a, &self.b, &self.c] {
+            write_matrix(&mut writer, matrix)?;
+        }
+        write_vector(&mut writer, &self.x)?;
+        write_vector(&mut writer, &self.w)
+    }
+
+    /// Read an instance from `reader` in the compact binary format.
+    pub fn read_compact<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let mut preamble = [0u8; 6];
+        reader.read_exact(&mut preamble)?;
+        if &preamble[..5] != COMPACT_MAGIC || preamble[5] != COMPACT_VERSION {
+            return Err(SerializationError::InvalidData);
+        }
+        let a = read_matrix(&mut reader)?;
+        let b = read_matrix(&mut reader)?;
+        let c = read_matrix(&mut reader)?;
+        let x = read_vector(&mut reader)?;
+        let w = read_vector(&mut reader)?;
+        Ok(R1cs {
+            a,
+            b,
+            c,
+            z: [x.as_slice(), w.as_slice()].concat(),
+            x,
+            w,
+        })
+    }
+}
+
+#[test]
+fn test_compact_r1cs() {
+    use ark_test_curves::bls12_381::Fr;
+
+    use crate::circuit::generator::R1csGenerator;
+    use crate::circuit::{generate_relation, random_circuit};
+
+    let rng = &mut ark_std::test_rng();
+    for r1cs in [
+        generate_relation::<Fr, _>(random_circuit(rng, 64, 64)),
+        R1csGenerator::new(64, 32)
+            .with_public_inputs(3)
+            .generate(rng),
+    ] {
+        let mut compact = Vec::new();
+        r1cs.write_compact(&mut compact).unwrap();
+        assert_eq!(R1cs::read_compact(&compact[..]).unwrap(), r1cs);
+
+        let mut canonical = Vec::new();
+        r1cs.serialize_compressed(&mut canonical).unwrap();
+        assert_eq!(R1cs::deserialize_compressed(&canonical[..]).unwrap(), r1cs);
+        assert!(compact.len() < canonical.len());
+    }
+
+    // circuits without assignment.
+    let r1cs = generate_relation::<Fr, _>(random_circuit(rng, 8, 8));
+    let circuit = R1cs::from_matrices(r1cs.a, r1cs.b, r1cs.c);
+    let mut compact = Vec::new();
+    circuit.write_compact(&mut compact).unwrap();
+    assert_eq!(R1cs::read_compact(&compact[..]).unwrap(), circuit);
+    assert!(R1cs::<Fr>::read_compact(&compact[1..]).is_err());
+
+    // column indices that do not fit in the packed entry.
+    #[cfg(target_pointer_width = "64")]
+    {
+        use ark_ff::One;
+
+        let overflowing = R1cs::from_matrices(vec![vec![(Fr::one(), 1 << 63)]], vec![], vec![]);
+        assert!(overflowing.write_compact(&mut Vec::new()).is_err());
+    }
+}
@@ -0,0 +1,270 @@
+//! Data-parallel circuits: many copies of the same sub-circuit sharing the statement.
+use ark_ff::Field;
+use ark_std::vec::Vec;
+
+use crate::misc::{hadamard, powers, tensor, vector_times_matrix, MatrixElement};
+
+use super::{Matrix, R1cs};
+
+/// An R1CS instance made of `copies` copies of the same sub-circuit,
+/// each with its own witness and all sharing the same statement.
+///
+/// The assignment is laid out as \(\vec z = (\vec x, \vec w_0, \dots, \vec w_{k-1})\),
+/// and the constraints of the \(j\)-th copy are the rows \(jm, \dots, (j+1)m - 1\),
+/// where \(m\) is the number of constraints of the sub-circuit.
+/// The \(j\)-th copy enforces the sub-circuit over \((\vec x, \vec w_j)\).
+///
+/// The matrices of the whole instance are never materialized:
+/// [`Proof::new_data_parallel`](crate::snark::Proof::new_data_parallel)
+/// reads only the matrices of the sub-circuit,
+/// and [`DataParallelR1cs::to_r1cs`] builds the whole instance for the verifier.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DataParallelR1cs<F: Field> {
+    circuit: R1cs<F>,
+    copies: usize,
+    x: Vec<F>,
+    pub(crate) w: Vec<F>,
+    pub(crate) z: Vec<F>,
+}
+
+impl<F: Field> DataParallelR1cs<F> {
+    /// Return the data-parallel instance of the sub-circuit with matrices `circuit.a`, `circuit.b`, `circuit.c`,
+    /// with shared statement `x` and one copy for each of the `witnesses`.
+    ///
+    /// The assignment of `circuit` is ignored.
+    ///
+    /// # Panics
+    /// If there are no witnesses, if the witnesses have different lengths,
+    /// or if the number of constraints of the sub-circuit is not a power of two
+    /// (see [`R1cs::pad`]).
+    pub fn new(circuit: R1cs<F>, x: Vec<F>, witnesses: &[Vec<F>]) -> Self {
+        assert!(!witnesses.is_empty(), "at least one copy is required");
+        assert!(
+            circuit.a.len().is_power_of_two(),
+            "the number of constraints of the sub-circuit must be a power of two"
+        );
+        let witness_len = witnesses[0].len();
+        assert!(
+            witnesses.iter().all(|w| w.len() == witness_len),
+            "all copies must have witnesses of the same length"
+        );
+
+        let w = witnesses.concat();
+        let z = [x.as_slice(), w.as_slice()].concat();
+        DataParallelR1cs {
+            circuit: R1cs::from_matrices(circuit.a, circuit.b, circuit.c),
+            copies: witnesses.len(),
+            x,
+            w,
+            z,
+        }
+    }
+
+    /// Return the matrices of the sub-circuit.
+    pub fn circuit(&self) -> &R1cs<F> {
+        &self.circuit
+    }
+
+    /// Return the number of copies of the sub-circuit.
+    pub fn copies(&self) -> usize {
+        self.copies
+    }
+
+    /// Return the shared statement.
+    pub fn x(&self) -> &[F] {
+        &self.x
+    }
+
+    /// Return the witness of all copies, concatenated.
+    pub fn w(&self) -> &[F] {
+        &self.w
+    }
+
+    /// Return the full assignment \((\vec x, \vec w_0, \dots, \vec w_{k-1})\).
+    pub fn z(&self) -> &[F] {
+        &self.z
+    }
+
+    /// Return the number of constraints of the whole instance.
+    pub fn num_constraints(&self) -> usize {
+        self.circuit.a.len() * self.copies
+    }
+
+    /// Materialize the whole instance, with block matrices.
+    pub fn to_r1cs(&self) -> R1cs<F> {
+        let [a, b, c] = [&self.circuit.a, &self.circuit.b, &self.circuit.c].map(|matrix| {
+            (0..self.copies)
+                .flat_map(|copy| {
+                    matrix.iter().map(move |row| {
+                        row.iter()
+                            .map(|&(coeff, column)| (coeff, self.column(copy, column)))
+                            .collect()
+                    })
+                })
+                .collect()
+        });
+        R1cs {
+            a,
+            b,
+            c,
+            z: self.z.clone(),
+            w: self.w.clone(),
+            x: self.x.clone(),
+        }
+    }
+
+    fn witness_len(&self) -> usize {
+        self.w.len() / self.copies
+    }
+
+    /// Map the column `column` of the sub-circuit to the column of the `copy`-th copy in the whole instance.
+    fn column(&self, copy: usize, column: usize) -> usize {
+        if column < self.x.len() {
+            column
+        } else {
+            column + copy * self.witness_len()
+        }
+    }
+
+    /// Iterate over the elements of the whole instance matrix built from the sub-circuit matrix `matrix`,
+    /// in the order of [`matrix_into_rowmaj`](super::matrix_into_rowmaj).
+    pub(crate) fn rowmaj_elements<'a>(
+        &'a self,
+        matrix: &'a Matrix<F>,
+    ) -> impl Iterator<Item = MatrixElement<F>> + 'a {
+        (0..self.copies).rev().flat_map(move |copy| {
+            matrix.iter().rev().flat_map(move |row| {
+                row.iter()
+                    .rev()
+                    .map(move |&(coeff, column)| {
+                        MatrixElement::Element((coeff, self.column(copy, column)))
+                    })
+                    .chain(Some(MatrixElement::EOL))
+            })
+        })
+    }
+
+    /// Compute \(A \vec z, B \vec z, C \vec z\) copy by copy.
+    pub(crate) fn product_matrices_vector(&self) -> [Vec<F>; 3] {
+        [&self.circuit.a, &self.circuit.b, &self.circuit.c].map(|matrix| {
+            (0..self.copies)
+                .flat_map(|copy| {
+                    matrix.iter().map(move |row| {
+                        row.iter()
+                            .map(|&(coeff, column)| self.z[self.column(copy, column)] * coeff)
+                            .sum()
+                    })
+                })
+                .collect()
+        })
+    }
+
+    /// Compute \(\vec r_A A + \eta \vec r_B B + \eta^2 \vec r_C C\), where
+    /// \(\vec r_B = \otimes_j (1, \rho_j)\) for the sumcheck `challenges` \(\rho_j\),
+    /// \(\vec r_C = (1, \alpha, \alpha^2, \dots)\), and \(\vec r_A = \vec r_B \circ \vec r_C\).
+    ///
+    /// With \(m = 2^\mu\) constraints per copy, the challenges of the \(j\)-th copy are
+    /// the challenges of the first copy scaled by a constant:
+    /// \(\otimes_{i \geq \mu} (1, \rho_i)\) at \(j\) for \(\vec r_B\), and \(\alpha^{jm}\) for \(\vec r_C\).
+    /// Hence the vector-matrix products are computed once on the sub-circuit matrices,
+    /// in time linear in their size plus the length of the witness.
+    pub(crate) fn tensored_matrices(&self, challenges: &[F], alpha: F, eta: F) -> Vec<F> {
+        let num_constraints = self.circuit.a.len();
+        let log_num_constraints = ark_std::log2(num_constraints) as usize;
+        let (low_challenges, high_challenges) = challenges.split_at(log_num_constraints);
+        let b_challenges = tensor_or_one(low_challenges);
+        let c_challenges = powers(alpha, num_constraints);
+        let a_challenges = hadamard(&b_challenges, &c_challenges);
+        let copy_challenges = tensor_or_one(high_challenges);
+
+        let num_columns = self.x.len() + self.witness_len();
+        let a_tensored = vector_times_matrix(&a_challenges, &self.circuit.a, num_columns);
+        let b_tensored = vector_times_matrix(&b_challenges, &self.circuit.b, num_columns);
+        let c_tensored = vector_times_matrix(&c_challenges, &self.circuit.c, num_columns);
+
+        let eta2 = eta.square();
+        let alpha_m = alpha.pow([num_constraints as u64]);
+        let copy_scalars = copy_challenges
+            .iter()
+            .zip(powers(alpha_m, self.copies))
+            .map(|(&b_scalar, c_scalar)| (b_scalar * c_scalar, b_scalar, c_scalar))
+            .collect::<Vec<_>>();
+        let combine = |(a_scalar, b_scalar, c_scalar): (F, F, F), column: usize| {
+            a_scalar * a_tensored[column]
+                + eta * b_scalar * b_tensored[column]
+                + eta2 * c_scalar * c_tensored[column]
+        };
+
+        // the statement is shared among all copies.
+        let statement_scalars = copy_scalars.iter().fold(
+            (F::zero(), F::zero(), F::zero()),
+            |(a_sum, b_sum, c_sum), &(a, b, c)| (a_sum + a, b_sum + b, c_sum + c),
+        );
+        let mut abc_tensored = (0..self.x.len())
+            .map(|column| combine(statement_scalars, column))
+            .collect::<Vec<_>>();
+        abc_tensored.reserve(self.w.len());
+        for &scalars in &copy_scalars {
+            abc_tensored.extend((self.x.len()..num_columns).map(|column| combine(scalars, column)));
+        }
+        abc_tensored
+    }
+}
+
+/// Expand the tensor product of `elements`, which is \(1\) if there are none.
+fn tensor_or_one<F: Field>(elements: &[F]) -> Vec<F> {
+    if elements.is_empty() {
+        vec![F::one()]
+    } else {
+        tensor(elements)
+    }
+}
+
+#[test]
+fn test_data_parallel_r1cs() {
+    use crate::circuit::{generate_relation, random_circuit};
+    use crate::misc::product_matrix_vector;
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::Fr;
+
+    let rng = &mut ark_std::test_rng();
+    let mut circuit = generate_relation(random_circuit::<Fr>(rng, 6, 8));
+    circuit.pad();
+    let witnesses = (0..3)
+        .map(|_| (0..circuit.w.len()).map(|_| Fr::rand(rng)).collect())
+        .collect::<Vec<Vec<Fr>>>();
+    let data_parallel = DataParallelR1cs::new(circuit.clone(), circuit.x.clone(), &witnesses);
+    let r1cs = data_parallel.to_r1cs();
+    assert_eq!(r1cs.a.len(), data_parallel.num_constraints());
+
+    let expected = [&r1cs.a, &r1cs.b, &r1cs.c].map(|matrix| product_matrix_vector(matrix, &r1cs.z));
+    assert_eq!(data_parallel.product_matrices_vector(), expected);
+
+    let expected = super::matrix_into_rowmaj(&r1cs.a);
+    let got = data_parallel
+        .rowmaj_elements(&circuit.a)
+        .collect::<Vec<_>>();
+    assert_eq!(got, expected);
+
+    let rounds = ark_std::log2(r1cs.a.len()) as usize;
+    let challenges = (0..rounds).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+    let alpha = Fr::rand(rng);
+    let eta = Fr::rand(rng);
+    let b_challenges = tensor(&challenges);
+    let c_challenges = powers(alpha, b_challenges.len());
+    let a_challenges = hadamard(&b_challenges, &c_challenges);
+    let num_columns = r1cs.z.len();
+    let a_tensored = vector_times_matrix(&a_challenges, &r1cs.a, num_columns);
+    let b_tensored = vector_times_matrix(&b_challenges, &r1cs.b, num_columns);
+    let c_tensored = vector_times_matrix(&c_challenges, &r1cs.c, num_columns);
+    let expected = a_tensored
+        .iter()
+        .zip(&b_tensored)
+        .zip(&c_tensored)
+        .map(|((a, b), c)| *a + eta * b + eta * eta * c)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        data_parallel.tensored_matrices(&challenges, alpha, eta),
+        expected
+    );
+}
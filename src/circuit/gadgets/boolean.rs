@@ -0,0 +1,75 @@
+//! Boolean decomposition.
+use ark_ff::{BigInteger, Field, PrimeField};
+use ark_std::vec::Vec;
+
+use super::{LinearCombination, R1csBuilder, Variable};
+
+/// Enforce that `variable` is boolean, i.e. \\(v \cdot (1 - v) = 0\\).
+pub fn enforce_boolean<F: Field>(builder: &mut R1csBuilder<F>, variable: Variable) {
+    builder.enforce(
+        variable,
+        LinearCombination::from(R1csBuilder::<F>::one()) - variable,
+        LinearCombination::zero(),
+    );
+}
+
+/// Allocate a new boolean witness variable assigned to `value`.
+pub fn new_boolean<F: Field>(builder: &mut R1csBuilder<F>, value: bool) -> Variable {
+    let variable = builder.new_witness(F::from(value));
+    enforce_boolean(builder, variable);
+    variable
+}
+
+/// Decompose `value` into `num_bits` boolean witness variables, least significant bit first,
+/// enforcing that \\(\sum_i 2^i b_i = v\\).
+///
+/// The constraints are unsatisfiable if the value assigned to `value` does not fit in `num_bits` bits.
+///
+/// # Panics
+/// If `num_bits` is not smaller than the bit size of the modulus,
+/// in which case the decomposition would not be unique.
+pub fn to_bits<F: PrimeField>(
+    builder: &mut R1csBuilder<F>,
+    value: impl Into<LinearCombination<F>>,
+    num_bits: usize,
+) -> Vec<Variable> {
+    assert!(
+        num_bits < F::MODULUS_BIT_SIZE as usize,
+        "the decomposition must not wrap around the modulus"
+    );
+    let value = value.into();
+    let assignment = builder.value(&value).into_bigint();
+    let bits = (0..num_bits)
+        .map(|i| new_boolean(builder, assignment.get_bit(i)))
+        .collect::<Vec<_>>();
+
+    let mut power = F::one();
+    let mut recomposition = LinearCombination::zero();
+    for &bit in &bits {
+        recomposition = recomposition + (power, bit);
+        power.double_in_place();
+    }
+    builder.enforce_equal(recomposition, value);
+    bits
+}
+
+#[test]
+fn test_to_bits() {
+    use ark_test_curves::bls12_381::Fr;
+
+    let mut builder = R1csBuilder::<Fr>::new();
+    let x = builder.new_input(Fr::from(0b1011u64));
+    let bits = to_bits(&mut builder, x, 4);
+    let values = bits
+        .iter()
+        .map(|&bit| builder.value(&bit.into()))
+        .collect::<Vec<_>>();
+    assert_eq!(values, [1u64, 1, 0, 1].map(Fr::from));
+    assert!(builder.build().is_satisfied().is_ok());
+
+    // values that do not fit are not satisfiable.
+    let mut builder = R1csBuilder::<Fr>::new();
+    let x = builder.new_input(Fr::from(0b10000u64));
+    to_bits(&mut builder, x, 4);
+    assert!(builder.build().is_satisfied().is_err());
+}
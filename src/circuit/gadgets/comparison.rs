@@ -0,0 +1,90 @@
+//! Comparisons between field elements.
+use ark_ff::{Field, PrimeField};
+
+use super::{to_bits, LinearCombination, R1csBuilder, Variable};
+
+/// Return a boolean witness variable set if and only if `a` and `b` are equal.
+///
+/// The inverse of their difference is allocated as auxiliary witness, enforcing
+/// \\((a - b) \cdot i = 1 - e\\) and \\((a - b) \cdot e = 0\\).
+pub fn is_equal<F: Field>(
+    builder: &mut R1csBuilder<F>,
+    a: impl Into<LinearCombination<F>>,
+    b: impl Into<LinearCombination<F>>,
+) -> Variable {
+    let difference = a.into() - b.into();
+    let assignment = builder.value(&difference);
+    let inverse = builder.new_witness(assignment.inverse().unwrap_or_else(F::zero));
+    let equal = builder.new_witness(F::from(assignment.is_zero()));
+    builder.enforce(
+        difference.clone(),
+        inverse,
+        LinearCombination::from(R1csBuilder::<F>::one()) - equal,
+    );
+    builder.enforce(difference, equal, LinearCombination::zero());
+    equal
+}
+
+/// Return a boolean linear combination set if and only if \\(a < b\\).
+///
+/// Both `a` and `b` must be smaller than \\(2^n\\) for `num_bits` \\(n\\):
+/// the constraints decompose \\(a - b + 2^n\\) into \\(n + 1\\) bits,
+/// whose most significant bit is set if and only if \\(a \geq b\\).
+pub fn is_less_than<F: PrimeField>(
+    builder: &mut R1csBuilder<F>,
+    a: impl Into<LinearCombination<F>>,
+    b: impl Into<LinearCombination<F>>,
+    num_bits: usize,
+) -> LinearCombination<F> {
+    let greater_or_equal = greater_or_equal(builder, a.into(), b.into(), num_bits);
+    LinearCombination::from(R1csBuilder::<F>::one()) - greater_or_equal
+}
+
+/// Enforce that \\(a < b\\), where both `a` and `b` are smaller than \\(2^n\\) for `num_bits` \\(n\\).
+pub fn enforce_less_than<F: PrimeField>(
+    builder: &mut R1csBuilder<F>,
+    a: impl Into<LinearCombination<F>>,
+    b: impl Into<LinearCombination<F>>,
+    num_bits: usize,
+) {
+    let greater_or_equal = greater_or_equal(builder, a.into(), b.into(), num_bits);
+    builder.enforce_equal(greater_or_equal, LinearCombination::zero());
+}
+
+fn greater_or_equal<F: PrimeField>(
+    builder: &mut R1csBuilder<F>,
+    a: LinearCombination<F>,
+    b: LinearCombination<F>,
+    num_bits: usize,
+) -> Variable {
+    let offset = LinearCombination::constant(F::from(2u64).pow([num_bits as u64]));
+    let bits = to_bits(builder, a - b + offset, num_bits + 1);
+    bits[num_bits]
+}
+
+#[test]
+fn test_comparison() {
+    use ark_test_curves::bls12_381::Fr;
+
+    let mut builder = R1csBuilder::<Fr>::new();
+    let a = builder.new_witness(Fr::from(3u64));
+    let b = builder.new_witness(Fr::from(5u64));
+    let equal = is_equal(&mut builder, a, b);
+    let self_equal = is_equal(&mut builder, a, a);
+    let less = is_less_than(&mut builder, a, b, 8);
+    let greater = is_less_than(&mut builder, b, a, 8);
+    let irreflexive = is_less_than(&mut builder, a, a, 8);
+    enforce_less_than(&mut builder, a, b, 8);
+
+    assert_eq!(builder.value(&equal.into()), Fr::from(0u64));
+    assert_eq!(builder.value(&self_equal.into()), Fr::from(1u64));
+    assert_eq!(builder.value(&less), Fr::from(1u64));
+    assert_eq!(builder.value(&greater), Fr::from(0u64));
+    assert_eq!(builder.value(&irreflexive), Fr::from(0u64));
+    assert!(builder.build().is_satisfied().is_ok());
+
+    let mut builder = R1csBuilder::<Fr>::new();
+    let a = builder.new_witness(Fr::from(5u64));
+    enforce_less_than(&mut builder, a, a, 8);
+    assert!(builder.build().is_satisfied().is_err());
+}
@@ -0,0 +1,126 @@
+//! Merkle path verification with the Poseidon hash.
+use ark_ff::Field;
+
+use super::{poseidon_hash, LinearCombination, PoseidonParameters, R1csBuilder, Variable};
+
+/// Return the root of the Merkle tree with leaf `leaf`, authentication path `siblings`
+/// (from the leaf up), and position given by `index_bits` (least significant bit first).
+///
+/// Each inner node is the Poseidon hash of its left and right children:
+/// the \\(i\\)-th bit of the index is set when the node at height \\(i\\) is a right child.
+/// The index bits must be boolean, see [`to_bits`](super::to_bits).
+///
+/// # Panics
+/// If `siblings` and `index_bits` have different lengths.
+pub fn merkle_root<F: Field>(
+    builder: &mut R1csBuilder<F>,
+    parameters: &PoseidonParameters<F>,
+    leaf: impl Into<LinearCombination<F>>,
+    siblings: &[LinearCombination<F>],
+    index_bits: &[Variable],
+) -> LinearCombination<F> {
+    assert_eq!(siblings.len(), index_bits.len());
+    let mut node = leaf.into();
+    for (sibling, &bit) in siblings.iter().zip(index_bits) {
+        // swap the node and its sibling if the bit is set.
+        let swap: LinearCombination<F> = builder.mul(bit, sibling.clone() - node.clone()).into();
+        let left = node.clone() + swap.clone();
+        let right = sibling.clone() - swap;
+        node = poseidon_hash(builder, parameters, &[left, right]);
+    }
+    node
+}
+
+/// Enforce that `leaf` is at position `index_bits` of the Merkle tree with root `root`,
+/// with authentication path `siblings`, see [`merkle_root`].
+pub fn enforce_merkle_path<F: Field>(
+    builder: &mut R1csBuilder<F>,
+    parameters: &PoseidonParameters<F>,
+    leaf: impl Into<LinearCombination<F>>,
+    siblings: &[LinearCombination<F>],
+    index_bits: &[Variable],
+    root: impl Into<LinearCombination<F>>,
+) {
+    let computed_root = merkle_root(builder, parameters, leaf, siblings, index_bits);
+    builder.enforce_equal(computed_root, root);
+}
+
+#[test]
+fn test_merkle_path() {
+    use ark_std::vec::Vec;
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::Fr;
+
+    use crate::circuit::gadgets::to_bits;
+
+    let rng = &mut ark_std::test_rng();
+    let width = 3;
+    let mds = (0..width)
+        .map(|_| (0..width).map(|_| Fr::rand(rng)).collect())
+        .collect();
+    let ark = (0..8 + 31)
+        .map(|_| (0..width).map(|_| Fr::rand(rng)).collect())
+        .collect();
+    let parameters = PoseidonParameters::new(8, 31, 5, mds, ark, 2, 1);
+
+    let height = 3;
+    let mut layer = (0..1 << height).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+    let leaves = layer.clone();
+    let index = 5;
+    let mut siblings = Vec::new();
+    let mut position = index;
+    while layer.len() > 1 {
+        siblings.push(layer[position ^ 1]);
+        layer = layer.chunks(2).map(|pair| parameters.hash(pair)).collect();
+        position >>= 1;
+    }
+    let root = layer[0];
+
+    let mut builder = R1csBuilder::new();
+    let root_variable = builder.new_input(root);
+    let leaf = builder.new_witness(leaves[index]);
+    let index_variable = builder.new_witness(Fr::from(index as u64));
+    let index_bits = to_bits(&mut builder, index_variable, height);
+    let sibling_variables = siblings
+        .iter()
+        .map(|&sibling| builder.new_witness(sibling).into())
+        .collect::<Vec<LinearCombination<Fr>>>();
+    let computed_root = merkle_root(
+        &mut builder,
+        &parameters,
+        leaf,
+        &sibling_variables,
+        &index_bits,
+    );
+    assert_eq!(builder.value(&computed_root), root);
+    enforce_merkle_path(
+        &mut builder,
+        &parameters,
+        leaf,
+        &sibling_variables,
+        &index_bits,
+        root_variable,
+    );
+    assert!(builder.build().is_satisfied().is_ok());
+
+    // a wrong leaf does not verify.
+    let mut builder = R1csBuilder::new();
+    let root_variable = builder.new_input(root);
+    let leaf = builder.new_witness(leaves[index ^ 1]);
+    let index_bits = (0..height)
+        .map(|i| builder.new_witness(Fr::from(((index >> i) & 1) as u64)))
+        .collect::<Vec<_>>();
+    let sibling_variables = siblings
+        .iter()
+        .map(|&sibling| builder.new_witness(sibling).into())
+        .collect::<Vec<LinearCombination<Fr>>>();
+    enforce_merkle_path(
+        &mut builder,
+        &parameters,
+        leaf,
+        &sibling_variables,
+        &index_bits,
+        root_variable,
+    );
+    assert!(builder.build().is_satisfied().is_err());
+}
@@ -0,0 +1,293 @@
+//! Builders of R1CS instances for common components,
+//! for writing circuits without an external frontend.
+//!
+//! Constraints are collected by an [`R1csBuilder`] over [`LinearCombination`]s of [`Variable`]s,
+//! and assigned while being built. The builder then emits an [`R1cs`] instance directly.
+//! The gadgets are:
+//! - [`enforce_boolean`], [`new_boolean`], and [`to_bits`], for boolean decomposition;
+//! - [`is_equal`], [`is_less_than`], and [`enforce_less_than`], for comparisons;
+//! - [`poseidon_hash`], for the Poseidon hash, and [`PoseidonSpongeVar`], for the Poseidon sponge;
+//! - [`merkle_root`] and [`enforce_merkle_path`], for Merkle path verification with the Poseidon hash.
+//!
+//! ```
+//! use ark_gemini::circuit::gadgets::{to_bits, R1csBuilder};
+//! use ark_test_curves::bls12_381::Fr;
+//!
+//! let mut builder = R1csBuilder::<Fr>::new();
+//! let x = builder.new_input(Fr::from(5u64));
+//! let bits = to_bits(&mut builder, x, 8);
+//! assert_eq!(bits.len(), 8);
+//! let r1cs = builder.build();
+//! assert!(r1cs.is_satisfied().is_ok());
+//! ```
+//!
+//! The verifier needs only the matrices, which can be obtained running the same builder
+//! on any assignment.
+use ark_ff::Field;
+use ark_std::collections::BTreeMap;
+use ark_std::ops::{Add, Mul, Sub};
+use ark_std::vec::Vec;
+
+use super::R1cs;
+
+mod boolean;
+mod comparison;
+mod merkle;
+mod poseidon;
+
+pub use boolean::{enforce_boolean, new_boolean, to_bits};
+pub use comparison::{enforce_less_than, is_equal, is_less_than};
+pub use merkle::{enforce_merkle_path, merkle_root};
+pub use poseidon::{poseidon_hash, PoseidonParameters, PoseidonSponge, PoseidonSpongeVar};
+
+/// A variable of the circuit.
+///
+/// The instance variable `0` is the constant one, see [`R1csBuilder::one`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Variable {
+    /// The `i`-th variable of the statement.
+    Instance(usize),
+    /// The `i`-th variable of the witness.
+    Witness(usize),
+}
+
+/// A linear combination of variables.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LinearCombination<F: Field>(BTreeMap<Variable, F>);
+
+impl<F: Field> LinearCombination<F> {
+    /// Return the empty linear combination.
+    pub fn zero() -> Self {
+        LinearCombination(BTreeMap::new())
+    }
+
+    /// Return the constant `value`, as a multiple of [`R1csBuilder::one`].
+    pub fn constant(value: F) -> Self {
+        (value, Variable::Instance(0)).into()
+    }
+
+    /// Iterate over the non-zero terms of the linear combination.
+    pub fn terms(&self) -> impl Iterator<Item = (F, Variable)> + '_ {
+        self.0
+            .iter()
+            .map(|(&variable, &coefficient)| (coefficient, variable))
+    }
+
+    fn add_term(&mut self, coefficient: F, variable: Variable) {
+        let entry = self.0.entry(variable).or_insert_with(F::zero);
+        *entry += coefficient;
+        if entry.is_zero() {
+            self.0.remove(&variable);
+        }
+    }
+}
+
+impl<F: Field> From<Variable> for LinearCombination<F> {
+    fn from(variable: Variable) -> Self {
+        (F::one(), variable).into()
+    }
+}
+
+impl<F: Field> From<(F, Variable)> for LinearCombination<F> {
+    fn from((coefficient, variable): (F, Variable)) -> Self {
+        let mut lc = LinearCombination::zero();
+        lc.add_term(coefficient, variable);
+        lc
+    }
+}
+
+impl<F: Field, T: Into<LinearCombination<F>>> Add<T> for LinearCombination<F> {
+    type Output = Self;
+
+    fn add(mut self, other: T) -> Self {
+        other
+            .into()
+            .terms()
+            .for_each(|(coefficient, variable)| self.add_term(coefficient, variable));
+        self
+    }
+}
+
+impl<F: Field, T: Into<LinearCombination<F>>> Sub<T> for LinearCombination<F> {
+    type Output = Self;
+
+    fn sub(mut self, other: T) -> Self {
+        other
+            .into()
+            .terms()
+            .for_each(|(coefficient, variable)| self.add_term(-coefficient, variable));
+        self
+    }
+}
+
+impl<F: Field> Mul<F> for LinearCombination<F> {
+    type Output = Self;
+
+    fn mul(self, scalar: F) -> Self {
+        if scalar.is_zero() {
+            return Self::zero();
+        }
+        LinearCombination(
+            self.0
+                .into_iter()
+                .map(|(variable, coefficient)| (variable, coefficient * scalar))
+                .collect(),
+        )
+    }
+}
+
+/// A builder of R1CS instances, assigning the variables as they are allocated.
+#[derive(Clone, Debug)]
+pub struct R1csBuilder<F: Field> {
+    a: Vec<LinearCombination<F>>,
+    b: Vec<LinearCombination<F>>,
+    c: Vec<LinearCombination<F>>,
+    instance: Vec<F>,
+    witness: Vec<F>,
+}
+
+impl<F: Field> Default for R1csBuilder<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Field> R1csBuilder<F> {
+    /// Create a new builder, with the constant one as only instance variable.
+    pub fn new() -> Self {
+        R1csBuilder {
+            a: Vec::new(),
+            b: Vec::new(),
+            c: Vec::new(),
+            instance: vec![F::one()],
+            witness: Vec::new(),
+        }
+    }
+
+    /// Return the variable holding the constant one.
+    pub fn one() -> Variable {
+        Variable::Instance(0)
+    }
+
+    /// Allocate a new variable of the statement, assigned to `value`.
+    pub fn new_input(&mut self, value: F) -> Variable {
+        self.instance.push(value);
+        Variable::Instance(self.instance.len() - 1)
+    }
+
+    /// Allocate a new variable of the witness, assigned to `value`.
+    pub fn new_witness(&mut self, value: F) -> Variable {
+        self.witness.push(value);
+        Variable::Witness(self.witness.len() - 1)
+    }
+
+    /// Return the value assigned to `lc`.
+    pub fn value(&self, lc: &LinearCombination<F>) -> F {
+        lc.terms()
+            .map(|(coefficient, variable)| {
+                coefficient
+                    * match variable {
+                        Variable::Instance(i) => self.instance[i],
+                        Variable::Witness(i) => self.witness[i],
+                    }
+            })
+            .sum()
+    }
+
+    /// Add the constraint \\(a \cdot b = c\\).
+    pub fn enforce(
+        &mut self,
+        a: impl Into<LinearCombination<F>>,
+        b: impl Into<LinearCombination<F>>,
+        c: impl Into<LinearCombination<F>>,
+    ) {
+        self.a.push(a.into());
+        self.b.push(b.into());
+        self.c.push(c.into());
+    }
+
+    /// Add the constraint \\(a = b\\).
+    pub fn enforce_equal(
+        &mut self,
+        a: impl Into<LinearCombination<F>>,
+        b: impl Into<LinearCombination<F>>,
+    ) {
+        self.enforce(a.into() - b.into(), Self::one(), LinearCombination::zero());
+    }
+
+    /// Allocate a new witness variable for the product of `a` and `b`.
+    pub fn mul(
+        &mut self,
+        a: impl Into<LinearCombination<F>>,
+        b: impl Into<LinearCombination<F>>,
+    ) -> Variable {
+        let (a, b) = (a.into(), b.into());
+        let product = self.new_witness(self.value(&a) * self.value(&b));
+        self.enforce(a, b, product);
+        product
+    }
+
+    /// Return the number of constraints added so far.
+    pub fn num_constraints(&self) -> usize {
+        self.a.len()
+    }
+
+    /// Return the R1CS instance.
+    ///
+    /// The statement is padded with zeros to a power of two,
+    /// as done by [`generate_relation`](super::generate_relation).
+    pub fn build(self) -> R1cs<F> {
+        let mut x = self.instance;
+        x.resize(x.len().next_power_of_two(), F::zero());
+        let num_instance_variables = x.len();
+        let into_matrix = |matrix: Vec<LinearCombination<F>>| {
+            matrix
+                .into_iter()
+                .map(|lc| {
+                    lc.terms()
+                        .map(|(coefficient, variable)| match variable {
+                            Variable::Instance(i) => (coefficient, i),
+                            Variable::Witness(i) => (coefficient, num_instance_variables + i),
+                        })
+                        .collect()
+                })
+                .collect()
+        };
+        let w = self.witness;
+        R1cs {
+            a: into_matrix(self.a),
+            b: into_matrix(self.b),
+            c: into_matrix(self.c),
+            z: [x.as_slice(), w.as_slice()].concat(),
+            w,
+            x,
+        }
+    }
+}
+
+#[test]
+fn test_r1cs_builder() {
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+
+    use crate::kzg::{CommitterKey, VerifierKey};
+    use crate::snark::Proof;
+
+    let rng = &mut ark_std::test_rng();
+    let mut builder = R1csBuilder::new();
+    let x = builder.new_input(Fr::from(3u64));
+    let y = builder.new_witness(Fr::from(4u64));
+    let xy = builder.mul(x, y);
+    let lc = LinearCombination::from(xy) + (Fr::from(2u64), x) - y;
+    assert_eq!(builder.value(&lc), Fr::from(14u64));
+    builder.enforce_equal(lc, LinearCombination::constant(Fr::from(14u64)));
+    assert_eq!(builder.num_constraints(), 2);
+
+    let r1cs = builder.build();
+    assert_eq!(r1cs.x.len(), 2);
+    assert!(r1cs.is_satisfied().is_ok());
+
+    let ck = CommitterKey::<Bls12_381>::new(8, 3, rng);
+    let vk = VerifierKey::from(&ck);
+    let proof = Proof::new_time(&r1cs, &ck);
+    assert!(proof.verify(&r1cs, &r1cs.x, &vk).is_ok());
+}
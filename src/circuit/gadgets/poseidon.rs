@@ -0,0 +1,300 @@
+//! The Poseidon hash.
+use ark_ff::{BitIteratorBE, Field};
+use ark_std::vec::Vec;
+
+use super::{LinearCombination, R1csBuilder};
+
+/// The parameters of the Poseidon permutation,
+/// laid out as the `PoseidonConfig` of [`ark-crypto-primitives`](https://docs.rs/ark-crypto-primitives/),
+/// so that the same round constants and MDS matrix can be used.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoseidonParameters<F: Field> {
+    /// The number of full rounds.
+    pub full_rounds: usize,
+    /// The number of partial rounds.
+    pub partial_rounds: usize,
+    /// The exponent of the S-box.
+    pub alpha: u64,
+    /// The MDS matrix, of size `rate + capacity`.
+    pub mds: Vec<Vec<F>>,
+    /// The round constants, one vector of size `rate + capacity` per round.
+    pub ark: Vec<Vec<F>>,
+    /// The rate of the sponge.
+    pub rate: usize,
+    /// The capacity of the sponge.
+    pub capacity: usize,
+}
+
+impl<F: Field> PoseidonParameters<F> {
+    /// Create new parameters for the Poseidon permutation.
+    ///
+    /// # Panics
+    /// If the number of round constants does not match the number of rounds,
+    /// or if the sizes of the MDS matrix or of the round constants do not match the state size.
+    pub fn new(
+        full_rounds: usize,
+        partial_rounds: usize,
+        alpha: u64,
+        mds: Vec<Vec<F>>,
+        ark: Vec<Vec<F>>,
+        rate: usize,
+        capacity: usize,
+    ) -> Self {
+        let width = rate + capacity;
+        assert_eq!(ark.len(), full_rounds + partial_rounds);
+        assert!(mds.len() == width && mds.iter().all(|row| row.len() == width));
+        assert!(ark.iter().all(|constants| constants.len() == width));
+        PoseidonParameters {
+            full_rounds,
+            partial_rounds,
+            alpha,
+            mds,
+            ark,
+            rate,
+            capacity,
+        }
+    }
+
+    /// Hash `inputs` natively, absorbing them into the sponge and squeezing one element.
+    pub fn hash(&self, inputs: &[F]) -> F {
+        let mut state = vec![F::zero(); self.rate + self.capacity];
+        for (i, chunk) in inputs.chunks(self.rate).enumerate() {
+            if i > 0 {
+                self.permute(&mut state);
+            }
+            for (element, input) in state[self.capacity..].iter_mut().zip(chunk) {
+                *element += input;
+            }
+        }
+        self.permute(&mut state);
+        state[self.capacity]
+    }
+
+    pub(crate) fn permute(&self, state: &mut [F]) {
+        for (round, constants) in self.ark.iter().enumerate() {
+            for (element, constant) in state.iter_mut().zip(constants) {
+                *element += constant;
+            }
+            if self.is_full_round(round) {
+                state
+                    .iter_mut()
+                    .for_each(|element| *element = element.pow([self.alpha]));
+            } else {
+                state[0] = state[0].pow([self.alpha]);
+            }
+            let mixed = self
+                .mds
+                .iter()
+                .map(|row| row.iter().zip(state.iter()).map(|(&m, &s)| m * s).sum())
+                .collect::<Vec<F>>();
+            state.copy_from_slice(&mixed);
+        }
+    }
+
+    /// Return true if `round` applies the S-box to the whole state.
+    fn is_full_round(&self, round: usize) -> bool {
+        let half_full_rounds = self.full_rounds / 2;
+        round < half_full_rounds || round >= half_full_rounds + self.partial_rounds
+    }
+}
+
+/// A duplex sponge over the Poseidon permutation, computed natively.
+///
+/// Elements are absorbed into the rate of the state, permuting whenever it is full,
+/// and each element is squeezed from the first position of the rate after a permutation.
+/// Absorbing `inputs` and squeezing a single element returns [`PoseidonParameters::hash`] of `inputs`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoseidonSponge<F: Field> {
+    parameters: PoseidonParameters<F>,
+    state: Vec<F>,
+    /// The number of elements absorbed into the rate since the last permutation.
+    absorbed: usize,
+}
+
+impl<F: Field> PoseidonSponge<F> {
+    /// Create a new sponge with the parameters `parameters`, starting from the all-zero state.
+    pub fn new(parameters: &PoseidonParameters<F>) -> Self {
+        PoseidonSponge {
+            parameters: parameters.clone(),
+            state: vec![F::zero(); parameters.rate + parameters.capacity],
+            absorbed: 0,
+        }
+    }
+
+    /// Absorb `elements` into the sponge.
+    pub fn absorb(&mut self, elements: &[F]) {
+        for element in elements {
+            if self.absorbed == self.parameters.rate {
+                self.parameters.permute(&mut self.state);
+                self.absorbed = 0;
+            }
+            self.state[self.parameters.capacity + self.absorbed] += element;
+            self.absorbed += 1;
+        }
+    }
+
+    /// Squeeze a single element from the sponge.
+    pub fn squeeze(&mut self) -> F {
+        self.parameters.permute(&mut self.state);
+        self.absorbed = 0;
+        self.state[self.parameters.capacity]
+    }
+}
+
+/// The gadget for [`PoseidonSponge`], absorbing and squeezing the same elements within a circuit.
+#[derive(Clone, Debug)]
+pub struct PoseidonSpongeVar<'a, F: Field> {
+    parameters: &'a PoseidonParameters<F>,
+    state: Vec<LinearCombination<F>>,
+    /// The number of elements absorbed into the rate since the last permutation.
+    absorbed: usize,
+}
+
+impl<'a, F: Field> PoseidonSpongeVar<'a, F> {
+    /// Create a new sponge gadget with the parameters `parameters`, starting from the all-zero state.
+    pub fn new(parameters: &'a PoseidonParameters<F>) -> Self {
+        PoseidonSpongeVar {
+            parameters,
+            state: vec![LinearCombination::zero(); parameters.rate + parameters.capacity],
+            absorbed: 0,
+        }
+    }
+
+    /// Absorb `elements` into the sponge.
+    pub fn absorb(&mut self, builder: &mut R1csBuilder<F>, elements: &[LinearCombination<F>]) {
+        for element in elements {
+            if self.absorbed == self.parameters.rate {
+                permute(builder, self.parameters, &mut self.state);
+                self.absorbed = 0;
+            }
+            let position = self.parameters.capacity + self.absorbed;
+            self.state[position] = self.state[position].clone() + element.clone();
+            self.absorbed += 1;
+        }
+    }
+
+    /// Squeeze a single element from the sponge.
+    pub fn squeeze(&mut self, builder: &mut R1csBuilder<F>) -> LinearCombination<F> {
+        permute(builder, self.parameters, &mut self.state);
+        self.absorbed = 0;
+        self.state[self.parameters.capacity].clone()
+    }
+}
+
+/// Return the Poseidon hash of `inputs` with parameters `parameters`,
+/// computed as in [`PoseidonParameters::hash`].
+///
+/// Each S-box costs a multiplication for each bit of the exponent after the first,
+/// and one more for each set bit after the first; the round constants and the MDS matrix are free.
+pub fn poseidon_hash<F: Field>(
+    builder: &mut R1csBuilder<F>,
+    parameters: &PoseidonParameters<F>,
+    inputs: &[LinearCombination<F>],
+) -> LinearCombination<F> {
+    let mut state = vec![LinearCombination::zero(); parameters.rate + parameters.capacity];
+    for (i, chunk) in inputs.chunks(parameters.rate).enumerate() {
+        if i > 0 {
+            permute(builder, parameters, &mut state);
+        }
+        for (element, input) in state[parameters.capacity..].iter_mut().zip(chunk) {
+            *element = element.clone() + input.clone();
+        }
+    }
+    permute(builder, parameters, &mut state);
+    state.swap_remove(parameters.capacity)
+}
+
+fn permute<F: Field>(
+    builder: &mut R1csBuilder<F>,
+    parameters: &PoseidonParameters<F>,
+    state: &mut [LinearCombination<F>],
+) {
+    for (round, constants) in parameters.ark.iter().enumerate() {
+        for (element, &constant) in state.iter_mut().zip(constants) {
+            *element = element.clone() + LinearCombination::constant(constant);
+        }
+        let sboxed = if parameters.is_full_round(round) {
+            state.len()
+        } else {
+            1
+        };
+        for element in &mut state[..sboxed] {
+            *element = sbox(builder, element.clone(), parameters.alpha);
+        }
+        let mixed = parameters
+            .mds
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .zip(state.iter())
+                    .fold(LinearCombination::zero(), |acc, (&m, s)| {
+                        acc + s.clone() * m
+                    })
+            })
+            .collect::<Vec<_>>();
+        state.clone_from_slice(&mixed);
+    }
+}
+
+/// Compute `element` to the power `alpha` by square-and-multiply.
+fn sbox<F: Field>(
+    builder: &mut R1csBuilder<F>,
+    element: LinearCombination<F>,
+    alpha: u64,
+) -> LinearCombination<F> {
+    let mut bits = BitIteratorBE::without_leading_zeros([alpha]);
+    if bits.next().is_none() {
+        return LinearCombination::constant(F::one());
+    }
+    let mut power = element.clone();
+    for bit in bits {
+        power = builder.mul(power.clone(), power).into();
+        if bit {
+            power = builder.mul(power, element.clone()).into();
+        }
+    }
+    power
+}
+
+#[test]
+fn test_poseidon_hash() {
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::Fr;
+
+    let rng = &mut ark_std::test_rng();
+    let width = 3;
+    let mds = (0..width)
+        .map(|_| (0..width).map(|_| Fr::rand(rng)).collect())
+        .collect();
+    let ark = (0..8 + 31)
+        .map(|_| (0..width).map(|_| Fr::rand(rng)).collect())
+        .collect();
+    let parameters = PoseidonParameters::new(8, 31, 17, mds, ark, 2, 1);
+
+    for len in [0, 1, 2, 5] {
+        let inputs = (0..len).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+        let mut builder = R1csBuilder::new();
+        let variables = inputs
+            .iter()
+            .map(|&input| builder.new_witness(input).into())
+            .collect::<Vec<LinearCombination<Fr>>>();
+        let digest = poseidon_hash(&mut builder, &parameters, &variables);
+        assert_eq!(builder.value(&digest), parameters.hash(&inputs));
+        assert!(builder.build().is_satisfied().is_ok());
+
+        // the sponges absorbing the inputs squeeze the hash, natively and within the circuit.
+        let mut sponge = PoseidonSponge::new(&parameters);
+        sponge.absorb(&inputs);
+        assert_eq!(sponge.squeeze(), parameters.hash(&inputs));
+        let mut builder = R1csBuilder::new();
+        let variables = inputs
+            .iter()
+            .map(|&input| builder.new_witness(input).into())
+            .collect::<Vec<LinearCombination<Fr>>>();
+        let mut sponge_var = PoseidonSpongeVar::new(&parameters);
+        sponge_var.absorb(&mut builder, &variables);
+        let squeezed = sponge_var.squeeze(&mut builder);
+        assert_eq!(builder.value(&squeezed), parameters.hash(&inputs));
+        assert!(builder.build().is_satisfied().is_ok());
+    }
+}
@@ -0,0 +1,137 @@
+//! Generation of random, satisfiable R1CS instances with a controllable shape,
+//! for benchmarking the provers on realistic circuits.
+//!
+//! ```
+//! use ark_gemini::circuit::generator::R1csGenerator;
+//! use ark_test_curves::bls12_381::Fr;
+//!
+//! let rng = &mut ark_std::test_rng();
+//! let r1cs = R1csGenerator::new(1 << 10, 1 << 9)
+//!     .with_public_inputs(3)
+//!     .with_density(4)
+//!     .generate::<Fr>(rng);
+//! assert!(r1cs.is_satisfied().is_ok());
+//! ```
+use ark_ff::Field;
+use ark_std::collections::BTreeSet;
+use ark_std::ops::Range;
+use ark_std::rand::{Rng, RngCore};
+use ark_std::vec::Vec;
+
+use super::R1cs;
+
+/// A generator of random R1CS instances.
+///
+/// Each row of \\(A\\) and \\(B\\) has a fixed number of non-zero entries at random columns,
+/// and the rows of \\(C\\) have the same number of entries,
+/// one of which on the constant column, chosen so that the random assignment satisfies the constraints.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct R1csGenerator {
+    num_constraints: usize,
+    num_variables: usize,
+    num_public_inputs: usize,
+    density: usize,
+}
+
+impl R1csGenerator {
+    /// Create a new generator for instances with `num_constraints` constraints
+    /// and `num_variables` variables (including the statement),
+    /// without public inputs and with two non-zero entries per row.
+    pub fn new(num_constraints: usize, num_variables: usize) -> Self {
+        R1csGenerator {
+            num_constraints,
+            num_variables,
+            num_public_inputs: 0,
+            density: 2,
+        }
+    }
+
+    /// Use `num_public_inputs` public inputs.
+    /// The statement consists of the constant, the public inputs, and zeros up to a power of two.
+    pub fn with_public_inputs(mut self, num_public_inputs: usize) -> Self {
+        self.num_public_inputs = num_public_inputs;
+        self
+    }
+
+    /// Use `density` non-zero entries per row of each matrix.
+    pub fn with_density(mut self, density: usize) -> Self {
+        assert!(density > 0, "rows must have at least one entry");
+        self.density = density;
+        self
+    }
+
+    /// Return the number of instance variables, including the constant and the padding.
+    pub fn num_instance_variables(&self) -> usize {
+        (1 + self.num_public_inputs).next_power_of_two()
+    }
+
+    /// Generate a random instance, together with a satisfying assignment.
+    ///
+    /// # Panics
+    /// If the statement does not fit in the variables,
+    /// or the rows have more entries than the number of variables.
+    pub fn generate<F: Field>(&self, rng: &mut impl RngCore) -> R1cs<F> {
+        let num_instance_variables = self.num_instance_variables();
+        assert!(
+            num_instance_variables <= self.num_variables,
+            "the statement does not fit in the variables"
+        );
+        assert!(self.density < self.num_variables, "rows are too dense");
+
+        let mut x = vec![F::one()];
+        x.extend((0..self.num_public_inputs).map(|_| F::rand(rng)));
+        x.resize(num_instance_variables, F::zero());
+        let w = (num_instance_variables..self.num_variables)
+            .map(|_| F::rand(rng))
+            .collect::<Vec<_>>();
+        let z = [x.as_slice(), w.as_slice()].concat();
+
+        let mut random_row = |columns: Range<usize>, len: usize| {
+            let mut indices = BTreeSet::new();
+            while indices.len() < len {
+                indices.insert(rng.gen_range(columns.clone()));
+            }
+            indices
+                .into_iter()
+                .map(|column| (F::rand(rng), column))
+                .collect::<Vec<_>>()
+        };
+        let evaluate = |row: &[(F, usize)]| row.iter().map(|&(e, j)| z[j] * e).sum::<F>();
+
+        let (mut a, mut b, mut c) = (Vec::new(), Vec::new(), Vec::new());
+        for _ in 0..self.num_constraints {
+            let a_row = random_row(0..self.num_variables, self.density);
+            let b_row = random_row(0..self.num_variables, self.density);
+            let mut c_row = random_row(1..self.num_variables, self.density - 1);
+            // fix the coefficient of the constant so that the constraint is satisfied.
+            let constant = evaluate(&a_row) * evaluate(&b_row) - evaluate(&c_row);
+            c_row.insert(0, (constant, 0));
+            a.push(a_row);
+            b.push(b_row);
+            c.push(c_row);
+        }
+
+        R1cs { a, b, c, z, w, x }
+    }
+}
+
+#[test]
+fn test_r1cs_generator() {
+    use ark_test_curves::bls12_381::Fr;
+
+    let rng = &mut ark_std::test_rng();
+    let generator = R1csGenerator::new(64, 16)
+        .with_public_inputs(2)
+        .with_density(3);
+    let r1cs = generator.generate::<Fr>(rng);
+
+    let shape = r1cs.shape();
+    assert_eq!(shape.num_constraints, 64);
+    assert_eq!(shape.num_variables, 16);
+    assert_eq!(shape.num_instance_variables, 4);
+    assert_eq!(r1cs.x[3], Fr::from(0u64));
+    assert!([&r1cs.a, &r1cs.b, &r1cs.c]
+        .iter()
+        .all(|matrix| matrix.iter().all(|row| row.len() == 3)));
+    assert_eq!(r1cs.is_satisfied(), Ok(()));
+}
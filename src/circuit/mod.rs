@@ -0,0 +1,683 @@
+//! R1CS instances, and their representations for the provers.
+//!
+//! An [`R1cs`] holds the matrices \\(A, B, C\\) as [`Matrix`], and can be converted to a [`SparseMatrix`]
+//! in compressed sparse row format.
+//! The random and dummy circuits were blatantly stolen from arkworks test suite.
+use ark_ff::{Field, PrimeField};
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+use ark_relations::{
+    lc,
+    r1cs::{
+        ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, OptimizationGoal,
+        SynthesisError,
+    },
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::collections::BTreeSet;
+use ark_std::fmt;
+use ark_std::rand::RngCore;
+use ark_std::vec::Vec;
+
+use crate::iterable::dummy::{RepeatMatrixStreamer, RepeatStreamer};
+use crate::iterable::Iterable;
+use crate::misc::MatrixElement;
+
+pub mod circom;
+mod compact;
+mod data_parallel;
+pub mod gadgets;
+pub mod generator;
+pub use data_parallel::DataParallelR1cs;
+mod sparse;
+pub use sparse::SparseMatrix;
+#[cfg(feature = "zkinterface")]
+pub mod zkinterface;
+
+#[derive(Copy, Clone)]
+pub struct Circuit<F: Field> {
+    a: Option<F>,
+    b: Option<F>,
+    num_constraints: usize,
+    num_variables: usize,
+}
+
+pub struct R1csStream<SM, SZ, SW> {
+    pub a_colmaj: SM,
+    pub b_colmaj: SM,
+    pub c_colmaj: SM,
+    pub a_rowmaj: SM,
+    pub b_rowmaj: SM,
+    pub c_rowmaj: SM,
+    pub z: SZ,
+    pub witness: SW,
+    pub z_a: SZ,
+    pub z_b: SZ,
+    pub z_c: SZ,
+    pub nonzero: usize,
+    pub joint_len: usize,
+}
+
+/// Represents a matrix.
+pub type Matrix<F> = Vec<Vec<(F, usize)>>;
+
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct R1cs<F: Field> {
+    pub a: Matrix<F>,
+    pub b: Matrix<F>,
+    pub c: Matrix<F>,
+    pub z: Vec<F>,
+    pub w: Vec<F>,
+    pub x: Vec<F>,
+}
+
+/// Wipe the assignment, leaving the statement and the matrices in place.
+///
+/// Wrap the instance in [`zeroize::Zeroizing`] to wipe the assignment on drop.
+#[cfg(feature = "zeroize")]
+impl<F: Field> zeroize::Zeroize for R1cs<F> {
+    fn zeroize(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.z);
+        zeroize::Zeroize::zeroize(&mut self.w);
+    }
+}
+
+/// A constraint violated by the assignment of an [`R1cs`] instance, see [`R1cs::is_satisfied`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnsatisfiedConstraint<F: Field> {
+    /// The index of the constraint.
+    pub index: usize,
+    /// The value \((Az)_i\).
+    pub az: F,
+    /// The value \((Bz)_i\).
+    pub bz: F,
+    /// The value \((Cz)_i\).
+    pub cz: F,
+    /// The variables involved in the constraint, as pairs of index within \(z\) and assigned value,
+    /// sorted by index.
+    pub variables: Vec<(usize, F)>,
+}
+
+impl<F: Field> fmt::Display for UnsatisfiedConstraint<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "constraint {} unsatisfied: (Az)_i = {}, (Bz)_i = {}, (Cz)_i = {}; variables:",
+            self.index, self.az, self.bz, self.cz
+        )?;
+        for (index, value) in &self.variables {
+            write!(f, " z[{}] = {}", index, value)?;
+        }
+        Ok(())
+    }
+}
+
+impl<F: Field> R1cs<F> {
+    /// Return the circuit description with matrices `a`, `b`, `c`,
+    /// without any assignment for the statement and the witness.
+    pub fn from_matrices(a: Matrix<F>, b: Matrix<F>, c: Matrix<F>) -> Self {
+        R1cs {
+            a,
+            b,
+            c,
+            z: Vec::new(),
+            w: Vec::new(),
+            x: Vec::new(),
+        }
+    }
+
+    /// Check that the assignment `z` satisfies the constraints \((Az)_i \cdot (Bz)_i = (Cz)_i\).
+    ///
+    /// Return the first violated constraint, together with the evaluations of the matrices
+    /// and the variables it involves, to debug failing witnesses before proving.
+    ///
+    /// # Panics
+    /// If the matrices refer to variables beyond the length of `z`.
+    pub fn is_satisfied(&self) -> Result<(), UnsatisfiedConstraint<F>> {
+        let row_product =
+            |row: &[(F, usize)]| row.iter().map(|&(coeff, j)| self.z[j] * coeff).sum::<F>();
+        for (index, ((a, b), c)) in self.a.iter().zip(&self.b).zip(&self.c).enumerate() {
+            let (az, bz, cz) = (row_product(a), row_product(b), row_product(c));
+            if az * bz != cz {
+                let indices = a
+                    .iter()
+                    .chain(b)
+                    .chain(c)
+                    .map(|&(_, j)| j)
+                    .collect::<BTreeSet<_>>();
+                let variables = indices.into_iter().map(|j| (j, self.z[j])).collect();
+                return Err(UnsatisfiedConstraint {
+                    index,
+                    az,
+                    bz,
+                    cz,
+                    variables,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Return the matrices `a`, `b`, `c` in compressed sparse row format,
+    /// with as many columns as the length of the assignment `z`.
+    pub fn sparse_matrices(&self) -> [SparseMatrix<F>; 3] {
+        [&self.a, &self.b, &self.c].map(|matrix| SparseMatrix::from_rows(matrix, self.z.len()))
+    }
+
+    /// Return the shape of the instance.
+    ///
+    /// Without an assignment, the number of variables is the number of columns the matrices refer to.
+    pub fn shape(&self) -> R1csShape {
+        let num_columns = [&self.a, &self.b, &self.c]
+            .iter()
+            .flat_map(|matrix| matrix.iter().flatten())
+            .map(|&(_, column)| column + 1)
+            .max()
+            .unwrap_or(0);
+        R1csShape {
+            num_constraints: self.a.len(),
+            num_variables: usize::max(self.z.len(), num_columns),
+            num_instance_variables: self.x.len(),
+        }
+    }
+
+    /// Return the shape of the instance after [`R1cs::pad`].
+    pub fn padded_shape(&self) -> R1csShape {
+        let shape = self.shape();
+        let num_instance_variables = if shape.num_instance_variables == 0 {
+            0
+        } else {
+            shape.num_instance_variables.next_power_of_two()
+        };
+        let num_variables =
+            shape.num_variables + num_instance_variables - shape.num_instance_variables;
+        R1csShape {
+            num_constraints: shape.num_constraints.next_power_of_two(),
+            num_variables: num_variables.next_power_of_two(),
+            num_instance_variables,
+        }
+    }
+
+    /// Pad the instance to a power-of-two number of constraints, variables, and instance variables,
+    /// as required by the sumcheck and the tensorcheck.
+    ///
+    /// The statement is padded with zeros, shifting the columns of the witness;
+    /// the constraints are padded with dummy constraints \(0 \cdot 0 = 0\),
+    /// and the variables with unconstrained witness variables assigned to zero.
+    /// The padded instance is satisfiable if and only if the original one is.
+    pub fn pad(&mut self) -> R1csShape {
+        let shape = self.shape();
+        let padded_shape = self.padded_shape();
+
+        let instance_padding = padded_shape.num_instance_variables - shape.num_instance_variables;
+        if instance_padding > 0 {
+            let num_instance_variables = shape.num_instance_variables;
+            [&mut self.a, &mut self.b, &mut self.c]
+                .into_iter()
+                .flat_map(|matrix| matrix.iter_mut().flatten())
+                .filter(|(_, column)| *column >= num_instance_variables)
+                .for_each(|(_, column)| *column += instance_padding);
+            self.x
+                .resize(padded_shape.num_instance_variables, F::zero());
+        }
+        for matrix in [&mut self.a, &mut self.b, &mut self.c] {
+            matrix.resize(padded_shape.num_constraints, Vec::new());
+        }
+        if !self.z.is_empty() {
+            let witness_len = padded_shape.num_variables - padded_shape.num_instance_variables;
+            self.w.resize(witness_len, F::zero());
+            self.z = [self.x.as_slice(), self.w.as_slice()].concat();
+        }
+        padded_shape
+    }
+}
+
+/// The shape of an R1CS instance, see [`R1cs::shape`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct R1csShape {
+    /// The number of constraints, i.e. the number of rows of the matrices.
+    pub num_constraints: usize,
+    /// The number of variables, including the statement.
+    pub num_variables: usize,
+    /// The number of variables of the statement, including the constant.
+    pub num_instance_variables: usize,
+}
+
+impl R1csShape {
+    /// Return true if all dimensions are powers of two, as produced by [`R1cs::pad`].
+    pub fn is_padded(&self) -> bool {
+        self.num_constraints.is_power_of_two()
+            && self.num_variables.is_power_of_two()
+            && (self.num_instance_variables == 0 || self.num_instance_variables.is_power_of_two())
+    }
+}
+
+impl<ConstraintF: Field> ConstraintSynthesizer<ConstraintF> for Circuit<ConstraintF> {
+    fn generate_constraints(
+        self,
+        cs: ConstraintSystemRef<ConstraintF>,
+    ) -> Result<(), SynthesisError> {
+        let a = cs.new_witness_variable(|| self.a.ok_or(SynthesisError::AssignmentMissing))?;
+        let b = cs.new_witness_variable(|| self.b.ok_or(SynthesisError::AssignmentMissing))?;
+        let c = cs.new_input_variable(|| {
+            let mut a = self.a.ok_or(SynthesisError::AssignmentMissing)?;
+            let b = self.b.ok_or(SynthesisError::AssignmentMissing)?;
+
+            a.mul_assign(&b);
+            Ok(a)
+        })?;
+        let d = cs.new_input_variable(|| {
+            let mut a = self.a.ok_or(SynthesisError::AssignmentMissing)?;
+            let b = self.b.ok_or(SynthesisError::AssignmentMissing)?;
+
+            a.mul_assign(&b);
+            a.mul_assign(&b);
+            Ok(a)
+        })?;
+
+        for _ in 0..(self.num_variables - 4) {
+            let _ = cs.new_witness_variable(|| self.a.ok_or(SynthesisError::AssignmentMissing))?;
+        }
+
+        for _ in 0..(self.num_constraints - 1) {
+            cs.enforce_constraint(lc!() + a, lc!() + b, lc!() + c)?;
+        }
+        cs.enforce_constraint(lc!() + c, lc!() + b, lc!() + d)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+/// Define a constraint system that would trigger outlining.
+struct OutlineTestCircuit;
+
+impl<F: Field> ConstraintSynthesizer<F> for OutlineTestCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        // This program checks if the input elements are between 0 and 9.
+        //
+        // Note that this constraint system is neither the most intuitive way nor
+        // the most efficient way for such a task. It is for testing purposes,
+        // as we want to trigger the outlining.
+        //
+        let mut inputs = Vec::new();
+        for i in 0..5 {
+            inputs.push(cs.new_input_variable(|| Ok(F::from(i as u128)))?);
+        }
+
+        for (i, &input) in inputs.iter().enumerate().take(5) {
+            let mut total_count_for_this_input = cs.new_lc(lc!()).unwrap();
+
+            for bucket in 0..10 {
+                let count_increment_for_this_bucket =
+                    cs.new_witness_variable(|| Ok(F::from(i == bucket)))?;
+
+                total_count_for_this_input = cs
+                    .new_lc(
+                        lc!()
+                            + (F::one(), total_count_for_this_input)
+                            + (F::one(), count_increment_for_this_bucket),
+                    )
+                    .unwrap();
+
+                // Only when `input[i]` equals `bucket` can `count_increment_for_this_bucket` be nonzero.
+                //
+                // A malicious prover can make `count_increment_for_this_bucket` neither 0 nor 1.
+                // But the constraint on `total_count_for_this_input` will reject such case.
+                //
+                // At a high level, only one of the `count_increment_for_this_bucket` among all the buckets
+                // could be nonzero, which equals `total_count_for_this_input`. Thus, by checking whether
+                // `total_count_for_this_input` is 1, we know this input number is in the range.
+                //
+                cs.enforce_constraint(
+                    lc!() + (F::one(), input)
+                        - (F::from(bucket as u128), ark_relations::r1cs::Variable::One),
+                    lc!() + (F::one(), count_increment_for_this_bucket),
+                    lc!(),
+                )?;
+            }
+
+            // Enforce `total_count_for_this_input` to be one.
+            cs.enforce_constraint(
+                lc!(),
+                lc!(),
+                lc!() + (F::one(), total_count_for_this_input)
+                    - (F::one(), ark_relations::r1cs::Variable::One),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn generate_relation<F: PrimeField, C: ConstraintSynthesizer<F>>(circuit: C) -> R1cs<F> {
+    try_generate_relation(circuit).unwrap()
+}
+
+/// Synthesize `circuit` and return the R1CS relation,
+/// propagating any error raised during constraint generation.
+pub fn try_generate_relation<F: PrimeField, C: ConstraintSynthesizer<F>>(
+    circuit: C,
+) -> Result<R1cs<F>, SynthesisError> {
+    let pcs = ConstraintSystem::new_ref();
+    pcs.set_optimization_goal(OptimizationGoal::Weight);
+    // pcs.set_optimization_goal(OptimizationGoal::Constraints);
+    pcs.set_mode(ark_relations::r1cs::SynthesisMode::Prove {
+        construct_matrices: true,
+    });
+    circuit.generate_constraints(pcs.clone())?;
+    R1cs::from_constraint_system(pcs)
+}
+
+impl<F: PrimeField> R1cs<F> {
+    /// Return the R1CS relation of the constraint system `cs`, together with the assignment of its variables.
+    ///
+    /// The constraints of `cs` can be generated by any arkworks gadget,
+    /// but `cs` must be in proving mode, constructing the matrices, and not yet finalized:
+    /// the statement is padded to a power of two, and the linear combinations are inlined here.
+    pub fn from_constraint_system(cs: ConstraintSystemRef<F>) -> Result<Self, SynthesisError> {
+        if cs.is_in_setup_mode() {
+            return Err(SynthesisError::AssignmentMissing);
+        }
+        pad_input_for_indexer_and_prover(cs.clone());
+        cs.finalize();
+        // make_matrices_square_for_prover(cs.clone());
+        let cs = cs.borrow().ok_or(SynthesisError::MissingCS)?;
+        let statement = cs.instance_assignment.as_slice();
+        let witness = cs.witness_assignment.as_slice();
+        let matrices = cs.to_matrices().ok_or(SynthesisError::MissingCS)?;
+        Ok(R1cs {
+            a: matrices.a,
+            b: matrices.b,
+            c: matrices.c,
+            z: statement.iter().chain(witness).cloned().collect(),
+            w: witness.to_vec(),
+            x: statement.to_vec(),
+        })
+    }
+}
+
+/// Return a matrix stream, col major.
+/// XXX. can this be done without the hint for the number of columns?
+pub(crate) fn matrix_into_colmaj<F: Field>(
+    a: &[Vec<(F, usize)>],
+    col_number: usize,
+) -> Vec<MatrixElement<F>> {
+    use ark_std::cmp::Ordering;
+    let mut a_row_flat = Vec::new();
+
+    for column in (0..col_number).rev() {
+        for (row, elements) in a.iter().enumerate().rev() {
+            for &(val, col) in elements.iter().rev() {
+                match col.cmp(&column) {
+                    Ordering::Equal => {
+                        a_row_flat.push(MatrixElement::Element((val, row)));
+                    }
+                    Ordering::Less => {
+                        break;
+                    }
+                    Ordering::Greater => {
+                        continue;
+                    }
+                }
+            }
+        }
+        a_row_flat.push(MatrixElement::EOL);
+    }
+    a_row_flat
+}
+
+// Return a matrix stream, row major.
+pub fn matrix_into_rowmaj<F: Field>(a: &[Vec<(F, usize)>]) -> Vec<MatrixElement<F>> {
+    let mut a_row_flat = Vec::new();
+
+    for (_row, elements) in a.iter().enumerate().rev() {
+        for &(val, col) in elements.iter().rev() {
+            a_row_flat.push(MatrixElement::Element((val, col)));
+        }
+        a_row_flat.push(MatrixElement::EOL);
+    }
+    a_row_flat
+}
+
+pub fn repeat_r1cs<'a, F: PrimeField>(
+    r1cs: &'a R1cs<F>,
+    repeat: usize,
+    [z_a, z_b, z_c]: [&'a [F]; 3],
+) -> R1csStream<
+    impl Iterable<Item = MatrixElement<F>>,
+    impl Iterable<Item = &'a F> + 'a,
+    impl Iterable<Item = &'a F> + 'a,
+> {
+    // XXX. change this
+    let nonzero = 0;
+    let block_size = 0;
+    let joint_len = 0;
+
+    let a_colm = RepeatMatrixStreamer::new(matrix_into_rowmaj(&r1cs.a), repeat, block_size);
+    let b_colm = RepeatMatrixStreamer::new(matrix_into_rowmaj(&r1cs.b), repeat, block_size);
+    let c_colm = RepeatMatrixStreamer::new(matrix_into_rowmaj(&r1cs.c), repeat, block_size);
+
+    let col_number = a_colm.len();
+    let a_rowm =
+        RepeatMatrixStreamer::new(matrix_into_colmaj(&r1cs.a, col_number), repeat, block_size);
+    let b_rowm =
+        RepeatMatrixStreamer::new(matrix_into_colmaj(&r1cs.b, col_number), repeat, block_size);
+    let c_rowm =
+        RepeatMatrixStreamer::new(matrix_into_colmaj(&r1cs.c, col_number), repeat, block_size);
+
+    let z = RepeatStreamer::new(&r1cs.z, repeat);
+    let witness = RepeatStreamer::new(&r1cs.w, repeat);
+    let z_a = RepeatStreamer::new(z_a, repeat);
+    let z_b = RepeatStreamer::new(z_b, repeat);
+    let z_c = RepeatStreamer::new(z_c, repeat);
+
+    R1csStream {
+        a_rowmaj: a_colm,
+        b_rowmaj: b_colm,
+        a_colmaj: a_rowm,
+        b_colmaj: b_rowm,
+        c_colmaj: c_rowm,
+        c_rowmaj: c_colm,
+        z,
+        witness,
+        z_a,
+        z_b,
+        z_c,
+        nonzero,
+        joint_len,
+    }
+}
+
+pub(crate) fn pad_input_for_indexer_and_prover<F: PrimeField>(cs: ConstraintSystemRef<F>) {
+    let formatted_input_size = cs.num_instance_variables();
+
+    let domain_x = GeneralEvaluationDomain::<F>::new(formatted_input_size);
+    assert!(domain_x.is_some());
+
+    let padded_size = domain_x.unwrap().size();
+
+    if padded_size > formatted_input_size {
+        for _ in 0..(padded_size - formatted_input_size) {
+            cs.new_input_variable(|| Ok(F::zero())).unwrap();
+        }
+    }
+}
+
+pub fn random_circuit<F: Field>(
+    rng: &mut impl RngCore,
+    num_constraints: usize,
+    num_variables: usize,
+) -> Circuit<F> {
+    let a = F::rand(rng);
+    let b = F::rand(rng);
+    let mut c = a;
+    c.mul_assign(&b);
+    let mut d = c;
+    d.mul_assign(&b);
+
+    Circuit {
+        a: Some(a),
+        b: Some(b),
+        num_constraints,
+        num_variables,
+    }
+}
+
+#[test]
+fn test_repeated_r1cs() {
+    use ark_test_curves::bls12_381::Fr;
+
+    use crate::misc::evaluate_be;
+    use crate::misc::ip;
+    use crate::misc::product_matrix_vector;
+    use ark_std::{One, Zero};
+
+    let rng = &mut ark_std::test_rng();
+    let num_constraints = 1 << 4;
+    let num_variables = 1 << 4;
+    let repeat = 10;
+    let circuit = random_circuit(rng, num_constraints, num_variables);
+    let r1cs = generate_relation(circuit);
+    let za = product_matrix_vector(&r1cs.a, &r1cs.z);
+    let zb = product_matrix_vector(&r1cs.b, &r1cs.z);
+    let zc = product_matrix_vector(&r1cs.c, &r1cs.z);
+    let repeated_r1cs = repeat_r1cs(&r1cs, repeat, [&za, &zb, &zc]);
+
+    // test that <z_a, z_b> = z_c(1)
+    assert_eq!(ip(&za, &zb), evaluate_be(zc.iter(), &Fr::one()));
+    assert_eq!(
+        repeated_r1cs
+            .z_a
+            .iter()
+            .zip(repeated_r1cs.z_b.iter())
+            .map(|(x, y)| *x * y)
+            .sum::<Fr>(),
+        evaluate_be(repeated_r1cs.z_c.iter(), &Fr::one())
+    );
+
+    // test that [Az](1) = z_a(1)
+    let expected = za.iter().sum::<Fr>() * Fr::from(repeat as u64);
+    let mut got = Fr::zero();
+    for matrix_element in repeated_r1cs.a_rowmaj.iter() {
+        match matrix_element {
+            MatrixElement::EOL => { /* do nothing here, all is being added up */ }
+            MatrixElement::Element((e, i)) => got += r1cs.z[i] * e,
+        }
+    }
+
+    assert_eq!(got, expected)
+}
+
+pub fn dummy_r1cs<F: Field>(rng: &mut impl RngCore, n: usize) -> R1cs<F> {
+    let e = F::rand(rng);
+    let inv_e = e.inverse().expect("Buy a lottery ticket and retry");
+    let z = vec![e; n];
+    let w = vec![e; n - 1];
+    let x = vec![e];
+
+    let diagonal_matrix = (0..n).map(|i| vec![(inv_e, i)]).collect::<Vec<_>>();
+    R1cs {
+        a: diagonal_matrix.clone(),
+        b: diagonal_matrix.clone(),
+        c: diagonal_matrix,
+        z,
+        w,
+        x,
+    }
+}
+
+#[test]
+fn test_r1cs_satisfiability() {
+    use ark_std::One;
+    use ark_test_curves::bls12_381::Fr;
+
+    let rng = &mut ark_std::test_rng();
+    let mut r1cs = generate_relation::<Fr, _>(random_circuit(rng, 8, 8));
+    assert_eq!(r1cs.is_satisfied(), Ok(()));
+
+    // corrupt the public input d = ab^2, involved only in the last constraint.
+    let d = 2;
+    r1cs.z[d] += Fr::one();
+    let unsatisfied = r1cs.is_satisfied().unwrap_err();
+    assert_eq!(unsatisfied.index, r1cs.a.len() - 1);
+    assert_ne!(unsatisfied.az * unsatisfied.bz, unsatisfied.cz);
+    assert!(unsatisfied.variables.contains(&(d, r1cs.z[d])));
+}
+
+#[test]
+fn test_r1cs_from_constraint_system() {
+    use ark_test_curves::bls12_381::Fr;
+
+    // a circuit whose linear combinations are outlined by the constraint system.
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    cs.set_optimization_goal(OptimizationGoal::Weight);
+    OutlineTestCircuit.generate_constraints(cs.clone()).unwrap();
+    assert!(cs.is_satisfied().unwrap());
+    let num_constraints = cs.num_constraints();
+
+    let r1cs = R1cs::from_constraint_system(cs).unwrap();
+    assert!(r1cs.a.len() >= num_constraints);
+    assert!(r1cs.x.len().is_power_of_two());
+    assert_eq!(r1cs.z, [r1cs.x.clone(), r1cs.w.clone()].concat());
+    assert_eq!(r1cs.is_satisfied(), Ok(()));
+
+    // without assignment, there is no relation.
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    cs.set_mode(ark_relations::r1cs::SynthesisMode::Setup);
+    OutlineTestCircuit.generate_constraints(cs.clone()).unwrap();
+    assert!(R1cs::from_constraint_system(cs).is_err());
+}
+
+#[test]
+fn test_r1cs_padding() {
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+
+    use crate::kzg::{CommitterKey, VerifierKey};
+    use crate::snark::Proof;
+
+    let rng = &mut ark_std::test_rng();
+    let mut r1cs = generate_relation::<Fr, _>(random_circuit(rng, 5, 7));
+    let shape = r1cs.shape();
+    assert!(!shape.is_padded());
+    assert_eq!(shape.num_constraints, 5);
+
+    let padded_shape = r1cs.padded_shape();
+    assert_eq!(r1cs.pad(), padded_shape);
+    assert_eq!(r1cs.shape(), padded_shape);
+    assert!(padded_shape.is_padded());
+    assert_eq!(padded_shape.num_constraints, 8);
+    assert_eq!(r1cs.z.len(), padded_shape.num_variables);
+    assert_eq!(r1cs.is_satisfied(), Ok(()));
+
+    // padding is idempotent.
+    let mut padded = r1cs.clone();
+    padded.pad();
+    assert_eq!(padded, r1cs);
+
+    let ck = CommitterKey::<Bls12_381>::new(
+        8 * (padded_shape.num_constraints + padded_shape.num_variables),
+        5,
+        rng,
+    );
+    let vk = VerifierKey::from(&ck);
+    let proof = Proof::new_time(&r1cs, &ck);
+    assert!(proof.verify(&r1cs, &r1cs.x, &vk).is_ok());
+}
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn test_r1cs_zeroize() {
+    use ark_test_curves::bls12_381::Fr;
+    use zeroize::{Zeroize, Zeroizing};
+
+    let rng = &mut ark_std::test_rng();
+    let mut r1cs = generate_relation::<Fr, _>(random_circuit(rng, 8, 8));
+    let (x, a) = (r1cs.x.clone(), r1cs.a.clone());
+    r1cs.zeroize();
+    assert!(r1cs.z.is_empty() && r1cs.w.is_empty());
+    assert_eq!((r1cs.x, r1cs.a), (x, a));
+
+    // instances wiped on drop are used through their reference.
+    let r1cs = Zeroizing::new(generate_relation::<Fr, _>(random_circuit(rng, 8, 8)));
+    assert_eq!(r1cs.is_satisfied(), Ok(()));
+}
@@ -0,0 +1,196 @@
+//! Sparse matrices in compressed sparse row (CSR) format.
+use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::vec::Vec;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use super::Matrix;
+
+/// A sparse matrix in compressed sparse row (CSR) format.
+///
+/// The non-zero entries are stored row after row in two contiguous vectors of values and column indices,
+/// and the entries of the \\(i\\)-th row lie within `row_offsets[i]..row_offsets[i+1]`.
+/// Compared to [`Matrix`], the representation saves one allocation per row,
+/// and the products with vectors run over contiguous memory.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SparseMatrix<F: Field> {
+    num_columns: usize,
+    row_offsets: Vec<usize>,
+    columns: Vec<usize>,
+    values: Vec<F>,
+}
+
+impl<F: Field> SparseMatrix<F> {
+    /// Return the CSR representation of the row-major matrix `rows`, with `num_columns` columns.
+    ///
+    /// # Panics
+    /// If any column index in `rows` is not smaller than `num_columns`.
+    pub fn from_rows(rows: &[Vec<(F, usize)>], num_columns: usize) -> Self {
+        let nnz = rows.iter().map(|row| row.len()).sum();
+        let mut row_offsets = Vec::with_capacity(rows.len() + 1);
+        let mut columns = Vec::with_capacity(nnz);
+        let mut values = Vec::with_capacity(nnz);
+        row_offsets.push(0);
+        for row in rows {
+            for &(value, column) in row {
+                assert!(column < num_columns, "column index out of bounds");
+                columns.push(column);
+                values.push(value);
+            }
+            row_offsets.push(columns.len());
+        }
+        SparseMatrix {
+            num_columns,
+            row_offsets,
+            columns,
+            values,
+        }
+    }
+
+    /// Return the number of rows.
+    pub fn num_rows(&self) -> usize {
+        self.row_offsets.len() - 1
+    }
+
+    /// Return the number of columns.
+    pub fn num_columns(&self) -> usize {
+        self.num_columns
+    }
+
+    /// Return the number of non-zero entries.
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Return an iterator over the entries `(value, column)` of the `i`-th row.
+    pub fn row(&self, i: usize) -> impl Iterator<Item = (F, usize)> + '_ {
+        let range = self.row_offsets[i]..self.row_offsets[i + 1];
+        self.values[range.clone()]
+            .iter()
+            .copied()
+            .zip(self.columns[range].iter().copied())
+    }
+
+    /// Return an iterator over the rows of the matrix.
+    pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = (F, usize)> + '_> + '_ {
+        (0..self.num_rows()).map(move |i| self.row(i))
+    }
+
+    /// Return an iterator over the entries `(value, row)` of the `j`-th column.
+    ///
+    /// This requires a pass over all the entries of the matrix:
+    /// to iterate over all the columns, iterate over the rows of [`SparseMatrix::transpose`].
+    pub fn column(&self, j: usize) -> impl Iterator<Item = (F, usize)> + '_ {
+        self.rows().enumerate().flat_map(move |(i, row)| {
+            row.filter(move |&(_, column)| column == j)
+                .map(move |(value, _)| (value, i))
+        })
+    }
+
+    /// Return the transpose of the matrix.
+    ///
+    /// Entries in each row of the transpose are sorted by column index.
+    pub fn transpose(&self) -> Self {
+        let mut row_offsets = vec![0; self.num_columns + 1];
+        for &column in &self.columns {
+            row_offsets[column + 1] += 1;
+        }
+        let mut offset = 0;
+        for row_offset in row_offsets.iter_mut() {
+            offset += *row_offset;
+            *row_offset = offset;
+        }
+        let mut next = row_offsets.clone();
+        let mut columns = vec![0; self.nnz()];
+        let mut values = vec![F::zero(); self.nnz()];
+        for (i, row) in self.rows().enumerate() {
+            for (value, column) in row {
+                columns[next[column]] = i;
+                values[next[column]] = value;
+                next[column] += 1;
+            }
+        }
+        SparseMatrix {
+            num_columns: self.num_rows(),
+            row_offsets,
+            columns,
+            values,
+        }
+    }
+
+    /// Return the row-major representation of the matrix.
+    pub fn to_rows(&self) -> Matrix<F> {
+        self.rows().map(|row| row.collect()).collect()
+    }
+
+    /// Given a vector `z`, compute the product of the matrix with `z`.
+    pub fn product_vector(&self, z: &[F]) -> Vec<F> {
+        assert_eq!(z.len(), self.num_columns, "dimension mismatch");
+        cfg_into_iter!(0..self.num_rows())
+            .map(|i| {
+                let range = self.row_offsets[i]..self.row_offsets[i + 1];
+                self.values[range.clone()]
+                    .iter()
+                    .zip(&self.columns[range])
+                    .map(|(coeff, &j)| if coeff.is_one() { z[j] } else { z[j] * coeff })
+                    .sum::<F>()
+            })
+            .collect()
+    }
+}
+
+impl<F: Field> From<&Matrix<F>> for SparseMatrix<F> {
+    /// Return the CSR representation of the row-major matrix `rows`,
+    /// with as many columns as the largest column index plus one.
+    fn from(rows: &Matrix<F>) -> Self {
+        let num_columns = rows
+            .iter()
+            .flatten()
+            .map(|&(_, column)| column + 1)
+            .max()
+            .unwrap_or(0);
+        Self::from_rows(rows, num_columns)
+    }
+}
+
+#[test]
+fn test_sparse_matrix() {
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::Fr;
+
+    use crate::circuit::{generate_relation, random_circuit};
+    use crate::misc::{product_matrix_vector, vector_times_matrix};
+
+    let rng = &mut ark_std::test_rng();
+    let r1cs = generate_relation::<Fr, _>(random_circuit(rng, 16, 16));
+    let num_columns = r1cs.z.len();
+
+    for matrix in [&r1cs.a, &r1cs.b, &r1cs.c] {
+        let sparse = SparseMatrix::from_rows(matrix, num_columns);
+        assert_eq!(sparse.num_rows(), matrix.len());
+        assert_eq!(sparse.nnz(), matrix.iter().map(|row| row.len()).sum());
+        assert_eq!(&sparse.to_rows(), matrix);
+        assert_eq!(
+            sparse.product_vector(&r1cs.z),
+            product_matrix_vector(matrix, &r1cs.z)
+        );
+
+        // the rows of the transpose are the columns of the matrix.
+        let transpose = sparse.transpose();
+        assert_eq!(transpose.num_rows(), num_columns);
+        assert_eq!(transpose.transpose(), sparse);
+        for j in 0..num_columns {
+            assert_eq!(
+                transpose.row(j).collect::<Vec<_>>(),
+                sparse.column(j).collect::<Vec<_>>()
+            );
+        }
+        let v = (0..matrix.len()).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+        assert_eq!(
+            transpose.product_vector(&v),
+            vector_times_matrix(&v, matrix, num_columns)
+        );
+    }
+}
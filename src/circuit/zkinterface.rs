@@ -0,0 +1,321 @@
+//! Import and export of circuits in the [zkInterface](https://github.com/QED-it/zkinterface) format,
+//! for interoperability with non-arkworks toolchains.
+//!
+//! A zkInterface circuit consists of a circuit header, listing the instance variables,
+//! constraint system messages, listing the bilinear constraints,
+//! and witness messages, assigning the remaining variables.
+//! Field elements are encoded in little-endian, and the variable \\(0\\) is the constant \\(1\\).
+//!
+//! The statement, made of the constant and the instance variables, is padded with zeros to a power of two
+//! as for circuits synthesized with arkworks, see [`try_generate_relation`](super::try_generate_relation).
+//! The SIEVE IR format is not supported.
+use ark_ff::{BigInteger, PrimeField};
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::collections::BTreeMap;
+use ark_std::fmt;
+use ark_std::io::{Read, Write};
+use ark_std::vec::Vec;
+use zkinterface::{
+    BilinearConstraint, CircuitHeader, ConstraintSystem, Messages, Reader, Variables, Witness,
+};
+
+use super::{Matrix, R1cs};
+
+/// Error identifying malformed or unsupported zkInterface messages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ZkInterfaceError {
+    /// The underlying reader or writer failed.
+    Io,
+    /// The messages are malformed.
+    InvalidFormat(&'static str),
+    /// The circuit is defined over a different prime field.
+    UnsupportedField,
+    /// Some field element is not canonically encoded.
+    InvalidFieldElement,
+    /// The witness does not assign all the variables of the circuit.
+    MissingWitness,
+}
+
+impl fmt::Display for ZkInterfaceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io => write!(f, "I/O error while processing zkInterface messages."),
+            Self::InvalidFormat(reason) => write!(f, "Invalid zkInterface format: {}.", reason),
+            Self::UnsupportedField => write!(f, "The circuit is defined over a different field."),
+            Self::InvalidFieldElement => write!(f, "Invalid field element encoding."),
+            Self::MissingWitness => write!(f, "The witness does not assign all variables."),
+        }
+    }
+}
+
+/// Decode the little-endian field element `bytes`, possibly shorter than the serialization of `F`.
+fn decode<F: PrimeField>(bytes: &[u8]) -> Result<F, ZkInterfaceError> {
+    let size = F::zero().uncompressed_size();
+    if bytes.len() > size {
+        return Err(ZkInterfaceError::InvalidFieldElement);
+    }
+    let mut padded = bytes.to_vec();
+    padded.resize(size, 0);
+    F::deserialize_uncompressed(&padded[..]).map_err(|_| ZkInterfaceError::InvalidFieldElement)
+}
+
+/// Return the identifiers of `variables`, together with their values.
+fn decode_variables<F: PrimeField>(
+    variables: &Variables,
+) -> Result<Vec<(u64, F)>, ZkInterfaceError> {
+    let ids = &variables.variable_ids;
+    let values = variables
+        .values
+        .as_ref()
+        .ok_or(ZkInterfaceError::InvalidFormat("missing values"))?;
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    if values.is_empty() {
+        return Err(ZkInterfaceError::InvalidFormat("missing values"));
+    }
+    if values.len() % ids.len() != 0 {
+        return Err(ZkInterfaceError::InvalidFormat("invalid values size"));
+    }
+    ids.iter()
+        .zip(values.chunks(values.len() / ids.len()))
+        .map(|(&id, value)| Ok((id, decode(value)?)))
+        .collect()
+}
+
+/// Encode the pairs of identifiers and values `assignment`.
+fn encode_variables<F: PrimeField>(assignment: impl Iterator<Item = (u64, F)>) -> Variables {
+    let mut variable_ids = Vec::new();
+    let mut values = Vec::new();
+    for (id, value) in assignment {
+        variable_ids.push(id);
+        values.extend(value.into_bigint().to_bytes_le());
+    }
+    Variables {
+        variable_ids,
+        values: Some(values),
+    }
+}
+
+/// Return the R1CS relation described by the zkInterface `messages`.
+///
+/// The assignment is included if the circuit header carries the values of the instance variables,
+/// and the witness messages assign all other variables.
+pub fn read_messages<F: PrimeField>(messages: &Messages) -> Result<R1cs<F>, ZkInterfaceError> {
+    let header = messages
+        .circuit_headers
+        .first()
+        .ok_or(ZkInterfaceError::InvalidFormat("missing circuit header"))?;
+    if let Some(field_maximum) = &header.field_maximum {
+        if decode::<F>(field_maximum) != Ok(-F::one()) {
+            return Err(ZkInterfaceError::UnsupportedField);
+        }
+    }
+
+    // arrange the columns as: the constant, the instance variables, the padding, the witness.
+    let instance_ids = &header.instance_variables.variable_ids;
+    let num_instance_variables = GeneralEvaluationDomain::<F>::new(1 + instance_ids.len())
+        .ok_or(ZkInterfaceError::InvalidFormat(
+            "too many instance variables",
+        ))?
+        .size();
+    let mut columns = BTreeMap::new();
+    columns.insert(0u64, 0usize);
+    for (k, &id) in instance_ids.iter().enumerate() {
+        if id == 0 || columns.insert(id, k + 1).is_some() {
+            return Err(ZkInterfaceError::InvalidFormat("invalid instance variable"));
+        }
+    }
+    // every variable is either an instance variable, or occurs in some constraint or witness message:
+    // bound the number of variables before allocating the columns.
+    let num_occurrences = messages
+        .constraint_systems
+        .iter()
+        .flat_map(|cs| &cs.constraints)
+        .map(|constraint| {
+            constraint.linear_combination_a.variable_ids.len()
+                + constraint.linear_combination_b.variable_ids.len()
+                + constraint.linear_combination_c.variable_ids.len()
+        })
+        .chain(
+            messages
+                .witnesses
+                .iter()
+                .map(|witness| witness.assigned_variables.variable_ids.len()),
+        )
+        .sum::<usize>();
+    if header.free_variable_id > (columns.len() + num_occurrences) as u64 {
+        return Err(ZkInterfaceError::InvalidFormat("too many variables"));
+    }
+    let witness_ids = (1..header.free_variable_id)
+        .filter(|id| !columns.contains_key(id))
+        .collect::<Vec<_>>();
+    for (k, &id) in witness_ids.iter().enumerate() {
+        columns.insert(id, num_instance_variables + k);
+    }
+
+    let row = |variables: &Variables| {
+        decode_variables::<F>(variables)?
+            .into_iter()
+            .map(|(id, value)| match columns.get(&id) {
+                Some(&column) => Ok((value, column)),
+                None => Err(ZkInterfaceError::InvalidFormat("undeclared variable")),
+            })
+            .collect::<Result<Vec<_>, _>>()
+    };
+    let (mut a, mut b, mut c): (Matrix<F>, Matrix<F>, Matrix<F>) = Default::default();
+    for constraint in messages
+        .constraint_systems
+        .iter()
+        .flat_map(|cs| &cs.constraints)
+    {
+        a.push(row(&constraint.linear_combination_a)?);
+        b.push(row(&constraint.linear_combination_b)?);
+        c.push(row(&constraint.linear_combination_c)?);
+    }
+    let mut r1cs = R1cs::from_matrices(a, b, c);
+
+    if header.instance_variables.values.is_some() {
+        let mut x = vec![F::one()];
+        x.extend(
+            decode_variables(&header.instance_variables)?
+                .into_iter()
+                .map(|(_, value)| value),
+        );
+        x.resize(num_instance_variables, F::zero());
+        let assignment = messages
+            .witnesses
+            .iter()
+            .map(|witness| decode_variables::<F>(&witness.assigned_variables))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .flatten()
+            .collect::<BTreeMap<_, _>>();
+        let w = witness_ids
+            .iter()
+            .map(|id| assignment.get(id).copied())
+            .collect::<Option<Vec<_>>>()
+            .ok_or(ZkInterfaceError::MissingWitness)?;
+        r1cs.z = [x.as_slice(), w.as_slice()].concat();
+        r1cs.x = x;
+        r1cs.w = w;
+    }
+    Ok(r1cs)
+}
+
+/// Read the R1CS relation from the zkInterface messages in `reader`, see [`read_messages`].
+pub fn read_zkinterface<F: PrimeField, R: Read>(
+    mut reader: R,
+) -> Result<R1cs<F>, ZkInterfaceError> {
+    let mut buffer = Vec::new();
+    reader
+        .read_to_end(&mut buffer)
+        .map_err(|_| ZkInterfaceError::Io)?;
+    let mut messages = Reader::new();
+    messages
+        .push_message(buffer)
+        .map_err(|_| ZkInterfaceError::InvalidFormat("invalid message"))?;
+    read_messages(&Messages::from(&messages))
+}
+
+/// Write the R1CS relation `r1cs`, together with its assignment, as zkInterface messages into `writer`.
+///
+/// The first entry of the statement must be the constant \\(1\\):
+/// the variable identifiers are the columns of the matrices.
+pub fn write_zkinterface<F: PrimeField, W: Write>(
+    r1cs: &R1cs<F>,
+    mut writer: W,
+) -> Result<(), ZkInterfaceError> {
+    if r1cs.x.first() != Some(&F::one()) || r1cs.z.len() != r1cs.x.len() + r1cs.w.len() {
+        return Err(ZkInterfaceError::MissingWitness);
+    }
+    let num_instance_variables = r1cs.x.len();
+
+    let header = CircuitHeader {
+        instance_variables: encode_variables((1..).zip(r1cs.x[1..].iter().copied())),
+        free_variable_id: r1cs.z.len() as u64,
+        field_maximum: Some((-F::one()).into_bigint().to_bytes_le()),
+        configuration: None,
+    };
+    let lc = |row: &Vec<(F, usize)>| {
+        encode_variables(row.iter().map(|&(value, column)| (column as u64, value)))
+    };
+    let constraint_system = ConstraintSystem {
+        constraints: r1cs
+            .a
+            .iter()
+            .zip(&r1cs.b)
+            .zip(&r1cs.c)
+            .map(|((a, b), c)| BilinearConstraint {
+                linear_combination_a: lc(a),
+                linear_combination_b: lc(b),
+                linear_combination_c: lc(c),
+            })
+            .collect(),
+    };
+    let witness = Witness {
+        assigned_variables: encode_variables(
+            (num_instance_variables as u64..).zip(r1cs.w.iter().copied()),
+        ),
+    };
+
+    header
+        .write_into(&mut writer)
+        .map_err(|_| ZkInterfaceError::Io)?;
+    constraint_system
+        .write_into(&mut writer)
+        .map_err(|_| ZkInterfaceError::Io)?;
+    witness
+        .write_into(&mut writer)
+        .map_err(|_| ZkInterfaceError::Io)
+}
+
+#[test]
+fn test_zkinterface_roundtrip() {
+    use ark_test_curves::bls12_381::Fr;
+
+    use crate::circuit::{generate_relation, random_circuit};
+
+    let rng = &mut ark_std::test_rng();
+    let r1cs = generate_relation::<Fr, _>(random_circuit(rng, 8, 8));
+
+    let mut buffer = Vec::new();
+    write_zkinterface(&r1cs, &mut buffer).unwrap();
+    let imported = read_zkinterface::<Fr, _>(&buffer[..]).unwrap();
+    assert_eq!(imported, r1cs);
+    assert_eq!(imported.is_satisfied(), Ok(()));
+
+    // circuits over other fields are rejected.
+    let mut messages = Reader::new();
+    messages.push_message(buffer).unwrap();
+    let mut messages = Messages::from(&messages);
+    messages.circuit_headers[0].field_maximum = Some(vec![0xff; 8]);
+    assert_eq!(
+        read_messages::<Fr>(&messages),
+        Err(ZkInterfaceError::UnsupportedField)
+    );
+    messages.circuit_headers[0].field_maximum = None;
+
+    // linear combinations without values are rejected.
+    let mut missing_values = messages.clone();
+    missing_values.constraint_systems[0]
+        .constraints
+        .iter_mut()
+        .find(|constraint| !constraint.linear_combination_a.variable_ids.is_empty())
+        .unwrap()
+        .linear_combination_a
+        .values = Some(Vec::new());
+    assert_eq!(
+        read_messages::<Fr>(&missing_values),
+        Err(ZkInterfaceError::InvalidFormat("missing values"))
+    );
+
+    // more variables than the messages can refer to.
+    let mut forged_header = messages.clone();
+    forged_header.circuit_headers[0].free_variable_id = u64::MAX;
+    assert_eq!(
+        read_messages::<Fr>(&forged_header),
+        Err(ZkInterfaceError::InvalidFormat("too many variables"))
+    );
+}
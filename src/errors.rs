@@ -2,13 +2,60 @@
 
 use ark_std::fmt;
 
-/// Error identifying a failure in the proof verification.
-#[derive(Debug, Clone)]
-pub struct VerificationError;
+/// Error identifying a failure in the proof verification,
+/// together with the sub-protocol that rejected the proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum VerificationError {
+    /// The sumcheck rejected the prover's messages at the given round.
+    /// The final foldings are checked after the last round,
+    /// hence a failure in the final check is reported with the number of rounds.
+    SumcheckRound(usize),
+    /// The inner-product argument of [`herring`](crate::herring) rejected the prover's messages at the given round.
+    /// As in the sumcheck, a failure in the final check is reported with the number of rounds.
+    InnerProductRound(usize),
+    /// The evaluations of the folded polynomials in the tensorcheck are not consistent.
+    TensorcheckConsistency,
+    /// The pairing check for the polynomial commitments failed.
+    PairingCheck,
+    /// The shape of the proof does not match the instance bound to the transcript,
+    /// for instance because the proof has the wrong number of rounds.
+    TranscriptMismatch,
+    /// The proof is malformed, for instance because it lacks some messages.
+    MalformedProof,
+    /// The inputs of the verifier are inconsistent, for instance because their lengths differ.
+    InvalidInput,
+}
 
 impl fmt::Display for VerificationError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Verification Error.")
+        match self {
+            Self::SumcheckRound(round) => {
+                write!(f, "Verification Error: sumcheck failed at round {}.", round)
+            }
+            Self::InnerProductRound(round) => {
+                write!(
+                    f,
+                    "Verification Error: inner-product argument failed at round {}.",
+                    round
+                )
+            }
+            Self::TensorcheckConsistency => {
+                write!(
+                    f,
+                    "Verification Error: inconsistent tensorcheck evaluations."
+                )
+            }
+            Self::PairingCheck => write!(f, "Verification Error: pairing check failed."),
+            Self::TranscriptMismatch => {
+                write!(
+                    f,
+                    "Verification Error: proof does not match the transcript."
+                )
+            }
+            Self::MalformedProof => write!(f, "Verification Error: malformed proof."),
+            Self::InvalidInput => write!(f, "Verification Error: invalid verifier input."),
+        }
     }
 }
 
@@ -24,8 +24,8 @@ use crate::transcript::GeminiTranscript;
 use ark_ec::CurveGroup;
 use ark_ec::{pairing::Pairing, PrimeGroup, VariableBaseMSM};
 use ark_ff::{Field, Zero};
+use ark_std::rand::Rng;
 use ark_std::UniformRand;
-use rand::Rng;
 
 fn po_from_g1<P: Pairing>(p: &P::G1) -> PairingOutput<P> {
     P::pairing(p, P::G2::generator())
@@ -339,7 +339,7 @@ impl<P: Pairing> InnerProductProof<P> {
         if reduced_claim == expected {
             Ok(())
         } else {
-            Err(VerificationError)
+            Err(VerificationError::InnerProductRound(rounds))
         }
     }
 
@@ -350,7 +350,6 @@ impl<P: Pairing> InnerProductProof<P> {
         g1_ip: Vec<Witness<G1Module<P>>>,
         g2_ip: Vec<Witness<G2Module<P>>>,
     ) -> Self {
-        println!("starting");
         // the full transcript will hold a set of messages, challenges, and batch challenges.
         let mut messages = Vec::new();
         let mut challenges = Vec::new();
@@ -419,8 +418,6 @@ impl<P: Pairing> InnerProductProof<P> {
 
         let mut provers_gg: Vec<TimeProver<_>> = Vec::new();
         for _ in 0..rounds - 1 {
-            println!("round");
-
             // step 2a; the verifier sends round and batch challenge
             let challenge = transcript.get_challenge(b"sumcheck-chal");
             verifier_message = Some(challenge);
@@ -562,11 +559,8 @@ impl<P: Pairing> InnerProductProof<P> {
         batch_challenges.push(P::ScalarField::one());
         batch_challenges.push(batch_challenge);
         batch_challenges.push(batch_challenge.square());
-        println!("first field fold");
         let msg_ff = prover_ff.next_message(verifier_message).unwrap();
-        println!("g1 fold");
         let msg_fg1 = prover_fg1.next_message(verifier_message).unwrap();
-        println!("g2 fold");
         let msg_fg2 = prover_fg2.next_message(verifier_message).unwrap();
         let prover_message = scalarfieldsm_to_posm(msg_ff)
             + g1sm_to_posm(msg_fg1 * &batch_challenge)
@@ -690,7 +684,7 @@ impl<P: Pairing> InnerProductProof<P> {
 fn test_correctness() {
     use ark_test_curves::bls12_381::{Bls12_381, Fr};
     let d = 1 << 8 + 2;
-    let rng = &mut rand::thread_rng();
+    let rng = &mut ark_std::test_rng();
     let mut transcript = Transcript::new(b"gemini-tests");
     let crs_time = start_timer!(|| "crs_time");
     let crs = Crs::<Bls12_381>::new(rng, d * 2);
@@ -704,15 +698,20 @@ fn test_correctness() {
     let y = crate::misc::ip(&a, &b);
 
     let verification = ipa.verify_transcript(&vrs, comm_a, comm_b, y);
-    assert!(verification.is_ok())
+    assert!(verification.is_ok());
+
+    let rounds = ipa.sumcheck.messages.len();
+    assert_eq!(
+        ipa.verify_transcript(&vrs, comm_a, comm_b, y + Fr::one()),
+        Err(VerificationError::InnerProductRound(rounds))
+    );
 }
 
 #[test]
 fn test_consistent_batch() {
     use ark_test_curves::bls12_381::{Bls12_381, Fr};
-    println!("hello");
     let d = 1 << 9 + 2;
-    let rng = &mut rand::thread_rng();
+    let rng = &mut ark_std::test_rng();
     let mut transcript1 = Transcript::new(b"gemini-tests");
     let mut transcript2 = Transcript::new(b"gemini-tests");
     let crs = Crs::<Bls12_381>::new(rng, d * 2);
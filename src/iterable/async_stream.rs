@@ -0,0 +1,144 @@
+//! Streams fed by asynchronous sources.
+//!
+//! Cloud provers may fetch the witness from remote storage, as a [`futures::Stream`].
+//! An [`AsyncStream`] bridges such sources into [`Iterable`]:
+//! every pass opens a new source, drives it on a background thread,
+//! and hands its elements over to the prover through a channel of bounded capacity,
+//! so that fetching the next elements overlaps with proving, without downloading the whole source.
+//!
+//! Since the space-efficient prover reads its streams many times,
+//! the source must be re-opened at every pass.
+//! Sources are expected not to fail: errors (e.g., network failures)
+//! should be retried upstream, as the prover cannot recover from a truncated stream.
+//! A pass over a source yielding fewer or more elements than the length of the stream panics.
+use std::sync::mpsc::{sync_channel, Receiver};
+use std::thread;
+
+use futures::executor::block_on_stream;
+use futures::Stream;
+
+use super::Iterable;
+
+/// A stream of `len` elements, read from the asynchronous sources returned by `open`.
+#[derive(Clone, Copy)]
+pub struct AsyncStream<O> {
+    open: O,
+    len: usize,
+    capacity: usize,
+}
+
+impl<O, S> AsyncStream<O>
+where
+    O: Fn() -> S + Send + Sync,
+    S: Stream + Send + 'static,
+    S::Item: Send + Sync + 'static,
+{
+    /// Create a new stream of `len` elements, opening a new source with `open` at every pass,
+    /// and buffering at most `capacity` elements ahead of the reader.
+    pub fn new(open: O, len: usize, capacity: usize) -> Self {
+        AsyncStream {
+            open,
+            len,
+            capacity,
+        }
+    }
+}
+
+impl<O, S> Iterable for AsyncStream<O>
+where
+    O: Fn() -> S + Send + Sync,
+    S: Stream + Send + 'static,
+    S::Item: Send + Sync + 'static,
+{
+    type Item = S::Item;
+    type Iter = AsyncStreamIter<S::Item>;
+
+    fn iter(&self) -> Self::Iter {
+        let source = Box::pin((self.open)());
+        let (sender, receiver) = sync_channel(self.capacity);
+        thread::spawn(move || {
+            for item in block_on_stream(source) {
+                // the reader dropped the iterator: stop fetching.
+                if sender.send(item).is_err() {
+                    break;
+                }
+            }
+        });
+        AsyncStreamIter {
+            receiver,
+            remaining: self.len,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+/// Iterator over a pass of an [`AsyncStream`].
+pub struct AsyncStreamIter<T> {
+    receiver: Receiver<T>,
+    /// The number of elements still expected from the source.
+    remaining: usize,
+}
+
+impl<T> Iterator for AsyncStreamIter<T> {
+    type Item = T;
+
+    /// Return the next element of the source.
+    ///
+    /// # Panics
+    ///
+    /// If the source ends before the length of the stream, or goes past it.
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.receiver.recv() {
+            Ok(item) => {
+                assert!(
+                    self.remaining > 0,
+                    "the asynchronous source is longer than the stream"
+                );
+                self.remaining -= 1;
+                Some(item)
+            }
+            Err(_) => {
+                assert_eq!(
+                    self.remaining, 0,
+                    "the asynchronous source ended before the end of the stream"
+                );
+                None
+            }
+        }
+    }
+}
+
+#[test]
+fn test_async_stream() {
+    use crate::kzg::{CommitterKey, CommitterKeyStream};
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+
+    let rng = &mut ark_std::test_rng();
+    let elements = (0..100).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+    let open = || futures::stream::iter(elements.clone().into_iter().rev());
+    let stream = AsyncStream::new(open, elements.len(), 8);
+
+    // every pass reads the whole source again.
+    for _ in 0..2 {
+        assert!(stream.iter().eq(elements.iter().rev().cloned()));
+    }
+    // an interrupted pass does not prevent the following ones.
+    assert_eq!(stream.iter().next(), elements.last().cloned());
+
+    let ck = CommitterKey::<Bls12_381>::new(elements.len(), 1, rng);
+    let ck_stream = CommitterKeyStream::from(&ck);
+    assert_eq!(ck_stream.commit(&stream), ck.commit(&elements));
+}
+
+#[test]
+#[should_panic(expected = "ended before the end of the stream")]
+fn test_async_stream_truncated() {
+    let open = || futures::stream::iter(0..99);
+    let stream = AsyncStream::new(open, 100, 8);
+    stream.iter().for_each(drop);
+}
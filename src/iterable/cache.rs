@@ -0,0 +1,203 @@
+//! A stream caching its chunks across passes.
+//!
+//! The space-efficient prover reads the same streams once per sumcheck round.
+//! When producing the stream is expensive (e.g., because it is read from disk or computed on the fly),
+//! a [`CachedStream`] keeps the most recently read chunks in memory, within a budget of bytes,
+//! and serves them on the following passes instead of reading the underlying stream again.
+//! Chunks are evicted in least-recently-used order once the budget is exhausted.
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use ark_std::vec::Vec;
+
+use super::Iterable;
+
+/// Hits and misses of a [`CachedStream`], counted in chunks.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// The number of chunks served from memory.
+    pub hits: usize,
+    /// The number of chunks read from the underlying stream.
+    pub misses: usize,
+}
+
+impl CacheStats {
+    /// Return the fraction of chunks served from memory, or zero if no chunk has been read.
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+struct Cache<T> {
+    chunks: BTreeMap<usize, Arc<Vec<T>>>,
+    recency: VecDeque<usize>,
+    stats: CacheStats,
+}
+
+/// A stream keeping up to `budget` bytes of chunks of `chunk_size` elements of `stream` in memory.
+///
+/// Since the cache is shared among all passes, the stream is iterated by reference.
+pub struct CachedStream<S: Iterable> {
+    stream: S,
+    chunk_size: usize,
+    max_chunks: usize,
+    cache: Mutex<Cache<S::Item>>,
+}
+
+impl<S> CachedStream<S>
+where
+    S: Iterable,
+    S::Item: Clone,
+{
+    /// Create a new stream caching chunks of `chunk_size` elements of `stream`,
+    /// using at most `budget` bytes of memory.
+    pub fn new(stream: S, chunk_size: usize, budget: usize) -> Self {
+        assert!(chunk_size > 0, "chunks must be non-empty");
+        let chunk_bytes = chunk_size * usize::max(ark_std::mem::size_of::<S::Item>(), 1);
+        CachedStream {
+            stream,
+            chunk_size,
+            max_chunks: budget / chunk_bytes,
+            cache: Mutex::new(Cache {
+                chunks: BTreeMap::new(),
+                recency: VecDeque::new(),
+                stats: CacheStats::default(),
+            }),
+        }
+    }
+
+    /// Return the hits and misses so far.
+    pub fn stats(&self) -> CacheStats {
+        self.cache.lock().expect("the cache is poisoned").stats
+    }
+
+    /// Return the chunk `index`, served from memory if possible,
+    /// and read from `source` (positioned at `*position`) otherwise.
+    fn chunk(&self, index: usize, source: &mut S::Iter, position: &mut usize) -> Arc<Vec<S::Item>> {
+        let mut cache = self.cache.lock().expect("the cache is poisoned");
+        if let Some(chunk) = cache.chunks.get(&index).cloned() {
+            cache.recency.retain(|&i| i != index);
+            cache.recency.push_back(index);
+            cache.stats.hits += 1;
+            return chunk;
+        }
+
+        let start = index * self.chunk_size;
+        if start > *position {
+            source.nth(start - *position - 1);
+        }
+        let chunk = Arc::new(source.take(self.chunk_size).collect::<Vec<_>>());
+        *position = start + chunk.len();
+        cache.stats.misses += 1;
+
+        if self.max_chunks > 0 {
+            if cache.recency.len() == self.max_chunks {
+                let evicted = cache.recency.pop_front().unwrap();
+                cache.chunks.remove(&evicted);
+            }
+            cache.chunks.insert(index, chunk.clone());
+            cache.recency.push_back(index);
+        }
+        chunk
+    }
+}
+
+impl<'a, S> Iterable for &'a CachedStream<S>
+where
+    S: Iterable,
+    S::Item: Clone,
+{
+    type Item = S::Item;
+    type Iter = CachedIter<'a, S>;
+
+    fn iter(&self) -> Self::Iter {
+        CachedIter {
+            stream: *self,
+            source: self.stream.iter(),
+            position: 0,
+            index: 0,
+            chunk: Arc::new(Vec::new()),
+            offset: 0,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.stream.len()
+    }
+}
+
+/// Iterator over a [`CachedStream`].
+pub struct CachedIter<'a, S: Iterable> {
+    stream: &'a CachedStream<S>,
+    source: S::Iter,
+    position: usize,
+    index: usize,
+    chunk: Arc<Vec<S::Item>>,
+    offset: usize,
+}
+
+impl<'a, S> Iterator for CachedIter<'a, S>
+where
+    S: Iterable,
+    S::Item: Clone,
+{
+    type Item = S::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset == self.chunk.len() {
+            if self.index * self.stream.chunk_size >= self.stream.stream.len() {
+                return None;
+            }
+            self.chunk = self
+                .stream
+                .chunk(self.index, &mut self.source, &mut self.position);
+            self.index += 1;
+            self.offset = 0;
+        }
+        let item = self.chunk.get(self.offset).cloned();
+        self.offset += 1;
+        item
+    }
+}
+
+#[test]
+fn test_cached_stream() {
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::Fr;
+
+    let rng = &mut ark_std::test_rng();
+    let elements = (0..100).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+    let chunk_bytes = 10 * ark_std::mem::size_of::<&Fr>();
+
+    // the whole stream fits in memory: only the first pass reads it.
+    let stream = CachedStream::new(elements.as_slice(), 10, 10 * chunk_bytes);
+    for _ in 0..3 {
+        assert!((&stream).iter().eq(elements.iter()));
+    }
+    assert_eq!(
+        stream.stats(),
+        CacheStats {
+            hits: 20,
+            misses: 10
+        }
+    );
+    assert!((stream.stats().hit_rate() - 2. / 3.).abs() < 1e-9);
+
+    // the budget fits half of the stream, with sequential passes evicting all chunks.
+    let stream = CachedStream::new(elements.as_slice(), 10, 5 * chunk_bytes);
+    for _ in 0..2 {
+        assert!((&stream).iter().eq(elements.iter()));
+    }
+    assert_eq!(stream.stats().hits, 0);
+
+    // partial passes are served from memory.
+    for _ in 0..2 {
+        assert!((&stream).iter().take(25).eq(elements.iter().take(25)));
+    }
+    assert_eq!(stream.stats().hits, 3);
+}
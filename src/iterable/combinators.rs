@@ -0,0 +1,266 @@
+//! Composable adaptors for streams.
+//!
+//! [`IterableExt`] provides, for any [`Iterable`], the adaptors of [`Iterator`]
+//! that preserve the ability to restart the stream from its beginning.
+//! Together with [`Tensor`](super::Tensor), they allow expressing derived streams
+//! without materializing intermediate vectors.
+//!
+//! Streams in this crate hold polynomials from their leading coefficient:
+//! [`IterableExt::zip`] and [`IterableExt::hadamard`] thus align the streams _at their end_,
+//! skipping the first elements of the longest one.
+use ark_ff::Field;
+use ark_std::borrow::Borrow;
+use ark_std::iter;
+use ark_std::vec::Vec;
+
+use super::Iterable;
+use crate::misc::ceil_div;
+
+const LEN_MISMATCH_ERRMSG: &str = "Iterable::len mismatch with actual stream length.";
+
+/// The stream of the elements of `stream` mapped through `f`, see [`IterableExt::map`].
+#[derive(Clone, Copy)]
+pub struct Map<S, F> {
+    stream: S,
+    f: F,
+}
+
+impl<S, F, T> Iterable for Map<S, F>
+where
+    S: Iterable,
+    F: Fn(S::Item) -> T + Clone + Send + Sync,
+    T: Send + Sync,
+{
+    type Item = T;
+    type Iter = iter::Map<S::Iter, F>;
+
+    fn iter(&self) -> Self::Iter {
+        self.stream.iter().map(self.f.clone())
+    }
+
+    fn len(&self) -> usize {
+        self.stream.len()
+    }
+}
+
+/// The stream of pairs of elements of two streams, see [`IterableExt::zip`].
+#[derive(Clone, Copy)]
+pub struct Zip<A, B>(A, B);
+
+impl<A, B> Iterable for Zip<A, B>
+where
+    A: Iterable,
+    B: Iterable,
+{
+    type Item = (A::Item, B::Item);
+    type Iter = iter::Zip<A::Iter, B::Iter>;
+
+    fn iter(&self) -> Self::Iter {
+        let mut iter0 = self.0.iter();
+        let mut iter1 = self.1.iter();
+        let len = self.len();
+        iter0
+            .advance_by(self.0.len() - len)
+            .expect(LEN_MISMATCH_ERRMSG);
+        iter1
+            .advance_by(self.1.len() - len)
+            .expect(LEN_MISMATCH_ERRMSG);
+        iter0.zip(iter1)
+    }
+
+    fn len(&self) -> usize {
+        usize::min(self.0.len(), self.1.len())
+    }
+}
+
+/// The stream of the elements of a stream followed by the elements of another, see [`IterableExt::chain`].
+#[derive(Clone, Copy)]
+pub struct Chain<A, B>(A, B);
+
+impl<A, B> Iterable for Chain<A, B>
+where
+    A: Iterable,
+    B: Iterable<Item = A::Item>,
+{
+    type Item = A::Item;
+    type Iter = iter::Chain<A::Iter, B::Iter>;
+
+    fn iter(&self) -> Self::Iter {
+        self.0.iter().chain(self.1.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.0.len() + self.1.len()
+    }
+}
+
+/// The stream of the first elements of a stream, see [`IterableExt::take`].
+#[derive(Clone, Copy)]
+pub struct Take<S> {
+    stream: S,
+    n: usize,
+}
+
+impl<S: Iterable> Iterable for Take<S> {
+    type Item = S::Item;
+    type Iter = iter::Take<S::Iter>;
+
+    fn iter(&self) -> Self::Iter {
+        self.stream.iter().take(self.n)
+    }
+
+    fn len(&self) -> usize {
+        usize::min(self.stream.len(), self.n)
+    }
+}
+
+/// The stream of the chunks of a stream, see [`IterableExt::chunk`].
+#[derive(Clone, Copy)]
+pub struct Chunk<S> {
+    stream: S,
+    size: usize,
+}
+
+impl<S: Iterable> Iterable for Chunk<S> {
+    type Item = Vec<S::Item>;
+    type Iter = ChunkIter<S::Iter>;
+
+    fn iter(&self) -> Self::Iter {
+        ChunkIter {
+            iter: self.stream.iter(),
+            size: self.size,
+        }
+    }
+
+    fn len(&self) -> usize {
+        ceil_div(self.stream.len(), self.size)
+    }
+}
+
+/// Iterator over the chunks of a stream.
+pub struct ChunkIter<I> {
+    iter: I,
+    size: usize,
+}
+
+impl<I: Iterator> Iterator for ChunkIter<I> {
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let chunk = (&mut self.iter).take(self.size).collect::<Vec<_>>();
+        (!chunk.is_empty()).then_some(chunk)
+    }
+}
+
+/// The stream of the entry-wise products of two streams, see [`IterableExt::hadamard`].
+pub type Hadamard<A, B, F> =
+    Map<Zip<A, B>, fn((<A as Iterable>::Item, <B as Iterable>::Item)) -> F>;
+
+fn product<F, X, Y>((x, y): (X, Y)) -> F
+where
+    F: Field,
+    X: Borrow<F>,
+    Y: Borrow<F>,
+{
+    *x.borrow() * y.borrow()
+}
+
+/// Adaptors for streams.
+pub trait IterableExt: Iterable + Sized {
+    /// Return the stream of the elements of `self` mapped through `f`.
+    fn map<F, T>(self, f: F) -> Map<Self, F>
+    where
+        F: Fn(Self::Item) -> T + Clone + Send + Sync,
+        T: Send + Sync,
+    {
+        Map { stream: self, f }
+    }
+
+    /// Return the stream of the pairs of elements of `self` and `other`,
+    /// aligned at their end.
+    fn zip<B: Iterable>(self, other: B) -> Zip<Self, B> {
+        Zip(self, other)
+    }
+
+    /// Return the stream of the elements of `self` followed by the elements of `other`.
+    fn chain<B>(self, other: B) -> Chain<Self, B>
+    where
+        B: Iterable<Item = Self::Item>,
+    {
+        Chain(self, other)
+    }
+
+    /// Return the stream of the first `n` elements of `self`.
+    fn take(self, n: usize) -> Take<Self> {
+        Take { stream: self, n }
+    }
+
+    /// Return the stream of the chunks of `size` elements of `self`.
+    /// The last chunk may be shorter.
+    fn chunk(self, size: usize) -> Chunk<Self> {
+        assert!(size > 0, "chunks must be non-empty");
+        Chunk { stream: self, size }
+    }
+
+    /// Return the stream of the entry-wise products of `self` and `other`,
+    /// aligned at their end.
+    fn hadamard<F, B>(self, other: B) -> Hadamard<Self, B, F>
+    where
+        F: Field,
+        B: Iterable,
+        Self::Item: Borrow<F>,
+        B::Item: Borrow<F>,
+    {
+        Map {
+            stream: Zip(self, other),
+            f: product::<F, Self::Item, B::Item>,
+        }
+    }
+}
+
+impl<S: Iterable> IterableExt for S {}
+
+#[test]
+fn test_combinators() {
+    use crate::iterable::{Reverse, Tensor};
+    use crate::misc::tensor;
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::Fr;
+
+    let rng = &mut ark_std::test_rng();
+    let a = (0..10).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+    let b = (0..8).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+    let challenges = (0..3).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+
+    let doubled = a.as_slice().map(|x: &Fr| x.double());
+    assert_eq!(doubled.len(), a.len());
+    assert!(doubled.iter().eq(a.iter().map(|x| x.double())));
+
+    let zipped = a.as_slice().zip(b.as_slice());
+    assert_eq!(zipped.len(), b.len());
+    assert!(zipped.iter().eq(a[2..].iter().zip(b.iter())));
+
+    let chained = a.as_slice().chain(b.as_slice());
+    assert_eq!(chained.len(), a.len() + b.len());
+    assert!(chained.iter().eq(a.iter().chain(b.iter())));
+
+    let taken = a.as_slice().take(3);
+    assert_eq!(taken.len(), 3);
+    assert!(taken.iter().eq(a.iter().take(3)));
+
+    let chunks = a.as_slice().chunk(4);
+    assert_eq!(chunks.len(), 3);
+    assert_eq!(chunks.iter().count(), 3);
+    assert!(chunks.iter().flatten().eq(a.iter()));
+
+    // the entry-wise product of a polynomial with a tensor, as streamed by the provers.
+    let expected = tensor(&challenges)
+        .iter()
+        .zip(&b)
+        .map(|(x, y)| *x * y)
+        .rev()
+        .collect::<Vec<_>>();
+    let hadamard = Tensor(&challenges).hadamard::<Fr, _>(Reverse(b.as_slice()));
+    assert_eq!(hadamard.len(), b.len());
+    assert_eq!(hadamard.iter().collect::<Vec<_>>(), expected);
+}
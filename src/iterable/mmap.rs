@@ -1,110 +1,216 @@
+//! Streams of field elements backed by memory-mapped files.
+//!
+//! The witness of huge instances may not fit in memory, even once.
+//! A [`FieldFile`] memory-maps a file of field elements, each serialized uncompressed with
+//! [`CanonicalSerialize`] and without any header, and [`FieldFile::stream`] returns
+//! a [`FieldMmap`] stream over it.
+//! Elements are deserialized on the fly as the stream is read, in either direction:
+//! [`Reverse`](crate::iterable::Reverse) yields them starting from the end of the file,
+//! as expected by the space-efficient prover.
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
 use std::marker::PhantomData;
+use std::path::Path;
+use std::slice::ChunksExact;
 
 use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::borrow::Borrow;
 use memmap::Mmap;
 
-/// A memory-mapped buffer for field elements.
-#[derive(Clone, Copy)]
-pub struct FieldMmap<'a, F>
-where
-    F: Field,
-{
-    mmap: &'a Mmap,
+use super::Iterable;
+
+/// A file of field elements, memory-mapped.
+pub struct FieldFile<F: Field> {
+    mmap: Mmap,
+    element_size: usize,
     _field: PhantomData<F>,
 }
 
-impl<'a, F> FieldMmap<'a, F>
-where
-    F: Field,
-{
-    /// Initialize a new memory map buffer.
-    pub fn new(mmap: &'a Mmap) -> Self {
-        Self {
+impl<F: Field> FieldFile<F> {
+    /// Write the field elements `elements` to the file at `path`, and memory-map it.
+    pub fn create<P, I>(path: P, elements: I) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+        I: IntoIterator,
+        I::Item: Borrow<F>,
+    {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path.as_ref())?;
+        let mut writer = BufWriter::new(file);
+        for element in elements {
+            element
+                .borrow()
+                .serialize_uncompressed(&mut writer)
+                .map_err(|_| io::Error::from(io::ErrorKind::InvalidData))?;
+        }
+        writer.flush()?;
+        drop(writer);
+
+        Self::open(path)
+    }
+
+    /// Memory-map the field elements stored in the file at `path`.
+    ///
+    /// _Nota bene:_ the file is assumed to come from trusted storage:
+    /// elements are not checked for being reduced modulo the field characteristic.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: the file is mapped read-only, and its contents are parsed before being used.
+        let mmap = unsafe { Mmap::map(&file) }?;
+        let element_size = F::zero().uncompressed_size();
+        if mmap.len() % element_size != 0 {
+            return Err(io::Error::from(io::ErrorKind::InvalidData));
+        }
+        Ok(Self {
             mmap,
+            element_size,
+            _field: PhantomData,
+        })
+    }
+
+    /// Return the number of field elements in the file.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.mmap.len() / self.element_size
+    }
+
+    /// Return `true` if the file holds no field element.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.mmap.is_empty()
+    }
+
+    /// Return a stream over the field elements in the file, in the order they were written.
+    pub fn stream(&self) -> FieldMmap<'_, F> {
+        FieldMmap {
+            bytes: &self.mmap[..],
+            element_size: self.element_size,
             _field: PhantomData,
         }
     }
 }
 
-impl<'a, F> Iterable for FieldMmap<'a, F>
-where
-    F: Field,
-{
-    type Item = &'a F;
+/// A stream of field elements deserialized from a memory-mapped buffer.
+#[derive(Clone, Copy)]
+pub struct FieldMmap<'a, F: Field> {
+    bytes: &'a [u8],
+    element_size: usize,
+    _field: PhantomData<F>,
+}
 
-    type Iter = std::slice::Iter<'a, F>;
+impl<'a, F: Field> Iterable for FieldMmap<'a, F> {
+    type Item = F;
+    type Iter = FieldMmapIter<'a, F>;
 
     fn iter(&self) -> Self::Iter {
-        let source =
-            unsafe { std::slice::from_raw_parts_mut(self.mmap.as_ptr() as *mut F, self.len()) }
-                as &[F];
-        source.iter()
+        FieldMmapIter {
+            chunks: self.bytes.chunks_exact(self.element_size),
+            _field: PhantomData,
+        }
     }
 
     fn len(&self) -> usize {
-        self.mmap.len() / std::mem::size_of::<F>()
+        self.bytes.len() / self.element_size
+    }
+}
+
+/// Iterator over the field elements of a [`FieldMmap`].
+pub struct FieldMmapIter<'a, F: Field> {
+    chunks: ChunksExact<'a, u8>,
+    _field: PhantomData<F>,
+}
+
+#[inline]
+fn read_element<F: Field>(mut bytes: &[u8]) -> F {
+    F::deserialize_uncompressed_unchecked(&mut bytes)
+        .expect("the file has been modified since it was mapped")
+}
+
+impl<'a, F: Field> Iterator for FieldMmapIter<'a, F> {
+    type Item = F;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.chunks.next().map(read_element)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.chunks.nth(n).map(read_element)
+    }
+}
+
+impl<'a, F: Field> DoubleEndedIterator for FieldMmapIter<'a, F> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.chunks.next_back().map(read_element)
+    }
+}
+
+impl<'a, F: Field> ExactSizeIterator for FieldMmapIter<'a, F> {}
+
+/// A path in the temporary directory unique to the test run, whose file is removed when dropped.
+#[cfg(test)]
+pub(crate) struct TempPath(std::path::PathBuf);
+
+#[cfg(test)]
+impl TempPath {
+    /// Return a new path whose file name starts with `prefix`,
+    /// followed by the process identifier and a random suffix.
+    pub(crate) fn new(prefix: &str) -> Self {
+        use std::collections::hash_map::RandomState;
+        use std::hash::{BuildHasher, Hasher};
+
+        let suffix = RandomState::new().build_hasher().finish();
+        let name = format!("{}-{}-{:016x}.bin", prefix, std::process::id(), suffix);
+        TempPath(std::env::temp_dir().join(name))
+    }
+}
+
+#[cfg(test)]
+impl AsRef<Path> for TempPath {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
     }
 }
 
-// #[test]
-// fn write_ck<G: AffineCurve>() {
-//     let length = std::mem::size_of::G
-//         let file = std::fs::OpenOptions::new()
-//             .read(true)
-//             .write(true)
-//             .create(true)
-//             .open(path)
-//             .unwrap();
-//         file.set_len(length as u64).unwrap();
-//         let mut mmap = unsafe { MmapOptions::new().map_mut(&file).unwrap() };
-//         let dst =
-//             unsafe { std::slice::from_raw_parts_mut(mmap.as_mut_ptr() as *mut F, self.len()) };
-//         let src = self.stream().cloned().collect::<Vec<_>>();
-
-// }
-// impl<'a, F: Fields> FieldStreamer<'a, F> {
-//     pub fn from_file(path: &str) -> Result<(Mmap, Self)> {
-//         let file = std::fs::File::open(path).map_err(|_e| StreamError).unwrap();
-
-//         let mmap = unsafe { MmapOptions::new().map(&file).unwrap() };
-//         let source = unsafe {
-//             std::slice::from_raw_parts_mut(
-//                 mmap.as_ptr() as *mut F,
-//                 mmap.len() / std::mem::size_of::<F>(),
-//             )
-//         } as &[F];
-
-//         Ok((mmap, source))
-//     }
-
-//     pub fn to_file(&self, path: &str) -> Result<()> {
-//         let length = std::mem::size_of::<F>() * self.len();
-
-//         let file = std::fs::OpenOptions::new()
-//             .read(true)
-//             .write(true)
-//             .create(true)
-//             .open(path)
-//             .unwrap();
-//         file.set_len(length as u64).unwrap();
-//         let mut mmap = unsafe { MmapOptions::new().map_mut(&file).unwrap() };
-//         let dst =
-//             unsafe { std::slice::from_raw_parts_mut(mmap.as_mut_ptr() as *mut F, self.len()) };
-//         let src = self.stream().cloned().collect::<Vec<_>>();
-
-//         dst.copy_from_slice(&src);
-//         Ok(())
-//     }
-// }
-
-// #[test]
-// fn test_stream_from_file() {
-//     use ark_ff::{One, Zero};
-//     type F = ark_bls12_381::Fr;
-//     let a = [F::one(), F::one(), F::zero()];
-//     let stream_a = &a[..];
-//     assert!(stream_a.to_file("/tmp/test.mmap").is_ok());
-
-//     let (mmap, read_stream_a) = FieldStreamer::<F>::from_file("/tmp/test.mmap").unwrap();
-//     assert_eq!(stream_a.stream().next(), read_stream_a.stream().next());
-// }
+#[test]
+fn test_field_mmap() {
+    use crate::iterable::Reverse;
+    use crate::kzg::{CommitterKey, CommitterKeyStream};
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+
+    let rng = &mut ark_std::test_rng();
+    let elements = (0..100).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+    let path = TempPath::new("gemini-test-field-mmap");
+    let file = FieldFile::<Fr>::create(&path, &elements).unwrap();
+    assert_eq!(file.len(), elements.len());
+
+    let stream = file.stream();
+    assert_eq!(stream.len(), elements.len());
+    assert!(stream.iter().eq(elements.iter().cloned()));
+    assert!(Reverse(stream).iter().eq(elements.iter().rev().cloned()));
+
+    // the space-efficient committer reads the polynomial from disk.
+    let ck = CommitterKey::<Bls12_381>::new(elements.len(), 1, rng);
+    let ck_stream = CommitterKeyStream::from(&ck);
+    assert_eq!(ck_stream.commit(&Reverse(stream)), ck.commit(&elements));
+}
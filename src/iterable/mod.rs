@@ -2,9 +2,26 @@
 //!
 //! This library extends the abstraction layer provided by [`ark_std::iterable::Iterable`]
 //! with streams that repeat the same element over and over, and that iterate in reversed order.
+//! Derived streams, such as the entry-wise product of a vector with a [`Tensor`], can be composed
+//! with the adaptors of [`IterableExt`].
+//! With the `std` feature, streams read many times can be cached in memory with a [`CachedStream`].
+//! With the `mmap` feature, streams can also be read from files of field elements, see [`mmap`].
+//! With the `async` feature, streams can be fed by asynchronous sources, see [`async_stream`].
 
+#[cfg(feature = "async")]
+pub mod async_stream;
+#[cfg(feature = "std")]
+mod cache;
+pub mod combinators;
 pub mod dummy;
+#[cfg(feature = "mmap")]
+pub mod mmap;
 pub(crate) mod slice;
+mod tensor;
 
 pub use ark_std::iterable::Iterable;
+#[cfg(feature = "std")]
+pub use cache::{CacheStats, CachedIter, CachedStream};
+pub use combinators::IterableExt;
 pub use slice::Reverse;
+pub use tensor::{Tensor, TensorIter};
@@ -0,0 +1,242 @@
+//! Vectorized field kernels for folding and entry-wise products.
+//!
+//! The per-round foldings \\(f_{2i} + \rho f_{2i+1}\\) and the Hadamard products
+//! dominate the running time of the sumcheck provers.
+//! With the `simd` feature, both are computed [`LANES`] elements at a time via [`core::simd`],
+//! which lowers to AVX2 on x86-64 and to NEON on aarch64,
+//! for the scalar field of BLS12-381.
+//! The kernels are written for any field with four 64-bit limbs in Montgomery form,
+//! but only the fields recognized by [`try_fold`] and [`try_hadamard`] are dispatched to them.
+//! Elements are split into eight 32-bit limbs, one vector per limb,
+//! so that the products of limbs fit in the 64-bit lanes,
+//! and multiplied with the CIOS Montgomery multiplication.
+//! Every other field falls back to the scalar implementation.
+use ark_ff::{BigInt, Field, Fp256, MontBackend, MontConfig, Zero};
+use ark_std::vec::Vec;
+use ark_test_curves::bls12_381::FrConfig;
+use core::any::{Any, TypeId};
+use core::simd::Simd;
+use core::{array, slice};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// The number of field elements processed at once.
+pub(crate) const LANES: usize = 4;
+/// The number of 32-bit limbs of a field element with four 64-bit limbs.
+const LIMBS: usize = 8;
+const MASK: u64 = 0xffff_ffff;
+
+type Lanes = Simd<u64, LANES>;
+type Fp<P> = Fp256<MontBackend<P, 4>>;
+
+/// The field with a vectorized implementation: the scalar field of BLS12-381.
+type VectorizedField = Fp<FrConfig>;
+
+/// Return `elements` as elements of [`VectorizedField`], if `F` is that field.
+fn as_vectorized<F: Field>(elements: &[F]) -> Option<&[VectorizedField]> {
+    (TypeId::of::<F>() == TypeId::of::<VectorizedField>()).then(|| {
+        // SAFETY: `F` and `VectorizedField` are the same type.
+        unsafe { slice::from_raw_parts(elements.as_ptr().cast(), elements.len()) }
+    })
+}
+
+/// Append the folding of `f` with the challenge `r` to `folded`, see [`crate::misc::fold_polynomial`].
+///
+/// Return `false`, leaving `folded` untouched, when the field has no vectorized implementation.
+pub(crate) fn try_fold<F: Field>(f: &[F], r: F, folded: &mut Vec<F>) -> bool {
+    let (f, r, folded) = match (
+        as_vectorized(f),
+        (&r as &dyn Any).downcast_ref::<VectorizedField>(),
+        (folded as &mut dyn Any).downcast_mut::<Vec<VectorizedField>>(),
+    ) {
+        (Some(f), Some(&r), Some(folded)) => (f, r, folded),
+        _ => return false,
+    };
+    #[cfg(feature = "parallel")]
+    if f.len() >= crate::PARALLEL_THRESHOLD {
+        folded.par_extend(
+            f.par_chunks(crate::PARALLEL_THRESHOLD)
+                .flat_map_iter(|chunk| {
+                    let mut folded_chunk = Vec::with_capacity(crate::PARALLEL_THRESHOLD / 2);
+                    fold(chunk, r, &mut folded_chunk);
+                    folded_chunk
+                }),
+        );
+        return true;
+    }
+    fold(f, r, folded);
+    true
+}
+
+/// Return the entry-wise product of `lhs` and `rhs`, of the same length,
+/// or `None` when the field has no vectorized implementation.
+pub(crate) fn try_hadamard<F: Field>(lhs: &[F], rhs: &[F]) -> Option<Vec<F>> {
+    let (lhs, rhs) = (as_vectorized(lhs)?, as_vectorized(rhs)?);
+    #[cfg(feature = "parallel")]
+    let product = if lhs.len() >= crate::PARALLEL_THRESHOLD {
+        lhs.par_chunks(crate::PARALLEL_THRESHOLD)
+            .zip(rhs.par_chunks(crate::PARALLEL_THRESHOLD))
+            .flat_map_iter(|(lhs, rhs)| hadamard(lhs, rhs))
+            .collect()
+    } else {
+        hadamard(lhs, rhs)
+    };
+    #[cfg(not(feature = "parallel"))]
+    let product = hadamard(lhs, rhs);
+    (&mut Some(product) as &mut dyn Any)
+        .downcast_mut::<Option<Vec<F>>>()?
+        .take()
+}
+
+fn fold<P: MontConfig<4>>(f: &[Fp<P>], r: Fp<P>, folded: &mut Vec<Fp<P>>) {
+    folded.reserve((f.len() + 1) / 2);
+    let r_lanes = to_lanes([&r; LANES]);
+    let mut blocks = f.chunks_exact(2 * LANES);
+    for block in &mut blocks {
+        let odd = to_lanes(array::from_fn(|lane| &block[2 * lane + 1]));
+        let products = from_lanes::<P>(mul::<P>(&odd, &r_lanes));
+        folded.extend((0..LANES).map(|lane| block[2 * lane] + products[lane]));
+    }
+    let zero = Fp::<P>::zero();
+    folded.extend(
+        blocks
+            .remainder()
+            .chunks(2)
+            .map(|pair| pair[0] + r * pair.get(1).unwrap_or(&zero)),
+    );
+}
+
+fn hadamard<P: MontConfig<4>>(lhs: &[Fp<P>], rhs: &[Fp<P>]) -> Vec<Fp<P>> {
+    let mut product = Vec::with_capacity(lhs.len());
+    let lhs_blocks = lhs.chunks_exact(LANES);
+    let rhs_blocks = rhs.chunks_exact(LANES);
+    let (lhs_tail, rhs_tail) = (lhs_blocks.remainder(), rhs_blocks.remainder());
+    for (lhs_block, rhs_block) in lhs_blocks.zip(rhs_blocks) {
+        let lhs_lanes = to_lanes(array::from_fn(|lane| &lhs_block[lane]));
+        let rhs_lanes = to_lanes(array::from_fn(|lane| &rhs_block[lane]));
+        product.extend(from_lanes::<P>(mul::<P>(&lhs_lanes, &rhs_lanes)));
+    }
+    product.extend(lhs_tail.iter().zip(rhs_tail).map(|(x, y)| *x * y));
+    product
+}
+
+/// Split `elements` into vectors of 32-bit limbs, the \\(k\\)-th vector holding the \\(k\\)-th limbs.
+#[inline(always)]
+fn to_lanes<P: MontConfig<4>>(elements: [&Fp<P>; LANES]) -> [Lanes; LIMBS] {
+    array::from_fn(|k| {
+        Lanes::from_array(array::from_fn(|lane| {
+            let limb = elements[lane].0 .0[k / 2];
+            if k % 2 == 0 {
+                limb & MASK
+            } else {
+                limb >> 32
+            }
+        }))
+    })
+}
+
+/// Join the vectors of 32-bit limbs `limbs` into field elements, see [`to_lanes`].
+#[inline(always)]
+fn from_lanes<P: MontConfig<4>>(limbs: [Lanes; LIMBS]) -> [Fp<P>; LANES] {
+    let limbs = limbs.map(|limb| limb.to_array());
+    array::from_fn(|lane| {
+        Fp::<P>::new_unchecked(BigInt(array::from_fn(|i| {
+            limbs[2 * i][lane] | (limbs[2 * i + 1][lane] << 32)
+        })))
+    })
+}
+
+/// Return the Montgomery product of `a` and `b`, lane by lane.
+///
+/// This is the coarsely integrated operand scanning (CIOS) multiplication over 32-bit words:
+/// the product of two words and the two carries fit in 64 bits.
+#[inline(always)]
+#[allow(clippy::needless_range_loop)]
+fn mul<P: MontConfig<4>>(a: &[Lanes; LIMBS], b: &[Lanes; LIMBS]) -> [Lanes; LIMBS] {
+    let modulus: [u64; LIMBS] = array::from_fn(|k| {
+        let limb = P::MODULUS.0[k / 2];
+        if k % 2 == 0 {
+            limb & MASK
+        } else {
+            limb >> 32
+        }
+    });
+    let modulus = modulus.map(Lanes::splat);
+    // -p^{-1} mod 2^64 reduces to -p^{-1} mod 2^32.
+    let inv = Lanes::splat(P::INV & MASK);
+    let mask = Lanes::splat(MASK);
+    let shift = Lanes::splat(32);
+
+    let mut t = [Lanes::splat(0); LIMBS + 2];
+    for &b_i in b {
+        let mut carry = Lanes::splat(0);
+        for j in 0..LIMBS {
+            let sum = t[j] + a[j] * b_i + carry;
+            t[j] = sum & mask;
+            carry = sum >> shift;
+        }
+        let sum = t[LIMBS] + carry;
+        t[LIMBS] = sum & mask;
+        t[LIMBS + 1] = sum >> shift;
+
+        let m = (t[0] * inv) & mask;
+        let mut carry = (t[0] + m * modulus[0]) >> shift;
+        for j in 1..LIMBS {
+            let sum = t[j] + m * modulus[j] + carry;
+            t[j - 1] = sum & mask;
+            carry = sum >> shift;
+        }
+        let sum = t[LIMBS] + carry;
+        t[LIMBS - 1] = sum & mask;
+        t[LIMBS] = t[LIMBS + 1] + (sum >> shift);
+    }
+
+    // the result is smaller than 2p: subtract p if it is not smaller than p.
+    let one = Lanes::splat(1);
+    let mut borrow = Lanes::splat(0);
+    let mut reduced = [Lanes::splat(0); LIMBS];
+    for j in 0..LIMBS {
+        let difference = (t[j] | (one << shift)) - modulus[j] - borrow;
+        reduced[j] = difference & mask;
+        borrow = one - (difference >> shift);
+    }
+    // keep t (all ones) if t < p, i.e. the subtraction borrowed and t has no overflow limb.
+    let keep = Lanes::splat(0) - (borrow & (one - t[LIMBS]));
+    array::from_fn(|j| (t[j] & keep) | (reduced[j] & !keep))
+}
+
+#[test]
+fn test_kernels() {
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::{Fq, Fr};
+
+    let rng = &mut ark_std::test_rng();
+    for len in [0, 1, 2 * LANES - 1, 2 * LANES, 6 * LANES + 3] {
+        let mut f = (0..len).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+        let mut g = (0..len).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+        // the largest element, whose square is the largest product.
+        if len > 0 {
+            f[0] = -Fr::from(1u64);
+            g[0] = -Fr::from(1u64);
+        }
+        let r = Fr::rand(rng);
+
+        let expected = f
+            .chunks(2)
+            .map(|pair| pair[0] + r * pair.get(1).unwrap_or(&Fr::from(0u64)))
+            .collect::<Vec<_>>();
+        let mut folded = Vec::new();
+        assert!(try_fold(&f, r, &mut folded));
+        assert_eq!(folded, expected);
+        let expected = f.iter().zip(&g).map(|(x, y)| *x * y).collect::<Vec<_>>();
+        assert_eq!(try_hadamard(&f, &g), Some(expected));
+    }
+
+    // fields with a different number of limbs are not vectorized.
+    let f = (0..2 * LANES).map(|_| Fq::rand(rng)).collect::<Vec<_>>();
+    let mut folded = Vec::new();
+    assert!(!try_fold(&f, Fq::rand(rng), &mut folded));
+    assert!(folded.is_empty());
+    assert_eq!(try_hadamard(&f, &f), None);
+}
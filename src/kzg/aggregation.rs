@@ -0,0 +1,622 @@
+//! Aggregation of many KZG evaluation proofs into a single logarithmic-size proof,
+//! following the inner-pairing-product arguments of
+//! [[BMMTV21](https://eprint.iacr.org/2019/1177.pdf)] and [[GMN21](https://eprint.iacr.org/2021/529.pdf)].
+//!
+//! Let $(C_i, z_i, y_i, \pi_i)$ for $i < n$ be the claims to aggregate,
+//! each satisfying $e(C_i - y_i G + z_i \pi_i, H) = e(\pi_i, \tau H)$.
+//! 1. The prover commits to the vector $\vec \pi$ as
+//!    $T_a = \prod_i e(\pi_i, a^i H)$ and $T_b = \prod_i e(\pi_i, b^i H)$,
+//!    where $a, b$ are the trapdoors of the [`AggregationKey`].
+//! 2. Given a random challenge $r$, the prover sends
+//!    $A = \sum_i r^i \pi_i$ and $B = \sum_i r^i z_i \pi_i$, so that all claims are implied by
+//!    $e(\sum_i r^i (C_i - y_i G) + B, H) = e(A, \tau H)$.
+//! 3. The prover shows that $A$ and $B$ are consistent with $T_a$ and $T_b$ via a
+//!    generalized inner product argument, halving $\vec \pi$, the scalars, and the key at each round.
+//! 4. The folded keys $f(a) H$ and $f(b) H$ are checked with a KZG evaluation proof
+//!    of the key polynomial $f(X) = \prod_k (1 + x_k^{-1} X^{2^{\ell - k - 1}})$ at a random point.
+//!
+//! The aggregated proof has size $O(\log n)$;
+//! the verifier still needs the $n$ claims, and computes a single multi-scalar multiplication over the commitments.
+use ark_ec::pairing::{Pairing, PairingOutput};
+use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::{Field, One, Zero};
+use ark_serialize::*;
+use ark_std::rand::RngCore;
+use ark_std::vec::Vec;
+use ark_std::UniformRand;
+use merlin::Transcript;
+
+use crate::misc::{batch_inverse, powers};
+use crate::transcript::GeminiTranscript;
+
+use super::{
+    linear_quotient, msm, Commitment, EvaluationProof, VerificationError, VerificationResult,
+    VerifierKey,
+};
+
+/// The key for aggregating evaluation proofs,
+/// consisting of the powers of two independent trapdoors \\(a, b\\) over \\(\GG_2\\).
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AggregationKey<E: Pairing> {
+    powers_of_a_g2: Vec<E::G2Affine>,
+    powers_of_b_g2: Vec<E::G2Affine>,
+    vk: AggregationVerifierKey<E>,
+}
+
+/// The key for verifying aggregated evaluation proofs.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AggregationVerifierKey<E: Pairing> {
+    g: E::G1Affine,
+    a_g: E::G1Affine,
+    b_g: E::G1Affine,
+    g2: E::G2Affine,
+}
+
+/// The messages sent by the prover in a single folding round.
+#[derive(CanonicalSerialize, Clone, Debug, PartialEq, Eq)]
+pub struct AggregationRound<E: Pairing> {
+    /// The cross terms of the commitment \\(T_a\\).
+    pub com_a: [PairingOutput<E>; 2],
+    /// The cross terms of the commitment \\(T_b\\).
+    pub com_b: [PairingOutput<E>; 2],
+    /// The cross terms of each batched evaluation proof.
+    pub batched: Vec<[E::G1; 2]>,
+}
+
+/// A single proof for many evaluation claims.
+#[derive(CanonicalSerialize, Clone, Debug, PartialEq, Eq)]
+pub struct AggregatedProof<E: Pairing> {
+    /// The commitments \\(T_a, T_b\\) to the evaluation proofs.
+    pub com: [PairingOutput<E>; 2],
+    /// The batched evaluation proofs, \\(A\\) and \\(B\\) for single-point claims.
+    pub batched: Vec<E::G1>,
+    /// The messages of each folding round.
+    pub rounds: Vec<AggregationRound<E>>,
+    /// The folded evaluation proof.
+    pub final_proof: E::G1Affine,
+    /// The folded keys \\(f(a) H\\) and \\(f(b) H\\).
+    pub final_key: [E::G2Affine; 2],
+    /// The evaluation proofs for the folded keys.
+    pub key_proofs: [E::G2; 2],
+}
+
+/// Return the coefficients of the key polynomial \\(f(X) = \prod_k (1 + c_k X^{h_k})\\),
+/// where \\(c_k\\) is the inverse challenge of round \\(k\\) and \\(h_k = m / 2^{k+1}\\).
+fn key_polynomial<F: Field>(inverse_challenges: &[F], m: usize) -> Vec<F> {
+    (0..m)
+        .map(|i| {
+            inverse_challenges
+                .iter()
+                .enumerate()
+                .filter(|&(k, _)| i & (m >> (k + 1)) != 0)
+                .map(|(_, c)| *c)
+                .product()
+        })
+        .collect()
+}
+
+/// Evaluate the key polynomial at `z`, in logarithmic time.
+fn evaluate_key_polynomial<F: Field>(inverse_challenges: &[F], m: usize, z: &F) -> F {
+    inverse_challenges
+        .iter()
+        .enumerate()
+        .map(|(k, c)| F::one() + *c * z.pow([(m >> (k + 1)) as u64]))
+        .product()
+}
+
+/// Return the inner pairing product \\(\prod_i e(P_i, Q_i)\\).
+fn inner_pairing<E: Pairing>(left: &[E::G1Affine], right: &[E::G2Affine]) -> PairingOutput<E> {
+    E::multi_pairing(left.iter().copied(), right.iter().copied())
+}
+
+/// Fold `vector` as \\(\vec v_L + x \vec v_R\\).
+fn fold_scalars<F: Field>(vector: &[F], x: &F) -> Vec<F> {
+    let (left, right) = vector.split_at(vector.len() / 2);
+    left.iter().zip(right).map(|(l, r)| *l + *r * x).collect()
+}
+
+/// Fold `points` as \\(\vec P_L + x \vec P_R\\).
+fn fold_points<G: AffineRepr>(points: &[G], x: &G::ScalarField) -> Vec<G> {
+    let (left, right) = points.split_at(points.len() / 2);
+    let folded = left
+        .iter()
+        .zip(right)
+        .map(|(l, r)| l.into_group() + *r * x)
+        .collect::<Vec<_>>();
+    G::Group::normalize_batch(&folded)
+}
+
+/// Add the statements to aggregate to the transcript, and return the batching challenge.
+fn batching_challenge<E: Pairing>(
+    transcript: &mut Transcript,
+    commitments: &[Commitment<E>],
+    points: &[E::ScalarField],
+    evaluations: &[E::ScalarField],
+    com: &[PairingOutput<E>; 2],
+) -> E::ScalarField {
+    transcript.append_serializable(b"aggregation-commitments", &commitments.to_vec());
+    transcript.append_serializable(b"aggregation-points", &points.to_vec());
+    transcript.append_serializable(b"aggregation-evaluations", &evaluations.to_vec());
+    transcript.append_serializable(b"aggregation-com", com);
+    transcript.get_challenge(b"aggregation-r")
+}
+
+/// Add the equations to aggregate to the transcript, and return the batching challenge.
+fn equations_challenge<E: Pairing>(
+    transcript: &mut Transcript,
+    equations: &[(E::G1, Vec<E::ScalarField>)],
+    com: &[PairingOutput<E>; 2],
+) -> E::ScalarField {
+    for (lhs, vanishing) in equations {
+        transcript.append_serializable(b"aggregation-lhs", lhs);
+        transcript.append_serializable(b"aggregation-vanishing", vanishing);
+    }
+    transcript.append_serializable(b"aggregation-com", com);
+    transcript.get_challenge(b"aggregation-r")
+}
+
+/// Return the vectors \\(\vec s_k = (r^i Z_{i, k})_i\\) for each coefficient \\(k\\)
+/// of the vanishing polynomials \\(Z_i\\) in `equations`.
+fn equations_scalars<E: Pairing>(
+    equations: &[(E::G1, Vec<E::ScalarField>)],
+    r: E::ScalarField,
+) -> Vec<Vec<E::ScalarField>> {
+    let degree = equations[0].1.len();
+    let powers_of_r = powers(r, equations.len());
+    (0..degree)
+        .map(|k| {
+            equations
+                .iter()
+                .zip(&powers_of_r)
+                .map(|((_, vanishing), s)| vanishing[k] * s)
+                .collect()
+        })
+        .collect()
+}
+
+impl<E: Pairing> AggregationKey<E> {
+    /// Generate an aggregation key for up to `max_proofs` evaluation proofs.
+    pub fn new(max_proofs: usize, rng: &mut impl RngCore) -> Self {
+        let len = max_proofs.next_power_of_two();
+        let a = E::ScalarField::rand(rng);
+        let b = E::ScalarField::rand(rng);
+        let g = E::G1::rand(rng);
+        let g2 = E::G2::rand(rng);
+
+        let powers_of_g2 = |trapdoor| {
+            let powers = powers(trapdoor, len)
+                .iter()
+                .map(|t| g2 * t)
+                .collect::<Vec<_>>();
+            E::G2::normalize_batch(&powers)
+        };
+        AggregationKey {
+            powers_of_a_g2: powers_of_g2(a),
+            powers_of_b_g2: powers_of_g2(b),
+            vk: AggregationVerifierKey {
+                g: g.into_affine(),
+                a_g: (g * a).into_affine(),
+                b_g: (g * b).into_affine(),
+                g2: g2.into_affine(),
+            },
+        }
+    }
+
+    /// Return the maximum number of evaluation proofs that can be aggregated.
+    pub fn max_proofs(&self) -> usize {
+        self.powers_of_a_g2.len()
+    }
+
+    /// Return the verification key for the aggregated proofs.
+    pub fn verifier_key(&self) -> &AggregationVerifierKey<E> {
+        &self.vk
+    }
+
+    /// Aggregate the evaluation proofs `proofs`,
+    /// where `proofs[i]` claims that the polynomial committed in `commitments[i]`
+    /// evaluates to `evaluations[i]` at `points[i]`.
+    pub fn aggregate(
+        &self,
+        transcript: &mut Transcript,
+        commitments: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evaluations: &[E::ScalarField],
+        proofs: &[EvaluationProof<E>],
+    ) -> AggregatedProof<E> {
+        let n = proofs.len();
+        assert!(commitments.len() == n && points.len() == n && evaluations.len() == n);
+        let (proofs, com) = self.commit_proofs(proofs);
+        let r = batching_challenge(transcript, commitments, points, evaluations, &com);
+
+        let scalars_a = powers(r, n);
+        let scalars_b = scalars_a
+            .iter()
+            .zip(points)
+            .map(|(s, z)| *s * z)
+            .collect::<Vec<_>>();
+        self.fold(transcript, proofs, vec![scalars_a, scalars_b], com)
+    }
+
+    /// Aggregate the multi-point evaluation proofs `proofs`,
+    /// where `proofs[i]` is valid if and only if \\(e(F_i - I_i, H) = e(\pi_i, Z_i(\tau) H)\\)
+    /// for `equations[i]` consisting of \\(F_i - I_i\\) and the coefficients of \\(Z_i\\),
+    /// as returned by [`VerifierKey::multi_points_equation_coeffs`].
+    ///
+    /// All the vanishing polynomials \\(Z_i\\) must have the same degree.
+    pub(crate) fn aggregate_equations(
+        &self,
+        transcript: &mut Transcript,
+        equations: &[(E::G1, Vec<E::ScalarField>)],
+        proofs: &[EvaluationProof<E>],
+    ) -> AggregatedProof<E> {
+        assert!(!equations.is_empty() && equations.len() == proofs.len());
+        assert!(equations
+            .iter()
+            .all(|(_, vanishing)| vanishing.len() == equations[0].1.len()));
+        let (proofs, com) = self.commit_proofs(proofs);
+        let r = equations_challenge(transcript, equations, &com);
+        self.fold(
+            transcript,
+            proofs,
+            equations_scalars::<E>(equations, r),
+            com,
+        )
+    }
+
+    /// Pad the evaluation proofs `proofs` to the next power of two,
+    /// and return them together with their commitments \\(T_a, T_b\\).
+    fn commit_proofs(
+        &self,
+        proofs: &[EvaluationProof<E>],
+    ) -> (Vec<E::G1Affine>, [PairingOutput<E>; 2]) {
+        assert!(
+            proofs.len() <= self.max_proofs(),
+            "too many proofs for the aggregation key"
+        );
+        let m = proofs.len().next_power_of_two();
+        let mut proofs = proofs.iter().map(|proof| proof.0).collect::<Vec<_>>();
+        proofs.resize(m, E::G1::zero());
+        let proofs = E::G1::normalize_batch(&proofs);
+        let com = [
+            inner_pairing::<E>(&proofs, &self.powers_of_a_g2[..m]),
+            inner_pairing::<E>(&proofs, &self.powers_of_b_g2[..m]),
+        ];
+        (proofs, com)
+    }
+
+    /// Prove that the evaluation proofs `proofs`, committed in `com`,
+    /// are consistent with the batched proofs \\(\sum_i s_{k, i} \pi_i\\)
+    /// for each vector of scalars \\(\vec s_k\\) in `scalars`.
+    fn fold(
+        &self,
+        transcript: &mut Transcript,
+        mut proofs: Vec<E::G1Affine>,
+        mut scalars: Vec<Vec<E::ScalarField>>,
+        com: [PairingOutput<E>; 2],
+    ) -> AggregatedProof<E> {
+        let m = proofs.len();
+        let mut key_a = self.powers_of_a_g2[..m].to_vec();
+        let mut key_b = self.powers_of_b_g2[..m].to_vec();
+        scalars
+            .iter_mut()
+            .for_each(|s| s.resize(m, E::ScalarField::zero()));
+        let batched = scalars
+            .iter()
+            .map(|s| msm::<E>(&proofs, s))
+            .collect::<Vec<_>>();
+        transcript.append_serializable(b"aggregation-batched", &batched);
+
+        let mut rounds = Vec::new();
+        let mut inverse_challenges = Vec::new();
+        while proofs.len() > 1 {
+            let half = proofs.len() / 2;
+            let (proofs_l, proofs_r) = proofs.split_at(half);
+            let round = AggregationRound {
+                com_a: [
+                    inner_pairing::<E>(proofs_l, &key_a[half..]),
+                    inner_pairing::<E>(proofs_r, &key_a[..half]),
+                ],
+                com_b: [
+                    inner_pairing::<E>(proofs_l, &key_b[half..]),
+                    inner_pairing::<E>(proofs_r, &key_b[..half]),
+                ],
+                batched: scalars
+                    .iter()
+                    .map(|s| {
+                        [
+                            msm::<E>(proofs_l, &s[half..]),
+                            msm::<E>(proofs_r, &s[..half]),
+                        ]
+                    })
+                    .collect(),
+            };
+            transcript.append_serializable(b"aggregation-round", &round);
+            let x = transcript.get_challenge::<E::ScalarField>(b"aggregation-x");
+            let x_inverse = x.inverse().unwrap();
+
+            proofs = fold_points(&proofs, &x);
+            scalars = scalars
+                .iter()
+                .map(|s| fold_scalars(s, &x_inverse))
+                .collect();
+            key_a = fold_points(&key_a, &x_inverse);
+            key_b = fold_points(&key_b, &x_inverse);
+            inverse_challenges.push(x_inverse);
+            rounds.push(round);
+        }
+
+        let final_key = [key_a[0], key_b[0]];
+        transcript.append_serializable(b"aggregation-final-proof", &proofs[0]);
+        transcript.append_serializable(b"aggregation-final-key", &final_key);
+        let z = transcript.get_challenge::<E::ScalarField>(b"aggregation-z");
+        let (_, quotient) = linear_quotient(&key_polynomial(&inverse_challenges, m), &z);
+        let key_proofs = [
+            E::G2::msm_unchecked(&self.powers_of_a_g2, &quotient),
+            E::G2::msm_unchecked(&self.powers_of_b_g2, &quotient),
+        ];
+
+        AggregatedProof {
+            com,
+            batched,
+            rounds,
+            final_proof: proofs[0],
+            final_key,
+            key_proofs,
+        }
+    }
+}
+
+impl<E: Pairing> From<&AggregationKey<E>> for AggregationVerifierKey<E> {
+    fn from(key: &AggregationKey<E>) -> Self {
+        key.vk.clone()
+    }
+}
+
+impl<E: Pairing> AggregationVerifierKey<E> {
+    /// Check that \\(f(\alpha) H\\) is the evaluation at \\(\alpha\\) of the key polynomial,
+    /// given \\(\alpha G\\), the evaluation `f_z` of the key polynomial at `z`, and a KZG proof.
+    fn check_key(
+        &self,
+        alpha_g: &E::G1Affine,
+        key: &E::G2Affine,
+        z: &E::ScalarField,
+        f_z: &E::ScalarField,
+        proof: &E::G2,
+    ) -> bool {
+        let lhs = key.into_group() - self.g2 * f_z;
+        let rhs = alpha_g.into_group() - self.g * z;
+        E::multi_pairing([self.g.into_group(), -rhs], [lhs, *proof]).is_zero()
+    }
+
+    /// Verify that the polynomials committed in `commitments` evaluate to `evaluations` at `points`,
+    /// given the aggregated proof `proof` and the verifier key `vk` for the evaluation proofs.
+    pub fn verify(
+        &self,
+        vk: &VerifierKey<E>,
+        transcript: &mut Transcript,
+        commitments: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evaluations: &[E::ScalarField],
+        proof: &AggregatedProof<E>,
+    ) -> VerificationResult {
+        let n = commitments.len();
+        if points.len() != n || evaluations.len() != n || !self.check_shape(n, 2, proof) {
+            return Err(VerificationError);
+        }
+        let r = batching_challenge(transcript, commitments, points, evaluations, &proof.com);
+
+        let scalars_a = powers(r, n);
+        let scalars_b = scalars_a
+            .iter()
+            .zip(points)
+            .map(|(s, z)| *s * z)
+            .collect::<Vec<_>>();
+        if !self.check_folding(transcript, vec![scalars_a, scalars_b], proof) {
+            return Err(VerificationError);
+        }
+
+        // e(\sum_i r^i (C_i - y_i G) + B, H) = e(A, \tau H)
+        let mut bases = commitments.iter().map(|c| c.0).collect::<Vec<_>>();
+        let mut scalars = powers(r, n);
+        let y = scalars
+            .iter()
+            .zip(evaluations)
+            .map(|(s, y)| *s * y)
+            .sum::<E::ScalarField>();
+        bases.push(vk.powers_of_g[0].into_group());
+        scalars.push(-y);
+        let lhs =
+            E::G1::msm_unchecked(&E::G1::normalize_batch(&bases), &scalars) + proof.batched[1];
+        if E::multi_pairing(
+            [lhs, -proof.batched[0]],
+            [vk.powers_of_g2[0], vk.powers_of_g2[1]],
+        )
+        .is_zero()
+        {
+            Ok(())
+        } else {
+            Err(VerificationError)
+        }
+    }
+
+    /// Verify the multi-point evaluation `equations`, as in [`AggregationKey::aggregate_equations`],
+    /// given the aggregated proof `proof` and the verifier key `vk` for the evaluation proofs.
+    pub(crate) fn verify_equations(
+        &self,
+        vk: &VerifierKey<E>,
+        transcript: &mut Transcript,
+        equations: &[(E::G1, Vec<E::ScalarField>)],
+        proof: &AggregatedProof<E>,
+    ) -> VerificationResult {
+        let degree = equations
+            .first()
+            .map_or(0, |(_, vanishing)| vanishing.len());
+        if equations
+            .iter()
+            .any(|(_, vanishing)| vanishing.len() != degree)
+            || degree > vk.powers_of_g2.len()
+            || !self.check_shape(equations.len(), degree, proof)
+        {
+            return Err(VerificationError);
+        }
+        let r = equations_challenge(transcript, equations, &proof.com);
+        if !self.check_folding(transcript, equations_scalars::<E>(equations, r), proof) {
+            return Err(VerificationError);
+        }
+
+        // e(\sum_i r^i (F_i - I_i), H) = \prod_k e(A_k, \tau^k H)
+        let lhs = equations
+            .iter()
+            .zip(powers(r, equations.len()))
+            .map(|((lhs, _), s)| *lhs * s)
+            .sum::<E::G1>();
+        let g1 = ark_std::iter::once(lhs).chain(proof.batched.iter().map(|a| -*a));
+        let g2 = ark_std::iter::once(vk.powers_of_g2[0])
+            .chain(vk.powers_of_g2[..degree].iter().copied());
+        if E::multi_pairing(g1, g2).is_zero() {
+            Ok(())
+        } else {
+            Err(VerificationError)
+        }
+    }
+
+    /// Check that `proof` aggregates `n > 0` evaluation proofs into `num_batched` batched proofs.
+    fn check_shape(&self, n: usize, num_batched: usize, proof: &AggregatedProof<E>) -> bool {
+        let m = n.next_power_of_two();
+        n > 0
+            && proof.rounds.len() == m.trailing_zeros() as usize
+            && proof.batched.len() == num_batched
+            && proof
+                .rounds
+                .iter()
+                .all(|round| round.batched.len() == num_batched)
+    }
+
+    /// Check the inner product argument in `proof`,
+    /// for the batched proofs \\(\sum_i s_{k, i} \pi_i\\) with each vector of scalars \\(\vec s_k\\) in `scalars`.
+    fn check_folding(
+        &self,
+        transcript: &mut Transcript,
+        mut scalars: Vec<Vec<E::ScalarField>>,
+        proof: &AggregatedProof<E>,
+    ) -> bool {
+        let m = 1 << proof.rounds.len();
+        scalars
+            .iter_mut()
+            .for_each(|s| s.resize(m, E::ScalarField::zero()));
+        transcript.append_serializable(b"aggregation-batched", &proof.batched);
+
+        let challenges = proof
+            .rounds
+            .iter()
+            .map(|round| {
+                transcript.append_serializable(b"aggregation-round", round);
+                transcript.get_challenge::<E::ScalarField>(b"aggregation-x")
+            })
+            .collect::<Vec<_>>();
+        let inverse_challenges = match batch_inverse(&challenges) {
+            Some(inverse_challenges) => inverse_challenges,
+            None => return false,
+        };
+
+        let [mut com_a, mut com_b] = proof.com;
+        let mut batched = proof.batched.clone();
+        for ((round, &x), &x_inverse) in proof
+            .rounds
+            .iter()
+            .zip(&challenges)
+            .zip(&inverse_challenges)
+        {
+            com_a += round.com_a[0] * x_inverse + round.com_a[1] * x;
+            com_b += round.com_b[0] * x_inverse + round.com_b[1] * x;
+            for ((a, cross), s) in batched.iter_mut().zip(&round.batched).zip(&mut scalars) {
+                *a += cross[0] * x_inverse + cross[1] * x;
+                *s = fold_scalars(s, &x_inverse);
+            }
+        }
+
+        transcript.append_serializable(b"aggregation-final-proof", &proof.final_proof);
+        transcript.append_serializable(b"aggregation-final-key", &proof.final_key);
+        let z = transcript.get_challenge::<E::ScalarField>(b"aggregation-z");
+        let f_z = evaluate_key_polynomial(&inverse_challenges, m, &z);
+
+        let final_proof = proof.final_proof;
+        com_a == E::pairing(final_proof, proof.final_key[0])
+            && com_b == E::pairing(final_proof, proof.final_key[1])
+            && batched
+                .iter()
+                .zip(&scalars)
+                .all(|(a, s)| *a == final_proof * s[0])
+            && self.check_key(
+                &self.a_g,
+                &proof.final_key[0],
+                &z,
+                &f_z,
+                &proof.key_proofs[0],
+            )
+            && self.check_key(
+                &self.b_g,
+                &proof.final_key[1],
+                &z,
+                &f_z,
+                &proof.key_proofs[1],
+            )
+    }
+}
+
+#[test]
+fn test_aggregation() {
+    use crate::kzg::CommitterKey;
+    use ark_poly::univariate::DensePolynomial;
+    use ark_poly::DenseUVPolynomial;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+
+    let rng = &mut ark_std::test_rng();
+    let ck = CommitterKey::<Bls12_381>::new(15, 1, rng);
+    let vk = VerifierKey::from(&ck);
+    let ak = AggregationKey::<Bls12_381>::new(8, rng);
+    let avk = AggregationVerifierKey::from(&ak);
+
+    let polynomials = (0..5)
+        .map(|_| DensePolynomial::<Fr>::rand(15, rng))
+        .collect::<Vec<_>>();
+    let commitments = polynomials.iter().map(|p| ck.commit(p)).collect::<Vec<_>>();
+    let points = (0..5).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+    let (evaluations, proofs): (Vec<_>, Vec<_>) = polynomials
+        .iter()
+        .zip(&points)
+        .map(|(p, z)| ck.open(p, z))
+        .unzip();
+
+    let proof = ak.aggregate(
+        &mut Transcript::new(b"test"),
+        &commitments,
+        &points,
+        &evaluations,
+        &proofs,
+    );
+    assert_eq!(proof.rounds.len(), 3);
+    assert!(avk
+        .verify(
+            &vk,
+            &mut Transcript::new(b"test"),
+            &commitments,
+            &points,
+            &evaluations,
+            &proof
+        )
+        .is_ok());
+
+    let mut wrong_evaluations = evaluations;
+    wrong_evaluations[4] += Fr::one();
+    assert!(avk
+        .verify(
+            &vk,
+            &mut Transcript::new(b"test"),
+            &commitments,
+            &points,
+            &wrong_evaluations,
+            &proof
+        )
+        .is_err());
+}
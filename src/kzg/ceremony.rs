@@ -0,0 +1,436 @@
+//! Loading of the committer key from existing powers-of-tau ceremonies.
+//!
+//! Two transcript formats are supported:
+//! - the [snarkjs](https://github.com/iden3/snarkjs) `.ptau` format,
+//!   where field elements are stored in little-endian Montgomery form;
+//! - the Zcash/[perpetual powers of tau](https://github.com/weijiekoh/perpetualpowersoftau) `challenge` format,
+//!   where points are stored uncompressed and field elements are in big-endian form.
+//!
+//! In both cases the powers $\alpha \tau^i G$ are used as blinding generators for hiding commitments,
+//! as their discrete logarithm with respect to $G$ is unknown.
+//!
+//! Transcripts are untrusted input:
+//! every point read from a transcript is checked for being on the curve and in the prime-order subgroup,
+//! which dominates the time to load large keys.
+use ark_ec::{pairing::Pairing, AffineRepr};
+use ark_ff::{BigInteger, Field, PrimeField};
+use ark_serialize::CanonicalDeserialize;
+use ark_serialize::CanonicalSerialize;
+use ark_std::io::Read;
+use ark_std::vec::Vec;
+
+use crate::misc::ceil_div;
+
+use super::time::{CommitterKey, HIDING_BOUND};
+use super::{SrsError, VerifierKey};
+
+/// The magic string at the beginning of a snarkjs `.ptau` file.
+const PTAU_MAGIC: &[u8; 4] = b"ptau";
+/// The snarkjs section identifiers we are interested in.
+const PTAU_SECTION_HEADER: u32 = 1;
+const PTAU_SECTION_TAU_G1: u32 = 2;
+const PTAU_SECTION_TAU_G2: u32 = 3;
+const PTAU_SECTION_ALPHA_TAU_G1: u32 = 4;
+/// Length of the hash prepended to a Zcash/perpetual powers of tau transcript.
+const ZCASH_HASH_LENGTH: usize = 64;
+
+/// The representation of base-field elements in a transcript.
+#[derive(Clone, Copy)]
+enum Encoding {
+    /// Little-endian, Montgomery form, coefficients of extension fields in increasing order (snarkjs).
+    Montgomery,
+    /// Big-endian, coefficients of extension fields in decreasing order, flags in the leading bits (Zcash).
+    BigEndian,
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32, SrsError> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf).map_err(|_| SrsError::Io)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> Result<u64, SrsError> {
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf).map_err(|_| SrsError::Io)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+/// Discard the next `len` bytes of `reader`.
+fn skip<R: Read>(reader: &mut R, mut len: u64) -> Result<(), SrsError> {
+    let mut buf = [0u8; 1 << 12];
+    while len > 0 {
+        let step = u64::min(len, buf.len() as u64) as usize;
+        reader
+            .read_exact(&mut buf[..step])
+            .map_err(|_| SrsError::Io)?;
+        len -= step as u64;
+    }
+    Ok(())
+}
+
+/// Return the size in bytes of a single point in the transcript.
+fn point_size<G: AffineRepr>(n8: usize) -> usize {
+    2 * n8 * G::BaseField::extension_degree() as usize
+}
+
+/// Read a single curve point from `reader`, encoded as described by `encoding`,
+/// where each base prime field element takes `n8` bytes.
+fn read_point<G: AffineRepr, R: Read>(
+    reader: &mut R,
+    n8: usize,
+    encoding: Encoding,
+) -> Result<G, SrsError> {
+    type Fq<G> = <<G as AffineRepr>::BaseField as Field>::BasePrimeField;
+
+    let degree = G::BaseField::extension_degree() as usize;
+    let mut raw = vec![0u8; point_size::<G>(n8)];
+    reader.read_exact(&mut raw).map_err(|_| SrsError::Io)?;
+
+    let mut coordinates = raw
+        .chunks_exact(n8)
+        .map(|chunk| chunk.to_vec())
+        .collect::<Vec<_>>();
+    match encoding {
+        Encoding::Montgomery => {
+            let r_inv = Fq::<G>::from(2u64)
+                .pow([8 * n8 as u64])
+                .inverse()
+                .ok_or(SrsError::InvalidFormat("invalid field size"))?;
+            coordinates.iter_mut().for_each(|bytes| {
+                let element = Fq::<G>::from_le_bytes_mod_order(bytes) * r_inv;
+                bytes.clear();
+                element.serialize_uncompressed(bytes).unwrap();
+            });
+        }
+        Encoding::BigEndian => {
+            if raw[0] & 0x40 != 0 {
+                return Err(SrsError::InvalidFormat("unexpected point at infinity"));
+            }
+            // remove the flags and move to little-endian.
+            coordinates[0][0] &= 0x3f;
+            coordinates.iter_mut().for_each(|bytes| bytes.reverse());
+            // coefficients of extension fields are in decreasing order.
+            coordinates
+                .chunks_exact_mut(degree)
+                .for_each(|coordinate| coordinate.reverse());
+        }
+    }
+
+    let bytes = coordinates.concat();
+    G::deserialize_uncompressed(&bytes[..])
+        .map_err(|_| SrsError::InvalidFormat("invalid curve point"))
+}
+
+/// Check that a section of `size` bytes holds exactly `total` points.
+fn check_section<G: AffineRepr>(size: u64, total: usize, n8: usize) -> Result<(), SrsError> {
+    match total.checked_mul(point_size::<G>(n8)) {
+        Some(expected) if expected as u64 == size => Ok(()),
+        _ => Err(SrsError::InvalidFormat("invalid section size")),
+    }
+}
+
+/// Return the number of powers \\(2^{\text{power}}\\) of a ceremony,
+/// making sure that the \\(2^{\text{power}+1} - 1\\) powers in G1 can be counted.
+fn ceremony_len(power: usize) -> Result<usize, SrsError> {
+    if power + 1 >= usize::BITS as usize {
+        return Err(SrsError::InvalidFormat("invalid power"));
+    }
+    Ok(1 << power)
+}
+
+/// Read `len` points from `reader`, and then skip the remaining `total - len` in the section.
+fn read_points<G: AffineRepr, R: Read>(
+    reader: &mut R,
+    len: usize,
+    total: usize,
+    n8: usize,
+    encoding: Encoding,
+) -> Result<Vec<G>, SrsError> {
+    if len > total {
+        return Err(SrsError::NotEnoughPowers);
+    }
+    let points = (0..len)
+        .map(|_| read_point::<G, R>(reader, n8, encoding))
+        .collect::<Result<Vec<_>, _>>()?;
+    skip(reader, ((total - len) * point_size::<G>(n8)) as u64)?;
+    Ok(points)
+}
+
+impl<E: Pairing> CommitterKey<E> {
+    /// Construct the committer key from a snarkjs `.ptau` transcript.
+    ///
+    /// Given a reader over the transcript `reader`,
+    /// a degree bound `max_degree`,
+    /// and an evaluation point bound `max_eval_points`,
+    /// construct the committer key using the first powers of \\(\tau\\) from the ceremony.
+    pub fn from_ptau<R: Read>(
+        mut reader: R,
+        max_degree: usize,
+        max_eval_points: usize,
+    ) -> Result<Self, SrsError> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic).map_err(|_| SrsError::Io)?;
+        if &magic != PTAU_MAGIC {
+            return Err(SrsError::InvalidFormat("invalid magic string"));
+        }
+        let _version = read_u32(&mut reader)?;
+        let sections = read_u32(&mut reader)?;
+
+        let mut header = None;
+        let mut powers_of_g = None;
+        let mut powers_of_g2 = None;
+        let mut powers_of_gamma_g = None;
+        for _ in 0..sections {
+            let section = read_u32(&mut reader)?;
+            let size = read_u64(&mut reader)?;
+            match (section, header) {
+                (PTAU_SECTION_HEADER, _) => {
+                    let n8 = read_u32(&mut reader)? as usize;
+                    let expected_modulus =
+                        <<E::G1Affine as AffineRepr>::BaseField as Field>::BasePrimeField::MODULUS
+                            .to_bytes_le();
+                    // check the length before reading the modulus, not to allocate an untrusted amount of memory.
+                    if n8 != expected_modulus.len() {
+                        return Err(SrsError::UnsupportedCurve);
+                    }
+                    if size < n8 as u64 + 8 {
+                        return Err(SrsError::InvalidFormat("invalid section size"));
+                    }
+                    let mut modulus = vec![0u8; n8];
+                    reader.read_exact(&mut modulus).map_err(|_| SrsError::Io)?;
+                    if modulus != expected_modulus {
+                        return Err(SrsError::UnsupportedCurve);
+                    }
+                    let power = read_u32(&mut reader)? as usize;
+                    skip(&mut reader, size - n8 as u64 - 8)?;
+                    header = Some((n8, ceremony_len(power)?));
+                }
+                (PTAU_SECTION_TAU_G1, Some((n8, len))) => {
+                    check_section::<E::G1Affine>(size, 2 * len - 1, n8)?;
+                    powers_of_g = Some(read_points(
+                        &mut reader,
+                        max_degree + 1,
+                        2 * len - 1,
+                        n8,
+                        Encoding::Montgomery,
+                    )?);
+                }
+                (PTAU_SECTION_TAU_G2, Some((n8, len))) => {
+                    check_section::<E::G2Affine>(size, len, n8)?;
+                    powers_of_g2 = Some(read_points(
+                        &mut reader,
+                        max_eval_points + 1,
+                        len,
+                        n8,
+                        Encoding::Montgomery,
+                    )?);
+                }
+                (PTAU_SECTION_ALPHA_TAU_G1, Some((n8, len))) => {
+                    check_section::<E::G1Affine>(size, len, n8)?;
+                    powers_of_gamma_g = Some(read_points(
+                        &mut reader,
+                        HIDING_BOUND + 1,
+                        len,
+                        n8,
+                        Encoding::Montgomery,
+                    )?);
+                }
+                _ => skip(&mut reader, size)?,
+            }
+        }
+
+        match (powers_of_g, powers_of_g2, powers_of_gamma_g) {
+            (Some(powers_of_g), Some(powers_of_g2), Some(powers_of_gamma_g)) => Ok(CommitterKey {
+                powers_of_g,
+                powers_of_g2,
+                powers_of_gamma_g,
+            }),
+            _ => Err(SrsError::InvalidFormat("missing section")),
+        }
+    }
+
+    /// Construct the committer key from a Zcash/perpetual powers of tau `challenge` transcript
+    /// for a ceremony of size $2^{\text{power}}$.
+    ///
+    /// Given a reader over the transcript `reader`,
+    /// a degree bound `max_degree`,
+    /// and an evaluation point bound `max_eval_points`,
+    /// construct the committer key using the first powers of \\(\tau\\) from the ceremony.
+    pub fn from_powersoftau<R: Read>(
+        mut reader: R,
+        power: usize,
+        max_degree: usize,
+        max_eval_points: usize,
+    ) -> Result<Self, SrsError> {
+        let n8 = ceil_div(
+            <<E::G1Affine as AffineRepr>::BaseField as Field>::BasePrimeField::MODULUS_BIT_SIZE
+                as usize,
+            8,
+        );
+        let len = ceremony_len(power)?;
+
+        skip(&mut reader, ZCASH_HASH_LENGTH as u64)?;
+        let powers_of_g = read_points(
+            &mut reader,
+            max_degree + 1,
+            2 * len - 1,
+            n8,
+            Encoding::BigEndian,
+        )?;
+        let powers_of_g2 = read_points(
+            &mut reader,
+            max_eval_points + 1,
+            len,
+            n8,
+            Encoding::BigEndian,
+        )?;
+        let powers_of_gamma_g =
+            read_points(&mut reader, HIDING_BOUND + 1, len, n8, Encoding::BigEndian)?;
+
+        Ok(CommitterKey {
+            powers_of_g,
+            powers_of_g2,
+            powers_of_gamma_g,
+        })
+    }
+}
+
+impl<E: Pairing> VerifierKey<E> {
+    /// Construct the verifier key from a snarkjs `.ptau` transcript.
+    ///
+    /// The degree bound `max_degree` and the evaluation point bound `max_eval_points`
+    /// must be the same as the ones of the committer key loaded via [`CommitterKey::from_ptau`],
+    /// for the two keys to match.
+    pub fn from_ptau<R: Read>(
+        reader: R,
+        max_degree: usize,
+        max_eval_points: usize,
+    ) -> Result<Self, SrsError> {
+        let ck = CommitterKey::<E>::from_ptau(reader, max_degree, max_eval_points)?;
+        Ok(VerifierKey::from(&ck))
+    }
+}
+
+/// Encode `ck` as a snarkjs `.ptau` transcript of a ceremony of size \(2^{\text{power}}\).
+///
+/// The committer key must hold \(2^{\text{power}+1} - 1\) powers in \(\GG_1\)
+/// and \(2^{\text{power}}\) powers in \(\GG_2\).
+#[cfg(test)]
+pub(crate) fn write_ptau<E: Pairing>(ck: &CommitterKey<E>, power: usize) -> Vec<u8> {
+    fn write_points<'a, G: AffineRepr>(points: impl IntoIterator<Item = &'a G>) -> Vec<u8> {
+        type Fq<G> = <<G as AffineRepr>::BaseField as Field>::BasePrimeField;
+
+        let n8 = Fq::<G>::MODULUS.to_bytes_le().len();
+        let r = Fq::<G>::from(2u64).pow([8 * n8 as u64]);
+        let mut out = Vec::new();
+        for point in points {
+            let mut bytes = Vec::new();
+            point.serialize_uncompressed(&mut bytes).unwrap();
+            // remove the flags from the last byte, and move to Montgomery form.
+            let last = bytes.len() - 1;
+            bytes[last] &= 0x3f;
+            bytes.chunks(n8).for_each(|chunk| {
+                let element = Fq::<G>::from_le_bytes_mod_order(chunk) * r;
+                out.extend(element.into_bigint().to_bytes_le());
+            });
+        }
+        out
+    }
+
+    fn write_section(out: &mut Vec<u8>, section: u32, body: &[u8]) {
+        out.extend(section.to_le_bytes());
+        out.extend((body.len() as u64).to_le_bytes());
+        out.extend(body);
+    }
+
+    let modulus =
+        <<E::G1Affine as AffineRepr>::BaseField as Field>::BasePrimeField::MODULUS.to_bytes_le();
+    let mut header = Vec::new();
+    header.extend((modulus.len() as u32).to_le_bytes());
+    header.extend(modulus);
+    header.extend((power as u32).to_le_bytes());
+    header.extend((power as u32).to_le_bytes());
+    let alpha_tau_g1 = (0..1 << power).map(|i| &ck.powers_of_gamma_g[usize::min(i, HIDING_BOUND)]);
+
+    let mut ptau = PTAU_MAGIC.to_vec();
+    ptau.extend(1u32.to_le_bytes());
+    ptau.extend(4u32.to_le_bytes());
+    write_section(&mut ptau, PTAU_SECTION_HEADER, &header);
+    write_section(
+        &mut ptau,
+        PTAU_SECTION_TAU_G1,
+        &write_points(&ck.powers_of_g),
+    );
+    write_section(
+        &mut ptau,
+        PTAU_SECTION_TAU_G2,
+        &write_points(&ck.powers_of_g2),
+    );
+    write_section(
+        &mut ptau,
+        PTAU_SECTION_ALPHA_TAU_G1,
+        &write_points(alpha_tau_g1),
+    );
+    ptau
+}
+
+#[test]
+fn test_ptau_roundtrip() {
+    use ark_test_curves::bls12_381::Bls12_381;
+
+    fn write_section(out: &mut Vec<u8>, section: u32, body: &[u8]) {
+        out.extend(section.to_le_bytes());
+        out.extend((body.len() as u64).to_le_bytes());
+        out.extend(body);
+    }
+
+    // Re-encode a committer key in the ptau format and load it back.
+    let rng = &mut ark_std::test_rng();
+    let power = 3;
+    let ck = CommitterKey::<Bls12_381>::new((2 << power) - 2, (1 << power) - 1, rng);
+    let ptau = write_ptau(&ck, power);
+    // skip the magic string, the version, the number of sections, and the section identifier and size.
+    let header = &ptau[24..24 + 4 + 48 + 8];
+    let tau_g1 = &ptau[24 + header.len() + 12..][..((2 << power) - 1) * 96];
+
+    let loaded = CommitterKey::<Bls12_381>::from_ptau(&ptau[..], 10, 3).unwrap();
+    assert_eq!(loaded.powers_of_g, ck.powers_of_g[..11]);
+    assert_eq!(loaded.powers_of_g2, ck.powers_of_g2[..4]);
+    assert_eq!(loaded.powers_of_gamma_g, ck.powers_of_gamma_g);
+    assert!(CommitterKey::<Bls12_381>::from_ptau(&ptau[..], 1 << (power + 1), 3).is_err());
+    assert_eq!(
+        VerifierKey::<Bls12_381>::from_ptau(&ptau[..], 10, 3).unwrap(),
+        VerifierKey::from(&loaded)
+    );
+
+    // points off the curve are rejected.
+    let mut off_curve = ptau.clone();
+    off_curve[24 + header.len() + 12 + 96 + 1] ^= 1;
+    assert!(CommitterKey::<Bls12_381>::from_ptau(&off_curve[..], 10, 3).is_err());
+
+    // malformed headers and sections are rejected instead of being trusted.
+    let mut truncated = PTAU_MAGIC.to_vec();
+    truncated.extend(1u32.to_le_bytes());
+    truncated.extend(1u32.to_le_bytes());
+    write_section(&mut truncated, PTAU_SECTION_HEADER, &header[..4 + 48]);
+    assert!(CommitterKey::<Bls12_381>::from_ptau(&truncated[..], 10, 3).is_err());
+
+    let mut huge_power = header.to_vec();
+    huge_power[4 + 48..4 + 48 + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+    let mut corrupted = PTAU_MAGIC.to_vec();
+    corrupted.extend(1u32.to_le_bytes());
+    corrupted.extend(1u32.to_le_bytes());
+    write_section(&mut corrupted, PTAU_SECTION_HEADER, &huge_power);
+    assert!(CommitterKey::<Bls12_381>::from_ptau(&corrupted[..], 10, 3).is_err());
+
+    let mut short_section = PTAU_MAGIC.to_vec();
+    short_section.extend(1u32.to_le_bytes());
+    short_section.extend(2u32.to_le_bytes());
+    write_section(&mut short_section, PTAU_SECTION_HEADER, header);
+    write_section(
+        &mut short_section,
+        PTAU_SECTION_TAU_G1,
+        &tau_g1[..tau_g1.len() / 2],
+    );
+    assert!(CommitterKey::<Bls12_381>::from_ptau(&short_section[..], 10, 3).is_err());
+}
@@ -0,0 +1,185 @@
+//! Updatable SRS contributions.
+//!
+//! A participant of a multi-party ceremony updates a committer key with powers of $\tau$
+//! into a committer key with powers of $s\tau$, for a fresh secret $s$,
+//! and publishes $sH$ (where $H$ is the generator of $\GG_2$) as proof of the update.
+//! The updated key is secure as long as at least one of the participants discarded their secret.
+//!
+//! Anyone can check that the contribution was computed correctly, i.e. that:
+//! - the generators are unchanged;
+//! - $e(s\tau G, H) = e(\tau G, sH)$, so that the new key builds on top of the previous one;
+//! - the new key consists of consecutive powers of the same element
+//!   (checked through random linear combinations, with a constant number of pairings).
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::Zero;
+use ark_serialize::*;
+use ark_std::rand::RngCore;
+use ark_std::vec::Vec;
+use ark_std::UniformRand;
+
+use crate::misc::{powers, wipe};
+
+use super::{CommitterKey, SrsError};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Proof of a contribution to the SRS, consisting of the new secret $s$ over $\GG_2$.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ContributionProof<E: Pairing> {
+    /// The element $sH$.
+    pub pubkey: E::G2Affine,
+}
+
+/// Multiply each element of `bases` by the corresponding power of `s`.
+fn rescale<G: AffineRepr>(bases: &[G], s: G::ScalarField) -> Vec<G> {
+    let powers_of_s = powers(s, bases.len());
+    let rescaled = cfg_iter!(bases)
+        .zip(cfg_iter!(powers_of_s))
+        .map(|(&base, s_i)| base * *s_i)
+        .collect::<Vec<_>>();
+    G::Group::normalize_batch(&rescaled)
+}
+
+/// Return \\(\sum_i \rho_i B_i\\) and \\(\sum_i \rho_i B_{i+1}\\) for random 128-bit scalars \\(\rho_i\\).
+fn random_shifted_combinations<G: AffineRepr>(
+    bases: &[G],
+    rng: &mut impl RngCore,
+) -> (G::Group, G::Group) {
+    let randomizers = (0..bases.len().saturating_sub(1))
+        .map(|_| G::ScalarField::from(u128::rand(rng)))
+        .collect::<Vec<_>>();
+    let lhs = G::Group::msm_unchecked(bases, &randomizers);
+    let rhs = G::Group::msm_unchecked(bases.get(1..).unwrap_or(&[]), &randomizers);
+    (lhs, rhs)
+}
+
+/// Check that `powers_of_g` are consecutive powers of \\(\tau\\) over \\(\GG_1\\),
+/// where \\(\tau\\) is given over \\(\GG_2\\) by `g2` and `tau_g2`.
+pub(crate) fn is_g1_power_sequence<E: Pairing>(
+    powers_of_g: &[E::G1Affine],
+    g2: E::G2Affine,
+    tau_g2: E::G2Affine,
+    rng: &mut impl RngCore,
+) -> bool {
+    let (lhs, rhs) = random_shifted_combinations(powers_of_g, rng);
+    E::multi_pairing([rhs, -lhs], [g2, tau_g2]).is_zero()
+}
+
+/// Check that `powers_of_g2` are consecutive powers of \\(\tau\\) over \\(\GG_2\\),
+/// where \\(\tau\\) is given over \\(\GG_1\\) by `g` and `tau_g`.
+pub(crate) fn is_g2_power_sequence<E: Pairing>(
+    powers_of_g2: &[E::G2Affine],
+    g: E::G1Affine,
+    tau_g: E::G1Affine,
+    rng: &mut impl RngCore,
+) -> bool {
+    let (lhs, rhs) = random_shifted_combinations(powers_of_g2, rng);
+    E::multi_pairing([g.into_group(), -tau_g.into_group()], [rhs, lhs]).is_zero()
+}
+
+impl<E: Pairing> CommitterKey<E> {
+    /// Re-randomize the committer key with a fresh secret $s$ sampled from `rng`.
+    ///
+    /// Return the updated committer key, together with a proof of the contribution
+    /// that can be checked with [`CommitterKey::verify_contribution`].
+    pub fn contribute(&self, rng: &mut impl RngCore) -> (Self, ContributionProof<E>) {
+        let mut s = loop {
+            let s = E::ScalarField::rand(rng);
+            if !s.is_zero() {
+                break s;
+            }
+        };
+        let contribution = CommitterKey {
+            powers_of_g: rescale(&self.powers_of_g, s),
+            powers_of_g2: rescale(&self.powers_of_g2, s),
+            powers_of_gamma_g: rescale(&self.powers_of_gamma_g, s),
+        };
+        let pubkey = (self.powers_of_g2[0] * s).into_affine();
+        wipe(&mut s);
+        (contribution, ContributionProof { pubkey })
+    }
+
+    /// Check that `next` has been obtained updating the current committer key,
+    /// as proven by `proof`.
+    ///
+    /// The randomness `rng` is used for batching the checks on the powers.
+    pub fn verify_contribution(
+        &self,
+        next: &Self,
+        proof: &ContributionProof<E>,
+        rng: &mut impl RngCore,
+    ) -> Result<(), SrsError> {
+        if next.powers_of_g.len() != self.powers_of_g.len()
+            || next.powers_of_g2.len() != self.powers_of_g2.len()
+            || next.powers_of_gamma_g.len() != self.powers_of_gamma_g.len()
+            || next.powers_of_g.len() < 2
+            || next.powers_of_g2.len() < 2
+        {
+            return Err(SrsError::NotEnoughPowers);
+        }
+        if next.powers_of_g[0] != self.powers_of_g[0]
+            || next.powers_of_g2[0] != self.powers_of_g2[0]
+            || next.powers_of_gamma_g[0] != self.powers_of_gamma_g[0]
+            || proof.pubkey.is_zero()
+        {
+            return Err(SrsError::InvalidContribution);
+        }
+
+        // e(sτG, H) = e(τG, sH)
+        let g2 = self.powers_of_g2[0];
+        let update = E::multi_pairing(
+            [
+                next.powers_of_g[1].into_group(),
+                -self.powers_of_g[1].into_group(),
+            ],
+            [g2, proof.pubkey],
+        );
+        if !update.is_zero() {
+            return Err(SrsError::InvalidContribution);
+        }
+
+        next.check_powers_consistency(rng)
+    }
+
+    /// Check that the elements of the committer key are consecutive powers of the same element.
+    pub(crate) fn check_powers_consistency(&self, rng: &mut impl RngCore) -> Result<(), SrsError> {
+        let g = self.powers_of_g[0];
+        let tau_g = self.powers_of_g[1];
+        let g2 = self.powers_of_g2[0];
+        let tau_g2 = self.powers_of_g2[1];
+        if is_g1_power_sequence::<E>(&self.powers_of_g, g2, tau_g2, rng)
+            && is_g1_power_sequence::<E>(&self.powers_of_gamma_g, g2, tau_g2, rng)
+            && is_g2_power_sequence::<E>(&self.powers_of_g2, g, tau_g, rng)
+        {
+            Ok(())
+        } else {
+            Err(SrsError::InconsistentPowers)
+        }
+    }
+}
+
+#[test]
+fn test_contribution() {
+    use ark_test_curves::bls12_381::Bls12_381;
+
+    let rng = &mut ark_std::test_rng();
+    let ck = CommitterKey::<Bls12_381>::new(15, 3, rng);
+    let (next, proof) = ck.contribute(rng);
+    assert!(ck.verify_contribution(&next, &proof, rng).is_ok());
+    let (last, last_proof) = next.contribute(rng);
+    assert!(next.verify_contribution(&last, &last_proof, rng).is_ok());
+
+    // the contribution must build on top of the previous key.
+    assert_eq!(
+        ck.verify_contribution(&last, &last_proof, rng),
+        Err(SrsError::InvalidContribution)
+    );
+    // the powers must be consistent.
+    let mut tampered = next.clone();
+    tampered.powers_of_g[5] = tampered.powers_of_g[4];
+    assert_eq!(
+        ck.verify_contribution(&tampered, &proof, rng),
+        Err(SrsError::InconsistentPowers)
+    );
+}
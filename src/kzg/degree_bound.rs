@@ -0,0 +1,138 @@
+//! Shifted commitments and degree-bound proofs.
+//!
+//! A polynomial $f$ committed under a key of maximum degree $D$ has degree less than $b$
+//! if and only if $x^{D - b + 1} f(x)$ can be committed under the same key.
+//! The prover sends the commitment $C_s$ to the shifted polynomial,
+//! and proves that it is consistent with the commitment $C_f$ to $f$
+//! by opening $C_s - z^{D-b+1} C_f$ to zero at a random point $z$.
+use ark_ec::{pairing::Pairing, VariableBaseMSM};
+use ark_ff::{Field, Zero};
+use ark_serialize::*;
+use merlin::Transcript;
+
+use crate::transcript::GeminiTranscript;
+
+use super::{
+    Commitment, CommitterKey, EvaluationProof, VerificationError, VerificationResult, VerifierKey,
+};
+
+/// Proof that a committed polynomial has degree less than a public bound.
+#[derive(CanonicalSerialize, Clone, Debug, PartialEq, Eq)]
+pub struct DegreeBoundProof<E: Pairing> {
+    /// The commitment to the shifted polynomial.
+    pub shifted_commitment: Commitment<E>,
+    /// The evaluation proof for the consistency of the shifted commitment.
+    pub proof: EvaluationProof<E>,
+}
+
+/// Return the shift needed for proving that a polynomial has degree less than `degree_bound`,
+/// or `None` if the bound cannot be proven with a key of maximum degree `max_degree`.
+fn degree_bound_shift(max_degree: usize, degree_bound: usize) -> Option<usize> {
+    (0 < degree_bound && degree_bound <= max_degree + 1).then(|| max_degree + 1 - degree_bound)
+}
+
+impl<E: Pairing> CommitterKey<E> {
+    /// Given a polynomial `polynomial` and a shift `shift`, return a commitment to $x^{\text{shift}} f(x)$.
+    pub fn commit_shifted(&self, polynomial: &[E::ScalarField], shift: usize) -> Commitment<E> {
+        assert!(shift + polynomial.len() <= self.powers_of_g.len());
+        Commitment(E::G1::msm_unchecked(&self.powers_of_g[shift..], polynomial))
+    }
+
+    /// Prove that `polynomial` has degree less than `degree_bound`.
+    ///
+    /// The commitment to `polynomial` is expected to be already in the `transcript`.
+    pub fn prove_degree_bound(
+        &self,
+        transcript: &mut Transcript,
+        polynomial: &[E::ScalarField],
+        degree_bound: usize,
+    ) -> DegreeBoundProof<E> {
+        assert!(polynomial.len() <= degree_bound);
+        let shift = degree_bound_shift(self.max_degree(), degree_bound)
+            .expect("degree bound exceeds the committer key");
+        let shifted_commitment = self.commit_shifted(polynomial, shift);
+        transcript.append_serializable(b"shifted-commitment", &shifted_commitment);
+        let z = transcript.get_challenge::<E::ScalarField>(b"degree-bound-chal");
+
+        // the polynomial x^shift f(x) - z^shift f(x) vanishes in z.
+        let z_shift = z.pow([shift as u64]);
+        let mut consistency_polynomial = vec![E::ScalarField::zero(); shift + polynomial.len()];
+        for (i, &c) in polynomial.iter().enumerate() {
+            consistency_polynomial[i] -= z_shift * c;
+            consistency_polynomial[i + shift] += c;
+        }
+        let (_, proof) = self.open(&consistency_polynomial, &z);
+
+        DegreeBoundProof {
+            shifted_commitment,
+            proof,
+        }
+    }
+}
+
+impl<E: Pairing> VerifierKey<E> {
+    /// Verify that the polynomial committed in `commitment` has degree less than `degree_bound`.
+    pub fn verify_degree_bound(
+        &self,
+        transcript: &mut Transcript,
+        commitment: &Commitment<E>,
+        degree_bound: usize,
+        proof: &DegreeBoundProof<E>,
+    ) -> VerificationResult {
+        let shift = degree_bound_shift(self.max_degree, degree_bound).ok_or(VerificationError)?;
+        transcript.append_serializable(b"shifted-commitment", &proof.shifted_commitment);
+        let z = transcript.get_challenge::<E::ScalarField>(b"degree-bound-chal");
+
+        let z_shift = z.pow([shift as u64]);
+        let consistency_commitment =
+            Commitment(proof.shifted_commitment.0 - commitment.0 * z_shift);
+        self.verify(
+            &consistency_commitment,
+            &z,
+            &E::ScalarField::zero(),
+            &proof.proof,
+        )
+    }
+}
+
+#[test]
+fn test_degree_bound() {
+    use ark_poly::univariate::DensePolynomial;
+    use ark_poly::DenseUVPolynomial;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+
+    let rng = &mut ark_std::test_rng();
+    let ck = CommitterKey::<Bls12_381>::new(64, 3, rng);
+    let vk = VerifierKey::from(&ck);
+    let polynomial = DensePolynomial::<Fr>::rand(9, rng);
+    let commitment = ck.commit(&polynomial);
+
+    let shifted = [vec![Fr::zero(); 5], polynomial.coeffs.to_vec()].concat();
+    assert_eq!(ck.commit_shifted(&polynomial, 5), ck.commit(&shifted));
+
+    let mut transcript = Transcript::new(b"test");
+    transcript.append_serializable(b"commitment", &commitment);
+    let proof = ck.prove_degree_bound(&mut transcript, &polynomial, 10);
+
+    let mut transcript = Transcript::new(b"test");
+    transcript.append_serializable(b"commitment", &commitment);
+    assert!(vk
+        .verify_degree_bound(&mut transcript, &commitment, 10, &proof)
+        .is_ok());
+
+    // the same proof is not valid for a tighter bound.
+    let mut transcript = Transcript::new(b"test");
+    transcript.append_serializable(b"commitment", &commitment);
+    assert!(vk
+        .verify_degree_bound(&mut transcript, &commitment, 9, &proof)
+        .is_err());
+
+    // bounds that the key cannot prove are rejected.
+    for degree_bound in [0, 66] {
+        let mut transcript = Transcript::new(b"test");
+        transcript.append_serializable(b"commitment", &commitment);
+        assert!(vk
+            .verify_degree_bound(&mut transcript, &commitment, degree_bound, &proof)
+            .is_err());
+    }
+}
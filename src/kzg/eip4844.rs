@@ -0,0 +1,757 @@
+//! Compatibility with [EIP-4844](https://eips.ethereum.org/EIPS/eip-4844) blob commitments.
+//!
+//! A blob is a vector of $4096$ field elements of BLS12-381,
+//! each encoded in $32$ big-endian bytes,
+//! representing a polynomial in evaluation form over the roots of unity of order $4096$
+//! taken in bit-reversed order.
+//! Commitments are computed with the Lagrange basis of the
+//! [Ethereum KZG ceremony](https://github.com/ethereum/kzg-ceremony),
+//! and commitments and proofs are encoded as compressed points in the Zcash format.
+//! All functions in this module follow the
+//! [Deneb polynomial commitments specification](https://github.com/ethereum/consensus-specs/blob/dev/specs/deneb/polynomial-commitments.md),
+//! and are byte-compatible with [`c-kzg`](https://github.com/ethereum/c-kzg-4844).
+//!
+//! The roots of unity are derived from the two-adic root of unity of the scalar field,
+//! which is a power of the multiplicative generator $7$, as in the specification.
+//!
+//! The curve is the BLS12-381 of [`ark-test-curves`](https://docs.rs/ark-test-curves/),
+//! built on the same `ark-ec` as the rest of the crate;
+//! points are encoded in the Zcash format by this module.
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_ff::{batch_inversion, FftField, Field, One, PrimeField, Zero};
+use ark_serialize::*;
+use ark_std::fmt;
+use ark_std::vec::Vec;
+use ark_test_curves::bls12_381::{Bls12_381, Fq, Fq2};
+use sha2::{Digest, Sha256};
+
+use super::{msm, SrsError, VerificationError, VerificationResult};
+
+type Fr = <Bls12_381 as Pairing>::ScalarField;
+type G1Affine = <Bls12_381 as Pairing>::G1Affine;
+type G1Projective = <Bls12_381 as Pairing>::G1;
+type G2Affine = <Bls12_381 as Pairing>::G2Affine;
+
+/// The number of field elements in a blob.
+pub const FIELD_ELEMENTS_PER_BLOB: usize = 4096;
+/// The size of an encoded field element.
+pub const BYTES_PER_FIELD_ELEMENT: usize = 32;
+/// The size of an encoded blob.
+pub const BYTES_PER_BLOB: usize = FIELD_ELEMENTS_PER_BLOB * BYTES_PER_FIELD_ELEMENT;
+/// The size of an encoded commitment or proof.
+pub const BYTES_PER_COMMITMENT: usize = 48;
+/// The number of powers of \\(\tau\\) over \\(\GG_2\\) in the ceremony output.
+const G2_POINTS_IN_SETUP: usize = 65;
+/// The domain separator for computing the evaluation challenge of a blob.
+const FIAT_SHAMIR_PROTOCOL_DOMAIN: &[u8] = b"FSBLOBVERIFY_V1_";
+/// The size of an encoded base field element.
+const BYTES_PER_FQ: usize = 48;
+/// The flag marking a compressed point, in the leading byte of its encoding.
+const COMPRESSION_FLAG: u8 = 0x80;
+/// The flag marking the point at infinity.
+const INFINITY_FLAG: u8 = 0x40;
+/// The flag marking a point whose ordinate is the lexicographically largest of the two.
+const SORT_FLAG: u8 = 0x20;
+
+/// Error identifying malformed blobs, commitments, or field elements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlobError {
+    /// The blob does not consist of [`BYTES_PER_BLOB`] bytes.
+    InvalidLength,
+    /// Some field element is not canonically encoded.
+    InvalidFieldElement,
+    /// The commitment is not a valid point of the prime-order subgroup.
+    InvalidPoint,
+}
+
+impl fmt::Display for BlobError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLength => write!(f, "The blob has invalid length."),
+            Self::InvalidFieldElement => write!(f, "Invalid field element encoding."),
+            Self::InvalidPoint => write!(f, "Invalid point encoding."),
+        }
+    }
+}
+
+/// The trusted setup for blob commitments.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlobSetup {
+    /// The Lagrange basis over \\(\GG_1\\), in bit-reversed order.
+    g1_lagrange: Vec<G1Affine>,
+    /// The generator of \\(\GG_2\\), together with its multiplication by the trapdoor.
+    powers_of_g2: [G2Affine; 2],
+    /// The roots of unity of order [`FIELD_ELEMENTS_PER_BLOB`], in bit-reversed order.
+    roots_of_unity: Vec<Fr>,
+}
+
+/// Return `elements` permuted in bit-reversed order.
+fn bit_reversal_permutation<T: Copy>(elements: &[T]) -> Vec<T> {
+    let shift = usize::BITS - elements.len().trailing_zeros();
+    (0..elements.len())
+        .map(|i| elements[i.reverse_bits().checked_shr(shift).unwrap_or(0)])
+        .collect()
+}
+
+/// Decode a hexadecimal string, with an optional `0x` prefix.
+fn decode_hex(string: &str) -> Option<Vec<u8>> {
+    let string = string.strip_prefix("0x").unwrap_or(string);
+    if string.len() % 2 != 0 {
+        return None;
+    }
+    (0..string.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(string.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Decode a field element from its 32-byte big-endian encoding, rejecting non-canonical ones.
+fn bytes_to_field(bytes: &[u8]) -> Result<Fr, BlobError> {
+    let mut le_bytes = bytes.to_vec();
+    le_bytes.reverse();
+    Fr::deserialize_compressed(&le_bytes[..]).map_err(|_| BlobError::InvalidFieldElement)
+}
+
+/// Encode a field element in 32 big-endian bytes.
+fn field_to_bytes(element: &Fr) -> [u8; BYTES_PER_FIELD_ELEMENT] {
+    let mut bytes = [0u8; BYTES_PER_FIELD_ELEMENT];
+    element
+        .serialize_compressed(&mut bytes[..])
+        .expect("field elements fit in 32 bytes");
+    bytes.reverse();
+    bytes
+}
+
+/// The big-endian encoding of the coordinates of points in the Zcash format.
+trait ZcashCoordinate: Sized {
+    /// Encode the coordinate, in [`BYTES_PER_FQ`] bytes for each coefficient.
+    fn to_be_bytes(&self) -> Vec<u8>;
+    /// Decode the coordinate, rejecting non-canonical encodings.
+    fn from_be_bytes(bytes: &[u8]) -> Option<Self>;
+}
+
+impl ZcashCoordinate for Fq {
+    fn to_be_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![0u8; BYTES_PER_FQ];
+        self.serialize_compressed(&mut bytes[..])
+            .expect("base field elements fit in 48 bytes");
+        bytes.reverse();
+        bytes
+    }
+
+    fn from_be_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut le_bytes = bytes.to_vec();
+        le_bytes.reverse();
+        Fq::deserialize_compressed(&le_bytes[..]).ok()
+    }
+}
+
+/// Elements of the quadratic extension are encoded with the coefficient of \\(u\\) first.
+impl ZcashCoordinate for Fq2 {
+    fn to_be_bytes(&self) -> Vec<u8> {
+        [self.c1.to_be_bytes(), self.c0.to_be_bytes()].concat()
+    }
+
+    fn from_be_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != 2 * BYTES_PER_FQ {
+            return None;
+        }
+        let (c1, c0) = bytes.split_at(BYTES_PER_FQ);
+        Some(Fq2::new(Fq::from_be_bytes(c0)?, Fq::from_be_bytes(c1)?))
+    }
+}
+
+/// Encode `point` in the compressed Zcash format:
+/// the big-endian abscissa, with the flags in the three leading bits.
+fn encode_point<P: SWCurveConfig>(point: &Affine<P>) -> Vec<u8>
+where
+    P::BaseField: ZcashCoordinate,
+{
+    if point.infinity {
+        let mut bytes = P::BaseField::zero().to_be_bytes();
+        bytes[0] = COMPRESSION_FLAG | INFINITY_FLAG;
+        return bytes;
+    }
+    let mut bytes = point.x.to_be_bytes();
+    bytes[0] |= COMPRESSION_FLAG;
+    if point.y > -point.y {
+        bytes[0] |= SORT_FLAG;
+    }
+    bytes
+}
+
+/// Decode a point in the compressed Zcash format, checking that it lies in the prime-order subgroup.
+fn decode_point<P: SWCurveConfig>(bytes: &[u8]) -> Option<Affine<P>>
+where
+    P::BaseField: ZcashCoordinate,
+{
+    let flags = bytes.first()? & (COMPRESSION_FLAG | INFINITY_FLAG | SORT_FLAG);
+    if flags & COMPRESSION_FLAG == 0 {
+        return None;
+    }
+    let mut x_bytes = bytes.to_vec();
+    x_bytes[0] &= !flags;
+    if flags & INFINITY_FLAG != 0 {
+        // the point at infinity has no other bit set.
+        let is_canonical = flags & SORT_FLAG == 0 && x_bytes.iter().all(|&b| b == 0);
+        return is_canonical.then(Affine::identity);
+    }
+    let x = P::BaseField::from_be_bytes(&x_bytes)?;
+    let point = Affine::<P>::get_point_from_x_unchecked(x, flags & SORT_FLAG != 0)?;
+    point
+        .is_in_correct_subgroup_assuming_on_curve()
+        .then(|| point)
+}
+
+/// Decode a commitment or a proof, checking that it lies in the prime-order subgroup.
+fn bytes_to_point(bytes: &[u8; BYTES_PER_COMMITMENT]) -> Result<G1Affine, BlobError> {
+    decode_point(&bytes[..]).ok_or(BlobError::InvalidPoint)
+}
+
+/// Encode a point of \\(\GG_1\\) in the compressed Zcash format.
+fn point_to_bytes(point: &G1Projective) -> [u8; BYTES_PER_COMMITMENT] {
+    let mut bytes = [0u8; BYTES_PER_COMMITMENT];
+    bytes.copy_from_slice(&encode_point(&point.into_affine()));
+    bytes
+}
+
+/// Decode a blob into the evaluations of the underlying polynomial.
+fn blob_to_polynomial(blob: &[u8]) -> Result<Vec<Fr>, BlobError> {
+    if blob.len() != BYTES_PER_BLOB {
+        return Err(BlobError::InvalidLength);
+    }
+    blob.chunks_exact(BYTES_PER_FIELD_ELEMENT)
+        .map(bytes_to_field)
+        .collect()
+}
+
+/// Return the evaluation challenge for `blob`, bound to its `commitment`.
+fn compute_challenge(blob: &[u8], commitment: &[u8; BYTES_PER_COMMITMENT]) -> Fr {
+    let mut hasher = Sha256::new();
+    hasher.update(FIAT_SHAMIR_PROTOCOL_DOMAIN);
+    hasher.update((FIELD_ELEMENTS_PER_BLOB as u128).to_be_bytes());
+    hasher.update(blob);
+    hasher.update(commitment);
+    Fr::from_be_bytes_mod_order(&hasher.finalize())
+}
+
+impl BlobSetup {
+    /// Construct the setup from the Lagrange basis over \\(\GG_1\\) (in bit-reversed order)
+    /// and the trapdoor over \\(\GG_2\\).
+    fn new(g1_lagrange: Vec<G1Affine>, powers_of_g2: [G2Affine; 2]) -> Self {
+        let root = Fr::get_root_of_unity(FIELD_ELEMENTS_PER_BLOB as u64)
+            .expect("the scalar field has a root of unity of order 4096");
+        let roots_of_unity = ark_std::iter::successors(Some(Fr::one()), |x| Some(*x * root))
+            .take(FIELD_ELEMENTS_PER_BLOB)
+            .collect::<Vec<_>>();
+        BlobSetup {
+            g1_lagrange,
+            powers_of_g2,
+            roots_of_unity: bit_reversal_permutation(&roots_of_unity),
+        }
+    }
+
+    /// Construct the setup from a `trusted_setup.txt` file, as distributed by `c-kzg`.
+    ///
+    /// The file consists of the number of points over \\(\GG_1\\) and \\(\GG_2\\),
+    /// followed by the Lagrange basis over \\(\GG_1\\) in bit-reversed order
+    /// and the powers of \\(\tau\\) over \\(\GG_2\\), each point hex-encoded in compressed form.
+    /// Any further section (such as the monomial basis over \\(\GG_1\\)) is ignored.
+    /// All points are checked to be in the prime-order subgroup.
+    pub fn from_trusted_setup(text: &str) -> Result<Self, SrsError> {
+        let mut tokens = text.split_whitespace();
+        let mut read_len = || {
+            tokens
+                .next()
+                .and_then(|token| token.parse::<usize>().ok())
+                .ok_or(SrsError::InvalidFormat("missing number of points"))
+        };
+        let g1_len = read_len()?;
+        let g2_len = read_len()?;
+        if g1_len != FIELD_ELEMENTS_PER_BLOB || g2_len != G2_POINTS_IN_SETUP {
+            return Err(SrsError::InvalidFormat("unexpected number of points"));
+        }
+
+        let mut read_point = |len| {
+            tokens
+                .next()
+                .and_then(decode_hex)
+                .filter(|bytes| bytes.len() == len)
+                .ok_or(SrsError::InvalidFormat("invalid hex encoding"))
+        };
+        let g1_lagrange = (0..g1_len)
+            .map(|_| {
+                let bytes = read_point(BYTES_PER_COMMITMENT)?;
+                decode_point(&bytes).ok_or(SrsError::InvalidPoint)
+            })
+            .collect::<Result<Vec<G1Affine>, _>>()?;
+        let powers_of_g2 = (0..2)
+            .map(|_| {
+                let bytes = read_point(2 * BYTES_PER_COMMITMENT)?;
+                decode_point(&bytes).ok_or(SrsError::InvalidPoint)
+            })
+            .collect::<Result<Vec<G2Affine>, _>>()?;
+        if powers_of_g2[0] != G2Affine::generator() {
+            return Err(SrsError::InvalidFormat("unexpected generator"));
+        }
+        Ok(BlobSetup::new(
+            g1_lagrange,
+            [powers_of_g2[0], powers_of_g2[1]],
+        ))
+    }
+
+    /// Construct the setup from the file at `path`, see [`BlobSetup::from_trusted_setup`].
+    pub fn load_trusted_setup<P: AsRef<std::path::Path>>(path: P) -> Result<Self, SrsError> {
+        let text = std::fs::read_to_string(path).map_err(|_| SrsError::Io)?;
+        Self::from_trusted_setup(&text)
+    }
+
+    /// Evaluate the polynomial `polynomial`, given in evaluation form, at the point `z`.
+    fn evaluate(&self, polynomial: &[Fr], z: &Fr) -> Fr {
+        if let Some(i) = self.roots_of_unity.iter().position(|root| root == z) {
+            return polynomial[i];
+        }
+        let mut denominators = self
+            .roots_of_unity
+            .iter()
+            .map(|root| *z - root)
+            .collect::<Vec<_>>();
+        batch_inversion(&mut denominators);
+        let sum = polynomial
+            .iter()
+            .zip(&self.roots_of_unity)
+            .zip(denominators)
+            .map(|((p, root), inverse)| *p * root * inverse)
+            .sum::<Fr>();
+        let n = Fr::from(FIELD_ELEMENTS_PER_BLOB as u64);
+        sum * (z.pow([FIELD_ELEMENTS_PER_BLOB as u64]) - Fr::one()) * n.inverse().unwrap()
+    }
+
+    /// Return the evaluation of `polynomial` at `z`, together with the encoded proof.
+    fn open(&self, polynomial: &[Fr], z: &Fr) -> (Fr, [u8; BYTES_PER_COMMITMENT]) {
+        let y = self.evaluate(polynomial, z);
+        // q(\omega_i) = (p(\omega_i) - y) / (\omega_i - z), and
+        // q(z) = \sum_{i \neq m} (p(\omega_i) - y) \omega_i / (z (z - \omega_i)) if z = \omega_m.
+        let mut inverses = self
+            .roots_of_unity
+            .iter()
+            .map(|root| *root - z)
+            .collect::<Vec<_>>();
+        batch_inversion(&mut inverses);
+        let mut quotient = polynomial
+            .iter()
+            .zip(&inverses)
+            .map(|(p, inverse)| (*p - y) * inverse)
+            .collect::<Vec<_>>();
+        if let Some(m) = self.roots_of_unity.iter().position(|root| root == z) {
+            let z_inverse = z.inverse().unwrap();
+            quotient[m] = -polynomial
+                .iter()
+                .zip(&self.roots_of_unity)
+                .zip(&inverses)
+                .enumerate()
+                .filter(|&(i, _)| i != m)
+                .map(|(_, ((p, root), inverse))| (*p - y) * root * inverse)
+                .sum::<Fr>()
+                * z_inverse;
+        }
+        let proof = msm::<Bls12_381>(&self.g1_lagrange, &quotient);
+        (y, point_to_bytes(&proof))
+    }
+
+    /// Return the commitment to `blob` (`blob_to_kzg_commitment`).
+    pub fn blob_to_commitment(&self, blob: &[u8]) -> Result<[u8; BYTES_PER_COMMITMENT], BlobError> {
+        let polynomial = blob_to_polynomial(blob)?;
+        Ok(point_to_bytes(&msm::<Bls12_381>(
+            &self.g1_lagrange,
+            &polynomial,
+        )))
+    }
+
+    /// Return a proof for the evaluation of `blob` at the point `z`,
+    /// together with the evaluation itself (`compute_kzg_proof`).
+    pub fn compute_proof(
+        &self,
+        blob: &[u8],
+        z: &[u8; BYTES_PER_FIELD_ELEMENT],
+    ) -> Result<([u8; BYTES_PER_COMMITMENT], [u8; BYTES_PER_FIELD_ELEMENT]), BlobError> {
+        let polynomial = blob_to_polynomial(blob)?;
+        let (y, proof) = self.open(&polynomial, &bytes_to_field(z)?);
+        Ok((proof, field_to_bytes(&y)))
+    }
+
+    /// Return a proof for the evaluation of `blob` at the Fiat--Shamir challenge
+    /// derived from `blob` and its `commitment` (`compute_blob_kzg_proof`).
+    pub fn compute_blob_proof(
+        &self,
+        blob: &[u8],
+        commitment: &[u8; BYTES_PER_COMMITMENT],
+    ) -> Result<[u8; BYTES_PER_COMMITMENT], BlobError> {
+        let polynomial = blob_to_polynomial(blob)?;
+        bytes_to_point(commitment)?;
+        let z = compute_challenge(blob, commitment);
+        Ok(self.open(&polynomial, &z).1)
+    }
+
+    /// Check the pairing equation \\(e(C - y G, H) = e(\pi, \tau H - z H)\\).
+    fn check(&self, commitment: &G1Affine, z: &Fr, y: &Fr, proof: &G1Affine) -> VerificationResult {
+        let lhs = commitment.into_group() - G1Affine::generator() * y;
+        let rhs = self.powers_of_g2[1].into_group() - self.powers_of_g2[0] * z;
+        if Bls12_381::multi_pairing(
+            [lhs, -proof.into_group()],
+            [self.powers_of_g2[0].into_group(), rhs],
+        )
+        .is_zero()
+        {
+            Ok(())
+        } else {
+            Err(VerificationError)
+        }
+    }
+
+    /// Verify that the polynomial committed in `commitment` evaluates to `y` at `z` (`verify_kzg_proof`).
+    ///
+    /// Malformed inputs are rejected.
+    pub fn verify_proof(
+        &self,
+        commitment: &[u8; BYTES_PER_COMMITMENT],
+        z: &[u8; BYTES_PER_FIELD_ELEMENT],
+        y: &[u8; BYTES_PER_FIELD_ELEMENT],
+        proof: &[u8; BYTES_PER_COMMITMENT],
+    ) -> VerificationResult {
+        let commitment = bytes_to_point(commitment).map_err(|_| VerificationError)?;
+        let proof = bytes_to_point(proof).map_err(|_| VerificationError)?;
+        let z = bytes_to_field(z).map_err(|_| VerificationError)?;
+        let y = bytes_to_field(y).map_err(|_| VerificationError)?;
+        self.check(&commitment, &z, &y, &proof)
+    }
+
+    /// Verify that `commitment` is a commitment to `blob`,
+    /// given a proof produced by [`BlobSetup::compute_blob_proof`] (`verify_blob_kzg_proof`).
+    ///
+    /// Malformed inputs are rejected.
+    pub fn verify_blob_proof(
+        &self,
+        blob: &[u8],
+        commitment: &[u8; BYTES_PER_COMMITMENT],
+        proof: &[u8; BYTES_PER_COMMITMENT],
+    ) -> VerificationResult {
+        let polynomial = blob_to_polynomial(blob).map_err(|_| VerificationError)?;
+        let commitment_point = bytes_to_point(commitment).map_err(|_| VerificationError)?;
+        let proof = bytes_to_point(proof).map_err(|_| VerificationError)?;
+        let z = compute_challenge(blob, commitment);
+        let y = self.evaluate(&polynomial, &z);
+        self.check(&commitment_point, &z, &y, &proof)
+    }
+}
+
+/// Return a setup for the trapdoor `tau`, for testing.
+#[cfg(test)]
+fn test_setup(tau: Fr) -> BlobSetup {
+    // construct the Lagrange basis L_i(\tau) = \omega_i (\tau^n - 1) / (n (\tau - \omega_i)).
+    let g = G1Affine::generator();
+    let g2 = G2Affine::generator();
+    let mut setup = BlobSetup::new(Vec::new(), [g2, (g2 * tau).into_affine()]);
+    let n_inverse = Fr::from(FIELD_ELEMENTS_PER_BLOB as u64).inverse().unwrap();
+    let vanishing = tau.pow([FIELD_ELEMENTS_PER_BLOB as u64]) - Fr::one();
+    let lagrange = setup
+        .roots_of_unity
+        .iter()
+        .map(|root| (g * (*root * vanishing * n_inverse / (tau - root))).into_affine())
+        .collect::<Vec<_>>();
+    setup.g1_lagrange = lagrange;
+    setup
+}
+
+#[test]
+fn test_blob_commitments() {
+    use ark_std::UniformRand;
+
+    let rng = &mut ark_std::test_rng();
+    let tau = Fr::rand(rng);
+    let g = G1Affine::generator();
+    let g2 = G2Affine::generator();
+    let setup = test_setup(tau);
+
+    // the setup can be parsed back from the c-kzg format.
+    fn hex<P: SWCurveConfig>(point: &Affine<P>) -> String
+    where
+        P::BaseField: ZcashCoordinate,
+    {
+        encode_point(point)
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+    let mut text = format!("{}\n{}\n", FIELD_ELEMENTS_PER_BLOB, G2_POINTS_IN_SETUP);
+    setup
+        .g1_lagrange
+        .iter()
+        .for_each(|point| text += &(hex(point) + "\n"));
+    (0..G2_POINTS_IN_SETUP)
+        .map(|i| (g2 * tau.pow([i as u64])).into_affine())
+        .for_each(|point| text += &(hex(&point) + "\n"));
+    assert_eq!(BlobSetup::from_trusted_setup(&text), Ok(setup.clone()));
+
+    let polynomial = (0..FIELD_ELEMENTS_PER_BLOB)
+        .map(|_| Fr::rand(rng))
+        .collect::<Vec<_>>();
+    let blob = polynomial
+        .iter()
+        .flat_map(field_to_bytes)
+        .collect::<Vec<_>>();
+    let commitment = setup.blob_to_commitment(&blob).unwrap();
+    assert_eq!(
+        bytes_to_point(&commitment).unwrap(),
+        (g * setup.evaluate(&polynomial, &tau)).into_affine()
+    );
+
+    let proof = setup.compute_blob_proof(&blob, &commitment).unwrap();
+    assert!(setup.verify_blob_proof(&blob, &commitment, &proof).is_ok());
+    let mut tampered_blob = blob.clone();
+    tampered_blob[BYTES_PER_BLOB - 1] ^= 1;
+    assert!(setup
+        .verify_blob_proof(&tampered_blob, &commitment, &proof)
+        .is_err());
+
+    // open both outside and inside the evaluation domain.
+    for z in [Fr::rand(rng), setup.roots_of_unity[3]] {
+        let z = field_to_bytes(&z);
+        let (proof, y) = setup.compute_proof(&blob, &z).unwrap();
+        assert!(setup.verify_proof(&commitment, &z, &y, &proof).is_ok());
+        let wrong_y = field_to_bytes(&(bytes_to_field(&y).unwrap() + Fr::one()));
+        assert!(setup
+            .verify_proof(&commitment, &z, &wrong_y, &proof)
+            .is_err());
+    }
+    assert_eq!(
+        bytes_to_field(&[0xff; 32]),
+        Err(BlobError::InvalidFieldElement)
+    );
+    assert_eq!(
+        setup.blob_to_commitment(&blob[1..]),
+        Err(BlobError::InvalidLength)
+    );
+}
+
+#[test]
+fn test_zcash_encoding() {
+    use ark_std::UniformRand;
+
+    // the compressed generators, as in the Zcash specification.
+    assert_eq!(
+        point_to_bytes(&G1Affine::generator().into_group()).to_vec(),
+        decode_hex("97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb").unwrap()
+    );
+    assert_eq!(
+        encode_point(&G2Affine::generator()),
+        decode_hex("93e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8").unwrap()
+    );
+    let infinity = G1Affine::identity();
+    let mut expected = [0u8; BYTES_PER_COMMITMENT];
+    expected[0] = 0xc0;
+    assert_eq!(point_to_bytes(&infinity.into_group()), expected);
+    assert_eq!(bytes_to_point(&expected), Ok(infinity));
+
+    let rng = &mut ark_std::test_rng();
+    for _ in 0..8 {
+        let point = (G1Affine::generator() * Fr::rand(rng)).into_affine();
+        let bytes = point_to_bytes(&point.into_group());
+        assert_eq!(bytes_to_point(&bytes), Ok(point));
+        let point = (G2Affine::generator() * Fr::rand(rng)).into_affine();
+        assert_eq!(decode_point(&encode_point(&point)), Some(point));
+
+        // uncompressed encodings are rejected, and flipping the sign negates the point.
+        let point = bytes_to_point(&bytes).unwrap();
+        let mut uncompressed = bytes;
+        uncompressed[0] &= !COMPRESSION_FLAG;
+        assert!(bytes_to_point(&uncompressed).is_err());
+        let mut negated = bytes;
+        negated[0] ^= SORT_FLAG;
+        assert_eq!(bytes_to_point(&negated), Ok(-point));
+    }
+}
+
+#[test]
+fn test_reference_vectors() {
+    use ark_std::UniformRand;
+
+    // the cases of the consensus specifications that hold for any trusted setup.
+    let setup = test_setup(Fr::rand(&mut ark_std::test_rng()));
+    let mut infinity = [0u8; BYTES_PER_COMMITMENT];
+    infinity[0] = 0xc0;
+    let zero_blob = vec![0u8; BYTES_PER_BLOB];
+    let z = field_to_bytes(&Fr::from(5u64));
+    let zero = [0u8; BYTES_PER_FIELD_ELEMENT];
+
+    // the zero polynomial is committed to, and opened at any point with, the point at infinity.
+    assert_eq!(setup.blob_to_commitment(&zero_blob), Ok(infinity));
+    assert_eq!(setup.compute_proof(&zero_blob, &z), Ok((infinity, zero)));
+    assert!(setup.verify_proof(&infinity, &z, &zero, &infinity).is_ok());
+    assert_eq!(
+        setup.compute_blob_proof(&zero_blob, &infinity),
+        Ok(infinity)
+    );
+    assert!(setup
+        .verify_blob_proof(&zero_blob, &infinity, &infinity)
+        .is_ok());
+
+    // field elements equal to or larger than the modulus are rejected.
+    let invalid_field_elements = [
+        "73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001",
+        "73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000002",
+        "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        "ffffffffffffffffffffffffffffffff00000000000000000000000000000000",
+    ];
+    for element in invalid_field_elements {
+        let element: [u8; BYTES_PER_FIELD_ELEMENT] =
+            decode_hex(element).unwrap().try_into().unwrap();
+        let mut blob = zero_blob.clone();
+        blob[..BYTES_PER_FIELD_ELEMENT].copy_from_slice(&element);
+        assert_eq!(
+            setup.blob_to_commitment(&blob),
+            Err(BlobError::InvalidFieldElement)
+        );
+        assert!(setup.compute_proof(&zero_blob, &element).is_err());
+        assert!(setup
+            .verify_proof(&infinity, &element, &zero, &infinity)
+            .is_err());
+        assert!(setup
+            .verify_proof(&infinity, &z, &element, &infinity)
+            .is_err());
+    }
+
+    // the generator is encoded as in the specifications, and points off the curve
+    // or outside the prime-order subgroup are rejected.
+    let generator = decode_hex("97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb").unwrap();
+    assert_eq!(
+        point_to_bytes(&G1Affine::generator().into_group()).to_vec(),
+        generator
+    );
+    let invalid_points = [
+        "8123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+        "8123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcde0",
+    ];
+    for point in invalid_points {
+        let point: [u8; BYTES_PER_COMMITMENT] = decode_hex(point).unwrap().try_into().unwrap();
+        assert_eq!(bytes_to_point(&point), Err(BlobError::InvalidPoint));
+        assert!(setup.compute_blob_proof(&zero_blob, &point).is_err());
+        assert!(setup.verify_proof(&point, &z, &zero, &infinity).is_err());
+        assert!(setup.verify_proof(&infinity, &z, &zero, &point).is_err());
+    }
+}
+
+/// Run the reference tests of the [consensus specifications](https://github.com/ethereum/consensus-spec-tests)
+/// over the Ethereum KZG ceremony.
+///
+/// The trusted setup is read from the `trusted_setup.txt` of `c-kzg` at the path `EIP4844_TRUSTED_SETUP`,
+/// and the test cases from the `general/deneb/kzg` directory of the consensus specification tests
+/// (or the `tests` directory of `c-kzg`) at the path `EIP4844_TEST_VECTORS`.
+/// Batched verification is not supported, and its cases are skipped.
+#[test]
+#[ignore = "requires the Ethereum trusted setup and the reference test vectors"]
+fn test_consensus_spec_vectors() {
+    use std::path::Path;
+
+    fn var(name: &str) -> String {
+        std::env::var(name).unwrap_or_else(|_| panic!("{} is not set", name))
+    }
+
+    /// Return the data of the test cases of `handler`.
+    fn cases(root: &str, handler: &str) -> Vec<String> {
+        let dir = Path::new(root).join(handler).join("kzg-mainnet");
+        let cases = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|case| std::fs::read_to_string(case.unwrap().path().join("data.yaml")).unwrap())
+            .collect::<Vec<_>>();
+        assert!(!cases.is_empty(), "no test cases in {}", dir.display());
+        cases
+    }
+
+    /// Return the decoded input `key`, if it is well-formed and has `N` bytes.
+    fn input<const N: usize>(data: &str, key: &str) -> Option<[u8; N]> {
+        let pattern = format!("{}:", key);
+        let start = data.find(&pattern)? + pattern.len();
+        let value = data[start..]
+            .split(|c: char| c == ',' || c == '}' || c == '\n')
+            .next()?
+            .trim()
+            .trim_matches('\'');
+        decode_hex(value)?.try_into().ok()
+    }
+
+    /// Return the hex-encoded values in the output, or the output itself if there are none.
+    fn output(data: &str) -> (Vec<Vec<u8>>, bool) {
+        let output = &data[data.find("output:").unwrap() + "output:".len()..];
+        let values = output
+            .split(|c: char| c.is_whitespace() || "[],'-".contains(c))
+            .filter(|token| token.starts_with("0x"))
+            .map(|token| decode_hex(token).unwrap())
+            .collect::<Vec<_>>();
+        (values, output.trim_start().starts_with("true"))
+    }
+
+    let setup = BlobSetup::load_trusted_setup(var("EIP4844_TRUSTED_SETUP")).unwrap();
+    let root = var("EIP4844_TEST_VECTORS");
+
+    for data in cases(&root, "blob_to_kzg_commitment") {
+        let commitment = input::<BYTES_PER_BLOB>(&data, "blob")
+            .and_then(|blob| setup.blob_to_commitment(&blob).ok());
+        let expected = output(&data).0;
+        assert_eq!(
+            commitment.map(|c| vec![c.to_vec()]).unwrap_or_default(),
+            expected
+        );
+    }
+    for data in cases(&root, "compute_kzg_proof") {
+        let proof = input::<BYTES_PER_BLOB>(&data, "blob")
+            .zip(input(&data, "z"))
+            .and_then(|(blob, z)| setup.compute_proof(&blob, &z).ok());
+        let expected = output(&data).0;
+        assert_eq!(
+            proof
+                .map(|(proof, y)| vec![proof.to_vec(), y.to_vec()])
+                .unwrap_or_default(),
+            expected
+        );
+    }
+    for data in cases(&root, "compute_blob_kzg_proof") {
+        let proof = input::<BYTES_PER_BLOB>(&data, "blob")
+            .zip(input(&data, "commitment"))
+            .and_then(|(blob, commitment)| setup.compute_blob_proof(&blob, &commitment).ok());
+        let expected = output(&data).0;
+        assert_eq!(
+            proof.map(|p| vec![p.to_vec()]).unwrap_or_default(),
+            expected
+        );
+    }
+    // invalid inputs (a null output) are rejected as invalid proofs.
+    for data in cases(&root, "verify_kzg_proof") {
+        let valid = match (
+            input(&data, "commitment"),
+            input(&data, "z"),
+            input(&data, "y"),
+            input(&data, "proof"),
+        ) {
+            (Some(commitment), Some(z), Some(y), Some(proof)) => {
+                setup.verify_proof(&commitment, &z, &y, &proof).is_ok()
+            }
+            _ => false,
+        };
+        assert_eq!(valid, output(&data).1);
+    }
+    for data in cases(&root, "verify_blob_kzg_proof") {
+        let valid = match (
+            input::<BYTES_PER_BLOB>(&data, "blob"),
+            input(&data, "commitment"),
+            input(&data, "proof"),
+        ) {
+            (Some(blob), Some(commitment), Some(proof)) => {
+                setup.verify_blob_proof(&blob, &commitment, &proof).is_ok()
+            }
+            _ => false,
+        };
+        assert_eq!(valid, output(&data).1);
+    }
+}
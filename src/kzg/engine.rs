@@ -0,0 +1,134 @@
+//! Pluggable backends for the multi-scalar multiplications of the commitment scheme.
+//!
+//! Committing and opening are dominated by a multi-scalar multiplication over \\(\GG_1\\).
+//! The [`MsmEngine`] trait abstracts over such computation,
+//! so that hardware-accelerated implementations (e.g., on CUDA or Metal devices)
+//! can be plugged into [`CommitterKey::commit_with`](super::CommitterKey::commit_with) and
+//! [`CommitterKey::open_with`](super::CommitterKey::open_with)
+//! without touching the rest of the protocol.
+//! The [`FixedWindowMsm`] engine commits to secret polynomials, such as the witness,
+//! without skipping any of the scalars' digits; it is not constant-time.
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_ff::{PrimeField, Zero};
+use ark_std::vec::Vec;
+
+use super::msm;
+use crate::misc::ceil_div;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// The width, in bits, of the windows of [`FixedWindowMsm`].
+/// It divides 64, so that no digit straddles two limbs of a scalar.
+const WINDOW_BITS: usize = 4;
+/// The number of bases sharing the doublings of [`FixedWindowMsm`].
+const WINDOW_CHUNK_SIZE: usize = 1 << 10;
+
+/// A backend computing multi-scalar multiplications over \\(\GG_1\\).
+pub trait MsmEngine<E: Pairing> {
+    /// Return \\(\sum_i s_i B_i\\) for the bases `bases` and the scalars `scalars`.
+    ///
+    /// If the two slices have different lengths, the longer one is truncated.
+    fn msm_g1(&self, bases: &[E::G1Affine], scalars: &[E::ScalarField]) -> E::G1;
+}
+
+/// The default engine, relying on arkworks' [`VariableBaseMSM`](ark_ec::VariableBaseMSM).
+///
+/// With the `parallel` feature, the multi-scalar multiplication is split across the available threads.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ArkworksMsm;
+
+impl<E: Pairing> MsmEngine<E> for ArkworksMsm {
+    #[inline]
+    fn msm_g1(&self, bases: &[E::G1Affine], scalars: &[E::ScalarField]) -> E::G1 {
+        msm::<E>(bases, scalars)
+    }
+}
+
+/// An engine whose sequence of group operations does not depend on the scalars.
+///
+/// Variable-time multi-scalar multiplications skip zero scalars and zero digits,
+/// hence their running time leaks information on the scalars, such as their Hamming weight.
+/// This engine instead runs over fixed windows of 4 bits covering the whole scalar field,
+/// performing the same doublings and additions for any scalars.
+/// The table of each base \(B\) holds the multiples \((d + 1) B\) for every digit \(d\),
+/// and the accumulator starts from the generator rather than the identity,
+/// so that no table entry and, but with negligible probability, no partial sum is the identity,
+/// not even for zero scalars.
+/// Both offsets are subtracted at the end, from the bases and the generator only.
+/// It is several times slower than [`ArkworksMsm`], and meant for the witness commitment only.
+///
+/// # Scope
+///
+/// This engine is **not** constant-time, and does not protect against timing attacks in general.
+/// It only removes the leakage due to skipped scalars and digits.
+/// Table entries are looked up by digit, hence the memory accesses depend on the scalars
+/// and leak through the cache.
+/// Even with a branch-free table scan, the field and curve arithmetic of arkworks is variable-time
+/// and exposes no constant-time conditional selection,
+/// so that a constant-time engine is out of reach of this crate.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FixedWindowMsm;
+
+impl<E: Pairing> MsmEngine<E> for FixedWindowMsm {
+    fn msm_g1(&self, bases: &[E::G1Affine], scalars: &[E::ScalarField]) -> E::G1 {
+        let size = usize::min(bases.len(), scalars.len());
+        let (bases, scalars) = (&bases[..size], &scalars[..size]);
+        cfg_chunks!(bases, WINDOW_CHUNK_SIZE)
+            .zip(cfg_chunks!(scalars, WINDOW_CHUNK_SIZE))
+            .map(|(bases, scalars)| fixed_window_msm::<E::G1>(bases, scalars))
+            .sum()
+    }
+}
+
+/// Return the multi-scalar multiplication of `bases` and `scalars` of the same length,
+/// sharing the doublings across all bases, see [`FixedWindowMsm`].
+fn fixed_window_msm<G: CurveGroup>(bases: &[G::Affine], scalars: &[G::ScalarField]) -> G {
+    let num_windows = ceil_div(G::ScalarField::MODULUS_BIT_SIZE as usize, WINDOW_BITS);
+    let tables = bases
+        .iter()
+        .map(|base| {
+            let mut multiple = base.into_group();
+            (0..1 << WINDOW_BITS)
+                .map(|_| {
+                    let entry = multiple;
+                    multiple += base;
+                    entry
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let scalars = scalars
+        .iter()
+        .map(|scalar| scalar.into_bigint())
+        .collect::<Vec<_>>();
+
+    // start from a nonzero point, so that the doublings never see the identity.
+    let start = G::generator();
+    let mut result = start;
+    for window in (0..num_windows).rev() {
+        for _ in 0..WINDOW_BITS {
+            result.double_in_place();
+        }
+        for (table, scalar) in tables.iter().zip(&scalars) {
+            result += table[digit(scalar.as_ref(), window)];
+        }
+    }
+    // each window added every base once more than its digit,
+    // hence the offset has all digits equal to one.
+    let radix = G::ScalarField::from(1u64 << WINDOW_BITS);
+    let offset = (0..num_windows).fold(G::ScalarField::zero(), |offset, _| {
+        offset * radix + G::ScalarField::from(1u64)
+    });
+    let bases_sum = bases.iter().map(|base| base.into_group()).sum::<G>();
+    // the starting point has been doubled once per bit of all windows.
+    let start_shift = radix.pow([num_windows as u64]);
+    result - bases_sum * offset - start * start_shift
+}
+
+/// Return the `window`-th digit of [`WINDOW_BITS`] bits of the little-endian limbs `limbs`.
+#[inline]
+fn digit(limbs: &[u64], window: usize) -> usize {
+    let bit = window * WINDOW_BITS;
+    ((limbs[bit / 64] >> (bit % 64)) & ((1 << WINDOW_BITS) - 1)) as usize
+}
@@ -0,0 +1,94 @@
+//! Commitments over \\(\GG_2\\).
+//!
+//! Small polynomials can be committed over \\(\GG_2\\) using the powers \\(\tau^i H\\) of the committer key.
+//! Given commitments \\(A\\) to \\(a\\) over \\(\GG_1\\), \\(B\\) to \\(b\\) over \\(\GG_2\\),
+//! and \\(C\\) to \\(c\\) over \\(\GG_1\\),
+//! the relation \\(c = a \cdot b\\) can then be checked with a single pairing equation
+//! \\(e(A, B) = e(C, H)\\).
+//! The degree of polynomials committed over \\(\GG_2\\) is bounded by the number of powers over \\(\GG_2\\),
+//! which can be enlarged at setup via [`CommitterKey::new_with_g2_degree`].
+use ark_ec::{pairing::Pairing, VariableBaseMSM};
+use ark_ff::Zero;
+use ark_serialize::*;
+use ark_std::rand::RngCore;
+
+use super::{Commitment, CommitterKey, VerificationError, VerificationResult, VerifierKey};
+
+/// A Kate polynomial commitment over \\(\GG_2\\).
+#[derive(CanonicalSerialize, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CommitmentG2<E: Pairing>(pub E::G2);
+
+impl<E: Pairing> CommitterKey<E> {
+    /// The setup algorithm for the commitment scheme,
+    /// additionally supporting commitments over \\(\GG_2\\) to polynomials of degree up to `max_g2_degree`.
+    ///
+    /// The resulting key supports up to `max(max_eval_points, max_g2_degree)` evaluation points.
+    pub fn new_with_g2_degree(
+        max_degree: usize,
+        max_eval_points: usize,
+        max_g2_degree: usize,
+        rng: &mut impl RngCore,
+    ) -> Self {
+        Self::new(max_degree, usize::max(max_eval_points, max_g2_degree), rng)
+    }
+
+    /// Return the bound on the degree of polynomials committed over \\(\GG_2\\).
+    #[inline]
+    pub fn max_g2_degree(&self) -> usize {
+        self.powers_of_g2.len() - 1
+    }
+
+    /// Given a polynomial `polynomial` of degree at most [`CommitterKey::max_g2_degree`],
+    /// return a commitment to `polynomial` over \\(\GG_2\\).
+    pub fn commit_g2(&self, polynomial: &[E::ScalarField]) -> CommitmentG2<E> {
+        assert!(
+            polynomial.len() <= self.powers_of_g2.len(),
+            "polynomial too large for the powers over G2"
+        );
+        CommitmentG2(E::G2::msm_unchecked(&self.powers_of_g2, polynomial))
+    }
+}
+
+impl<E: Pairing> VerifierKey<E> {
+    /// Check that the polynomial committed in `product` is the product of the polynomials
+    /// committed in `lhs` (over \\(\GG_1\\)) and `rhs` (over \\(\GG_2\\)),
+    /// i.e. that \\(e(A, B) = e(C, H)\\).
+    pub fn verify_product(
+        &self,
+        lhs: &Commitment<E>,
+        rhs: &CommitmentG2<E>,
+        product: &Commitment<E>,
+    ) -> VerificationResult {
+        let g2 = self.powers_of_g2[0];
+        if E::multi_pairing([lhs.0, -product.0], [rhs.0, g2.into()]).is_zero() {
+            Ok(())
+        } else {
+            Err(VerificationError)
+        }
+    }
+}
+
+#[test]
+fn test_commit_g2() {
+    use ark_poly::univariate::DensePolynomial;
+    use ark_poly::DenseUVPolynomial;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+
+    let rng = &mut ark_std::test_rng();
+    let ck = CommitterKey::<Bls12_381>::new_with_g2_degree(20, 2, 4, rng);
+    let vk = VerifierKey::from(&ck);
+    assert_eq!(ck.max_g2_degree(), 4);
+
+    let a = DensePolynomial::<Fr>::rand(15, rng);
+    let b = DensePolynomial::<Fr>::rand(4, rng);
+    let c = &a * &b;
+    let a_commitment = ck.commit(&a);
+    let b_commitment = ck.commit_g2(&b);
+    let c_commitment = ck.commit(&c);
+    assert!(vk
+        .verify_product(&a_commitment, &b_commitment, &c_commitment)
+        .is_ok());
+    assert!(vk
+        .verify_product(&c_commitment, &b_commitment, &a_commitment)
+        .is_err());
+}
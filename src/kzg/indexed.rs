@@ -0,0 +1,105 @@
+//! Committer keys preprocessed for an index.
+//!
+//! Given a list of indices \\((i_0, \dots, i_{n-1})\\),
+//! the preprocessed key commits to a polynomial \\(f\\) as \\(\sum_j f_{i_j} \tau^j G\\),
+//! i.e. to the polynomial whose \\(j\\)-th coefficient is \\(f_{i_j}\\).
+//! Only the first \\(n\\) bases are stored, together with the indices,
+//! independently of the size of the SRS.
+use ark_ec::pairing::Pairing;
+use ark_ff::Zero;
+use ark_std::borrow::Borrow;
+use ark_std::sync::Arc;
+use ark_std::vec::Vec;
+
+use super::{msm, Commitment, CommitterKey};
+
+/// A committer key preprocessed for the indices `indices`, obtained via [`CommitterKey::index_by`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndexedCommitterKey<E: Pairing> {
+    indices: Vec<usize>,
+    powers_of_g: Vec<E::G1Affine>,
+    /// The powers of \\(\tau\\) over \\(\GG_2\\), shared across all keys indexed from the same committer key.
+    pub powers_of_g2: Arc<Vec<E::G2Affine>>,
+}
+
+impl<E: Pairing> CommitterKey<E> {
+    /// Obtain a new preprocessed committer key defined by the indices `indices`.
+    pub fn index_by(&self, indices: &[usize]) -> IndexedCommitterKey<E> {
+        let [indexed_ck] = self.index_by_many([indices]);
+        indexed_ck
+    }
+
+    /// Obtain many preprocessed committer keys, one for each list of indices in `indices`,
+    /// sharing the same powers over \\(\GG_2\\).
+    pub fn index_by_many<const N: usize>(
+        &self,
+        indices: [&[usize]; N],
+    ) -> [IndexedCommitterKey<E>; N] {
+        let powers_of_g2 = Arc::new(self.powers_of_g2.clone());
+        indices.map(|indices| {
+            let len = usize::min(indices.len(), self.powers_of_g.len());
+            IndexedCommitterKey {
+                indices: indices[..len].to_vec(),
+                powers_of_g: self.powers_of_g[..len].to_vec(),
+                powers_of_g2: powers_of_g2.clone(),
+            }
+        })
+    }
+}
+
+impl<E: Pairing> IndexedCommitterKey<E> {
+    /// Given a polynomial `polynomial`, return a commitment to the polynomial
+    /// whose coefficients are the ones of `polynomial` at the key's indices.
+    pub fn commit(&self, polynomial: &[E::ScalarField]) -> Commitment<E> {
+        let scalars = self
+            .indices
+            .iter()
+            .map(|&i| {
+                polynomial
+                    .get(i)
+                    .copied()
+                    .unwrap_or_else(E::ScalarField::zero)
+            })
+            .collect::<Vec<_>>();
+        Commitment(msm::<E>(&self.powers_of_g, &scalars))
+    }
+
+    /// Given an iterator over `polynomials`, expressed as vectors of coefficients, return a vector of commitmetns to all of them.
+    pub fn batch_commit<J>(&self, polynomials: J) -> Vec<Commitment<E>>
+    where
+        J: IntoIterator,
+        J::Item: Borrow<Vec<E::ScalarField>>,
+    {
+        polynomials
+            .into_iter()
+            .map(|p| self.commit(p.borrow()))
+            .collect()
+    }
+}
+
+#[test]
+fn test_index_by() {
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+
+    let rng = &mut ark_std::test_rng();
+    let ck = CommitterKey::<Bls12_381>::new(15, 3, rng);
+    let polynomial = (0..10).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+    let indices = [3, 0, 3, 9, 12, 1];
+
+    let [indexed_ck, other_indexed_ck] = ck.index_by_many([&indices[..], &[1, 2]]);
+    assert!(Arc::ptr_eq(
+        &indexed_ck.powers_of_g2,
+        &other_indexed_ck.powers_of_g2
+    ));
+
+    let looked_up = indices
+        .iter()
+        .map(|&i| polynomial.get(i).copied().unwrap_or_else(Fr::zero))
+        .collect::<Vec<_>>();
+    assert_eq!(indexed_ck.commit(&polynomial), ck.commit(&looked_up));
+    assert_eq!(
+        ck.index_by(&indices).commit(&polynomial),
+        ck.commit(&looked_up)
+    );
+}
@@ -0,0 +1,85 @@
+//! Commitments to polynomials in evaluation form.
+//!
+//! Given an evaluation domain $\Omega$ of size $n$, the committer key in the Lagrange basis consists of
+//! $(L_0(\tau) G, \dots, L_{n-1}(\tau) G)$, where $L_i$ is the $i$-th Lagrange polynomial over $\Omega$.
+//! It can be derived from the monomial committer key with a single inverse FFT over $\GG_1$,
+//! since $L_i(\tau) = \frac{1}{n} \sum_j \omega^{-ij} \tau^j$.
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_poly::EvaluationDomain;
+use ark_std::vec::Vec;
+
+use super::{Commitment, CommitterKey};
+
+/// The committer key for polynomials in evaluation form over a fixed domain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LagrangeCommitterKey<E: Pairing> {
+    lagrange_powers_of_g: Vec<E::G1Affine>,
+}
+
+impl<E: Pairing> LagrangeCommitterKey<E> {
+    /// Return a commitment to the polynomial whose evaluations over the domain are `evals`.
+    pub fn commit(&self, evals: &[E::ScalarField]) -> Commitment<E> {
+        assert!(evals.len() <= self.lagrange_powers_of_g.len());
+        Commitment(E::G1::msm_unchecked(&self.lagrange_powers_of_g, evals))
+    }
+
+    /// Return the size of the underlying evaluation domain.
+    pub fn domain_size(&self) -> usize {
+        self.lagrange_powers_of_g.len()
+    }
+}
+
+impl<E: Pairing> CommitterKey<E> {
+    /// Derive the committer key in the Lagrange basis over the evaluation domain `domain`.
+    pub fn lagrange_key<D>(&self, domain: &D) -> LagrangeCommitterKey<E>
+    where
+        D: EvaluationDomain<E::ScalarField>,
+    {
+        assert!(
+            domain.size() <= self.powers_of_g.len(),
+            "domain larger than the committer key"
+        );
+        let powers_of_g = self.powers_of_g[..domain.size()]
+            .iter()
+            .map(|g| g.into_group())
+            .collect::<Vec<_>>();
+        let lagrange_powers_of_g = E::G1::normalize_batch(&domain.ifft(&powers_of_g));
+        LagrangeCommitterKey {
+            lagrange_powers_of_g,
+        }
+    }
+
+    /// Given the evaluations `evals` of a polynomial over the domain `domain`,
+    /// return a commitment to the polynomial.
+    ///
+    /// This derives the Lagrange basis at each call:
+    /// when committing to multiple polynomials over the same domain,
+    /// compute once a [`LagrangeCommitterKey`] via [`CommitterKey::lagrange_key`].
+    pub fn commit_evals<D>(&self, evals: &[E::ScalarField], domain: &D) -> Commitment<E>
+    where
+        D: EvaluationDomain<E::ScalarField>,
+    {
+        self.lagrange_key(domain).commit(evals)
+    }
+}
+
+#[test]
+fn test_commit_evals() {
+    use ark_poly::univariate::DensePolynomial;
+    use ark_poly::{DenseUVPolynomial, Radix2EvaluationDomain};
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+
+    let rng = &mut ark_std::test_rng();
+    let ck = CommitterKey::<Bls12_381>::new(31, 3, rng);
+    let domain = Radix2EvaluationDomain::<Fr>::new(16).unwrap();
+    let polynomial = DensePolynomial::<Fr>::rand(15, rng);
+    let evals = domain.fft(&polynomial.coeffs);
+
+    let lagrange_ck = ck.lagrange_key(&domain);
+    assert_eq!(lagrange_ck.domain_size(), 16);
+    assert_eq!(lagrange_ck.commit(&evals), ck.commit(&polynomial.coeffs));
+    assert_eq!(
+        ck.commit_evals(&evals, &domain),
+        ck.commit(&polynomial.coeffs)
+    );
+}
@@ -0,0 +1,218 @@
+//! A committer key whose powers over \\(\GG_1\\) live on disk.
+//!
+//! For large SRSs (say, \\(2^{28}\\) elements of \\(\GG_1\\)) keeping the committer key in memory
+//! next to the witness is prohibitive.
+//! [`MmapCommitterKey`] memory-maps the powers of \\(\tau\\) over \\(\GG_1\\) from a file,
+//! and feeds them to the multi-scalar multiplication in chunks of [`MMAP_CHUNK_SIZE`] elements,
+//! so that only a chunk at a time is resident in memory.
+//!
+//! The file layout is:
+//! - the number of powers over \\(\GG_1\\), as a little-endian `u64`;
+//! - the powers over \\(\GG_2\\), serialized uncompressed;
+//! - the powers over the blinding generator \\(\gamma G\\), serialized uncompressed;
+//! - the powers over \\(\GG_1\\), each serialized uncompressed.
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use ark_ec::pairing::Pairing;
+use ark_ff::Zero;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::borrow::Borrow;
+use ark_std::vec::Vec;
+use memmap::Mmap;
+
+use super::time::CommitterKey;
+use super::{linear_quotient, msm, Commitment, EvaluationProof, SrsError, VerifierKey};
+
+/// The number of elements of \\(\GG_1\\) read from disk at once.
+pub const MMAP_CHUNK_SIZE: usize = 1 << 16;
+
+/// A committer key backed by a memory-mapped file.
+pub struct MmapCommitterKey<E: Pairing> {
+    mmap: Mmap,
+    offset: usize,
+    len: usize,
+    point_size: usize,
+    pub(crate) powers_of_g2: Vec<E::G2Affine>,
+    pub(crate) powers_of_gamma_g: Vec<E::G1Affine>,
+}
+
+impl<E: Pairing> MmapCommitterKey<E> {
+    /// Write the committer key `ck` to the file at `path`, and memory-map it.
+    pub fn create<P: AsRef<Path>>(ck: &CommitterKey<E>, path: P) -> Result<Self, SrsError> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path.as_ref())
+            .map_err(|_| SrsError::Io)?;
+        let mut writer = BufWriter::new(file);
+        writer
+            .write_all(&(ck.powers_of_g.len() as u64).to_le_bytes())
+            .map_err(|_| SrsError::Io)?;
+        ck.powers_of_g2
+            .serialize_uncompressed(&mut writer)
+            .map_err(|_| SrsError::Io)?;
+        ck.powers_of_gamma_g
+            .serialize_uncompressed(&mut writer)
+            .map_err(|_| SrsError::Io)?;
+        for g in &ck.powers_of_g {
+            g.serialize_uncompressed(&mut writer)
+                .map_err(|_| SrsError::Io)?;
+        }
+        writer.flush().map_err(|_| SrsError::Io)?;
+        drop(writer);
+
+        Self::open(path)
+    }
+
+    /// Memory-map the committer key stored in the file at `path`.
+    ///
+    /// Only the (short) powers over \\(\GG_2\\) and over the blinding generator are loaded in memory.
+    /// _Nota bene:_ the file is assumed to come from trusted storage:
+    /// points are checked neither for being on the curve nor for being in the prime-order subgroup.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, SrsError> {
+        let file = File::open(path).map_err(|_| SrsError::Io)?;
+        // SAFETY: the file is mapped read-only, and its contents are parsed before being used.
+        let mmap = unsafe { Mmap::map(&file) }.map_err(|_| SrsError::Io)?;
+
+        let mut reader = &mmap[..];
+        let mut len_bytes = [0u8; 8];
+        len_bytes.copy_from_slice(
+            reader
+                .get(..8)
+                .ok_or(SrsError::InvalidFormat("missing header"))?,
+        );
+        reader = &reader[8..];
+        let len = usize::try_from(u64::from_le_bytes(len_bytes))
+            .map_err(|_| SrsError::InvalidFormat("length overflows usize"))?;
+        let powers_of_g2 = Vec::<E::G2Affine>::deserialize_uncompressed_unchecked(&mut reader)
+            .map_err(|_| SrsError::InvalidFormat("invalid powers over G2"))?;
+        let powers_of_gamma_g = Vec::<E::G1Affine>::deserialize_uncompressed_unchecked(&mut reader)
+            .map_err(|_| SrsError::InvalidFormat("invalid blinding powers"))?;
+        let offset = mmap.len() - reader.len();
+
+        let point_size = E::G1Affine::zero().uncompressed_size();
+        if reader.len() != len * point_size {
+            return Err(SrsError::InvalidFormat("unexpected file length"));
+        }
+        if len == 0 || powers_of_g2.is_empty() {
+            return Err(SrsError::NotEnoughPowers);
+        }
+
+        Ok(Self {
+            mmap,
+            offset,
+            len,
+            point_size,
+            powers_of_g2,
+            powers_of_gamma_g,
+        })
+    }
+
+    /// Return the bound on evaluation points.
+    #[inline]
+    pub fn max_eval_points(&self) -> usize {
+        self.powers_of_g2.len() - 1
+    }
+
+    /// Return the bound on the degree of committed polynomials.
+    #[inline]
+    pub fn max_degree(&self) -> usize {
+        self.len - 1
+    }
+
+    /// Read the powers of \\(\tau\\) over \\(\GG_1\\) in the range `start..end`.
+    fn powers_of_g(&self, start: usize, end: usize) -> Vec<E::G1Affine> {
+        let bytes =
+            &self.mmap[self.offset + start * self.point_size..self.offset + end * self.point_size];
+        bytes
+            .chunks_exact(self.point_size)
+            .map(|mut point| {
+                E::G1Affine::deserialize_uncompressed_unchecked(&mut point)
+                    .expect("the file has been modified since it was mapped")
+            })
+            .collect()
+    }
+
+    /// Given a polynomial `polynomial` of degree less than `max_degree`, return a commitment to `polynomial`.
+    ///
+    /// The bases are read from disk [`MMAP_CHUNK_SIZE`] at a time.
+    pub fn commit(&self, polynomial: &[E::ScalarField]) -> Commitment<E> {
+        let size = usize::min(polynomial.len(), self.len);
+        let commitment = polynomial[..size]
+            .chunks(MMAP_CHUNK_SIZE)
+            .enumerate()
+            .map(|(i, chunk)| {
+                let start = i * MMAP_CHUNK_SIZE;
+                let bases = self.powers_of_g(start, start + chunk.len());
+                msm::<E>(&bases, chunk)
+            })
+            .sum();
+        Commitment(commitment)
+    }
+
+    /// Given an iterator over `polynomials`, expressed as vectors of coefficients, return a vector of commitmetns to all of them.
+    pub fn batch_commit<J>(&self, polynomials: J) -> Vec<Commitment<E>>
+    where
+        J: IntoIterator,
+        J::Item: Borrow<Vec<E::ScalarField>>,
+    {
+        polynomials
+            .into_iter()
+            .map(|p| self.commit(p.borrow()))
+            .collect()
+    }
+
+    /// Given a polynomial `polynomial` and an evaluation point `evaluation_point`,
+    /// return the evaluation of `polynomial in `evaluation_point`,
+    /// together with an evaluation proof.
+    pub fn open(
+        &self,
+        polynomial: &[E::ScalarField],
+        evaluation_point: &E::ScalarField,
+    ) -> (E::ScalarField, EvaluationProof<E>) {
+        let (evaluation, quotient) = linear_quotient(polynomial, evaluation_point);
+        (evaluation, EvaluationProof(self.commit(&quotient).0))
+    }
+}
+
+impl<E: Pairing> From<&MmapCommitterKey<E>> for VerifierKey<E> {
+    fn from(ck: &MmapCommitterKey<E>) -> VerifierKey<E> {
+        let max_eval_points = ck.max_eval_points();
+        let powers_of_g = ck.powers_of_g(0, usize::min(max_eval_points, ck.len));
+
+        VerifierKey {
+            powers_of_g,
+            powers_of_g2: ck.powers_of_g2.clone(),
+            gamma_g: ck.powers_of_gamma_g[0],
+            max_degree: ck.max_degree(),
+        }
+    }
+}
+
+#[test]
+fn test_mmap_commitment() {
+    use ark_poly::univariate::DensePolynomial;
+    use ark_poly::DenseUVPolynomial;
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+
+    let rng = &mut ark_std::test_rng();
+    let d = MMAP_CHUNK_SIZE + 10;
+    let ck = CommitterKey::<Bls12_381>::new(d, 3, rng);
+    let path = crate::iterable::mmap::TempPath::new("gemini-test-mmap-ck");
+    let mmap_ck = MmapCommitterKey::create(&ck, &path).unwrap();
+    assert_eq!(mmap_ck.max_degree(), ck.max_degree());
+    assert_eq!(VerifierKey::from(&mmap_ck), VerifierKey::from(&ck));
+
+    let polynomial = DensePolynomial::<Fr>::rand(d, rng);
+    let alpha = Fr::rand(rng);
+    assert_eq!(mmap_ck.commit(&polynomial), ck.commit(&polynomial));
+    assert_eq!(
+        mmap_ck.open(&polynomial, &alpha),
+        ck.open(&polynomial, &alpha)
+    );
+}
@@ -30,14 +30,27 @@
 //! by asking the verifier a random challenge $\eta$, and opening instead
 //! $\sum_i \eta^i f_i $.
 //!
-//! _Nota bene:_ despite it is also possible to open multiple polynomials
-//! over different points [[BDFG20](https://eprint.iacr.org/2020/081.pdf)],
-//! however this is not currently supported by our implementation.
+//! Commitments can also be made hiding by adding a random blinding polynomial $r$
+//! over an independent generator $\gamma G$, i.e. $C \defeq \langle \vec f, \vec G \rangle + \langle \vec r, \gamma \vec G \rangle$
+//! (see [`CommitterKey::commit_hiding`](self::CommitterKey::commit_hiding)).
+//! The evaluation proof then additionally reveals $r(\alpha)$.
+//!
+//! Multiple polynomials can also be opened over different sets of points
+//! [[BDFG20](https://eprint.iacr.org/2020/081.pdf)]
+//! via [`CommitterKey::open_shplonk`](self::CommitterKey::open_shplonk),
+//! with a proof of two group elements.
+//! Conversely, many evaluation proofs can be aggregated into a single proof of logarithmic size
+//! via an [`AggregationKey`](self::AggregationKey).
 //!
 //!
 //! # Examples
 //!
-//! When creating a new SRS, one must specify a degree bound `max_degree`
+//! In production, the SRS should be loaded from an existing powers-of-tau ceremony, via
+//! [`CommitterKey::from_ptau`](self::CommitterKey::from_ptau) or
+//! [`CommitterKey::from_powersoftau`](self::CommitterKey::from_powersoftau).
+//! Keys too large to fit in memory can be stored on disk and memory-mapped via `MmapCommitterKey`
+//! (requires the `mmap` feature).
+//! For testing, when creating a new SRS, one must specify a degree bound `max_degree`
 //! for the commitment polynomials, and a degree bound `max_evals` for
 //! the maximum number of opening points.
 //! From the SRS, it is possible to derive the verification key
@@ -83,34 +96,156 @@
 //! ```
 //!
 
+mod aggregation;
+mod ceremony;
+mod contribution;
+mod degree_bound;
+#[cfg(feature = "eip4844")]
+mod eip4844;
+mod engine;
+mod g2;
+mod indexed;
+mod lagrange;
+#[cfg(feature = "mmap")]
+mod mmap;
+mod pipeline;
+#[cfg(feature = "poly-commit")]
+mod poly_commit;
+mod prepared;
+mod shplonk;
 mod space;
+mod split;
 mod time;
+mod validate;
 
+pub use aggregation::{AggregatedProof, AggregationKey, AggregationRound, AggregationVerifierKey};
 use ark_ec::CurveGroup;
 use ark_std::vec::Vec;
-pub use space::CommitterKeyStream;
-pub use time::CommitterKey;
+pub use contribution::ContributionProof;
+pub use degree_bound::DegreeBoundProof;
+#[cfg(feature = "eip4844")]
+pub use eip4844::{BlobError, BlobSetup, BYTES_PER_BLOB, FIELD_ELEMENTS_PER_BLOB};
+pub use engine::{ArkworksMsm, FixedWindowMsm, MsmEngine};
+pub use g2::CommitmentG2;
+pub use indexed::IndexedCommitterKey;
+pub use lagrange::LagrangeCommitterKey;
+#[cfg(feature = "mmap")]
+pub use mmap::{MmapCommitterKey, MMAP_CHUNK_SIZE};
+#[cfg(feature = "poly-commit")]
+pub use poly_commit::{GeminiKzg, PcCommitment, PcProof, UniversalParams};
+pub use prepared::PreparedVerifierKey;
+pub use shplonk::ShplonkProof;
+pub use space::{CommitterKeyStream, SpaceProverConfig};
+pub use split::SplitEvaluationProof;
+pub use time::{CommitterKey, Insecure, OpeningKey, STREAM_CHUNK_SIZE};
 
+#[cfg(test)]
+pub(crate) use ceremony::write_ptau;
 #[cfg(test)]
 pub mod tests;
 
-use ark_ec::{pairing::Pairing, VariableBaseMSM};
-use ark_ff::{Field, One, Zero};
+use ark_ec::{pairing::Pairing, AffineRepr, VariableBaseMSM};
+use ark_ff::{FftField, Field, One, Zero};
 use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
 use ark_serialize::*;
 use ark_std::fmt;
 use ark_std::ops::{Add, Mul};
+use ark_std::rand::RngCore;
+use ark_std::UniformRand;
+
+use crate::misc::{batch_inverse, ceil_div, linear_combination, powers};
 
-use crate::misc::{linear_combination, powers};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Run `f` using at most `num_threads` threads for the parallel sections of the commitment scheme,
+/// i.e. the multi-scalar multiplications, the batch commitments, and the SRS generation.
+///
+/// Without the `parallel` feature, `f` is simply executed on the current thread.
+pub fn with_thread_budget<R: Send>(num_threads: usize, f: impl FnOnce() -> R + Send) -> R {
+    #[cfg(feature = "parallel")]
+    {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()
+            .expect("failed to build the thread pool")
+            .install(f)
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        let _ = num_threads;
+        f()
+    }
+}
+
+/// Multi-scalar multiplication over \\(\GG_1\\),
+/// splitting `bases` and `scalars` into one chunk per available thread.
+pub(crate) fn msm<E: Pairing>(bases: &[E::G1Affine], scalars: &[E::ScalarField]) -> E::G1 {
+    msm_group::<E::G1>(bases, scalars)
+}
+
+/// Multi-scalar multiplication over the group `G`,
+/// splitting `bases` and `scalars` into one chunk per available thread.
+pub(crate) fn msm_group<G: CurveGroup>(bases: &[G::Affine], scalars: &[G::ScalarField]) -> G {
+    let size = usize::min(bases.len(), scalars.len());
+    #[cfg(feature = "parallel")]
+    {
+        let chunk_size = usize::max(ceil_div(size, rayon::current_num_threads()), 1);
+        bases[..size]
+            .par_chunks(chunk_size)
+            .zip(scalars[..size].par_chunks(chunk_size))
+            .map(|(bases, scalars)| G::msm_unchecked(bases, scalars))
+            .sum()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        G::msm_unchecked(&bases[..size], &scalars[..size])
+    }
+}
 
 /// A Kate polynomial commitment over a bilinear group, represented as a single \\(\GG_1\\) element.
-#[derive(CanonicalSerialize, Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(CanonicalSerialize, CanonicalDeserialize, Copy, Clone, Debug, PartialEq, Eq)]
 pub struct Commitment<E: Pairing>(pub E::G1);
 
 /// Polynomial evaluation proof, represented as a single $\GG_1$ element.
-#[derive(CanonicalSerialize, Clone, Debug, PartialEq, Eq)]
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
 pub struct EvaluationProof<E: Pairing>(pub E::G1);
 
+/// The blinding polynomial used in a hiding commitment, represented as a vector of its coefficients.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Randomness<E: Pairing>(pub Vec<E::ScalarField>);
+
+#[cfg(feature = "zeroize")]
+impl<E: Pairing> Drop for Randomness<E> {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.0);
+    }
+}
+
+/// Hiding polynomial evaluation proof, represented as a single $\GG_1$ element
+/// together with the evaluation of the blinding polynomial.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct HidingEvaluationProof<E: Pairing> {
+    /// The commitment to the quotient of both the committed and the blinding polynomial.
+    pub proof: EvaluationProof<E>,
+    /// The evaluation of the blinding polynomial.
+    pub blinding_evaluation: E::ScalarField,
+}
+
+impl<E: Pairing> Add for Commitment<E> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Commitment(self.0 + rhs.0)
+    }
+}
+
+impl<E: Pairing> core::iter::Sum for Commitment<E> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Commitment(E::G1::zero()), |x, y| x + y)
+    }
+}
+
 impl<E: Pairing> Add for EvaluationProof<E> {
     type Output = Self;
 
@@ -137,21 +272,59 @@ impl fmt::Display for VerificationError {
 
 pub(crate) type VerificationResult = Result<(), VerificationError>;
 
+/// Error type denoting a failure in loading or validating a structured reference string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SrsError {
+    /// The underlying reader failed.
+    Io,
+    /// The transcript is malformed.
+    InvalidFormat(&'static str),
+    /// The transcript has been generated for a different curve.
+    UnsupportedCurve,
+    /// The transcript does not contain enough powers for the requested degree.
+    NotEnoughPowers,
+    /// Some SRS element is not a valid point of the prime-order subgroup.
+    InvalidPoint,
+    /// The SRS elements are not consecutive powers of the same element.
+    InconsistentPowers,
+    /// The SRS update is not consistent with the previous SRS.
+    InvalidContribution,
+}
+
+impl fmt::Display for SrsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io => write!(f, "I/O error while reading the SRS."),
+            Self::InvalidFormat(reason) => write!(f, "Invalid SRS format: {}.", reason),
+            Self::UnsupportedCurve => write!(f, "The SRS is defined over a different curve."),
+            Self::NotEnoughPowers => write!(f, "The SRS is too short for the requested degree."),
+            Self::InvalidPoint => write!(f, "The SRS contains an invalid point."),
+            Self::InconsistentPowers => write!(f, "The SRS elements are not consecutive powers."),
+            Self::InvalidContribution => write!(f, "The SRS contribution is invalid."),
+        }
+    }
+}
+
 // XXX.  add const generic argument for the size.
 /// The verification key for the polynomial commitment scheme.
 /// It also implements verification functions for the evaluation proof.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
 pub struct VerifierKey<E: Pairing> {
     /// The generator of $\GG_1$
     powers_of_g: Vec<E::G1Affine>,
     /// The generator of $\GG_2$, together with its multiplication by the trapdoor.
     pub powers_of_g2: Vec<E::G2Affine>,
+    /// The blinding generator $\gamma G$ of $\GG_1$, used for hiding commitments.
+    pub gamma_g: E::G1Affine,
+    /// The maximum degree supported by the committer key, used for checking degree bounds.
+    pub max_degree: usize,
 }
 
 impl<E: Pairing> VerifierKey<E> {
     /// The verification procedure for the EvaluationProof with a single polynomial evaluated at a single evaluation point.
     /// The polynomial are evaluated at the point ``alpha`` and is committed as ``commitment``.
     /// The evaluation proof can be obtained either in a space-efficient or a time-efficient flavour.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub fn verify(
         &self,
         commitment: &Commitment<E>,
@@ -171,12 +344,96 @@ impl<E: Pairing> VerifierKey<E> {
         }
     }
 
+    /// The batch verification procedure for many EvaluationProofs,
+    /// each for a single polynomial evaluated at a single evaluation point.
+    ///
+    /// Each element of ``instances`` is a tuple ``(commitment, alpha, evaluation, proof)``
+    /// as in [`Self::verify`].
+    /// All the pairing equations are combined with random 128-bit scalars sampled from ``rng``
+    /// and checked with a single multi-pairing:
+    /// \\[
+    /// e\left(\sum_i r_i (C_i - \mu_i G + \alpha_i \pi_i), H\right) = e\left(\sum_i r_i \pi_i, \tau H\right).
+    /// \\]
+    pub fn verify_batch(
+        &self,
+        instances: &[(
+            Commitment<E>,
+            E::ScalarField,
+            E::ScalarField,
+            EvaluationProof<E>,
+        )],
+        rng: &mut impl RngCore,
+    ) -> VerificationResult {
+        let randomizers = (0..instances.len())
+            .map(|_| E::ScalarField::from(u128::rand(rng)))
+            .collect::<Vec<_>>();
+
+        let mut lhs_bases = Vec::with_capacity(2 * instances.len() + 1);
+        let mut lhs_scalars = Vec::with_capacity(2 * instances.len() + 1);
+        let mut rhs_bases = Vec::with_capacity(instances.len());
+        let mut batched_evaluation = E::ScalarField::zero();
+        for ((commitment, alpha, evaluation, proof), r) in instances.iter().zip(&randomizers) {
+            lhs_bases.push(commitment.0);
+            lhs_scalars.push(*r);
+            lhs_bases.push(proof.0);
+            lhs_scalars.push(*r * alpha);
+            rhs_bases.push(proof.0);
+            batched_evaluation += *r * evaluation;
+        }
+        let lhs = E::G1::msm_unchecked(&E::G1::normalize_batch(&lhs_bases), &lhs_scalars)
+            - self.powers_of_g[0] * batched_evaluation;
+        let rhs = E::G1::msm_unchecked(&E::G1::normalize_batch(&rhs_bases), &randomizers);
+
+        let g2 = self.powers_of_g2[0];
+        let tau_g2 = self.powers_of_g2[1];
+        if E::multi_pairing([lhs, -rhs], [g2, tau_g2]).is_zero() {
+            Ok(())
+        } else {
+            Err(VerificationError)
+        }
+    }
+
+    /// The verification procedure for the [`HidingEvaluationProof`] of a hiding commitment.
+    /// The polynomial is evaluated at the point ``alpha`` and is committed as ``commitment``.
+    pub fn verify_hiding(
+        &self,
+        commitment: &Commitment<E>,
+        &alpha: &E::ScalarField,
+        evaluation: &E::ScalarField,
+        proof: &HidingEvaluationProof<E>,
+    ) -> VerificationResult {
+        let blinded_commitment =
+            Commitment(commitment.0 - self.gamma_g * proof.blinding_evaluation);
+        self.verify(&blinded_commitment, &alpha, evaluation, &proof.proof)
+    }
+
+    /// The verification procedure for the EvaluationProof with a single polynomial evaluated at a set of evaluation points.
+    /// The polynomial is committed as ``commitment``,
+    /// and ``evaluations`` follows the same evaluation point order as ``eval_points``.
+    /// The evaluation proof can be obtained via [`CommitterKey::open_multi_points_with_evals`].
+    pub fn verify_multi_points_single(
+        &self,
+        commitment: &Commitment<E>,
+        eval_points: &[E::ScalarField],
+        evaluations: &[E::ScalarField],
+        proof: &EvaluationProof<E>,
+    ) -> VerificationResult {
+        self.verify_multi_points(
+            &[*commitment],
+            eval_points,
+            &[evaluations.to_vec()],
+            proof,
+            &E::ScalarField::one(),
+        )
+    }
+
     /// The verification procedure for the EvaluationProof with a set of polynomials evaluated at a set of evaluation points.
     /// All the polynomials are evaluated at the set of points ``eval_points`` and are committed as ``commitments``.
     /// ``evaluations`` contains evaluations of each polynomial at each point in ``eval_points``.
     /// ``evaluations`` follows the same polynomial order as ``commitments`` and the same evaluation point order as ``eval_points``.
     /// The evaluation proof can be obtained either in a space-efficient or a time-efficient flavour.
     /// ``open_chal`` is a random challenge for batching evaluation proofs across different polynomials.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(commitments = commitments.len(), points = eval_points.len())))]
     pub fn verify_multi_points(
         &self,
         commitments: &[Commitment<E>],
@@ -185,23 +442,63 @@ impl<E: Pairing> VerifierKey<E> {
         proof: &EvaluationProof<E>,
         open_chal: &E::ScalarField,
     ) -> VerificationResult {
+        let (lhs, zeros) =
+            self.multi_points_equation(commitments, eval_points, evaluations, open_chal)?;
+        let g2 = self.powers_of_g2[0];
+
+        if E::pairing(lhs, g2) == E::pairing(proof.0, zeros) {
+            Ok(())
+        } else {
+            Err(VerificationError)
+        }
+    }
+
+    /// Return the elements \\(F - I \in \GG_1\\) and \\(Z(\tau) H \in \GG_2\\)
+    /// such that a multi-point evaluation proof \\(\pi\\) is valid if and only if
+    /// \\(e(F - I, H) = e(\pi, Z(\tau) H)\\),
+    /// where \\(F\\) is the batched commitment, \\(I\\) is the commitment to the batched interpolating polynomial,
+    /// and \\(Z\\) is the vanishing polynomial over `eval_points`.
+    ///
+    /// Fails if `eval_points` are not distinct, or `commitments` and `evaluations` differ in length.
+    pub(crate) fn multi_points_equation(
+        &self,
+        commitments: &[Commitment<E>],
+        eval_points: &[E::ScalarField],
+        evaluations: &[Vec<E::ScalarField>],
+        open_chal: &E::ScalarField,
+    ) -> Result<(E::G1, E::G2), VerificationError> {
+        let (lhs, zeros) =
+            self.multi_points_equation_coeffs(commitments, eval_points, evaluations, open_chal)?;
+        let zeros = E::G2::msm_unchecked(&self.powers_of_g2, &zeros);
+        Ok((lhs, zeros))
+    }
+
+    /// Return the element \\(F - I \in \GG_1\\) as in [`Self::multi_points_equation`],
+    /// together with the coefficients of the vanishing polynomial \\(Z\\) over `eval_points`.
+    pub(crate) fn multi_points_equation_coeffs(
+        &self,
+        commitments: &[Commitment<E>],
+        eval_points: &[E::ScalarField],
+        evaluations: &[Vec<E::ScalarField>],
+        open_chal: &E::ScalarField,
+    ) -> Result<(E::G1, Vec<E::ScalarField>), VerificationError> {
         // Computing the vanishing polynomial over eval_points
         let zeros = vanishing_polynomial(eval_points);
-        let zeros = E::G2::msm_unchecked(&self.powers_of_g2, &zeros.coeffs());
 
         // Computing the inverse for the interpolation
-        let mut sca_inverse = Vec::new();
+        let mut sca = Vec::new();
         for (j, x_j) in eval_points.iter().enumerate() {
-            let mut sca = E::ScalarField::one();
+            let mut sca_j = E::ScalarField::one();
             for (k, x_k) in eval_points.iter().enumerate() {
                 if j == k {
                     continue;
                 }
-                sca *= *x_j - x_k;
+                sca_j *= *x_j - x_k;
             }
-            sca = sca.inverse().unwrap();
-            sca_inverse.push(sca);
+            sca.push(sca_j);
         }
+        // repeated evaluation points admit no interpolating polynomial.
+        let sca_inverse = batch_inverse(&sca).ok_or(VerificationError)?;
 
         // Computing the lagrange polynomial for the interpolation
         let mut lang = Vec::new();
@@ -233,10 +530,40 @@ impl<E: Pairing> VerifierKey<E> {
             .iter()
             .map(|x| x.0.into_affine())
             .collect::<Vec<_>>();
-        let f_comm = E::G1::msm(&comm_vec, &etas).unwrap();
-        let g2 = self.powers_of_g2[0];
+        let f_comm = E::G1::msm(&comm_vec, &etas).map_err(|_| VerificationError)?;
+        Ok((f_comm - i_comm, zeros.coeffs))
+    }
 
-        if E::pairing(f_comm - i_comm, g2) == E::pairing(proof.0, zeros) {
+    /// Verify at once many multi-point evaluation proofs `proofs`,
+    /// given their `equations` \\((F_k - I_k, Z_k(\tau) H)\\) as returned by [`Self::multi_points_equation`].
+    ///
+    /// The equations are combined with the random scalars `randomizers`, checking that
+    /// \\(e(\sum_k r_k (F_k - I_k), H) = \prod_k e(r_k \pi_k, Z_k(\tau) H)\\)
+    /// with a single multi-pairing.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(equations = equations.len())))]
+    pub(crate) fn verify_multi_points_equations(
+        &self,
+        equations: &[(E::G1, E::G2)],
+        proofs: &[EvaluationProof<E>],
+        randomizers: &[E::ScalarField],
+    ) -> VerificationResult {
+        if equations.len() != proofs.len() || equations.len() != randomizers.len() {
+            return Err(VerificationError);
+        }
+        let lhs = equations
+            .iter()
+            .zip(randomizers)
+            .map(|((lhs, _zeros), r)| *lhs * r)
+            .sum::<E::G1>();
+        let g1 = ark_std::iter::once(lhs).chain(
+            proofs
+                .iter()
+                .zip(randomizers)
+                .map(|(proof, r)| -(proof.0 * r)),
+        );
+        let g2 = ark_std::iter::once(self.powers_of_g2[0].into_group())
+            .chain(equations.iter().map(|(_lhs, zeros)| *zeros));
+        if E::multi_pairing(g1, g2).is_zero() {
             Ok(())
         } else {
             Err(VerificationError)
@@ -244,6 +571,50 @@ impl<E: Pairing> VerifierKey<E> {
     }
 }
 
+/// Verification of multi-point evaluation proofs,
+/// implemented by both [`VerifierKey`] and [`PreparedVerifierKey`].
+///
+/// Protocols verifying evaluation proofs are generic over this trait,
+/// so that they can be given a prepared verifier key directly.
+pub trait MultiPointVerifier<E: Pairing> {
+    /// See [`VerifierKey::verify_multi_points`].
+    fn verify_multi_points(
+        &self,
+        commitments: &[Commitment<E>],
+        eval_points: &[E::ScalarField],
+        evaluations: &[Vec<E::ScalarField>],
+        proof: &EvaluationProof<E>,
+        open_chal: &E::ScalarField,
+    ) -> VerificationResult;
+
+    /// Return the underlying verifier key.
+    fn verifier_key(&self) -> &VerifierKey<E>;
+}
+
+impl<E: Pairing> MultiPointVerifier<E> for VerifierKey<E> {
+    fn verify_multi_points(
+        &self,
+        commitments: &[Commitment<E>],
+        eval_points: &[E::ScalarField],
+        evaluations: &[Vec<E::ScalarField>],
+        proof: &EvaluationProof<E>,
+        open_chal: &E::ScalarField,
+    ) -> VerificationResult {
+        VerifierKey::verify_multi_points(
+            self,
+            commitments,
+            eval_points,
+            evaluations,
+            proof,
+            open_chal,
+        )
+    }
+
+    fn verifier_key(&self) -> &VerifierKey<E> {
+        self
+    }
+}
+
 fn interpolate_poly<E: Pairing>(
     eval_points: &[E::ScalarField],
     evals: &[E::ScalarField],
@@ -258,6 +629,28 @@ fn interpolate_poly<E: Pairing>(
     res
 }
 
+/// Divide `polynomial` by \\((X - \alpha)\\), where \\(\alpha\\) is `point`,
+/// and return the remainder (i.e., the evaluation of `polynomial` at `point`) together with the quotient.
+///
+/// The quotient is computed via synthetic division, filling it from the leading coefficient.
+pub(crate) fn linear_quotient<F: Field>(polynomial: &[F], point: &F) -> (F, Vec<F>) {
+    let mut quotient = vec![F::zero(); polynomial.len()];
+
+    let mut previous = F::zero();
+    for (q, &c) in quotient.iter_mut().rev().zip(polynomial.iter().rev()) {
+        let coefficient = c + previous * point;
+        *q = coefficient;
+        previous = coefficient;
+    }
+
+    if quotient.is_empty() {
+        (F::zero(), quotient)
+    } else {
+        let evaluation = quotient.remove(0);
+        (evaluation, quotient)
+    }
+}
+
 /// The polynomial in $\FF$ that vanishes in all the points `points`.
 fn vanishing_polynomial<F: Field>(points: &[F]) -> DensePolynomial<F> {
     let one = DensePolynomial::from_coefficients_vec(vec![F::one()]);
@@ -267,6 +660,56 @@ fn vanishing_polynomial<F: Field>(points: &[F]) -> DensePolynomial<F> {
         .fold(one, |x, y| x.naive_mul(&y))
 }
 
+/// The inverse of the power series `p` modulo $X^k$.
+///
+/// Uses Newton iteration $g \gets g (2 - p g)$, doubling the precision at each step,
+/// with FFT-based multiplications.
+/// It requires `p[0]` to be non-zero.
+fn inverse_series<F: FftField>(p: &[F], k: usize) -> DensePolynomial<F> {
+    let mut inverse = DensePolynomial {
+        coeffs: vec![p[0].inverse().expect("constant term must be non-zero")],
+    };
+    let mut precision = 1;
+    while precision < k {
+        precision = usize::min(2 * precision, k);
+        let p_truncated =
+            DensePolynomial::from_coefficients_slice(&p[..usize::min(precision, p.len())]);
+        let mut correction = -(&p_truncated * &inverse);
+        correction.coeffs.resize(precision, F::zero());
+        correction.coeffs[0] += F::from(2u64);
+        inverse = &inverse * &correction;
+        inverse.coeffs.truncate(precision);
+    }
+    inverse
+}
+
+/// The quotient of the division of `polynomial` by the monic polynomial `divisor`.
+///
+/// Instead of long division, the quotient is computed from the reversed polynomials as
+/// $\mathrm{rev}(q) = \mathrm{rev}(f) \cdot \mathrm{rev}(z)^{-1} \bmod X^{n-m+1}$,
+/// which takes $O(n \log n)$ field operations.
+fn fft_division<F: FftField>(polynomial: &[F], divisor: &DensePolynomial<F>) -> Vec<F> {
+    let divisor_degree = divisor.coeffs.len() - 1;
+    if polynomial.len() <= divisor_degree {
+        return Vec::new();
+    }
+    let quotient_len = polynomial.len() - divisor_degree;
+
+    let reversed_divisor = divisor.coeffs.iter().rev().cloned().collect::<Vec<_>>();
+    let reversed_polynomial = polynomial
+        .iter()
+        .rev()
+        .take(quotient_len)
+        .cloned()
+        .collect::<Vec<_>>();
+    let inverse = inverse_series(&reversed_divisor, quotient_len);
+
+    let mut quotient = &DensePolynomial::from_coefficients_vec(reversed_polynomial) * &inverse;
+    quotient.coeffs.resize(quotient_len, F::zero());
+    quotient.coeffs.reverse();
+    quotient.coeffs
+}
+
 #[test]
 fn test_vanishing_polynomial() {
     use crate::misc::evaluate_le;
@@ -279,3 +722,23 @@ fn test_vanishing_polynomial() {
     assert_eq!(evaluate_le(&zeros, &points[1]), F::zero());
     assert_eq!(evaluate_le(&zeros, &points[2]), F::zero());
 }
+
+#[test]
+fn test_fft_division() {
+    use ark_std::ops::Div;
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::Fr as F;
+
+    let rng = &mut ark_std::test_rng();
+    let points = (0..5).map(|_| F::rand(rng)).collect::<Vec<_>>();
+    let divisor = vanishing_polynomial(&points);
+    for degree in [0, 3, 4, 5, 100, 257] {
+        let polynomial = DensePolynomial::<F>::rand(degree, rng);
+        let expected = polynomial.div(&divisor);
+        let mut got = fft_division(&polynomial.coeffs, &divisor);
+        while got.last().map_or(false, |c| c.is_zero()) {
+            got.pop();
+        }
+        assert_eq!(got, expected.coeffs);
+    }
+}
@@ -0,0 +1,146 @@
+//! Streaming multi-scalar multiplications overlapping I/O and compute.
+//!
+//! The space-efficient committer reads bases and scalars from streams, possibly backed by disk,
+//! and multiplies them in chunks.
+//! A [`PipelinedPippenger`] hands each full chunk over to the thread pool,
+//! where it is multiplied in parallel,
+//! while the current thread keeps reading the next chunk from the streams.
+//! At most [`MAX_PENDING_CHUNKS`] chunks are multiplied in the background at once:
+//! past that, the current thread multiplies the chunk itself,
+//! so that memory stays bounded by `MAX_PENDING_CHUNKS + 1` buffers.
+//!
+//! Without the `parallel` feature, chunks are multiplied on the current thread as soon as they are full.
+use ark_ec::CurveGroup;
+use ark_std::borrow::Borrow;
+use ark_std::vec::Vec;
+#[cfg(feature = "parallel")]
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+use super::msm_group;
+
+/// The maximum number of chunks multiplied in the background by a [`PipelinedPippenger`].
+pub(crate) const MAX_PENDING_CHUNKS: usize = 1;
+
+/// Streaming multi-scalar multiplication over the group `G`,
+/// buffering `buf_size` pairs of bases and scalars at a time.
+pub(crate) struct PipelinedPippenger<G: CurveGroup> {
+    bases: Vec<G::Affine>,
+    scalars: Vec<G::ScalarField>,
+    buf_size: usize,
+    result: G,
+    #[cfg(feature = "parallel")]
+    pending: usize,
+    #[cfg(feature = "parallel")]
+    sender: Sender<G>,
+    #[cfg(feature = "parallel")]
+    receiver: Receiver<G>,
+}
+
+impl<G: CurveGroup> PipelinedPippenger<G> {
+    /// Initialize a new instance with buffers of `buf_size` elements.
+    pub(crate) fn new(buf_size: usize) -> Self {
+        let buf_size = usize::max(buf_size, 1);
+        #[cfg(feature = "parallel")]
+        let (sender, receiver) = channel();
+        Self {
+            bases: Vec::with_capacity(buf_size),
+            scalars: Vec::with_capacity(buf_size),
+            buf_size,
+            result: G::zero(),
+            #[cfg(feature = "parallel")]
+            pending: 0,
+            #[cfg(feature = "parallel")]
+            sender,
+            #[cfg(feature = "parallel")]
+            receiver,
+        }
+    }
+
+    /// Add a new (base, scalar) pair into the instance.
+    #[inline(always)]
+    pub(crate) fn add<B: Borrow<G::Affine>>(&mut self, base: B, scalar: G::ScalarField) {
+        self.bases.push(*base.borrow());
+        self.scalars.push(scalar);
+        if self.bases.len() == self.buf_size {
+            self.flush();
+        }
+    }
+
+    /// Multiply the buffered chunk, in the background if fewer than [`MAX_PENDING_CHUNKS`] are pending.
+    fn flush(&mut self) {
+        #[cfg(feature = "parallel")]
+        {
+            self.collect();
+            if self.pending < MAX_PENDING_CHUNKS {
+                let bases =
+                    ark_std::mem::replace(&mut self.bases, Vec::with_capacity(self.buf_size));
+                let scalars =
+                    ark_std::mem::replace(&mut self.scalars, Vec::with_capacity(self.buf_size));
+                let sender = self.sender.clone();
+                self.pending += 1;
+                rayon::spawn(move || {
+                    // the receiver outlives all pending chunks.
+                    let _ = sender.send(msm_group::<G>(&bases, &scalars));
+                });
+                return;
+            }
+        }
+        self.result += msm_group::<G>(&self.bases, &self.scalars);
+        self.bases.clear();
+        self.scalars.clear();
+    }
+
+    /// Accumulate the chunks multiplied in the background so far.
+    #[cfg(feature = "parallel")]
+    fn collect(&mut self) {
+        while let Ok(partial) = self.receiver.try_recv() {
+            self.result += partial;
+            self.pending -= 1;
+        }
+    }
+
+    /// Output the result of the multi-scalar multiplication.
+    pub(crate) fn finalize(mut self) -> G {
+        self.result += msm_group::<G>(&self.bases, &self.scalars);
+        #[cfg(feature = "parallel")]
+        while self.pending > 0 {
+            self.collect();
+            if self.pending == 0 {
+                break;
+            }
+            // a worker thread must not block on a chunk sitting in its own queue:
+            // it runs pending jobs instead.
+            match rayon::yield_now() {
+                Some(_) => std::thread::yield_now(),
+                None => {
+                    self.result += self.receiver.recv().expect("pending chunks are sent");
+                    self.pending -= 1;
+                }
+            }
+        }
+        self.result
+    }
+}
+
+#[test]
+fn test_pipelined_pippenger() {
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::{Fr, G1Projective as G1};
+
+    let rng = &mut ark_std::test_rng();
+    let n = 100;
+    let bases = (0..n)
+        .map(|_| G1::rand(rng).into_affine())
+        .collect::<Vec<_>>();
+    let scalars = (0..n).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+    let expected = msm_group::<G1>(&bases, &scalars);
+
+    for buf_size in [1, 7, n, 2 * n] {
+        let mut pippenger = PipelinedPippenger::<G1>::new(buf_size);
+        bases
+            .iter()
+            .zip(&scalars)
+            .for_each(|(base, scalar)| pippenger.add(base, *scalar));
+        assert_eq!(pippenger.finalize(), expected);
+    }
+}
@@ -0,0 +1,395 @@
+//! Compatibility layer with [`ark_poly_commit`].
+//!
+//! [`GeminiKzg`] exposes the time-efficient committer key through the
+//! [`PolynomialCommitment`] trait, so that it can be plugged into arkworks' proof systems
+//! (e.g., Marlin) in place of `ark_poly_commit::marlin_pc` or `ark_poly_commit::sonic_pc`.
+//!
+//! Polynomials opened at the same point are batched with challenges
+//! drawn from the challenge generator, and verified with a single pairing equation.
+//! Hiding commitments are supported for polynomials with a hiding bound,
+//! while degree bounds are not supported.
+use ark_crypto_primitives::sponge::{CryptographicSponge, FieldElementSize};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_ff::{Field, Zero};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly_commit::{
+    ChallengeGenerator, Error, LabeledCommitment, LabeledPolynomial, PCCommitment, PCCommitterKey,
+    PCPreparedCommitment, PCPreparedVerifierKey, PCRandomness, PCUniversalParams, PCVerifierKey,
+    PolynomialCommitment,
+};
+use ark_serialize::*;
+use ark_std::marker::PhantomData;
+use ark_std::rand::RngCore;
+use ark_std::vec::Vec;
+use ark_std::UniformRand;
+
+use super::time::HIDING_BOUND;
+use super::{
+    Commitment, CommitterKey, EvaluationProof, HidingEvaluationProof, PreparedVerifierKey,
+    Randomness, VerifierKey,
+};
+
+/// The size of the challenges used for batching polynomials.
+const CHALLENGE_SIZE: FieldElementSize = FieldElementSize::Truncated(128);
+
+/// The time-efficient KZG polynomial commitment, seen as an [`ark_poly_commit::PolynomialCommitment`].
+pub struct GeminiKzg<E: Pairing>(PhantomData<E>);
+
+/// The universal parameters of [`GeminiKzg`], i.e. a committer key for the largest supported degree.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug)]
+pub struct UniversalParams<E: Pairing>(pub CommitterKey<E>);
+
+/// A commitment of [`GeminiKzg`].
+#[derive(CanonicalSerialize, CanonicalDeserialize, Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PcCommitment<E: Pairing>(pub E::G1Affine);
+
+/// An evaluation proof of [`GeminiKzg`].
+///
+/// For hiding commitments, it also carries the evaluation of the batched blinding polynomial.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PcProof<E: Pairing> {
+    /// The commitment to the quotient polynomial.
+    pub w: E::G1Affine,
+    /// The evaluation of the blinding polynomial, if any.
+    pub random_v: Option<E::ScalarField>,
+}
+
+impl<E: Pairing> PCUniversalParams for UniversalParams<E> {
+    fn max_degree(&self) -> usize {
+        self.0.max_degree()
+    }
+}
+
+impl<E: Pairing> PCCommitterKey for CommitterKey<E> {
+    fn max_degree(&self) -> usize {
+        CommitterKey::max_degree(self)
+    }
+
+    fn supported_degree(&self) -> usize {
+        CommitterKey::max_degree(self)
+    }
+}
+
+impl<E: Pairing> PCVerifierKey for VerifierKey<E> {
+    fn max_degree(&self) -> usize {
+        self.max_degree
+    }
+
+    fn supported_degree(&self) -> usize {
+        self.max_degree
+    }
+}
+
+impl<E: Pairing> PCPreparedVerifierKey<VerifierKey<E>> for PreparedVerifierKey<E> {
+    fn prepare(vk: &VerifierKey<E>) -> Self {
+        vk.prepare()
+    }
+}
+
+impl<E: Pairing> PCCommitment for PcCommitment<E> {
+    fn empty() -> Self {
+        PcCommitment(E::G1Affine::zero())
+    }
+
+    fn has_degree_bound(&self) -> bool {
+        false
+    }
+}
+
+impl<E: Pairing> PCPreparedCommitment<PcCommitment<E>> for PcCommitment<E> {
+    fn prepare(commitment: &PcCommitment<E>) -> Self {
+        *commitment
+    }
+}
+
+impl<E: Pairing> PCRandomness for Randomness<E> {
+    fn empty() -> Self {
+        Randomness(Vec::new())
+    }
+
+    fn rand<R: RngCore>(
+        _num_queries: usize,
+        _has_degree_bound: bool,
+        _num_vars: Option<usize>,
+        rng: &mut R,
+    ) -> Self {
+        Randomness(
+            (0..HIDING_BOUND + 1)
+                .map(|_| E::ScalarField::rand(rng))
+                .collect(),
+        )
+    }
+}
+
+/// Add `scalar` times `rhs` to `lhs`, extending `lhs` if needed.
+fn add_scaled<F: Field>(lhs: &mut Vec<F>, rhs: &[F], scalar: F) {
+    if lhs.len() < rhs.len() {
+        lhs.resize(rhs.len(), F::zero());
+    }
+    lhs.iter_mut().zip(rhs).for_each(|(l, &r)| *l += scalar * r);
+}
+
+impl<E, S> PolynomialCommitment<E::ScalarField, DensePolynomial<E::ScalarField>, S> for GeminiKzg<E>
+where
+    E: Pairing,
+    S: CryptographicSponge,
+{
+    type UniversalParams = UniversalParams<E>;
+    type CommitterKey = CommitterKey<E>;
+    type VerifierKey = VerifierKey<E>;
+    type PreparedVerifierKey = PreparedVerifierKey<E>;
+    type Commitment = PcCommitment<E>;
+    type PreparedCommitment = PcCommitment<E>;
+    type Randomness = Randomness<E>;
+    type Proof = PcProof<E>;
+    type BatchProof = Vec<PcProof<E>>;
+    type Error = Error;
+
+    fn setup<R: RngCore>(
+        max_degree: usize,
+        _num_vars: Option<usize>,
+        rng: &mut R,
+    ) -> Result<Self::UniversalParams, Self::Error> {
+        if max_degree < 1 {
+            return Err(Error::DegreeIsZero);
+        }
+        Ok(UniversalParams(CommitterKey::new(max_degree, 1, rng)))
+    }
+
+    fn trim(
+        pp: &Self::UniversalParams,
+        supported_degree: usize,
+        supported_hiding_bound: usize,
+        enforced_degree_bounds: Option<&[usize]>,
+    ) -> Result<(Self::CommitterKey, Self::VerifierKey), Self::Error> {
+        if let Some(&bound) = enforced_degree_bounds.and_then(|bounds| bounds.first()) {
+            return Err(Error::UnsupportedDegreeBound(bound));
+        }
+        if supported_hiding_bound > HIDING_BOUND {
+            return Err(Error::HidingBoundToolarge {
+                hiding_poly_degree: supported_hiding_bound,
+                num_powers: HIDING_BOUND + 1,
+            });
+        }
+        let ck =
+            pp.0.trim(supported_degree, 1)
+                .map_err(|_| Error::TrimmingDegreeTooLarge)?;
+        let vk = VerifierKey::from(&ck);
+        Ok((ck, vk))
+    }
+
+    fn commit<'a>(
+        ck: &Self::CommitterKey,
+        polynomials: impl IntoIterator<
+            Item = &'a LabeledPolynomial<E::ScalarField, DensePolynomial<E::ScalarField>>,
+        >,
+        mut rng: Option<&mut dyn RngCore>,
+    ) -> Result<
+        (
+            Vec<LabeledCommitment<Self::Commitment>>,
+            Vec<Self::Randomness>,
+        ),
+        Self::Error,
+    >
+    where
+        DensePolynomial<E::ScalarField>: 'a,
+    {
+        let mut commitments = Vec::new();
+        let mut randomness = Vec::new();
+        for polynomial in polynomials {
+            if let Some(bound) = polynomial.degree_bound() {
+                return Err(Error::UnsupportedDegreeBound(bound));
+            }
+            let coefficients = &polynomial.polynomial().coeffs;
+            if coefficients.len() > ck.powers_of_g.len() {
+                return Err(Error::TooManyCoefficients {
+                    num_coefficients: coefficients.len(),
+                    num_powers: ck.powers_of_g.len(),
+                });
+            }
+            let (commitment, rand) = match polynomial.hiding_bound() {
+                Some(hiding_bound) if hiding_bound > HIDING_BOUND => {
+                    return Err(Error::HidingBoundToolarge {
+                        hiding_poly_degree: hiding_bound,
+                        num_powers: HIDING_BOUND + 1,
+                    })
+                }
+                Some(_) => {
+                    let rng = rng.as_mut().ok_or(Error::MissingRng)?;
+                    ck.commit_hiding(coefficients, rng)
+                }
+                None => (ck.commit(coefficients), Randomness::empty()),
+            };
+            commitments.push(LabeledCommitment::new(
+                polynomial.label().clone(),
+                PcCommitment(commitment.0.into_affine()),
+                None,
+            ));
+            randomness.push(rand);
+        }
+        Ok((commitments, randomness))
+    }
+
+    fn open<'a>(
+        ck: &Self::CommitterKey,
+        labeled_polynomials: impl IntoIterator<
+            Item = &'a LabeledPolynomial<E::ScalarField, DensePolynomial<E::ScalarField>>,
+        >,
+        _commitments: impl IntoIterator<Item = &'a LabeledCommitment<Self::Commitment>>,
+        point: &'a E::ScalarField,
+        challenge_generator: &mut ChallengeGenerator<E::ScalarField, S>,
+        rands: impl IntoIterator<Item = &'a Self::Randomness>,
+        _rng: Option<&mut dyn RngCore>,
+    ) -> Result<Self::Proof, Self::Error>
+    where
+        Self::Randomness: 'a,
+        Self::Commitment: 'a,
+        DensePolynomial<E::ScalarField>: 'a,
+    {
+        let mut batched_polynomial = Vec::new();
+        let mut batched_randomness = Vec::new();
+        let mut hiding = false;
+        for (polynomial, rand) in labeled_polynomials.into_iter().zip(rands) {
+            let challenge = challenge_generator.try_next_challenge_of_size(CHALLENGE_SIZE);
+            add_scaled(
+                &mut batched_polynomial,
+                &polynomial.polynomial().coeffs,
+                challenge,
+            );
+            hiding |= polynomial.hiding_bound().is_some();
+            add_scaled(&mut batched_randomness, &rand.0, challenge);
+        }
+
+        let proof = if hiding {
+            batched_randomness.resize(HIDING_BOUND + 1, E::ScalarField::zero());
+            let (
+                _,
+                HidingEvaluationProof {
+                    proof,
+                    blinding_evaluation,
+                },
+            ) = ck.open_hiding(&batched_polynomial, &Randomness(batched_randomness), point);
+            PcProof {
+                w: proof.0.into_affine(),
+                random_v: Some(blinding_evaluation),
+            }
+        } else {
+            let (_, proof) = ck.open(&batched_polynomial, point);
+            PcProof {
+                w: proof.0.into_affine(),
+                random_v: None,
+            }
+        };
+        Ok(proof)
+    }
+
+    fn check<'a>(
+        vk: &Self::VerifierKey,
+        commitments: impl IntoIterator<Item = &'a LabeledCommitment<Self::Commitment>>,
+        point: &'a E::ScalarField,
+        values: impl IntoIterator<Item = E::ScalarField>,
+        proof: &Self::Proof,
+        challenge_generator: &mut ChallengeGenerator<E::ScalarField, S>,
+        _rng: Option<&mut dyn RngCore>,
+    ) -> Result<bool, Self::Error>
+    where
+        Self::Commitment: 'a,
+    {
+        let mut batched_commitment = E::G1::zero();
+        let mut batched_value = E::ScalarField::zero();
+        for (commitment, value) in commitments.into_iter().zip(values) {
+            let challenge = challenge_generator.try_next_challenge_of_size(CHALLENGE_SIZE);
+            batched_commitment += commitment.commitment().0 * challenge;
+            batched_value += value * challenge;
+        }
+
+        let batched_commitment = Commitment(batched_commitment);
+        let evaluation_proof = EvaluationProof(proof.w.into_group());
+        let result = match proof.random_v {
+            Some(blinding_evaluation) => vk.verify_hiding(
+                &batched_commitment,
+                point,
+                &batched_value,
+                &HidingEvaluationProof {
+                    proof: evaluation_proof,
+                    blinding_evaluation,
+                },
+            ),
+            None => vk.verify(
+                &batched_commitment,
+                point,
+                &batched_value,
+                &evaluation_proof,
+            ),
+        };
+        Ok(result.is_ok())
+    }
+}
+
+#[test]
+fn test_polynomial_commitment_trait() {
+    use ark_crypto_primitives::sponge::poseidon::{PoseidonConfig, PoseidonSponge};
+    use ark_ff::One;
+    use ark_poly::DenseUVPolynomial;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+
+    type Pc = GeminiKzg<Bls12_381>;
+    type Sponge = PoseidonSponge<Fr>;
+    type P = DensePolynomial<Fr>;
+
+    fn sponge() -> Sponge {
+        let mds = vec![
+            vec![Fr::one(), Fr::zero(), Fr::one()],
+            vec![Fr::one(), Fr::one(), Fr::zero()],
+            vec![Fr::zero(), Fr::one(), Fr::one()],
+        ];
+        let ark = vec![vec![Fr::one(); 3]; 39];
+        Sponge::new(&PoseidonConfig::new(8, 31, 17, mds, ark, 2, 1))
+    }
+
+    let rng = &mut ark_std::test_rng();
+    let pp = <Pc as PolynomialCommitment<Fr, P, Sponge>>::setup(31, None, rng).unwrap();
+    let (ck, vk) = <Pc as PolynomialCommitment<Fr, P, Sponge>>::trim(&pp, 31, 1, None).unwrap();
+    let polynomials = [
+        LabeledPolynomial::new("a".into(), P::rand(31, rng), None, None),
+        LabeledPolynomial::new("b".into(), P::rand(10, rng), None, Some(1)),
+    ];
+    let (commitments, rands) =
+        <Pc as PolynomialCommitment<Fr, P, Sponge>>::commit(&ck, &polynomials, Some(rng)).unwrap();
+
+    let point = Fr::rand(rng);
+    let values = polynomials
+        .iter()
+        .map(|p| crate::misc::evaluate_le(&p.polynomial().coeffs, &point))
+        .collect::<Vec<_>>();
+    let proof = <Pc as PolynomialCommitment<Fr, P, Sponge>>::open(
+        &ck,
+        &polynomials,
+        &commitments,
+        &point,
+        &mut ChallengeGenerator::new_univariate(&mut sponge()),
+        &rands,
+        None,
+    )
+    .unwrap();
+    assert!(<Pc as PolynomialCommitment<Fr, P, Sponge>>::check(
+        &vk,
+        &commitments,
+        &point,
+        values.clone(),
+        &proof,
+        &mut ChallengeGenerator::new_univariate(&mut sponge()),
+        None,
+    )
+    .unwrap());
+    assert!(!<Pc as PolynomialCommitment<Fr, P, Sponge>>::check(
+        &vk,
+        &commitments,
+        &point,
+        values.iter().map(|v| *v + Fr::one()),
+        &proof,
+        &mut ChallengeGenerator::new_univariate(&mut sponge()),
+        None,
+    )
+    .unwrap());
+}
@@ -0,0 +1,151 @@
+//! A verifier key with precomputed pairing data.
+//!
+//! Each pairing check of the verifier involves the generator \\(H\\) of \\(\GG_2\\),
+//! and possibly \\(\tau H\\).
+//! [`PreparedVerifierKey`] stores their preparation for the Miller loop,
+//! together with the pairing \\(e(G, H)\\),
+//! so that repeated verifications skip such precomputation.
+use ark_ec::pairing::{Pairing, PairingOutput};
+use ark_ff::Zero;
+
+use super::{
+    Commitment, EvaluationProof, MultiPointVerifier, VerificationError, VerificationResult,
+    VerifierKey,
+};
+
+/// The verification key for the polynomial commitment scheme,
+/// with \\(H\\) and \\(\tau H\\) prepared for the Miller loop.
+#[derive(Clone, Debug)]
+pub struct PreparedVerifierKey<E: Pairing> {
+    vk: VerifierKey<E>,
+    prepared_g2: E::G2Prepared,
+    prepared_tau_g2: E::G2Prepared,
+    g_g2: PairingOutput<E>,
+}
+
+impl<E: Pairing> From<&VerifierKey<E>> for PreparedVerifierKey<E> {
+    fn from(vk: &VerifierKey<E>) -> Self {
+        let g2 = vk.powers_of_g2[0];
+        PreparedVerifierKey {
+            vk: vk.clone(),
+            prepared_g2: E::G2Prepared::from(g2),
+            prepared_tau_g2: E::G2Prepared::from(vk.powers_of_g2[1]),
+            g_g2: E::pairing(vk.powers_of_g[0], g2),
+        }
+    }
+}
+
+impl<E: Pairing> VerifierKey<E> {
+    /// Prepare the verifier key for repeated verifications.
+    pub fn prepare(&self) -> PreparedVerifierKey<E> {
+        self.into()
+    }
+}
+
+impl<E: Pairing> PreparedVerifierKey<E> {
+    /// Return the underlying verifier key.
+    pub fn verifier_key(&self) -> &VerifierKey<E> {
+        &self.vk
+    }
+
+    /// Same as [`VerifierKey::verify`], checking
+    /// \\(e(C + \alpha \pi, H) = e(\pi, \tau H) \cdot e(G, H)^\mu\\)
+    /// with the prepared elements.
+    pub fn verify(
+        &self,
+        commitment: &Commitment<E>,
+        &alpha: &E::ScalarField,
+        evaluation: &E::ScalarField,
+        proof: &EvaluationProof<E>,
+    ) -> VerificationResult {
+        let lhs = commitment.0 + proof.0 * alpha;
+        let pairing = E::multi_pairing(
+            [lhs, -proof.0],
+            [self.prepared_g2.clone(), self.prepared_tau_g2.clone()],
+        );
+        if pairing == self.g_g2 * evaluation {
+            Ok(())
+        } else {
+            Err(VerificationError)
+        }
+    }
+
+    /// Same as [`VerifierKey::verify_multi_points`], with \\(H\\) prepared.
+    pub fn verify_multi_points(
+        &self,
+        commitments: &[Commitment<E>],
+        eval_points: &[E::ScalarField],
+        evaluations: &[Vec<E::ScalarField>],
+        proof: &EvaluationProof<E>,
+        open_chal: &E::ScalarField,
+    ) -> VerificationResult {
+        let (lhs, zeros) =
+            self.vk
+                .multi_points_equation(commitments, eval_points, evaluations, open_chal)?;
+        let pairing = E::multi_pairing(
+            [lhs, -proof.0],
+            [self.prepared_g2.clone(), E::G2Prepared::from(zeros)],
+        );
+        if pairing.is_zero() {
+            Ok(())
+        } else {
+            Err(VerificationError)
+        }
+    }
+}
+
+impl<E: Pairing> MultiPointVerifier<E> for PreparedVerifierKey<E> {
+    fn verify_multi_points(
+        &self,
+        commitments: &[Commitment<E>],
+        eval_points: &[E::ScalarField],
+        evaluations: &[Vec<E::ScalarField>],
+        proof: &EvaluationProof<E>,
+        open_chal: &E::ScalarField,
+    ) -> VerificationResult {
+        PreparedVerifierKey::verify_multi_points(
+            self,
+            commitments,
+            eval_points,
+            evaluations,
+            proof,
+            open_chal,
+        )
+    }
+
+    fn verifier_key(&self) -> &VerifierKey<E> {
+        &self.vk
+    }
+}
+
+#[test]
+fn test_prepared_verifier_key() {
+    use crate::kzg::CommitterKey;
+    use ark_poly::univariate::DensePolynomial;
+    use ark_poly::DenseUVPolynomial;
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+
+    let rng = &mut ark_std::test_rng();
+    let ck = CommitterKey::<Bls12_381>::new(15, 3, rng);
+    let pvk = VerifierKey::from(&ck).prepare();
+    let polynomial = DensePolynomial::<Fr>::rand(15, rng);
+    let commitment = ck.commit(&polynomial);
+
+    let alpha = Fr::rand(rng);
+    let (evaluation, proof) = ck.open(&polynomial, &alpha);
+    assert!(pvk.verify(&commitment, &alpha, &evaluation, &proof).is_ok());
+    assert!(pvk
+        .verify(&commitment, &alpha, &(evaluation + Fr::from(1u64)), &proof)
+        .is_err());
+
+    let eval_points = [Fr::rand(rng), Fr::rand(rng)];
+    let (evaluations, proof) = ck.open_multi_points_with_evals(&polynomial, &eval_points);
+    let one = Fr::from(1u64);
+    assert!(pvk
+        .verify_multi_points(&[commitment], &eval_points, &[evaluations], &proof, &one)
+        .is_ok());
+    assert!(pvk
+        .verify_multi_points(&[commitment], &eval_points, &[vec![one; 2]], &proof, &one)
+        .is_err());
+}
@@ -0,0 +1,294 @@
+//! Batched openings of many polynomials, each at its own set of points,
+//! following [[BDFG20](https://eprint.iacr.org/2020/081.pdf)] (a.k.a. SHPLONK).
+//!
+//! Let $f_0, \dots, f_{k-1}$ be polynomials to be opened at the sets of points $S_0, \dots, S_{k-1}$,
+//! let $r_i$ be the polynomial interpolating $f_i$ over $S_i$,
+//! and let $T = \bigcup_i S_i$.
+//! Denote with $Z_S$ the vanishing polynomial over $S$. Then:
+//! 1. The prover sends the commitment $W$ to $h(X) = \sum_i \gamma^i (f_i(X) - r_i(X)) / Z_{S_i}(X)$
+//!    for a random challenge $\gamma$.
+//! 2. Given a random challenge $z$, the polynomial
+//!    $L(X) = \sum_i \gamma^i Z_{T \setminus S_i}(z) (f_i(X) - r_i(z)) - Z_T(z) h(X)$
+//!    vanishes in $z$, and the prover sends the commitment $W'$ to $L(X) / (X - z)$.
+//! 3. The verifier computes the commitment $F$ to $L$ from the commitments to $f_i$ and $W$,
+//!    and checks $e(F + z W', H) = e(W', \tau H)$.
+//!
+//! The proof consists of two elements of $\GG_1$, independently of the number of polynomials and points.
+use ark_ec::{pairing::Pairing, CurveGroup, VariableBaseMSM};
+use ark_ff::{Field, One, Zero};
+use ark_serialize::*;
+use ark_std::vec::Vec;
+use merlin::Transcript;
+
+use crate::misc::{batch_inverse, evaluate_le, powers};
+use crate::transcript::GeminiTranscript;
+
+use super::{
+    fft_division, linear_quotient, vanishing_polynomial, Commitment, CommitterKey,
+    VerificationError, VerificationResult, VerifierKey,
+};
+
+/// Batched evaluation proof for polynomials opened at distinct sets of points.
+#[derive(CanonicalSerialize, Clone, Debug, PartialEq, Eq)]
+pub struct ShplonkProof<E: Pairing> {
+    /// The commitment $W$ to the batched quotient $h$.
+    pub batched_quotient: Commitment<E>,
+    /// The commitment $W'$ to the opening quotient of $L$ in $z$.
+    pub opening_quotient: Commitment<E>,
+}
+
+/// Return the union of `point_sets`, without repetitions.
+fn union<F: Field>(point_sets: &[Vec<F>]) -> Vec<F> {
+    let mut union = Vec::new();
+    for point in point_sets.iter().flatten() {
+        if !union.contains(point) {
+            union.push(*point);
+        }
+    }
+    union
+}
+
+/// Evaluate at `z` the polynomial interpolating `evaluations` over `points`,
+/// or return `None` if `points` has repetitions.
+fn interpolate_at<F: Field>(points: &[F], evaluations: &[F], z: &F) -> Option<F> {
+    let (numerators, denominators): (Vec<F>, Vec<F>) = points
+        .iter()
+        .enumerate()
+        .map(|(j, x_j)| {
+            points
+                .iter()
+                .enumerate()
+                .filter(|&(k, _)| k != j)
+                .fold((F::one(), F::one()), |(num, den), (_, x_k)| {
+                    (num * (*z - x_k), den * (*x_j - x_k))
+                })
+        })
+        .unzip();
+    let inverse_denominators = batch_inverse(&denominators)?;
+    let evaluation = evaluations
+        .iter()
+        .zip(numerators)
+        .zip(inverse_denominators)
+        .map(|((y_j, numerator), inverse_denominator)| *y_j * numerator * inverse_denominator)
+        .sum();
+    Some(evaluation)
+}
+
+/// Evaluate at `z` the polynomial vanishing over `points`.
+fn vanishing_at<'a, F: Field>(points: impl Iterator<Item = &'a F>, z: &F) -> F {
+    points.fold(F::one(), |acc, point| acc * (*z - point))
+}
+
+/// Return the evaluations at `z` of the vanishing polynomials \\(Z_{T \setminus S_i}\\) for each set \\(S_i\\)
+/// in `point_sets`, together with the evaluation of \\(Z_T\\).
+fn complement_vanishing_evaluations<F: Field>(point_sets: &[Vec<F>], z: &F) -> (Vec<F>, F) {
+    let all_points = union(point_sets);
+    let complements = point_sets
+        .iter()
+        .map(|points| vanishing_at(all_points.iter().filter(|x| !points.contains(x)), z))
+        .collect();
+    (complements, vanishing_at(all_points.iter(), z))
+}
+
+impl<E: Pairing> CommitterKey<E> {
+    /// Open each polynomial in `polynomials` at the respective set of points in `point_sets`,
+    /// and return the evaluations together with a single [`ShplonkProof`].
+    ///
+    /// The commitments to `polynomials` are expected to be already in the `transcript`.
+    pub fn open_shplonk(
+        &self,
+        transcript: &mut Transcript,
+        polynomials: &[Vec<E::ScalarField>],
+        point_sets: &[Vec<E::ScalarField>],
+    ) -> (Vec<Vec<E::ScalarField>>, ShplonkProof<E>) {
+        assert_eq!(polynomials.len(), point_sets.len());
+        let evaluations = polynomials
+            .iter()
+            .zip(point_sets)
+            .map(|(polynomial, points)| {
+                points
+                    .iter()
+                    .map(|point| evaluate_le(polynomial, point))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        transcript.append_serializable(b"shplonk-evaluations", &evaluations);
+        let gamma = transcript.get_challenge::<E::ScalarField>(b"shplonk-gamma");
+        let gammas = powers(gamma, polynomials.len());
+
+        // h(X) = \sum_i \gamma^i (f_i(X) - r_i(X)) / Z_{S_i}(X)
+        let mut batched_quotient = Vec::new();
+        for ((polynomial, points), gamma_i) in polynomials.iter().zip(point_sets).zip(&gammas) {
+            let quotient = fft_division(polynomial, &vanishing_polynomial(points));
+            if batched_quotient.len() < quotient.len() {
+                batched_quotient.resize(quotient.len(), E::ScalarField::zero());
+            }
+            batched_quotient
+                .iter_mut()
+                .zip(quotient)
+                .for_each(|(h, q)| *h += q * gamma_i);
+        }
+        let batched_quotient_commitment = self.commit(&batched_quotient);
+        transcript.append_serializable(b"shplonk-w", &batched_quotient_commitment);
+        let z = transcript.get_challenge::<E::ScalarField>(b"shplonk-z");
+
+        // L(X) = \sum_i \gamma^i Z_{T \ S_i}(z) (f_i(X) - r_i(z)) - Z_T(z) h(X)
+        let (complements, vanishing_z) = complement_vanishing_evaluations(point_sets, &z);
+        let max_len = polynomials.iter().map(|p| p.len()).max().unwrap_or(0);
+        let l_len = usize::max(max_len, batched_quotient.len()).max(1);
+        let mut l_polynomial = vec![E::ScalarField::zero(); l_len];
+        for (i, polynomial) in polynomials.iter().enumerate() {
+            let scalar = gammas[i] * complements[i];
+            let remainder = interpolate_at(&point_sets[i], &evaluations[i], &z)
+                .expect("repeated evaluation points");
+            l_polynomial
+                .iter_mut()
+                .zip(polynomial)
+                .for_each(|(l, c)| *l += scalar * c);
+            l_polynomial[0] -= scalar * remainder;
+        }
+        l_polynomial
+            .iter_mut()
+            .zip(&batched_quotient)
+            .for_each(|(l, h)| *l -= vanishing_z * h);
+        let (_, opening_quotient) = linear_quotient(&l_polynomial, &z);
+
+        let proof = ShplonkProof {
+            batched_quotient: batched_quotient_commitment,
+            opening_quotient: self.commit(&opening_quotient),
+        };
+        (evaluations, proof)
+    }
+}
+
+impl<E: Pairing> VerifierKey<E> {
+    /// Verify that the polynomials committed in `commitments` evaluate to `evaluations`
+    /// at the respective sets of points `point_sets`.
+    pub fn verify_shplonk(
+        &self,
+        transcript: &mut Transcript,
+        commitments: &[Commitment<E>],
+        point_sets: &[Vec<E::ScalarField>],
+        evaluations: &[Vec<E::ScalarField>],
+        proof: &ShplonkProof<E>,
+    ) -> VerificationResult {
+        if commitments.len() != point_sets.len()
+            || evaluations.len() != point_sets.len()
+            || point_sets
+                .iter()
+                .zip(evaluations)
+                .any(|(points, evals)| points.len() != evals.len())
+        {
+            return Err(VerificationError);
+        }
+        transcript.append_serializable(b"shplonk-evaluations", &evaluations.to_vec());
+        let gamma = transcript.get_challenge::<E::ScalarField>(b"shplonk-gamma");
+        let gammas = powers(gamma, commitments.len());
+        transcript.append_serializable(b"shplonk-w", &proof.batched_quotient);
+        let z = transcript.get_challenge::<E::ScalarField>(b"shplonk-z");
+
+        // F = \sum_i \gamma^i Z_{T \ S_i}(z) (C_i - r_i(z) G) - Z_T(z) W
+        let (complements, vanishing_z) = complement_vanishing_evaluations(point_sets, &z);
+        let mut bases = Vec::with_capacity(commitments.len() + 3);
+        let mut scalars = Vec::with_capacity(commitments.len() + 3);
+        let mut remainder = E::ScalarField::zero();
+        for (i, commitment) in commitments.iter().enumerate() {
+            let scalar = gammas[i] * complements[i];
+            bases.push(commitment.0);
+            scalars.push(scalar);
+            remainder += scalar
+                * interpolate_at(&point_sets[i], &evaluations[i], &z).ok_or(VerificationError)?;
+        }
+        bases.push(self.powers_of_g[0].into());
+        scalars.push(-remainder);
+        bases.push(proof.batched_quotient.0);
+        scalars.push(-vanishing_z);
+        // F + z W'
+        bases.push(proof.opening_quotient.0);
+        scalars.push(z);
+        let lhs = E::G1::msm_unchecked(&E::G1::normalize_batch(&bases), &scalars);
+
+        let g2 = self.powers_of_g2[0];
+        let tau_g2 = self.powers_of_g2[1];
+        if E::multi_pairing([lhs, -proof.opening_quotient.0], [g2, tau_g2]).is_zero() {
+            Ok(())
+        } else {
+            Err(VerificationError)
+        }
+    }
+}
+
+#[test]
+fn test_shplonk() {
+    use ark_poly::univariate::DensePolynomial;
+    use ark_poly::DenseUVPolynomial;
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+
+    let rng = &mut ark_std::test_rng();
+    let ck = CommitterKey::<Bls12_381>::new(31, 1, rng);
+    let vk = VerifierKey::from(&ck);
+    let polynomials = [10, 31, 3]
+        .iter()
+        .map(|&d| DensePolynomial::<Fr>::rand(d, rng).coeffs)
+        .collect::<Vec<_>>();
+    let shared_point = Fr::rand(rng);
+    let point_sets = vec![
+        vec![Fr::rand(rng), shared_point],
+        vec![shared_point],
+        vec![Fr::rand(rng), Fr::rand(rng), shared_point],
+    ];
+    let commitments = polynomials.iter().map(|p| ck.commit(p)).collect::<Vec<_>>();
+
+    let mut transcript = Transcript::new(b"test");
+    transcript.append_serializable(b"commitments", &commitments);
+    let (evaluations, proof) = ck.open_shplonk(&mut transcript, &polynomials, &point_sets);
+    assert_eq!(
+        evaluations[1],
+        vec![evaluate_le(&polynomials[1], &shared_point)]
+    );
+
+    let mut transcript = Transcript::new(b"test");
+    transcript.append_serializable(b"commitments", &commitments);
+    assert!(vk
+        .verify_shplonk(
+            &mut transcript,
+            &commitments,
+            &point_sets,
+            &evaluations,
+            &proof
+        )
+        .is_ok());
+
+    let mut wrong_evaluations = evaluations;
+    wrong_evaluations[2][1] += Fr::one();
+    let mut transcript = Transcript::new(b"test");
+    transcript.append_serializable(b"commitments", &commitments);
+    assert!(vk
+        .verify_shplonk(
+            &mut transcript,
+            &commitments,
+            &point_sets,
+            &wrong_evaluations,
+            &proof
+        )
+        .is_err());
+
+    // repeated points are rejected instead of panicking.
+    let repeated_point_sets = vec![
+        point_sets[0].clone(),
+        vec![shared_point],
+        vec![shared_point, shared_point, shared_point],
+    ];
+    let mut transcript = Transcript::new(b"test");
+    transcript.append_serializable(b"commitments", &commitments);
+    assert!(vk
+        .verify_shplonk(
+            &mut transcript,
+            &commitments,
+            &repeated_point_sets,
+            &wrong_evaluations,
+            &proof
+        )
+        .is_err());
+}
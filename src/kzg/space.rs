@@ -1,6 +1,9 @@
 //! Space-efficient implementation of the polynomial commitment of Kate et al.
+//!
+//! Streams are multiplied in chunks: with the `parallel` feature,
+//! each chunk is multiplied on the thread pool while the next one is read from the streams.
 use ark_ec::pairing::Pairing;
-use ark_ec::scalar_mul::variable_base::{ChunkedPippenger, HashMapPippenger};
+use ark_ec::scalar_mul::variable_base::HashMapPippenger;
 use ark_ec::CurveGroup;
 use ark_ff::{PrimeField, Zero};
 use ark_poly::Polynomial;
@@ -13,13 +16,58 @@ use crate::kzg::vanishing_polynomial;
 use crate::misc::ceil_div;
 use crate::subprotocols::sumcheck::streams::FoldedPolynomialTree;
 
+use super::pipeline::PipelinedPippenger;
 use super::{time::CommitterKey, VerifierKey};
 use super::{Commitment, EvaluationProof};
 
 const LENGTH_MISMATCH_MSG: &str = "Expecting at least one element in the committer key.";
 
-/// Steaming multi-scalar multiplication algorithm with hard-coded chunk size.
+/// The default number of elements read at once from the streams, see [`SpaceProverConfig`].
+pub const DEFAULT_CHUNK_SIZE: usize = 1 << 20;
+
+/// Configuration of the space-efficient committer.
+///
+/// Larger chunks use more memory, but require fewer passes over the streams.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SpaceProverConfig {
+    /// The number of elements read at once from the streams in multi-scalar multiplications.
+    pub chunk_size: usize,
+}
+
+impl SpaceProverConfig {
+    /// Create a new configuration with chunks of `chunk_size` elements.
+    pub fn new(chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "chunks must be non-empty");
+        SpaceProverConfig { chunk_size }
+    }
+}
+
+impl Default for SpaceProverConfig {
+    fn default() -> Self {
+        SpaceProverConfig::new(DEFAULT_CHUNK_SIZE)
+    }
+}
+
+/// Steaming multi-scalar multiplication algorithm with chunks of [`DEFAULT_CHUNK_SIZE`] elements.
 pub fn msm_chunks<G, F, I: ?Sized, J>(bases_stream: &J, scalars_stream: &I) -> G
+where
+    G: CurveGroup<ScalarField = F>,
+    I: Iterable,
+    F: PrimeField,
+    I::Item: Borrow<F>,
+    J: Iterable,
+    J::Item: Borrow<G::Affine>,
+{
+    msm_chunks_with_size(bases_stream, scalars_stream, DEFAULT_CHUNK_SIZE)
+}
+
+/// Steaming multi-scalar multiplication algorithm, reading `step` elements at once from the streams.
+pub fn msm_chunks_with_size<G, F, I: ?Sized, J>(
+    bases_stream: &J,
+    scalars_stream: &I,
+    step: usize,
+) -> G
 where
     G: CurveGroup<ScalarField = F>,
     I: Iterable,
@@ -32,26 +80,17 @@ where
 
     // remove offset
     let mut bases = bases_stream.iter();
-    let mut scalars = scalars_stream.iter();
+    let scalars = scalars_stream.iter();
 
     // align the streams
     bases
         .advance_by(bases_stream.len() - scalars_stream.len())
         .expect("bases not long enough");
-    let step: usize = 1 << 20;
-    let mut result = G::zero();
-    for _ in 0..(scalars_stream.len() + step - 1) / step {
-        let bases_step = (&mut bases)
-            .take(step)
-            .map(|b| *b.borrow())
-            .collect::<Vec<_>>();
-        let scalars_step = (&mut scalars)
-            .take(step)
-            .map(|s| *s.borrow())
-            .collect::<Vec<_>>();
-        result += G::msm(bases_step.as_slice(), scalars_step.as_slice()).unwrap();
+    let mut result = PipelinedPippenger::<G>::new(step);
+    for (base, scalar) in bases.zip(scalars) {
+        result.add(base, *scalar.borrow());
     }
-    result
+    result.finalize()
 }
 
 /// The streaming SRS for the polynomial commitment scheme consists of the stream of consecutive powers of $G$.
@@ -66,6 +105,10 @@ where
     pub powers_of_g: SG,
     /// Two G2 elements needed for the committer.
     pub powers_of_g2: Vec<E::G2Affine>,
+    /// The powers of the blinding generator, needed for hiding commitments.
+    pub powers_of_gamma_g: Vec<E::G1Affine>,
+    /// The configuration of the committer.
+    pub config: SpaceProverConfig,
 }
 
 impl<E, SG> CommitterKeyStream<E, SG>
@@ -74,6 +117,12 @@ where
     SG: Iterable,
     SG::Item: Borrow<E::G1Affine>,
 {
+    /// Return the same committer key, configured with `config`.
+    pub fn with_config(mut self, config: SpaceProverConfig) -> Self {
+        self.config = config;
+        self
+    }
+
     /// Turn a streaming SRS into a normal SRS.
     pub fn as_committer_key(&self, max_degree: usize) -> CommitterKey<E> {
         let offset = self.powers_of_g.len() - max_degree;
@@ -85,24 +134,61 @@ where
             .collect::<Vec<_>>();
         powers_of_g.reverse();
         let powers_of_g2 = self.powers_of_g2.clone().to_vec();
+        let powers_of_gamma_g = self.powers_of_gamma_g.clone();
         CommitterKey {
             powers_of_g,
             powers_of_g2,
+            powers_of_gamma_g,
         }
     }
 
     /// Evaluate a single polynomial at the point `alpha`, and provide an evaluation proof along with the evaluation.
+    ///
+    /// The quotient is computed via Horner's method starting from the leading coefficient,
+    /// and committed in chunks of `self.config.chunk_size` elements,
+    /// in a single pass over the streams.
+    /// This is the same interface as [`CommitterKey::open`].
     pub fn open<SF>(
         &self,
         polynomial: &SF,
         alpha: &E::ScalarField,
+    ) -> (E::ScalarField, EvaluationProof<E>)
+    where
+        SF: Iterable + ?Sized,
+        SF::Item: Borrow<E::ScalarField>,
+    {
+        let mut bases = self.powers_of_g.iter();
+        let scalars = polynomial.iter();
+
+        // align the streams and remove one degree
+        bases
+            .advance_by(self.powers_of_g.len() - polynomial.len())
+            .expect(LENGTH_MISMATCH_MSG);
+
+        let mut previous = E::ScalarField::zero();
+        let mut quotient = PipelinedPippenger::<E::G1>::new(self.config.chunk_size);
+        for (scalar, base) in scalars.zip(bases) {
+            quotient.add(base, previous);
+            previous = previous * alpha + scalar.borrow();
+        }
+        let quotient = quotient.finalize();
+
+        (previous, EvaluationProof(quotient))
+    }
+
+    /// Same as [`CommitterKeyStream::open`], but accumulating the quotient commitment
+    /// in a streaming Pippenger with a buffer of `max_msm_buffer` elements.
+    pub fn open_with_buffer<SF>(
+        &self,
+        polynomial: &SF,
+        alpha: &E::ScalarField,
         max_msm_buffer: usize,
     ) -> (E::ScalarField, EvaluationProof<E>)
     where
         SF: Iterable,
         SF::Item: Borrow<E::ScalarField>,
     {
-        let mut quotient = ChunkedPippenger::<E::G1>::new(max_msm_buffer);
+        let mut quotient = PipelinedPippenger::<E::G1>::new(max_msm_buffer);
 
         let mut bases = self.powers_of_g.iter();
         let scalars = polynomial.iter();
@@ -114,7 +200,7 @@ where
 
         let mut previous = E::ScalarField::zero();
         for (scalar, base) in scalars.zip(bases) {
-            quotient.add(base, previous.into_bigint());
+            quotient.add(base, previous);
             let coefficient = previous * alpha + scalar.borrow();
             previous = coefficient;
         }
@@ -125,6 +211,7 @@ where
     }
 
     /// Evaluate a single polynomial at a set of points `points`, and provide an evaluation proof along with evaluations.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(degree = polynomial.len(), points = points.len())))]
     pub fn open_multi_points<SF>(
         &self,
         polynomial: &SF,
@@ -136,7 +223,7 @@ where
         SF::Item: Borrow<E::ScalarField>,
     {
         let zeros = vanishing_polynomial(points);
-        let mut quotient = ChunkedPippenger::<E::G1>::new(max_msm_buffer);
+        let mut quotient = PipelinedPippenger::<E::G1>::new(max_msm_buffer);
         let mut bases = self.powers_of_g.iter();
         bases
             .advance_by(self.powers_of_g.len() - polynomial.len() + zeros.degree())
@@ -158,7 +245,7 @@ where
                 state[i] -= zeros.coeffs[zeros.degree() - i - 1] * quotient_coefficient;
             });
             let base = bases.next().unwrap();
-            quotient.add(base, quotient_coefficient.into_bigint());
+            quotient.add(base, quotient_coefficient);
         }
         let remainder = state.make_contiguous().to_vec();
         let commitment = EvaluationProof(quotient.finalize());
@@ -166,6 +253,9 @@ where
     }
 
     /// The commitment procedures, that takes as input a committer key and the streaming coefficients of polynomial, and produces the desired commitment.
+    ///
+    /// The streams are read in chunks of `self.config.chunk_size` elements.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(degree = polynomial.len())))]
     pub fn commit<SF: ?Sized>(&self, polynomial: &SF) -> Commitment<E>
     where
         SF: Iterable,
@@ -173,7 +263,11 @@ where
     {
         assert!(self.powers_of_g.len() >= polynomial.len());
 
-        Commitment(msm_chunks(&self.powers_of_g, polynomial))
+        Commitment(msm_chunks_with_size(
+            &self.powers_of_g,
+            polynomial,
+            self.config.chunk_size,
+        ))
     }
 
     pub fn batch_commit<'a, F>(
@@ -189,6 +283,7 @@ where
     /// The commitment procedures for our tensor check protocol.
     /// The algorithm takes advantage of the tree structure of folding polynomials in our protocol. Please refer to our paper for more details.
     /// The function takes as input a committer key and the tree structure of all the folding polynomials, and produces the desired commitment for each polynomial.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(max_msm_buffer = max_msm_buffer)))]
     pub fn commit_folding<SF>(
         &self,
         polynomials: &FoldedPolynomialTree<'_, E::ScalarField, SF>,
@@ -199,10 +294,10 @@ where
         SF::Item: Borrow<E::ScalarField>,
     {
         let n = polynomials.depth();
-        let mut pippengers: Vec<ChunkedPippenger<E::G1>> = Vec::new();
+        let mut pippengers: Vec<PipelinedPippenger<E::G1>> = Vec::new();
         let mut folded_bases = Vec::new();
         for i in 1..n + 1 {
-            let pippenger = ChunkedPippenger::with_size(max_msm_buffer / n);
+            let pippenger = PipelinedPippenger::new(max_msm_buffer / n);
             let mut bases = self.powers_of_g.iter();
 
             let delta = self.powers_of_g.len() - ceil_div(polynomials.len(), 1 << i);
@@ -213,7 +308,7 @@ where
 
         for (i, coefficient) in polynomials.iter() {
             let base = folded_bases[i - 1].next().unwrap();
-            pippengers[i - 1].add(base, coefficient.into_bigint());
+            pippengers[i - 1].add(base, coefficient);
         }
 
         pippengers
@@ -226,6 +321,7 @@ where
     /// The algorithm takes advantage of the tree structure of folding polynomials in our protocol. Please refer to our paper for more details.
     /// The function evaluates all the folding polynomials at a set of evaluation points `points` and produces a single batched evaluation proof.
     /// `eta` is the random challenge for batching folding polynomials.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(points = points.len(), max_msm_buffer = max_msm_buffer)))]
     pub fn open_folding<'a, SF>(
         &self,
         polynomials: FoldedPolynomialTree<'a, E::ScalarField, SF>,
@@ -292,6 +388,8 @@ impl<'a, E: Pairing> From<&'a CommitterKey<E>>
         CommitterKeyStream {
             powers_of_g: Reverse(ck.powers_of_g.as_slice()),
             powers_of_g2: ck.powers_of_g2.clone(),
+            powers_of_gamma_g: ck.powers_of_gamma_g.clone(),
+            config: SpaceProverConfig::default(),
         }
     }
 }
@@ -311,9 +409,13 @@ where
             .last()
             .expect(LENGTH_MISMATCH_MSG)
             .borrow();
+        let gamma_g = ck.powers_of_gamma_g[0];
+        let max_degree = ck.powers_of_g.len() - 1;
         Self {
             powers_of_g2,
             powers_of_g: vec![g],
+            gamma_g,
+            max_degree,
         }
     }
 }
@@ -364,7 +466,8 @@ fn test_open_multi_points() {
     let beta = Fr::rand(rng);
     let (_, evaluation_proof_batch) =
         space_ck.open_multi_points(&polynomial_stream, &[beta], max_msm_buffer);
-    let (_, evaluation_proof_single) = space_ck.open(&polynomial_stream, &beta, max_msm_buffer);
+    let (_, evaluation_proof_single) =
+        space_ck.open_with_buffer(&polynomial_stream, &beta, max_msm_buffer);
     assert_eq!(evaluation_proof_batch, evaluation_proof_single);
 
     let (remainder, _evaluation_poof) = space_ck.open_multi_points(
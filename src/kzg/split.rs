@@ -0,0 +1,160 @@
+//! Commitments to polynomials larger than the SRS.
+//!
+//! Let $N$ be the number of powers of $\tau$ over $\GG_1$ in the committer key.
+//! A polynomial $f$ of degree at least $N$ is split in pieces $f_0, \dots, f_{k-1}$ of $N$ coefficients each,
+//! so that $f(x) = \sum_j x^{jN} f_j(x)$, and each piece is committed separately.
+//! To open $f$ at a point $z$, the prover sends the evaluations $f_j(z)$ of the pieces
+//! together with a single batched evaluation proof for all of them;
+//! the verifier recombines the evaluations into $f(z) = \sum_j z^{jN} f_j(z)$.
+use ark_ec::pairing::Pairing;
+use ark_ff::Field;
+use ark_serialize::*;
+use ark_std::vec::Vec;
+use merlin::Transcript;
+
+use crate::misc::evaluate_le;
+use crate::transcript::GeminiTranscript;
+
+use super::{
+    Commitment, CommitterKey, EvaluationProof, VerificationError, VerificationResult, VerifierKey,
+};
+
+/// Evaluation proof for a polynomial committed in pieces.
+#[derive(CanonicalSerialize, Clone, Debug, PartialEq, Eq)]
+pub struct SplitEvaluationProof<E: Pairing> {
+    /// The evaluations of each piece at the evaluation point.
+    pub piece_evaluations: Vec<E::ScalarField>,
+    /// The batched evaluation proof for all pieces.
+    pub proof: EvaluationProof<E>,
+}
+
+impl<E: Pairing> CommitterKey<E> {
+    /// Given a polynomial `polynomial` of arbitrary degree,
+    /// split it in pieces of `max_degree + 1` coefficients and return a commitment to each piece.
+    pub fn commit_split(&self, polynomial: &[E::ScalarField]) -> Vec<Commitment<E>> {
+        polynomial
+            .chunks(self.powers_of_g.len())
+            .map(|piece| self.commit(piece))
+            .collect()
+    }
+
+    /// Given a polynomial `polynomial` committed via [`CommitterKey::commit_split`]
+    /// and an evaluation point `evaluation_point`,
+    /// return the evaluation of `polynomial` at `evaluation_point`, together with an evaluation proof.
+    ///
+    /// The commitments to the pieces of `polynomial` are expected to be already in the `transcript`.
+    pub fn open_split(
+        &self,
+        transcript: &mut Transcript,
+        polynomial: &[E::ScalarField],
+        evaluation_point: &E::ScalarField,
+    ) -> (E::ScalarField, SplitEvaluationProof<E>) {
+        let pieces = polynomial
+            .chunks(self.powers_of_g.len())
+            .map(|piece| piece.to_vec())
+            .collect::<Vec<_>>();
+        let piece_evaluations = pieces
+            .iter()
+            .map(|piece| evaluate_le(piece, evaluation_point))
+            .collect::<Vec<_>>();
+        transcript.append_serializable(b"piece-evaluations", &piece_evaluations);
+        let open_chal = transcript.get_challenge::<E::ScalarField>(b"split-chal");
+
+        let pieces = pieces.iter().collect::<Vec<_>>();
+        let proof = self.batch_open_multi_points(&pieces, &[*evaluation_point], &open_chal);
+        let evaluation = evaluate_le(polynomial, evaluation_point);
+        (
+            evaluation,
+            SplitEvaluationProof {
+                piece_evaluations,
+                proof,
+            },
+        )
+    }
+}
+
+impl<E: Pairing> VerifierKey<E> {
+    /// Verify that the polynomial committed in pieces as `commitments` evaluates to `evaluation` at `alpha`.
+    pub fn verify_split(
+        &self,
+        transcript: &mut Transcript,
+        commitments: &[Commitment<E>],
+        alpha: &E::ScalarField,
+        evaluation: &E::ScalarField,
+        proof: &SplitEvaluationProof<E>,
+    ) -> VerificationResult {
+        if commitments.len() != proof.piece_evaluations.len() {
+            return Err(VerificationError);
+        }
+        let shift = alpha.pow([(self.max_degree + 1) as u64]);
+        if evaluate_le(&proof.piece_evaluations, &shift) != *evaluation {
+            return Err(VerificationError);
+        }
+
+        transcript.append_serializable(b"piece-evaluations", &proof.piece_evaluations);
+        let open_chal = transcript.get_challenge::<E::ScalarField>(b"split-chal");
+        let evaluations = proof
+            .piece_evaluations
+            .iter()
+            .map(|&evaluation| vec![evaluation])
+            .collect::<Vec<_>>();
+        self.verify_multi_points(
+            commitments,
+            &[*alpha],
+            &evaluations,
+            &proof.proof,
+            &open_chal,
+        )
+    }
+}
+
+#[test]
+fn test_split_commitment() {
+    use ark_poly::univariate::DensePolynomial;
+    use ark_poly::DenseUVPolynomial;
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+
+    let rng = &mut ark_std::test_rng();
+    let ck = CommitterKey::<Bls12_381>::new(15, 3, rng);
+    let vk = VerifierKey::from(&ck);
+    let polynomial = DensePolynomial::<Fr>::rand(40, rng);
+    let alpha = Fr::rand(rng);
+
+    let commitments = ck.commit_split(&polynomial);
+    assert_eq!(commitments.len(), 3);
+    assert_eq!(commitments[0], ck.commit(&polynomial[..16]));
+
+    let mut transcript = Transcript::new(b"test");
+    transcript.append_serializable(b"commitments", &commitments);
+    let (evaluation, proof) = ck.open_split(&mut transcript, &polynomial, &alpha);
+    assert_eq!(evaluation, evaluate_le(&polynomial, &alpha));
+
+    let mut transcript = Transcript::new(b"test");
+    transcript.append_serializable(b"commitments", &commitments);
+    assert!(vk
+        .verify_split(&mut transcript, &commitments, &alpha, &evaluation, &proof)
+        .is_ok());
+
+    let mut transcript = Transcript::new(b"test");
+    transcript.append_serializable(b"commitments", &commitments);
+    assert!(vk
+        .verify_split(
+            &mut transcript,
+            &commitments,
+            &alpha,
+            &(evaluation + Fr::from(1u64)),
+            &proof
+        )
+        .is_err());
+}
+
+#[test]
+#[should_panic(expected = "polynomial degree exceeds the committer key")]
+fn test_commit_too_large() {
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+
+    let rng = &mut ark_std::test_rng();
+    let ck = CommitterKey::<Bls12_381>::new(15, 3, rng);
+    ck.commit(&[Fr::from(1u64); 17]);
+}
@@ -1,5 +1,6 @@
 use ark_poly::univariate::DensePolynomial;
 use ark_poly::DenseUVPolynomial;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::test_rng;
 use ark_std::vec::Vec;
 use ark_std::UniformRand;
@@ -8,7 +9,7 @@ use ark_test_curves::bls12_381::{Bls12_381, Fr};
 use crate::iterable::{Iterable, Reverse};
 use crate::kzg::space::CommitterKeyStream;
 use crate::kzg::time::CommitterKey;
-use crate::kzg::VerifierKey;
+use crate::kzg::{Commitment, VerifierKey};
 use crate::misc::evaluate_le;
 
 #[test]
@@ -27,6 +28,52 @@ fn test_commitment_consistency() {
     assert_eq!(space_commitment, time_commitment);
 }
 
+#[test]
+fn test_commit_stream() {
+    let rng = &mut ark_std::test_rng();
+    let d = crate::kzg::STREAM_CHUNK_SIZE + 10;
+    let polynomial = DensePolynomial::<Fr>::rand(d, rng);
+    let polynomial_stream = Reverse(polynomial.coeffs());
+    let time_ck = CommitterKey::<Bls12_381>::new(d + 1, 1, rng);
+    let space_ck = CommitterKeyStream::from(&time_ck);
+
+    let stream_commitment = time_ck.commit_stream(&polynomial_stream);
+    assert_eq!(stream_commitment, time_ck.commit(&polynomial));
+    assert_eq!(stream_commitment, space_ck.commit(&polynomial_stream));
+}
+
+#[test]
+fn test_commit_shard() {
+    let rng = &mut ark_std::test_rng();
+    let d = 99;
+    let polynomial = DensePolynomial::<Fr>::rand(d, rng);
+    let time_ck = CommitterKey::<Bls12_381>::new(d + 1, 1, rng);
+
+    let shards = polynomial.coeffs().chunks(32).enumerate();
+    let commitment = shards
+        .map(|(i, shard)| time_ck.commit_shard(i * 32, shard))
+        .sum::<Commitment<Bls12_381>>();
+    assert_eq!(commitment, time_ck.commit(&polynomial));
+}
+
+#[test]
+fn test_space_prover_config() {
+    use crate::kzg::SpaceProverConfig;
+
+    let rng = &mut ark_std::test_rng();
+    let d = 100;
+    let polynomial = DensePolynomial::<Fr>::rand(d, rng);
+    let polynomial_stream = Reverse(polynomial.coeffs());
+    let time_ck = CommitterKey::<Bls12_381>::new(d + 1, 1, rng);
+    let space_ck = CommitterKeyStream::from(&time_ck).with_config(SpaceProverConfig::new(7));
+
+    assert_eq!(space_ck.config.chunk_size, 7);
+    assert_eq!(
+        space_ck.commit(&polynomial_stream),
+        time_ck.commit(&polynomial)
+    );
+}
+
 #[test]
 fn test_srs() {
     use ark_test_curves::bls12_381::Bls12_381;
@@ -51,10 +98,17 @@ fn test_open_consistency() {
 
     // compute the time commitment
     let (time_evaluation, time_open) = time_ck.open(&polynomials, &alpha);
-    let (space_evaluation, space_open) = space_ck.open(&polynomial_stream, &alpha, max_msm_buffer);
+    let (space_evaluation, space_open) =
+        space_ck.open_with_buffer(&polynomial_stream, &alpha, max_msm_buffer);
     // compute the space commitment
     assert_eq!(time_evaluation, space_evaluation);
     assert_eq!(time_open, space_open);
+
+    // open in small chunks
+    let space_ck = space_ck.with_config(crate::kzg::SpaceProverConfig::new(4));
+    let (space_evaluation, space_open) = space_ck.open(&polynomial_stream, &alpha);
+    assert_eq!(time_evaluation, space_evaluation);
+    assert_eq!(time_open, space_open);
 }
 
 #[test]
@@ -98,4 +152,269 @@ fn test_open_multipoints_correctness() {
     );
 
     assert!(verification_result.is_ok());
+
+    // repeated evaluation points, and fewer evaluations than commitments, are rejected.
+    let mut repeated_points = eval_points.clone();
+    repeated_points[1] = repeated_points[0];
+    assert!(time_vk
+        .verify_multi_points(
+            &time_batched_commitments,
+            &repeated_points,
+            &evals,
+            &proof,
+            &eta
+        )
+        .is_err());
+    assert!(time_vk
+        .verify_multi_points(
+            &time_batched_commitments,
+            &eval_points,
+            &evals[1..],
+            &proof,
+            &eta
+        )
+        .is_err());
+}
+
+#[test]
+fn test_hiding_commitment() {
+    let rng = &mut test_rng();
+    let d = 15;
+    let polynomial = DensePolynomial::<Fr>::rand(d, rng);
+    let ck = CommitterKey::<Bls12_381>::new(d + 1, 3, rng);
+    let vk = VerifierKey::from(&ck);
+    let alpha = Fr::rand(rng);
+
+    let (commitment, randomness) = ck.commit_hiding(&polynomial, rng);
+    assert_ne!(commitment, ck.commit(&polynomial));
+    let (evaluation, proof) = ck.open_hiding(&polynomial, &randomness, &alpha);
+    assert_eq!(evaluation, evaluate_le(&polynomial, &alpha));
+    assert!(vk
+        .verify_hiding(&commitment, &alpha, &evaluation, &proof)
+        .is_ok());
+    assert!(vk
+        .verify_hiding(&commitment, &alpha, &(evaluation + Fr::from(1u64)), &proof)
+        .is_err());
+}
+
+#[test]
+fn test_keys_serialization() {
+    let rng = &mut test_rng();
+    let ck = CommitterKey::<Bls12_381>::new(15, 3, rng);
+    let vk = VerifierKey::from(&ck);
+
+    let mut compressed = Vec::new();
+    ck.serialize_compressed(&mut compressed).unwrap();
+    assert_eq!(compressed.len(), ck.compressed_size());
+    let got = CommitterKey::<Bls12_381>::deserialize_compressed(&compressed[..]).unwrap();
+    assert_eq!(got, ck);
+
+    let mut uncompressed = Vec::new();
+    ck.serialize_uncompressed(&mut uncompressed).unwrap();
+    let got =
+        CommitterKey::<Bls12_381>::deserialize_uncompressed_unchecked(&uncompressed[..]).unwrap();
+    assert_eq!(got, ck);
+
+    let mut serialized_vk = Vec::new();
+    vk.serialize_compressed(&mut serialized_vk).unwrap();
+    let got = VerifierKey::<Bls12_381>::deserialize_compressed(&serialized_vk[..]).unwrap();
+    assert_eq!(got, vk);
+
+    // a truncated key must not deserialize.
+    let truncated = &compressed[..compressed.len() - 1];
+    assert!(CommitterKey::<Bls12_381>::deserialize_compressed(truncated).is_err());
+}
+
+#[test]
+fn test_verify_batch() {
+    let rng = &mut test_rng();
+    let d = 15;
+    let ck = CommitterKey::<Bls12_381>::new(d + 1, 3, rng);
+    let vk = VerifierKey::from(&ck);
+
+    let mut instances = (0..10)
+        .map(|_| {
+            let polynomial = DensePolynomial::<Fr>::rand(d, rng);
+            let alpha = Fr::rand(rng);
+            let commitment = ck.commit(&polynomial);
+            let (evaluation, proof) = ck.open(&polynomial, &alpha);
+            (commitment, alpha, evaluation, proof)
+        })
+        .collect::<Vec<_>>();
+    assert!(vk.verify_batch(&instances, rng).is_ok());
+    assert!(vk.verify_batch(&[], rng).is_ok());
+
+    instances[3].2 += Fr::from(1u64);
+    assert!(vk.verify_batch(&instances, rng).is_err());
+}
+
+#[test]
+fn test_thread_budget() {
+    let rng = &mut test_rng();
+    let d = 100;
+    let polynomials = (0..4)
+        .map(|_| DensePolynomial::<Fr>::rand(d, rng).coeffs)
+        .collect::<Vec<_>>();
+    let ck = CommitterKey::<Bls12_381>::new(d + 1, 3, rng);
+
+    let expected = polynomials.iter().map(|p| ck.commit(p)).collect::<Vec<_>>();
+    for num_threads in [1, 3] {
+        let got = crate::kzg::with_thread_budget(num_threads, || ck.batch_commit(&polynomials));
+        assert_eq!(got, expected);
+    }
+}
+
+#[test]
+fn test_msm_engine() {
+    use crate::kzg::MsmEngine;
+    use ark_ec::pairing::Pairing;
+    use ark_std::cell::Cell;
+
+    /// An engine counting the number of multi-scalar multiplications it performs.
+    struct CountingMsm(Cell<usize>);
+
+    impl MsmEngine<Bls12_381> for CountingMsm {
+        fn msm_g1(
+            &self,
+            bases: &[<Bls12_381 as Pairing>::G1Affine],
+            scalars: &[Fr],
+        ) -> <Bls12_381 as Pairing>::G1 {
+            self.0.set(self.0.get() + 1);
+            crate::kzg::ArkworksMsm.msm_g1(bases, scalars)
+        }
+    }
+
+    let rng = &mut test_rng();
+    let d = 15;
+    let polynomial = DensePolynomial::<Fr>::rand(d, rng);
+    let alpha = Fr::rand(rng);
+    let ck = CommitterKey::<Bls12_381>::new(d + 1, 3, rng);
+    let engine = CountingMsm(Cell::new(0));
+
+    assert_eq!(ck.commit_with(&engine, &polynomial), ck.commit(&polynomial));
+    assert_eq!(
+        ck.open_with(&engine, &polynomial, &alpha),
+        ck.open(&polynomial, &alpha)
+    );
+    assert_eq!(engine.0.get(), 2);
+}
+
+#[test]
+fn test_fixed_window_msm() {
+    use crate::kzg::FixedWindowMsm;
+
+    let rng = &mut test_rng();
+    // span multiple chunks of bases.
+    let d = (1 << 10) + 7;
+    let ck = CommitterKey::<Bls12_381>::new(d + 1, 1, rng);
+    let mut polynomial = DensePolynomial::<Fr>::rand(d, rng).coeffs;
+    // zero and largest scalars, and scalars with few bits set.
+    polynomial[0] = Fr::from(0u64);
+    polynomial[1] = -Fr::from(1u64);
+    polynomial[2] = Fr::from(1u64 << 40);
+    assert_eq!(
+        ck.commit_with(&FixedWindowMsm, &polynomial),
+        ck.commit(&polynomial)
+    );
+    assert_eq!(
+        ck.commit_with(&FixedWindowMsm, &[Fr::from(0u64); 8]),
+        ck.commit(&[])
+    );
+}
+
+#[test]
+fn test_bn254_vectors() {
+    use ark_bn254::{Bn254, Fr};
+    use ark_std::string::String;
+
+    fn hex<T: CanonicalSerialize>(element: &T, compressed: bool) -> String {
+        let mut bytes = Vec::new();
+        if compressed {
+            element.serialize_compressed(&mut bytes).unwrap();
+        } else {
+            element.serialize_uncompressed(&mut bytes).unwrap();
+        }
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    // The first power of the key is the generator (1, 2) of BN254, whatever the trapdoor:
+    // commitments to constant polynomials are fixed multiples of the generator.
+    let ck = CommitterKey::<Bn254>::new_deterministic(15, 3, b"test vectors").into_insecure_inner();
+    let vk = VerifierKey::from(&ck);
+    let vectors = [
+        (
+            0u64,
+            "0000000000000000000000000000000000000000000000000000000000000040",
+            "00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000040",
+        ),
+        (
+            1,
+            "0100000000000000000000000000000000000000000000000000000000000000",
+            "01000000000000000000000000000000000000000000000000000000000000000200000000000000000000000000000000000000000000000000000000000000",
+        ),
+        (
+            3,
+            "f0ab15199655d3f279e6b81547d8159315bdb6b1bc3202f43fea6bc59abf6987",
+            "f0ab15199655d3f279e6b81547d8159315bdb6b1bc3202f43fea6bc59abf69076122fed93dfff1cd575b9c0bb4639e317564088d7cdb4f55299448e0be99b7aa",
+        ),
+        (
+            5,
+            "a93f16faa7a849e89ca35389d8dee46243772b760402bc66f7e0fe0edf39c117",
+            "a93f16faa7a849e89ca35389d8dee46243772b760402bc66f7e0fe0edf39c1177cccd4c61857fc3f2759b9e05892aa0be79f8aaf57a364476660b1ac9b55e001",
+        ),
+    ];
+    for (constant, compressed, uncompressed) in vectors {
+        let polynomial = [Fr::from(constant)];
+        let commitment = ck.commit(&polynomial);
+        assert_eq!(hex(&commitment, true), compressed);
+        assert_eq!(hex(&commitment, false), uncompressed);
+        let decoded = Commitment::deserialize_compressed(hex_decode(compressed).as_slice());
+        assert_eq!(decoded.unwrap(), commitment);
+
+        // the quotient of a constant polynomial is zero, and so is the evaluation proof.
+        let alpha = Fr::from(7u64);
+        let (evaluation, proof) = ck.open(&polynomial, &alpha);
+        assert_eq!(evaluation, Fr::from(constant));
+        assert_eq!(hex(&proof, true), vectors[0].1);
+        assert!(vk.verify(&commitment, &alpha, &evaluation, &proof).is_ok());
+    }
+    assert_eq!(
+        hex(&Fr::from(5u64), true),
+        "0500000000000000000000000000000000000000000000000000000000000000"
+    );
+
+    fn hex_decode(string: &str) -> Vec<u8> {
+        (0..string.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&string[i..i + 2], 16).unwrap())
+            .collect()
+    }
+}
+
+#[test]
+fn test_bn254_open() {
+    use ark_bn254::{Bn254, Fr};
+
+    let rng = &mut test_rng();
+    let d = 15;
+    let polynomial = DensePolynomial::<Fr>::rand(d, rng);
+    let alpha = Fr::rand(rng);
+    let ck = CommitterKey::<Bn254>::new(d + 1, 3, rng);
+    let vk = VerifierKey::from(&ck);
+
+    let commitment = ck.commit(&polynomial);
+    let (evaluation, proof) = ck.open(&polynomial, &alpha);
+    assert_eq!(evaluation, evaluate_le(&polynomial, &alpha));
+    assert!(vk.verify(&commitment, &alpha, &evaluation, &proof).is_ok());
+    assert!(vk
+        .verify(&commitment, &alpha, &(evaluation + Fr::from(1u64)), &proof)
+        .is_err());
+
+    let mut bytes = Vec::new();
+    commitment.serialize_compressed(&mut bytes).unwrap();
+    assert_eq!(bytes.len(), 32);
+    assert_eq!(
+        Commitment::<Bn254>::deserialize_compressed(bytes.as_slice()).unwrap(),
+        commitment
+    );
 }
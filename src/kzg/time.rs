@@ -1,19 +1,37 @@
 //! An impementation of a time-efficient version of Kate et al's polynomial commitment,
 //! with optimization from [\[BDFG20\]](https://eprint.iacr.org/2020/081.pdf).
 use ark_ec::scalar_mul::fixed_base::FixedBase;
-use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup, Group, VariableBaseMSM};
 use ark_ff::{PrimeField, Zero};
-use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_serialize::*;
 use ark_std::borrow::Borrow;
-use ark_std::ops::Div;
+use ark_std::ops::Deref;
 use ark_std::rand::RngCore;
 use ark_std::vec::Vec;
 use ark_std::UniformRand;
+use hashbrown::HashMap;
+use merlin::Transcript;
 
-use crate::kzg::{Commitment, EvaluationProof, VerifierKey};
-use crate::misc::{linear_combination, powers};
+use crate::iterable::Iterable;
+use crate::kzg::{
+    ArkworksMsm, Commitment, EvaluationProof, HidingEvaluationProof, MsmEngine, Randomness,
+    SrsError, VerifierKey,
+};
+use crate::misc::{evaluate_le, ip, linear_combination, powers, wipe};
+use crate::transcript::GeminiTranscript;
 
-use super::vanishing_polynomial;
+use super::{fft_division, linear_quotient, msm, vanishing_polynomial};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// The degree of the blinding polynomial used in hiding commitments.
+/// A blinding polynomial of degree $d$ keeps the commitment hiding for up to $d$ openings.
+pub(crate) const HIDING_BOUND: usize = 1;
+
+/// The number of coefficients kept in memory by [`CommitterKey::commit_stream`].
+pub const STREAM_CHUNK_SIZE: usize = 1 << 16;
 
 /// The SRS for the polynomial commitment scheme for a max
 ///
@@ -21,9 +39,45 @@ use super::vanishing_polynomial;
 /// plus the `max_eval_degree` powers over \\(\GG_2\\),
 /// where `max_degree` is the max polynomial degree to commit to,
 /// and `max_eval_degree` is the max number of different points to open simultaneously.
+/// Additionally, it holds the first powers of \\(\tau\\) over the blinding generator \\(\gamma G\\),
+/// used for hiding commitments.
+///
+/// The committer key can be serialized and deserialized in both compressed and uncompressed form;
+/// [`CanonicalDeserialize::deserialize_compressed`] additionally checks that all points are on the curve and in the prime-order subgroup,
+/// while the `_unchecked` variants skip such checks for keys coming from trusted storage.
+/// Keys from untrusted sources should additionally be checked with [`CommitterKey::validate`].
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
 pub struct CommitterKey<E: Pairing> {
     pub(crate) powers_of_g: Vec<E::G1Affine>,
     pub(crate) powers_of_g2: Vec<E::G2Affine>,
+    pub(crate) powers_of_gamma_g: Vec<E::G1Affine>,
+}
+
+/// The domain separator for deriving insecure, deterministic SRSs.
+const INSECURE_SRS_DOMAIN_SEPARATOR: &[u8] = b"GEMINI-v0-INSECURE-SRS";
+
+/// A marker for keys whose trapdoor is publicly known, such as the ones generated by
+/// [`CommitterKey::new_deterministic`].
+///
+/// The wrapped key can be used as usual (via [`Deref`]) in tests,
+/// but passing it on where a key is expected requires an explicit call to [`Insecure::into_insecure_inner`].
+/// **Insecure keys must never be used in production.**
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Insecure<T>(T);
+
+impl<T> Insecure<T> {
+    /// Unwrap the insecure key.
+    pub fn into_insecure_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Insecure<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
 }
 
 impl<E: Pairing> From<&CommitterKey<E>> for VerifierKey<E> {
@@ -31,10 +85,14 @@ impl<E: Pairing> From<&CommitterKey<E>> for VerifierKey<E> {
         let max_eval_points = ck.max_eval_points();
         let powers_of_g2 = ck.powers_of_g2[..max_eval_points + 1].to_vec();
         let powers_of_g = ck.powers_of_g[..max_eval_points].to_vec();
+        let gamma_g = ck.powers_of_gamma_g[0];
+        let max_degree = ck.max_degree();
 
         VerifierKey {
             powers_of_g,
             powers_of_g2,
+            gamma_g,
+            max_degree,
         }
     }
 }
@@ -47,27 +105,84 @@ impl<E: Pairing> CommitterKey<E> {
     /// and a cryptographically-secure random number generator `rng`,
     /// construct the committer key.
     pub fn new(max_degree: usize, max_eval_points: usize, rng: &mut impl RngCore) -> Self {
+        let mut tau = E::ScalarField::rand(rng);
+        let g = E::G1::rand(rng);
+        let g2 = E::G2::rand(rng);
+        let gamma_g = E::G1::rand(rng);
+        let ck = Self::from_trapdoor(max_degree, max_eval_points, tau, g, g2, gamma_g);
+        wipe(&mut tau);
+        ck
+    }
+
+    /// Construct a committer key from a _publicly-known_ seed `seed`,
+    /// for a degree bound `max_degree` and an evaluation point bound `max_eval_points`.
+    ///
+    /// The trapdoor \\(\tau\\) is derived from `seed` via a [`merlin`] transcript,
+    /// and the key is built over the standard generators of \\(\GG_1\\) and \\(\GG_2\\).
+    /// This makes the key reproducible across runs and across implementations,
+    /// which is useful for test vectors.
+    /// Since anyone knowing the seed can compute the trapdoor,
+    /// the key is returned wrapped in [`Insecure`]; **it must never be used in production.**
+    pub fn new_deterministic(
+        max_degree: usize,
+        max_eval_points: usize,
+        seed: &[u8],
+    ) -> Insecure<Self> {
+        let mut transcript = Transcript::new(INSECURE_SRS_DOMAIN_SEPARATOR);
+        transcript.append_message(b"seed", seed);
+        let tau = transcript.get_challenge::<E::ScalarField>(b"tau");
+        let gamma = transcript.get_challenge::<E::ScalarField>(b"gamma");
+
+        let g = E::G1::generator();
+        let g2 = E::G2::generator();
+        let gamma_g = g * gamma;
+        Insecure(Self::from_trapdoor(
+            max_degree,
+            max_eval_points,
+            tau,
+            g,
+            g2,
+            gamma_g,
+        ))
+    }
+
+    /// Compute the consecutive powers of the trapdoor `tau` over the generators `g`, `g2`, and `gamma_g`.
+    ///
+    /// The trapdoor and its powers are wiped once the key is computed.
+    fn from_trapdoor(
+        max_degree: usize,
+        max_eval_points: usize,
+        mut tau: E::ScalarField,
+        g: E::G1,
+        g2: E::G2,
+        gamma_g: E::G1,
+    ) -> Self {
         // Compute the consecutive powers of an element.
-        let tau = E::ScalarField::rand(rng);
-        let powers_of_tau = powers(tau, max_degree + 1);
+        let mut powers_of_tau = powers(tau, max_degree + 1);
 
-        let g = E::G1::rand(rng);
         let window_size = FixedBase::get_mul_window_size(max_degree + 1);
         let scalar_bits = E::ScalarField::MODULUS_BIT_SIZE as usize;
         let g_table = FixedBase::get_window_table(scalar_bits, window_size, g);
         let powers_of_g_proj = FixedBase::msm(scalar_bits, window_size, &g_table, &powers_of_tau);
         let powers_of_g = E::G1::normalize_batch(&powers_of_g_proj);
 
-        let g2 = E::G2::rand(rng).into_affine();
-        let powers_of_g2 = powers_of_tau
-            .iter()
+        let g2 = g2.into_affine();
+        let powers_of_g2 = cfg_iter!(powers_of_tau)
             .take(max_eval_points + 1)
             .map(|t| (g2 * t).into_affine())
             .collect::<Vec<_>>();
 
+        let powers_of_gamma_g = cfg_iter!(powers_of_tau)
+            .take(HIDING_BOUND + 1)
+            .map(|t| (gamma_g * t).into_affine())
+            .collect::<Vec<_>>();
+        wipe(&mut tau);
+        wipe(&mut powers_of_tau);
+
         CommitterKey {
             powers_of_g,
             powers_of_g2,
+            powers_of_gamma_g,
         }
     }
 
@@ -77,31 +192,196 @@ impl<E: Pairing> CommitterKey<E> {
         self.powers_of_g2.len() - 1
     }
 
+    /// Return the bound on the degree of committed polynomials.
+    #[inline]
+    pub fn max_degree(&self) -> usize {
+        self.powers_of_g.len() - 1
+    }
+
+    /// Return a new committer key supporting only polynomials of degree up to `max_degree`
+    /// and up to `max_eval_points` evaluation points,
+    /// obtained copying a prefix of the current key.
+    ///
+    /// This allows a single universal SRS to serve circuits of different sizes.
+    pub fn trim(&self, max_degree: usize, max_eval_points: usize) -> Result<Self, SrsError> {
+        if max_degree > self.max_degree() || max_eval_points > self.max_eval_points() {
+            return Err(SrsError::NotEnoughPowers);
+        }
+        Ok(CommitterKey {
+            powers_of_g: self.powers_of_g[..max_degree + 1].to_vec(),
+            powers_of_g2: self.powers_of_g2[..max_eval_points + 1].to_vec(),
+            powers_of_gamma_g: self.powers_of_gamma_g.clone(),
+        })
+    }
+
     /// Given a polynomial `polynomial` of degree less than `max_degree`, return a commitment to `polynomial`.
+    ///
+    /// # Panics
+    /// If `polynomial` has degree larger than `max_degree`:
+    /// larger polynomials must be committed via [`CommitterKey::commit_split`].
     pub fn commit(&self, polynomial: &[E::ScalarField]) -> Commitment<E> {
-        Commitment(E::G1::msm_unchecked(&self.powers_of_g, polynomial))
-    }
-
-    /// Obtain a new preprocessed committer key defined by the indices `indices`.
-    pub fn index_by(&self, indices: &[usize]) -> Self {
-        let mut indexed_powers_of_g = vec![E::G1Affine::zero(); self.powers_of_g.len()];
-        indices.iter().zip(&self.powers_of_g).for_each(|(&i, &g)| {
-            indexed_powers_of_g[i] = (indexed_powers_of_g[i] + g).into_affine()
-        });
-        Self {
-            powers_of_g2: self.powers_of_g2.clone(),
-            powers_of_g: indexed_powers_of_g,
+        self.commit_with(&ArkworksMsm, polynomial)
+    }
+
+    /// Same as [`CommitterKey::commit`], but computing the multi-scalar multiplication with `engine`.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(degree = polynomial.len())))]
+    pub fn commit_with<M: MsmEngine<E>>(
+        &self,
+        engine: &M,
+        polynomial: &[E::ScalarField],
+    ) -> Commitment<E> {
+        assert!(
+            polynomial.len() <= self.powers_of_g.len(),
+            "polynomial degree exceeds the committer key"
+        );
+        Commitment(engine.msm_g1(&self.powers_of_g, polynomial))
+    }
+
+    /// Return the share of the commitment to a polynomial
+    /// whose coefficients from degree `offset` onwards start with `shard`.
+    ///
+    /// When a polynomial is split into shards, the commitment is the sum of the shares of all of them,
+    /// hence each share can be computed by a different machine holding only its own shard of the coefficients.
+    pub fn commit_shard(&self, offset: usize, shard: &[E::ScalarField]) -> Commitment<E> {
+        assert!(offset + shard.len() <= self.powers_of_g.len());
+        Commitment(msm::<E>(&self.powers_of_g[offset..], shard))
+    }
+
+    /// Given a polynomial `polynomial` as a stream of coefficients, starting from the leading one,
+    /// return a commitment to `polynomial`.
+    ///
+    /// The stream is consumed in chunks of [`STREAM_CHUNK_SIZE`] coefficients,
+    /// so that at most one chunk is kept in memory at any time.
+    /// This is the same interface as [`CommitterKeyStream::commit`](crate::kzg::CommitterKeyStream::commit),
+    /// so that either key can be used for streams.
+    pub fn commit_stream<SF>(&self, polynomial: &SF) -> Commitment<E>
+    where
+        SF: Iterable + ?Sized,
+        SF::Item: Borrow<E::ScalarField>,
+    {
+        assert!(self.powers_of_g.len() >= polynomial.len());
+
+        let mut coefficients = polynomial.iter();
+        let mut chunk = Vec::with_capacity(usize::min(STREAM_CHUNK_SIZE, polynomial.len()));
+        let mut commitment = E::G1::zero();
+        let mut end = polynomial.len();
+        while end > 0 {
+            let start = end.saturating_sub(STREAM_CHUNK_SIZE);
+            chunk.clear();
+            chunk.extend((&mut coefficients).take(end - start).map(|c| *c.borrow()));
+            chunk.reverse();
+            commitment += msm::<E>(&self.powers_of_g[start..end], &chunk);
+            end = start;
         }
+        Commitment(commitment)
+    }
+
+    /// Given a sparse polynomial `polynomial`, expressed as a list of `(degree, coefficient)` pairs,
+    /// return a commitment to `polynomial`.
+    ///
+    /// Only the bases of the SRS corresponding to non-zero coefficients are used in the multi-scalar multiplication.
+    pub fn commit_sparse(&self, polynomial: &[(usize, E::ScalarField)]) -> Commitment<E> {
+        let (bases, scalars): (Vec<_>, Vec<_>) = polynomial
+            .iter()
+            .map(|&(i, coefficient)| (self.powers_of_g[i], coefficient))
+            .unzip();
+        Commitment(E::G1::msm_unchecked(&bases, &scalars))
+    }
+
+    /// Given a sparse polynomial `polynomial`, expressed as a list of `(degree, coefficient)` pairs,
+    /// and an evaluation point `evaluation_point`,
+    /// return the evaluation of `polynomial` in `evaluation_point`,
+    /// together with an evaluation proof.
+    ///
+    /// The quotient polynomial is in general dense,
+    /// but it is computed without materializing the dense representation of `polynomial`.
+    pub fn open_sparse(
+        &self,
+        polynomial: &[(usize, E::ScalarField)],
+        evaluation_point: &E::ScalarField,
+    ) -> (E::ScalarField, EvaluationProof<E>) {
+        let mut terms = polynomial.to_vec();
+        terms.sort_unstable_by(|x, y| y.0.cmp(&x.0));
+        let degree = terms.first().map(|&(i, _)| i).unwrap_or(0);
+
+        let mut quotient = vec![E::ScalarField::zero(); degree];
+        let mut terms = terms.into_iter().peekable();
+        let mut previous = E::ScalarField::zero();
+        for i in (0..=degree).rev() {
+            let mut coefficient = previous * evaluation_point;
+            while let Some((_, c)) = terms.next_if(|&(j, _)| j == i) {
+                coefficient += c;
+            }
+            if i > 0 {
+                quotient[i - 1] = coefficient;
+            }
+            previous = coefficient;
+        }
+
+        let evaluation_proof = E::G1::msm_unchecked(&self.powers_of_g, &quotient);
+        (previous, EvaluationProof(evaluation_proof))
+    }
+
+    /// Given a polynomial `polynomial` of degree less than `max_degree`,
+    /// return a hiding commitment to `polynomial` together with the blinding randomness used.
+    ///
+    /// The commitment is $C \defeq \langle \vec f, \vec G \rangle + \langle \vec r, \gamma \vec G \rangle$,
+    /// where $r$ is a random polynomial of degree `HIDING_BOUND`.
+    pub fn commit_hiding(
+        &self,
+        polynomial: &[E::ScalarField],
+        rng: &mut impl RngCore,
+    ) -> (Commitment<E>, Randomness<E>) {
+        let blinding_polynomial = (0..self.powers_of_gamma_g.len())
+            .map(|_| E::ScalarField::rand(rng))
+            .collect::<Vec<_>>();
+        let commitment = msm::<E>(&self.powers_of_g, polynomial)
+            + E::G1::msm_unchecked(&self.powers_of_gamma_g, &blinding_polynomial);
+        (Commitment(commitment), Randomness(blinding_polynomial))
+    }
+
+    /// Given a polynomial `polynomial`, the blinding randomness `randomness` produced by [`Self::commit_hiding`]
+    /// and an evaluation point `evaluation_point`,
+    /// return the evaluation of `polynomial` in `evaluation_point`,
+    /// together with a hiding evaluation proof.
+    pub fn open_hiding(
+        &self,
+        polynomial: &[E::ScalarField],
+        randomness: &Randomness<E>,
+        evaluation_point: &E::ScalarField,
+    ) -> (E::ScalarField, HidingEvaluationProof<E>) {
+        let (evaluation, EvaluationProof(polynomial_proof)) =
+            self.open(polynomial, evaluation_point);
+
+        let mut blinding_quotient = Vec::with_capacity(randomness.0.len());
+        let mut previous = E::ScalarField::zero();
+        for &c in randomness.0.iter().rev() {
+            previous = c + previous * evaluation_point;
+            blinding_quotient.push(previous);
+        }
+        blinding_quotient.reverse();
+        let (&blinding_evaluation, blinding_quotient) = blinding_quotient
+            .split_first()
+            .unwrap_or((&E::ScalarField::zero(), &[]));
+        let blinding_proof = E::G1::msm_unchecked(&self.powers_of_gamma_g, blinding_quotient);
+
+        let proof = HidingEvaluationProof {
+            proof: EvaluationProof(polynomial_proof + blinding_proof),
+            blinding_evaluation,
+        };
+        (evaluation, proof)
     }
 
     /// Given an iterator over `polynomials`, expressed as vectors of coefficients, return a vector of commitmetns to all of them.
+    ///
+    /// With the `parallel` feature, the commitments are computed concurrently.
     pub fn batch_commit<J>(&self, polynomials: J) -> Vec<Commitment<E>>
     where
         J: IntoIterator,
-        J::Item: Borrow<Vec<E::ScalarField>>,
+        J::Item: Borrow<Vec<E::ScalarField>> + Sync,
     {
-        polynomials
-            .into_iter()
+        let polynomials = polynomials.into_iter().collect::<Vec<_>>();
+        cfg_iter!(polynomials)
             .map(|p| self.commit(p.borrow()))
             .collect::<Vec<_>>()
     }
@@ -114,23 +394,24 @@ impl<E: Pairing> CommitterKey<E> {
         polynomial: &[E::ScalarField],
         evalualtion_point: &E::ScalarField,
     ) -> (E::ScalarField, EvaluationProof<E>) {
-        let mut quotient = Vec::new();
-
-        let mut previous = E::ScalarField::zero();
-        for &c in polynomial.iter().rev() {
-            let coefficient = c + previous * evalualtion_point;
-            quotient.insert(0, coefficient);
-            previous = coefficient;
-        }
+        self.open_with(&ArkworksMsm, polynomial, evalualtion_point)
+    }
 
-        let (&evaluation, quotient) = quotient
-            .split_first()
-            .unwrap_or((&E::ScalarField::zero(), &[]));
-        let evaluation_proof = E::G1::msm_unchecked(&self.powers_of_g, quotient);
+    /// Same as [`CommitterKey::open`], but computing the multi-scalar multiplication with `engine`.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(degree = polynomial.len())))]
+    pub fn open_with<M: MsmEngine<E>>(
+        &self,
+        engine: &M,
+        polynomial: &[E::ScalarField],
+        evalualtion_point: &E::ScalarField,
+    ) -> (E::ScalarField, EvaluationProof<E>) {
+        let (evaluation, quotient) = linear_quotient(polynomial, evalualtion_point);
+        let evaluation_proof = engine.msm_g1(&self.powers_of_g, &quotient);
         (evaluation, EvaluationProof(evaluation_proof))
     }
 
     /// Evaluate a single polynomial at a set of points `eval_points`, and provide a single evaluation proof.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(degree = polynomial.len(), points = eval_points.len())))]
     pub fn open_multi_points(
         &self,
         polynomial: &[E::ScalarField],
@@ -139,13 +420,29 @@ impl<E: Pairing> CommitterKey<E> {
         // Computing the vanishing polynomial over eval_points
         let z_poly = vanishing_polynomial(eval_points);
 
-        let f_poly = DensePolynomial::from_coefficients_slice(polynomial);
-        let q_poly = f_poly.div(&z_poly);
-        EvaluationProof(self.commit(&q_poly.coeffs).0)
+        let quotient = fft_division(polynomial, &z_poly);
+        EvaluationProof(self.commit(&quotient).0)
+    }
+
+    /// Evaluate a single polynomial at a set of points `eval_points`,
+    /// and provide the evaluations together with a single evaluation proof.
+    ///
+    /// The evaluations follow the same order as `eval_points`.
+    pub fn open_multi_points_with_evals(
+        &self,
+        polynomial: &[E::ScalarField],
+        eval_points: &[E::ScalarField],
+    ) -> (Vec<E::ScalarField>, EvaluationProof<E>) {
+        let evaluations = eval_points
+            .iter()
+            .map(|point| evaluate_le(polynomial, point))
+            .collect();
+        (evaluations, self.open_multi_points(polynomial, eval_points))
     }
 
     /// Evaluate a set of polynomials at a set of points `eval_points`, and provide a single batched evaluation proof.
     /// `eval_chal` is the random challenge for batching evaluation proofs across different polynomials.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(polynomials = polynomials.len(), points = eval_points.len())))]
     pub fn batch_open_multi_points(
         &self,
         polynomials: &[&Vec<E::ScalarField>],
@@ -159,6 +456,104 @@ impl<E: Pairing> CommitterKey<E> {
     }
 }
 
+/// Precomputed evaluation proofs for a single polynomial,
+/// following Feist and Khovratovich [[FK20](https://github.com/khovratovich/Kate/blob/master/Kate_amortized.pdf)].
+///
+/// Let $f$ be a polynomial of degree $d-1$.
+/// The commitment to the quotient of $f$ by $(x - z)$ can be written as
+/// $\pi_z = \sum_k z^k H_k$, where $H_k \defeq \sum_{j > k} f_j \tau^{j-k-1} G$
+/// is a Toeplitz matrix-vector product, computed with a single FFT over $\GG_1$.
+/// The evaluation proofs at all the points of the domain of size $n \geq d$
+/// are then obtained via one more FFT.
+pub struct OpeningKey<E: Pairing> {
+    domain: Radix2EvaluationDomain<E::ScalarField>,
+    /// The position of each element of the domain.
+    positions: HashMap<E::ScalarField, usize>,
+    /// The evaluations of the polynomial over the domain.
+    evaluations: Vec<E::ScalarField>,
+    /// The evaluation proofs over the domain, as bases for opening outside the domain.
+    proof_bases: Vec<E::G1Affine>,
+    proofs: Vec<EvaluationProof<E>>,
+}
+
+impl<E: Pairing> OpeningKey<E> {
+    /// Precompute all evaluation proofs for `polynomial` using the committer key `ck`.
+    pub fn new(ck: &CommitterKey<E>, polynomial: &[E::ScalarField]) -> Self {
+        let d = usize::max(polynomial.len(), 2);
+        assert!(d <= ck.powers_of_g.len() + 1, "polynomial degree too large");
+        let domain = Radix2EvaluationDomain::<E::ScalarField>::new(d)
+            .expect("field is not smooth enough to construct domain");
+        let double_domain = Radix2EvaluationDomain::<E::ScalarField>::new(2 * d)
+            .expect("field is not smooth enough to construct domain");
+
+        // H_k is the (k + d - 1)-th coefficient of the product of f with the reversed SRS.
+        let mut reversed_srs = ck.powers_of_g[..d - 1]
+            .iter()
+            .rev()
+            .map(|g| g.into_group())
+            .collect::<Vec<_>>();
+        reversed_srs.resize(double_domain.size(), E::G1::zero());
+        let mut coefficients = polynomial.to_vec();
+        coefficients.resize(double_domain.size(), E::ScalarField::zero());
+
+        let srs_evaluations = double_domain.fft(&reversed_srs);
+        let coefficients_evaluations = double_domain.fft(&coefficients);
+        let product = srs_evaluations
+            .iter()
+            .zip(coefficients_evaluations)
+            .map(|(&g, c)| g * c)
+            .collect::<Vec<_>>();
+        let mut quotient_coefficients = double_domain.ifft(&product)[d - 1..2 * d - 2].to_vec();
+
+        // The evaluation proofs on the domain are the evaluations of the quotient coefficients.
+        quotient_coefficients.resize(domain.size(), E::G1::zero());
+        let proof_bases = E::G1::normalize_batch(&domain.fft(&quotient_coefficients));
+        let proofs = proof_bases
+            .iter()
+            .map(|p| EvaluationProof(p.into_group()))
+            .collect();
+        let positions = domain.elements().enumerate().map(|(i, x)| (x, i)).collect();
+
+        OpeningKey {
+            domain,
+            positions,
+            evaluations: domain.fft(polynomial),
+            proof_bases,
+            proofs,
+        }
+    }
+
+    /// Return the evaluation proofs for all the elements of the domain, in order.
+    pub fn proofs(&self) -> &[EvaluationProof<E>] {
+        &self.proofs
+    }
+
+    /// Return the evaluation of the polynomial in `evaluation_point`,
+    /// together with an evaluation proof.
+    ///
+    /// If `evaluation_point` is in the domain, the evaluation and the proof are looked up.
+    /// Otherwise, they are interpolated from the ones over the domain:
+    /// as $(f(X) - f(z))/(X - z)$ has degree less than $n$ in $z$,
+    /// the proof in $z$ is $\sum_i L_i(z) \pi_i$, where $\pi_i$ is the proof in $\omega^i$.
+    pub fn open(&self, evaluation_point: &E::ScalarField) -> (E::ScalarField, EvaluationProof<E>) {
+        if self
+            .domain
+            .evaluate_vanishing_polynomial(*evaluation_point)
+            .is_zero()
+        {
+            let position = self.positions[evaluation_point];
+            (self.evaluations[position], self.proofs[position].clone())
+        } else {
+            let lagrange_coefficients = self
+                .domain
+                .evaluate_all_lagrange_coefficients(*evaluation_point);
+            let evaluation = ip(&self.evaluations, &lagrange_coefficients);
+            let proof = E::G1::msm_unchecked(&self.proof_bases, &lagrange_coefficients);
+            (evaluation, EvaluationProof(proof))
+        }
+    }
+}
+
 #[test]
 fn test_srs() {
     use ark_test_curves::bls12_381::Bls12_381;
@@ -209,3 +604,120 @@ fn test_commitment() {
     assert_eq!(evaluation, expected_evaluation);
     assert!(vk.verify(&commitment, &alpha, &evaluation, &proof).is_ok())
 }
+
+#[test]
+fn test_trim() {
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+
+    let rng = &mut ark_std::test_rng();
+    let ck = CommitterKey::<Bls12_381>::new(100, 5, rng);
+    let trimmed_ck = ck.trim(10, 2).unwrap();
+    assert_eq!(trimmed_ck.max_degree(), 10);
+    assert_eq!(trimmed_ck.max_eval_points(), 2);
+    assert!(ck.trim(101, 2).is_err());
+    assert!(ck.trim(10, 6).is_err());
+
+    // commitments from the trimmed key are consistent with the full one.
+    let polynomial = (0..11).map(|i| Fr::from(i as u64)).collect::<Vec<_>>();
+    assert_eq!(trimmed_ck.commit(&polynomial), ck.commit(&polynomial));
+    let vk = VerifierKey::from(&trimmed_ck);
+    let alpha = Fr::from(42u64);
+    let (evaluation, proof) = trimmed_ck.open(&polynomial, &alpha);
+    assert!(vk
+        .verify(&ck.commit(&polynomial), &alpha, &evaluation, &proof)
+        .is_ok());
+}
+
+#[test]
+fn test_opening_key() {
+    use ark_poly::univariate::DensePolynomial;
+    use ark_poly::DenseUVPolynomial;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+
+    let rng = &mut ark_std::test_rng();
+    let d = 13;
+    let ck = CommitterKey::<Bls12_381>::new(d, 3, rng);
+    let polynomial = DensePolynomial::<Fr>::rand(d - 1, rng);
+    let opening_key = OpeningKey::new(&ck, &polynomial);
+
+    let domain = Radix2EvaluationDomain::<Fr>::new(d).unwrap();
+    assert_eq!(opening_key.proofs().len(), domain.size());
+    for (omega, proof) in domain.elements().zip(opening_key.proofs()) {
+        let (_, expected) = ck.open(&polynomial, &omega);
+        assert_eq!(proof, &expected);
+    }
+
+    let alpha = Fr::rand(rng);
+    assert_eq!(opening_key.open(&alpha), ck.open(&polynomial, &alpha));
+    let omega = domain.element(3);
+    assert_eq!(opening_key.open(&omega), ck.open(&polynomial, &omega));
+}
+
+#[test]
+fn test_sparse_commitment() {
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+
+    let rng = &mut ark_std::test_rng();
+    let ck = CommitterKey::<Bls12_381>::new(100, 3, rng);
+    let vk = VerifierKey::from(&ck);
+    let sparse_polynomial = [
+        (97, Fr::from(3u64)),
+        (0, Fr::from(1u64)),
+        (42, Fr::rand(rng)),
+        (43, Fr::rand(rng)),
+    ];
+    let mut polynomial = vec![Fr::zero(); 98];
+    sparse_polynomial
+        .iter()
+        .for_each(|&(i, c)| polynomial[i] = c);
+
+    let commitment = ck.commit_sparse(&sparse_polynomial);
+    assert_eq!(commitment, ck.commit(&polynomial));
+
+    let alpha = Fr::rand(rng);
+    let (evaluation, proof) = ck.open_sparse(&sparse_polynomial, &alpha);
+    assert_eq!((evaluation, proof.clone()), ck.open(&polynomial, &alpha));
+    assert!(vk.verify(&commitment, &alpha, &evaluation, &proof).is_ok());
+}
+
+#[test]
+fn test_open_multi_points_with_evals() {
+    use ark_poly::univariate::DensePolynomial;
+    use ark_poly::DenseUVPolynomial;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+
+    let rng = &mut ark_std::test_rng();
+    let ck = CommitterKey::<Bls12_381>::new(100, 4, rng);
+    let vk = VerifierKey::from(&ck);
+    let polynomial = DensePolynomial::<Fr>::rand(100, rng);
+    let eval_points = (0..4).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+
+    let commitment = ck.commit(&polynomial);
+    let (evaluations, proof) = ck.open_multi_points_with_evals(&polynomial, &eval_points);
+    assert_eq!(evaluations[2], evaluate_le(&polynomial, &eval_points[2]));
+    assert!(vk
+        .verify_multi_points_single(&commitment, &eval_points, &evaluations, &proof)
+        .is_ok());
+    let mut wrong_evaluations = evaluations;
+    wrong_evaluations[0] += Fr::from(1u64);
+    assert!(vk
+        .verify_multi_points_single(&commitment, &eval_points, &wrong_evaluations, &proof)
+        .is_err());
+}
+
+#[test]
+fn test_new_deterministic() {
+    use ark_test_curves::bls12_381::Bls12_381;
+
+    let ck = CommitterKey::<Bls12_381>::new_deterministic(15, 3, b"test vectors");
+    let same_ck = CommitterKey::<Bls12_381>::new_deterministic(15, 3, b"test vectors");
+    let other_ck = CommitterKey::<Bls12_381>::new_deterministic(15, 3, b"other test vectors");
+    assert_eq!(ck, same_ck);
+    assert_ne!(ck, other_ck);
+    assert_eq!(ck.max_degree(), 15);
+    assert_eq!(ck.max_eval_points(), 3);
+    assert_eq!(
+        ck.into_insecure_inner().powers_of_g[0],
+        <Bls12_381 as Pairing>::G1Affine::generator()
+    );
+}
@@ -0,0 +1,124 @@
+//! Validation of keys coming from untrusted sources.
+//!
+//! A key is valid if all its points are on the curve and in the prime-order subgroup,
+//! and if its elements are consecutive powers of the same trapdoor \\(\tau\\).
+//! The latter is checked via random linear combinations, with a constant number of pairings;
+//! the randomness is derived from the key itself, Fiat--Shamir style.
+use ark_ec::pairing::Pairing;
+use ark_ec::AffineRepr;
+use ark_serialize::*;
+use ark_std::io::{Result as IoResult, Write};
+use ark_std::rand::{rngs::StdRng, SeedableRng};
+use merlin::Transcript;
+
+use super::contribution::{is_g1_power_sequence, is_g2_power_sequence};
+use super::{CommitterKey, SrsError, VerifierKey};
+
+/// The domain separator for deriving the randomness of the validation checks.
+const VALIDATION_DOMAIN_SEPARATOR: &[u8] = b"GEMINI-v0-SRS-VALIDATION";
+
+/// Adapter feeding serialized data into a transcript, without buffering it entirely in memory.
+struct TranscriptWriter<'a>(&'a mut Transcript);
+
+impl Write for TranscriptWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        self.0.append_message(b"key", buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> IoResult<()> {
+        Ok(())
+    }
+}
+
+/// Return a random number generator seeded with the hash of `key`.
+fn validation_rng<S: CanonicalSerialize>(key: &S) -> StdRng {
+    let mut transcript = Transcript::new(VALIDATION_DOMAIN_SEPARATOR);
+    key.serialize_uncompressed(TranscriptWriter(&mut transcript))
+        .expect("serialization into a transcript cannot fail");
+    let mut seed = [0u8; 32];
+    transcript.challenge_bytes(b"seed", &mut seed);
+    StdRng::from_seed(seed)
+}
+
+impl<E: Pairing> CommitterKey<E> {
+    /// Check that the committer key is well-formed, i.e. that:
+    /// - all points are on the curve and in the prime-order subgroup;
+    /// - the generators are not the point at infinity;
+    /// - `powers_of_g`, `powers_of_g2`, and `powers_of_gamma_g` are consecutive powers of the same element.
+    ///
+    /// Keys loaded from untrusted sources should be validated before use.
+    pub fn validate(&self) -> Result<(), SrsError> {
+        if self.powers_of_g.len() < 2 || self.powers_of_g2.len() < 2 {
+            return Err(SrsError::NotEnoughPowers);
+        }
+        self.check().map_err(|_| SrsError::InvalidPoint)?;
+        if self.powers_of_g[0].is_zero()
+            || self.powers_of_g2[0].is_zero()
+            || self.powers_of_gamma_g.first().map_or(true, |g| g.is_zero())
+        {
+            return Err(SrsError::InvalidPoint);
+        }
+        self.check_powers_consistency(&mut validation_rng(self))
+    }
+}
+
+impl<E: Pairing> VerifierKey<E> {
+    /// Check that the verifier key is well-formed, i.e. that:
+    /// - all points are on the curve and in the prime-order subgroup;
+    /// - the generators are not the point at infinity;
+    /// - `powers_of_g` and `powers_of_g2` are consecutive powers of the same element.
+    ///
+    /// Keys loaded from untrusted sources should be validated before use.
+    pub fn validate(&self) -> Result<(), SrsError> {
+        if self.powers_of_g.is_empty() || self.powers_of_g2.len() < 2 {
+            return Err(SrsError::NotEnoughPowers);
+        }
+        self.check().map_err(|_| SrsError::InvalidPoint)?;
+        if self.powers_of_g[0].is_zero() || self.powers_of_g2[0].is_zero() || self.gamma_g.is_zero()
+        {
+            return Err(SrsError::InvalidPoint);
+        }
+
+        let rng = &mut validation_rng(self);
+        let g2 = self.powers_of_g2[0];
+        let tau_g2 = self.powers_of_g2[1];
+        let consistent_g1 = is_g1_power_sequence::<E>(&self.powers_of_g, g2, tau_g2, rng);
+        // with a single power over G1, the powers over G2 cannot be checked beyond tau.
+        let consistent_g2 = self.powers_of_g.len() < 2
+            || is_g2_power_sequence::<E>(
+                &self.powers_of_g2,
+                self.powers_of_g[0],
+                self.powers_of_g[1],
+                rng,
+            );
+        if consistent_g1 && consistent_g2 {
+            Ok(())
+        } else {
+            Err(SrsError::InconsistentPowers)
+        }
+    }
+}
+
+#[test]
+fn test_validate() {
+    use ark_test_curves::bls12_381::Bls12_381;
+
+    let rng = &mut ark_std::test_rng();
+    let ck = CommitterKey::<Bls12_381>::new(15, 3, rng);
+    let vk = VerifierKey::from(&ck);
+    assert!(ck.validate().is_ok());
+    assert!(vk.validate().is_ok());
+
+    let mut tampered_ck = ck.clone();
+    tampered_ck.powers_of_g.swap(3, 4);
+    assert_eq!(tampered_ck.validate(), Err(SrsError::InconsistentPowers));
+
+    let mut tampered_vk = vk.clone();
+    tampered_vk.powers_of_g2.swap(2, 3);
+    assert_eq!(tampered_vk.validate(), Err(SrsError::InconsistentPowers));
+
+    let mut tampered_vk = vk;
+    tampered_vk.gamma_g = <Bls12_381 as Pairing>::G1Affine::zero();
+    assert_eq!(tampered_vk.validate(), Err(SrsError::InvalidPoint));
+}
@@ -11,6 +11,8 @@
 //! Choice of the pairing-friendly elliptic curve,
 //! is entirely up to the implementor.
 //! All arguments are internally using the [`kzg`](crate::kzg) commitment scheme.
+//! The reduction from multilinear evaluations to univariate openings is also available
+//! as a standalone polynomial commitment in [`multilinear::MultilinearKzg`].
 //! Support for generic univariate or multivariate commitments will is scheduled and will
 //! happen at some point in the future.
 //!
@@ -24,8 +26,26 @@
 //! on:
 //!
 //! - `asm`, to turn on the assembly backend within [`ark-ff`](https://docs.rs/ark-ff/);
-//! - `parallel`, to turn on multi-threading. This requires the additional dependency [`rayon`](https://docs.rs/rayon/latest/rayon/);
-//! - `std`, to rely on the Rust Standard library;
+//! - `parallel`, to turn on multi-threading. This requires the additional dependency [`rayon`](https://docs.rs/rayon/latest/rayon/).
+//!   The number of threads used by the commitment scheme can be bounded with [`kzg::with_thread_budget`];
+//! - `std`, to rely on the Rust Standard library. Without it, the crate only requires [`alloc`](https://doc.rust-lang.org/alloc/),
+//!   so that proofs and KZG openings can be verified in embedded and SGX environments:
+//!   the features relying on file I/O or threads (`async`, `mmap`, `parallel`, ...) enable `std` themselves;
+//! - `circom-witness`, to run the WebAssembly witness generators of Circom circuits via [`circuit::circom::WitnessCalculator`]. This requires the additional dependency [`wasmer`](https://docs.rs/wasmer/latest/wasmer/);
+//! - `cli`, to build the `gemini` command line tool, proving and verifying circuits and witnesses read from files over BLS12-381 or BN254. This requires the additional dependencies [`clap`](https://docs.rs/clap/latest/clap/), [`ark-test-curves`](https://docs.rs/ark-test-curves/latest/ark_test_curves/), and [`ark-bn254`](https://docs.rs/ark-bn254/latest/ark_bn254/);
+//! - `eip4844`, to commit to and open [EIP-4844](https://eips.ethereum.org/EIPS/eip-4844) blobs compatibly with [`c-kzg`](https://github.com/ethereum/c-kzg-4844). This requires the additional dependencies [`ark-test-curves`](https://docs.rs/ark-test-curves/latest/ark_test_curves/) and [`sha2`](https://docs.rs/sha2/latest/sha2/);
+//! - `async`, to stream witnesses from asynchronous sources via [`iterable::async_stream`]. This requires the additional dependency [`futures`](https://docs.rs/futures/latest/futures/);
+//! - `fs-audit`, a debugging aid asserting, via [`transcript::AuditTranscript`], that every challenge is squeezed after absorbing the prover messages it depends on;
+//! - `mmap`, to load large committer keys and witnesses from memory-mapped files. This requires the additional dependency [`memmap`](https://docs.rs/memmap/latest/memmap/);
+//! - `mpc`, to experiment with collaborative proving over additively secret-shared witnesses via [`mpc`], computing the linear phases of the prover on the shares;
+//! - `memory-telemetry`, to measure the peak memory of each phase of the SNARK provers with [`snark::memory::TrackingAllocator`], installed as the global allocator;
+//! - `poly-commit`, to expose the KZG commitment through [`ark_poly_commit::PolynomialCommitment`]. This requires the additional dependencies [`ark-poly-commit`](https://docs.rs/ark-poly-commit/latest/ark_poly_commit/) and [`ark-crypto-primitives`](https://docs.rs/ark-crypto-primitives/latest/ark_crypto_primitives/);
+//! - `simd`, to fold and multiply entry-wise vectors over the scalar field of BLS12-381 with vectorized kernels from the nightly [`core::simd`](https://doc.rust-lang.org/nightly/core/simd/index.html), compiled to AVX2 or NEON instructions. The other fields keep the scalar implementation;
+//! - `snark-trait`, to expose [`snark::Proof`] through the [`ark_snark::SNARK`] trait as [`snark::Gemini`]. This requires the additional dependency [`ark-snark`](https://docs.rs/ark-snark/latest/ark_snark/);
+//! - `tracing`, to instrument the commitment scheme, the sub-protocols, the provers, and the verifiers with [`tracing`](https://docs.rs/tracing/latest/tracing/) spans, recording the sizes of the instances as fields. This requires the additional dependency [`tracing`](https://docs.rs/tracing/latest/tracing/);
+//! - `wasm-bindgen`, to expose the verifier to JavaScript via [`wasm::verify`], when compiling to `wasm32-unknown-unknown` without the features `asm` and `parallel`. This requires the additional dependencies [`wasm-bindgen`](https://docs.rs/wasm-bindgen/latest/wasm_bindgen/) and [`ark-test-curves`](https://docs.rs/ark-test-curves/latest/ark_test_curves/);
+//! - `zkinterface`, to import and export circuits in the [zkInterface](https://github.com/QED-it/zkinterface) format via [`circuit::zkinterface`]. This requires the additional dependency [`zkinterface`](https://docs.rs/zkinterface/latest/zkinterface/);
+//! - `zeroize`, to overwrite with zeros the secrets held by the library once they are no longer needed: the trapdoor of the setup, and the copies of the witness made by the provers. The assignment of an [`circuit::R1cs`] instance is owned by the caller, who can wrap it in [`zeroize::Zeroizing`](https://docs.rs/zeroize/latest/zeroize/struct.Zeroizing.html) to wipe it on drop. This requires the additional dependency [`zeroize`](https://docs.rs/zeroize/latest/zeroize/);
 //! - `print-trace`, to print additional information concerning the execution time of the sub-protocols. **This feature must be enabled if you want to print the execution time of the examples.**
 //!
 //! # Benchmarking
@@ -55,6 +75,7 @@
     slice_as_chunks,
     array_chunks
 )]
+#![cfg_attr(feature = "simd", feature(portable_simd))]
 #![no_std]
 #![deny(unused_comparisons, bare_trait_objects, unused_must_use)]
 // #![forbid(unsafe_code)]
@@ -71,24 +92,39 @@
 // #![deny(renamed_and_removed_lints, stable_features, unused_allocation)]
 #[macro_use]
 extern crate ark_std;
+#[cfg(feature = "std")]
+extern crate std;
 
 /// The domain separator, used when proving statements on gemini.
 pub(crate) const PROTOCOL_NAME: &[u8] = b"GEMINI-v0";
 /// The threshold for switching from space to time prover within the sumcheck.
 const SPACE_TIME_THRESHOLD: usize = 22;
-// const SUMCHECK_BUF_SIZE: usize = 1 << 20;
+/// The number of pairs of coefficients buffered by the space-efficient sumcheck prover
+/// for summing them in parallel.
+#[cfg(feature = "parallel")]
+const SUMCHECK_BUF_SIZE: usize = 1 << 16;
+/// The length below which vectors are processed sequentially, even with the `parallel` feature:
+/// short inputs are not worth the overhead of the thread pool.
+#[cfg(feature = "parallel")]
+const PARALLEL_THRESHOLD: usize = 1 << 12;
 
+pub mod circuit;
 pub mod errors;
 pub mod herring;
 pub mod iterable;
 pub mod kzg;
+#[cfg(feature = "mpc")]
+pub mod mpc;
+pub mod multilinear;
 pub mod psnark;
 pub mod snark;
 pub mod subprotocols;
+pub mod transcript;
+#[cfg(feature = "wasm-bindgen")]
+pub mod wasm;
 
 // private modules
 
-#[doc(hidden)]
-pub mod circuit;
+#[cfg(feature = "simd")]
+mod kernels;
 pub mod misc;
-mod transcript;
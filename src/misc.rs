@@ -7,6 +7,9 @@ use ark_std::Zero;
 use crate::circuit::Matrix;
 use ark_std::collections::{BTreeMap, BTreeSet};
 
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
 pub(crate) const TENSOR_EXPANSION_LOG: usize = 16;
 pub(crate) const TENSOR_EXPANSION: usize = (1 << TENSOR_EXPANSION_LOG) - 1;
 
@@ -30,8 +33,7 @@ pub fn strip_last<T>(v: &[T]) -> &[T] {
 /// Return ceil(x / y).
 #[inline]
 pub fn ceil_div(x: usize, y: usize) -> usize {
-    // XXX. warning: this expression can overflow.
-    (x + y - 1) / y
+    x / y + (x % y != 0) as usize
 }
 
 /// Compute a linear combination of the polynomials `polynomials` with the given challenges.
@@ -51,11 +53,95 @@ where
 /// Helper function for folding single polynomial.
 #[inline]
 pub(crate) fn fold_polynomial<F: Field>(f: &[F], r: F) -> Vec<F> {
-    f.chunks(2)
-        .map(|pair| pair[0] + r * pair.get(1).unwrap_or(&F::zero()))
-        .collect()
+    let mut folded = Vec::with_capacity(ceil_div(f.len(), 2));
+    fold_polynomial_into(f, r, &mut folded);
+    folded
 }
 
+/// Fold the polynomial `f` with the challenge `r` into `folded`, overwriting its content.
+///
+/// This reuses the allocation of `folded`, see [`BufferPool`].
+#[inline]
+pub(crate) fn fold_polynomial_into<F: Field>(f: &[F], r: F, folded: &mut Vec<F>) {
+    folded.clear();
+    #[cfg(feature = "simd")]
+    if crate::kernels::try_fold(f, r, folded) {
+        return;
+    }
+    let fold = |pair: &[F]| pair[0] + r * pair.get(1).unwrap_or(&F::zero());
+    #[cfg(feature = "parallel")]
+    if f.len() >= crate::PARALLEL_THRESHOLD {
+        folded.par_extend(
+            f.par_chunks(2)
+                .with_min_len(crate::PARALLEL_THRESHOLD / 2)
+                .map(fold),
+        );
+        return;
+    }
+    folded.extend(f.chunks(2).map(fold));
+}
+
+/// A pool of buffers reused across the rounds of a prover.
+///
+/// Folding a vector in each round would otherwise allocate a new vector and free the old one.
+/// Instead, the old vectors are [`put`](Self::put) back in the pool,
+/// and [`take`](Self::take) hands them out again, so that only the first round allocates.
+pub(crate) struct BufferPool<T> {
+    buffers: Vec<Vec<T>>,
+}
+
+impl<T> Default for BufferPool<T> {
+    fn default() -> Self {
+        BufferPool {
+            buffers: Vec::new(),
+        }
+    }
+}
+
+impl<T> BufferPool<T> {
+    /// Return an empty buffer with capacity at least `capacity`,
+    /// reusing the smallest such buffer in the pool, if any.
+    pub(crate) fn take(&mut self, capacity: usize) -> Vec<T> {
+        let reusable = self
+            .buffers
+            .iter()
+            .enumerate()
+            .filter(|(_, buffer)| buffer.capacity() >= capacity)
+            .min_by_key(|(_, buffer)| buffer.capacity())
+            .map(|(i, _)| i);
+        match reusable {
+            Some(i) => self.buffers.swap_remove(i),
+            None => Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Clear `buffer` and return it to the pool.
+    pub(crate) fn put(&mut self, mut buffer: Vec<T>) {
+        buffer.clear();
+        self.buffers.push(buffer);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<T: zeroize::Zeroize> zeroize::Zeroize for BufferPool<T> {
+    fn zeroize(&mut self) {
+        // cleared buffers still hold the previous foldings beyond their length.
+        zeroize::Zeroize::zeroize(&mut self.buffers);
+    }
+}
+
+/// Overwrite the secret `secret` with zeros, if the feature `zeroize` is enabled.
+#[cfg(feature = "zeroize")]
+#[inline]
+pub(crate) fn wipe<Z: zeroize::Zeroize + ?Sized>(secret: &mut Z) {
+    secret.zeroize();
+}
+
+/// Overwrite the secret `secret` with zeros, if the feature `zeroize` is enabled.
+#[cfg(not(feature = "zeroize"))]
+#[inline]
+pub(crate) fn wipe<Z: ?Sized>(_secret: &mut Z) {}
+
 /// Return a vector of length `len` containing the consecutive powers of element.
 pub(crate) fn powers<F: Field>(element: F, len: usize) -> Vec<F> {
     let mut powers = vec![F::one(); len];
@@ -98,8 +184,11 @@ impl<T> MatrixElement<T> {
 }
 
 /// Given a sparse matrix `matrix` and a vector `z`, compute `matrix * z`.
+///
+/// With the feature `parallel`, the rows are split in chunks of at least
+/// [`PARALLEL_THRESHOLD`](crate::PARALLEL_THRESHOLD) rows, computed concurrently.
 pub fn product_matrix_vector<F: Field>(matrix: &[Vec<(F, usize)>], z: &[F]) -> Vec<F> {
-    let inner_prod_fn = |row: &[(F, usize)]| {
+    let inner_prod_fn = |row: &Vec<(F, usize)>| {
         let mut acc = F::zero();
         for &(ref coeff, i) in row {
             acc += if coeff.is_one() { z[i] } else { z[i] * coeff };
@@ -107,24 +196,69 @@ pub fn product_matrix_vector<F: Field>(matrix: &[Vec<(F, usize)>], z: &[F]) -> V
         acc
     };
 
-    matrix.iter().map(|row| inner_prod_fn(row)).collect()
+    #[cfg(feature = "parallel")]
+    if matrix.len() >= crate::PARALLEL_THRESHOLD {
+        return matrix
+            .par_iter()
+            .with_min_len(crate::PARALLEL_THRESHOLD)
+            .map(inner_prod_fn)
+            .collect();
+    }
+    matrix.iter().map(inner_prod_fn).collect()
 }
 
-/// Given a vector `z` and a sparse matrix `matrix`, compute `z * matrix`.
-#[allow(unused)]
-pub fn product_vector_matrix<F: Field>(z: &[F], matrix: &[Vec<(F, usize)>]) -> Vec<F> {
-    let mut res = vec![F::zero(); z.len()];
-    for (row_index, row) in matrix.iter().enumerate() {
-        for &(ref coeff, i) in row {
-            res[i] += if coeff.is_one() {
-                z[row_index]
-            } else {
-                z[row_index] * coeff
-            };
+/// Given a vector `z` and a sparse matrix `matrix` with `num_columns` columns, compute `z * matrix`,
+/// i.e., the product of the transpose of `matrix` with `z`.
+///
+/// With the feature `parallel`, the rows are split in one chunk per thread,
+/// each accumulating its own vector of `num_columns` elements, summed at the end.
+pub fn vector_times_matrix<F: Field>(
+    z: &[F],
+    matrix: &[Vec<(F, usize)>],
+    num_columns: usize,
+) -> Vec<F> {
+    let accumulate = |mut res: Vec<F>, (row, &z_row): (&Vec<(F, usize)>, &F)| {
+        for &(coeff, col) in row {
+            res[col] += z_row * coeff;
         }
+        res
+    };
+
+    #[cfg(feature = "parallel")]
+    if matrix.len() >= crate::PARALLEL_THRESHOLD {
+        let chunk_size = ceil_div(matrix.len(), rayon::current_num_threads());
+        return matrix
+            .par_chunks(chunk_size)
+            .zip(z.par_chunks(chunk_size))
+            .map(|(rows, z)| {
+                rows.iter()
+                    .zip(z)
+                    .fold(vec![F::zero(); num_columns], accumulate)
+            })
+            .reduce_with(|mut lhs, rhs| {
+                lhs.iter_mut().zip(rhs).for_each(|(l, r)| *l += r);
+                lhs
+            })
+            .unwrap_or_else(|| vec![F::zero(); num_columns]);
     }
+    matrix
+        .iter()
+        .zip(z)
+        .fold(vec![F::zero(); num_columns], accumulate)
+}
 
-    res
+/// Run `oper_a` and `oper_b`, concurrently if the feature `parallel` is enabled.
+pub(crate) fn join<A, B, RA, RB>(oper_a: A, oper_b: B) -> (RA, RB)
+where
+    A: FnOnce() -> RA + Send,
+    B: FnOnce() -> RB + Send,
+    RA: Send,
+    RB: Send,
+{
+    #[cfg(feature = "parallel")]
+    return rayon::join(oper_a, oper_b);
+    #[cfg(not(feature = "parallel"))]
+    return (oper_a(), oper_b());
 }
 
 /// Given as input `elements`, an array of field elements
@@ -133,6 +267,10 @@ pub fn product_vector_matrix<F: Field>(z: &[F], matrix: &[Vec<(F, usize)>]) -> V
 /// \\( \otimes_j (1, \rho_j )\\)
 pub fn tensor<F: Field>(elements: &[F]) -> Vec<F> {
     assert!(!elements.is_empty());
+    assert!(
+        elements.len() < usize::BITS as usize,
+        "the tensor product is too long to be indexed"
+    );
     let mut tensor = vec![F::one(); 1 << elements.len()];
     let mut elements_iterator = elements.iter().enumerate();
 
@@ -142,10 +280,40 @@ pub fn tensor<F: Field>(elements: &[F]) -> Vec<F> {
         .1;
     // guaranteed to have at least one element.
     for (i, element) in elements_iterator {
-        for j in 0..1 << i {
-            tensor[(1 << i) + j] = tensor[j] * element;
+        let (low, high) = tensor.split_at_mut(1 << i);
+        cfg_iter_mut!(high[..1 << i])
+            .zip(cfg_iter!(low))
+            .for_each(|(high, low)| *high = *low * element);
+    }
+    tensor
+}
+
+/// Return the first `len` entries of the tensor product
+/// \( \otimes_j (1, \rho_j )\)
+/// of `elements`, without materializing the remaining ones.
+///
+/// This is `tensor(elements)[..len]` in \(O(\text{len})\) time and space,
+/// for when only a prefix of the tensor is needed, e.g., one entry per constraint.
+///
+/// # Panics
+/// If `len` is larger than the length of the tensor product.
+pub fn tensor_prefix<F: Field>(elements: &[F], len: usize) -> Vec<F> {
+    assert!(
+        elements.len() >= usize::BITS as usize || len <= 1 << elements.len(),
+        "the prefix is longer than the tensor product"
+    );
+    let mut tensor = Vec::with_capacity(len);
+    tensor.push(F::one());
+    // after the i-th element, the tensor has (at most) 2^{i+1} entries.
+    for element in elements {
+        if tensor.len() >= len {
+            break;
         }
+        let low_len = tensor.len();
+        tensor.extend_from_within(..usize::min(low_len, len - low_len));
+        cfg_iter_mut!(tensor[low_len..]).for_each(|high| *high *= element);
     }
+    tensor.truncate(len);
     tensor
 }
 
@@ -205,6 +373,10 @@ where
 #[inline]
 pub fn hadamard<F: Field>(lhs: &[F], rhs: &[F]) -> Vec<F> {
     assert_eq!(lhs.len(), rhs.len());
+    #[cfg(feature = "simd")]
+    if let Some(product) = crate::kernels::try_hadamard(lhs, rhs) {
+        return product;
+    }
     hadamard_unsafe(lhs, rhs)
 }
 
@@ -218,6 +390,32 @@ pub fn ip<F: Field>(lhs: &[F], rhs: &[F]) -> F {
     ip_unsafe(lhs.iter(), rhs.iter())
 }
 
+/// Return the inverses of `elements`, or `None` if any of them is zero.
+///
+/// All elements are inverted with a single field inversion and \(3(n-1)\) multiplications,
+/// using Montgomery's trick.
+pub fn batch_inverse<F: Field>(elements: &[F]) -> Option<Vec<F>> {
+    // the i-th prefix product is x_0 * ... * x_{i-1}.
+    let mut prefix_products = Vec::with_capacity(elements.len());
+    let mut product = F::one();
+    for element in elements {
+        prefix_products.push(product);
+        product *= element;
+    }
+    let mut inverse = product.inverse()?;
+    let mut inverses = vec![F::zero(); elements.len()];
+    for ((element, prefix_product), result) in elements
+        .iter()
+        .zip(prefix_products)
+        .zip(&mut inverses)
+        .rev()
+    {
+        *result = inverse * prefix_product;
+        inverse *= element;
+    }
+    Some(inverses)
+}
+
 pub(crate) fn hadamard_unsafe<F: Field, I, J>(lhs: I, rhs: J) -> Vec<F>
 where
     I: IntoIterator,
@@ -399,6 +597,15 @@ pub fn evaluate_index_poly<F: Field>(x: F, n: usize) -> F {
     x * (F::one() - x_n) / x1.square() - F::from((n - 1) as u64) * x_n * x / x1
 }
 
+#[test]
+fn test_ceil_div() {
+    assert_eq!(ceil_div(0, 3), 0);
+    assert_eq!(ceil_div(7, 3), 3);
+    assert_eq!(ceil_div(9, 3), 3);
+    assert_eq!(ceil_div(usize::MAX, 2), usize::MAX / 2 + 1);
+    assert_eq!(ceil_div(usize::MAX, usize::MAX), 1);
+}
+
 #[test]
 fn test_linear_combination() {
     use ark_test_curves::bls12_381::Fr;
@@ -436,3 +643,92 @@ fn test_evaluate_index_poly() {
     let expected = evaluate_le(&index_polynomial, &x);
     assert_eq!(got, expected);
 }
+
+#[test]
+fn test_batch_inverse() {
+    use ark_ff::UniformRand;
+    use ark_test_curves::bls12_381::Fr;
+
+    let rng = &mut ark_std::test_rng();
+    let elements = (0..13).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+    let expected = elements
+        .iter()
+        .map(|x| x.inverse().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(batch_inverse(&elements), Some(expected));
+    assert_eq!(batch_inverse::<Fr>(&[]), Some(Vec::new()));
+
+    let mut elements = elements;
+    elements[5] = Fr::zero();
+    assert_eq!(batch_inverse(&elements), None);
+}
+
+#[test]
+fn test_buffer_pool() {
+    use ark_test_curves::bls12_381::Fr;
+
+    let mut pool = BufferPool::default();
+    let f = (0..13u64).map(Fr::from).collect::<Vec<_>>();
+    let mut folded = pool.take(7);
+    fold_polynomial_into(&f, Fr::from(2u64), &mut folded);
+    assert_eq!(folded, fold_polynomial(&f, Fr::from(2u64)));
+
+    // the buffer is handed out again, cleared, if it is large enough.
+    let pointer = folded.as_ptr();
+    pool.put(folded);
+    let buffer = pool.take(4);
+    assert!(buffer.is_empty());
+    assert_eq!(buffer.as_ptr(), pointer);
+    pool.put(buffer);
+    assert!(pool.take(8).capacity() >= 8);
+    assert_eq!(pool.take(1).as_ptr(), pointer);
+}
+
+#[test]
+fn test_vector_times_matrix() {
+    use ark_ff::UniformRand;
+    use ark_std::rand::Rng;
+    use ark_test_curves::bls12_381::Fr;
+
+    let rng = &mut ark_std::test_rng();
+    // enough rows to be split across threads.
+    let num_rows = 1 << 13;
+    let num_columns = 100;
+    let matrix = (0..num_rows)
+        .map(|_| {
+            (0..3)
+                .map(|_| (Fr::rand(rng), rng.gen_range(0..num_columns)))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let mut transpose = vec![Vec::new(); num_columns];
+    for (i, row) in matrix.iter().enumerate() {
+        for &(coeff, j) in row {
+            transpose[j].push((coeff, i));
+        }
+    }
+
+    let z = (0..num_rows).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+    assert_eq!(
+        vector_times_matrix(&z, &matrix, num_columns),
+        product_matrix_vector(&transpose, &z)
+    );
+    let z = (0..num_columns).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+    assert_eq!(
+        product_matrix_vector(&matrix, &z),
+        vector_times_matrix(&z, &transpose, num_rows)
+    );
+}
+
+#[test]
+fn test_tensor_prefix() {
+    use ark_ff::UniformRand;
+    use ark_test_curves::bls12_381::Fr;
+
+    let rng = &mut ark_std::test_rng();
+    let elements = (0..6).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+    let expected = tensor(&elements);
+    for len in [0, 1, 2, 13, 32, 33, 64] {
+        assert_eq!(tensor_prefix(&elements, len), &expected[..len]);
+    }
+}
@@ -0,0 +1,257 @@
+//! Experimental collaborative proving over additively secret-shared witnesses.
+//!
+//! The witness \\(w\\) is split among \\(m\\) parties with [`share`],
+//! so that party \\(i\\) holds a uniformly random share \\(w_i\\) and \\(w = \sum_i w_i\\):
+//! no strict subset of the parties learns anything about the witness.
+//! All the phases of the prover that are linear in the witness are then computed by each party on its own share,
+//! and combined by summing the results:
+//! - the commitments, since \\(\mathsf{Com}(w) = \sum_i \mathsf{Com}(w_i)\\):
+//!   each party commits to its share with [`CommitterKey::commit`](crate::kzg::CommitterKey::commit),
+//!   and the commitments are summed;
+//! - the matrix-vector products, since \\(A z = \sum_i A z_i\\):
+//!   each party multiplies the matrices with its share of the assignment from [`share_assignment`]
+//!   via [`product_matrix_vector`](crate::misc::product_matrix_vector);
+//! - the sumcheck messages of a scalar product \\(\langle f, g \rangle\\) with \\(f\\) shared and \\(g\\) public,
+//!   as the messages are linear in \\(f\\):
+//!   each party runs a [`ShareProver`] and the [`SharedProver`] sums the partial messages,
+//!   which are the same as the ones of [`TimeProver`](crate::subprotocols::sumcheck::TimeProver) over \\(f\\).
+//!
+//! The combined values are public, as they are part of the proof.
+//! Products of two shared vectors, such as \\(z_a \circ z_b\\) in the first sumcheck of the SNARK,
+//! are not linear and need interaction among the parties (e.g., Beaver triples), which is not provided here.
+//! Parties are assumed to be semi-honest: shares are not authenticated.
+use ark_ff::Field;
+use ark_std::log2;
+use ark_std::marker::PhantomData;
+use ark_std::rand::RngCore;
+use ark_std::vec::Vec;
+use ark_std::UniformRand;
+
+use crate::subprotocols::sumcheck::{Prover, RoundMsg, ShardProver, Worker};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Split `secret` into `parties` additive shares.
+///
+/// The first `parties - 1` shares are uniformly random,
+/// and the last one is the difference between `secret` and their sum.
+pub fn share<F: Field, R: RngCore>(secret: &[F], parties: usize, rng: &mut R) -> Vec<Vec<F>> {
+    assert!(parties > 0, "At least one party is needed.");
+    let mut shares = (1..parties)
+        .map(|_| (0..secret.len()).map(|_| F::rand(rng)).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let last = secret
+        .iter()
+        .enumerate()
+        .map(|(i, &x)| x - shares.iter().map(|share| share[i]).sum::<F>())
+        .collect();
+    shares.push(last);
+    shares
+}
+
+/// Return the secret whose additive shares are `shares`, i.e., their entry-wise sum.
+pub fn reconstruct<F: Field>(shares: &[Vec<F>]) -> Vec<F> {
+    let len = shares.iter().map(Vec::len).max().unwrap_or(0);
+    let mut secret = vec![F::zero(); len];
+    for share in shares {
+        secret.iter_mut().zip(share).for_each(|(x, y)| *x += y);
+    }
+    secret
+}
+
+/// Return the shares of the assignment \\(z = (x, w)\\), for the public statement `x`
+/// and the witness `w` split into `parties` additive shares.
+///
+/// The statement is public, hence it is held by the first party only, and the others hold zeros in its place.
+pub fn share_assignment<F: Field, R: RngCore>(
+    x: &[F],
+    w: &[F],
+    parties: usize,
+    rng: &mut R,
+) -> Vec<Vec<F>> {
+    share(w, parties, rng)
+        .into_iter()
+        .enumerate()
+        .map(|(party, w_share)| {
+            let mut z_share = if party == 0 {
+                x.to_vec()
+            } else {
+                vec![F::zero(); x.len()]
+            };
+            z_share.extend(w_share);
+            z_share
+        })
+        .collect()
+}
+
+/// The prover of a party for the scalar product of a shared vector \\(f\\) with a public vector \\(g\\).
+///
+/// The final folding of \\(g\\) is public too:
+/// it is reported by the first party only, so that the final foldings of all parties sum up to the final ones.
+pub struct ShareProver<F: Field> {
+    /// The prover over the share of the party and the public vector.
+    prover: ShardProver<F>,
+    /// Whether this is the first party.
+    leader: bool,
+}
+
+impl<F: Field> ShareProver<F> {
+    /// Create a new prover for the share `f_share` of the party,
+    /// of the scalar product with `g` twisted by `twist`.
+    /// Exactly one of the parties is the `leader`.
+    /// This will cause a copy of the vectors.
+    pub fn new(f_share: &[F], g: &[F], twist: F, leader: bool) -> Self {
+        ShareProver {
+            prover: ShardProver::new(f_share, g, twist, 0),
+            leader,
+        }
+    }
+}
+
+impl<F: Field> Worker<F> for ShareProver<F> {
+    fn round_message(&mut self) -> RoundMsg<F> {
+        self.prover.round_message()
+    }
+
+    fn fold(&mut self, challenge: F) {
+        self.prover.fold(challenge)
+    }
+
+    fn final_foldings(&self) -> [F; 2] {
+        let [f, g] = self.prover.final_foldings();
+        [f, if self.leader { g } else { F::zero() }]
+    }
+}
+
+/// The prover of a twisted scalar product \\(\langle f, g \rangle\\) jointly run by the parties,
+/// summing their partial messages.
+pub struct SharedProver<F: Field, W: Worker<F>> {
+    parties: Vec<W>,
+    /// Round counter.
+    round: usize,
+    /// Total number of rounds.
+    tot_rounds: usize,
+    _field: PhantomData<F>,
+}
+
+impl<F: Field, W: Worker<F> + Send + Sync> SharedProver<F, W> {
+    /// Create a new prover for vectors of length `len`, combining the messages of `parties`.
+    pub fn new(len: usize, parties: Vec<W>) -> Self {
+        SharedProver {
+            parties,
+            round: 0,
+            tot_rounds: log2(len) as usize,
+            _field: PhantomData,
+        }
+    }
+}
+
+impl<F: Field, W: Worker<F> + Send + Sync> Prover<F> for SharedProver<F, W> {
+    /// Broadcast the challenge to the parties.
+    fn fold(&mut self, challenge: F) {
+        cfg_iter_mut!(self.parties).for_each(|party| party.fold(challenge));
+    }
+
+    /// Sum the partial messages of the parties.
+    fn next_message(&mut self, verifier_message: Option<F>) -> Option<RoundMsg<F>> {
+        assert!(self.round <= self.tot_rounds, "More rounds than needed.");
+        if let Some(challenge) = verifier_message {
+            self.fold(challenge);
+        }
+        if self.round == self.tot_rounds {
+            return None;
+        }
+        let message = cfg_iter_mut!(self.parties)
+            .map(|party| party.round_message())
+            .sum();
+        self.round += 1;
+        Some(message)
+    }
+
+    #[inline]
+    fn rounds(&self) -> usize {
+        self.tot_rounds
+    }
+
+    fn round(&self) -> usize {
+        self.round
+    }
+
+    fn final_foldings(&self) -> Option<[F; 2]> {
+        (self.round == self.tot_rounds).then(|| {
+            self.parties
+                .iter()
+                .map(|party| party.final_foldings())
+                .fold([F::zero(); 2], |[f, g], [f_share, g_share]| {
+                    [f + f_share, g + g_share]
+                })
+        })
+    }
+}
+
+#[test]
+fn test_linear_phases_on_shares() {
+    use ark_test_curves::bls12_381::Bls12_381;
+
+    use crate::circuit::{generate_relation, random_circuit};
+    use crate::kzg::{Commitment, CommitterKey};
+    use crate::misc::product_matrix_vector;
+
+    let rng = &mut ark_std::test_rng();
+    let parties = 3;
+    let r1cs = generate_relation(random_circuit(rng, 32, 32));
+    let ck = CommitterKey::<Bls12_381>::new(32, 1, rng);
+
+    let w_shares = share(&r1cs.w, parties, rng);
+    assert_eq!(reconstruct(&w_shares), r1cs.w);
+    let commitment = w_shares
+        .iter()
+        .map(|w_share| ck.commit(w_share))
+        .sum::<Commitment<Bls12_381>>();
+    assert_eq!(commitment, ck.commit(&r1cs.w));
+
+    let z_shares = share_assignment(&r1cs.x, &r1cs.w, parties, rng);
+    assert_eq!(reconstruct(&z_shares), r1cs.z);
+    for matrix in [&r1cs.a, &r1cs.b, &r1cs.c] {
+        let products = z_shares
+            .iter()
+            .map(|z_share| product_matrix_vector(matrix, z_share))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            reconstruct(&products),
+            product_matrix_vector(matrix, &r1cs.z)
+        );
+    }
+}
+
+#[test]
+fn test_shared_prover() {
+    use ark_test_curves::bls12_381::Fr;
+    use merlin::Transcript;
+
+    use crate::subprotocols::sumcheck::time_prover::Witness;
+    use crate::subprotocols::sumcheck::{Sumcheck, TimeProver};
+
+    let rng = &mut ark_std::test_rng();
+    for (len, parties) in [(1, 1), (16, 2), (29, 3)] {
+        let f = (0..len).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+        let g = (0..len).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+        let twist = Fr::rand(rng);
+
+        let provers = share(&f, parties, rng)
+            .iter()
+            .enumerate()
+            .map(|(party, f_share)| ShareProver::new(f_share, &g, twist, party == 0))
+            .collect::<Vec<_>>();
+        let sumcheck = Sumcheck::prove(
+            &mut Transcript::new(b"mpc"),
+            SharedProver::new(len, provers),
+        );
+        let expected = Sumcheck::prove(
+            &mut Transcript::new(b"mpc"),
+            TimeProver::new(Witness::new(&f, &g, &twist)),
+        );
+        assert_eq!(sumcheck, expected);
+    }
+}
@@ -0,0 +1,237 @@
+//! The Gemini multilinear polynomial commitment.
+//!
+//! A multilinear polynomial $f(x_0, \dots, x_{n-1}) = \sum_{b} f_b \prod_j x_j^{b_j}$
+//! is represented by the vector of its $2^n$ coefficients,
+//! where the coefficient $f_b$ of the monomial $\prod_j x_j^{b_j}$ is stored in position $\sum_j b_j 2^j$.
+//! The commitment to $f$ is the KZG commitment to the univariate polynomial with the same coefficients,
+//! and its evaluation at $\vec \rho$ is the scalar product $\langle \vec f, \otimes_j (1, \rho_j) \rangle$.
+//!
+//! Evaluation proofs reduce the multilinear evaluation to univariate KZG openings via
+//! the split-and-fold reduction of the [`tensorcheck`](crate::subprotocols::tensorcheck):
+//! the prover commits to the foldings $f^{(j+1)}(x) = f^{(j)}_e(x) + \rho_j f^{(j)}_o(x)$,
+//! and all foldings are opened at $\beta^2, \beta, -\beta$ for a random challenge $\beta$
+//! with a single batched evaluation proof.
+use ark_ec::pairing::Pairing;
+use ark_ff::{Field, One};
+use ark_serialize::*;
+use ark_std::marker::PhantomData;
+use ark_std::rand::RngCore;
+use ark_std::vec::Vec;
+
+use crate::kzg::{
+    Commitment, CommitterKey, EvaluationProof, MultiPointVerifier, VerificationError,
+    VerificationResult,
+};
+use crate::misc::{evaluate_le, fold_polynomial, ip, tensor};
+use crate::subprotocols::tensorcheck::evaluate_sq_fp;
+use crate::transcript::GeminiTranscript;
+
+/// Evaluation proof for a multilinear polynomial.
+#[derive(CanonicalSerialize, Clone, Debug, PartialEq, Eq)]
+pub struct MultilinearProof<E: Pairing> {
+    /// The commitments to the foldings \\(f^{(1)}, \dots, f^{(n-1)}\\).
+    pub folded_polynomials_commitments: Vec<Commitment<E>>,
+    /// The evaluations of \\(f\\) at \\(\beta^2, \beta, -\beta\\).
+    pub base_polynomial_evaluations: [E::ScalarField; 3],
+    /// The evaluations of each folding at \\(\beta, -\beta\\).
+    pub folded_polynomials_evaluations: Vec<[E::ScalarField; 2]>,
+    /// The batched evaluation proof for all polynomials.
+    pub evaluation_proof: EvaluationProof<E>,
+}
+
+/// The Gemini polynomial commitment for multilinear polynomials, built on top of KZG.
+pub struct MultilinearKzg<E: Pairing>(PhantomData<E>);
+
+impl<E: Pairing> MultilinearKzg<E> {
+    /// Generate a committer key for multilinear polynomials in `num_vars` variables.
+    pub fn setup(num_vars: usize, rng: &mut impl RngCore) -> CommitterKey<E> {
+        CommitterKey::new(1 << num_vars, 3, rng)
+    }
+
+    /// Return a commitment to the multilinear polynomial with coefficients `polynomial`.
+    pub fn commit(ck: &CommitterKey<E>, polynomial: &[E::ScalarField]) -> Commitment<E> {
+        ck.commit(polynomial)
+    }
+
+    /// Evaluate the multilinear polynomial `polynomial` at `point`,
+    /// and return the evaluation together with an evaluation proof.
+    ///
+    /// The commitment to `polynomial` is expected to be already in the `transcript`.
+    pub fn open(
+        ck: &CommitterKey<E>,
+        transcript: &mut impl GeminiTranscript,
+        polynomial: &[E::ScalarField],
+        point: &[E::ScalarField],
+    ) -> (E::ScalarField, MultilinearProof<E>) {
+        assert!(!point.is_empty(), "expecting at least one variable");
+        assert!(polynomial.len() <= 1 << point.len());
+        let evaluation = ip(polynomial, &tensor(point)[..polynomial.len()]);
+        transcript.append_serializable(b"point", &point.to_vec());
+        transcript.append_serializable(b"evaluation", &evaluation);
+
+        // the last folding is the constant evaluation, and is not committed.
+        let foldings = point[..point.len() - 1]
+            .iter()
+            .scan(polynomial.to_vec(), |folding, &challenge| {
+                *folding = fold_polynomial(folding, challenge);
+                Some(folding.clone())
+            })
+            .collect::<Vec<_>>();
+        let folded_polynomials_commitments = ck.batch_commit(&foldings);
+        transcript.append_serializables(b"commitment", &folded_polynomials_commitments);
+
+        let eval_chal = transcript.get_challenge::<E::ScalarField>(b"evaluation-chal");
+        let eval_points = [eval_chal.square(), eval_chal, -eval_chal];
+        let base_polynomial_evaluations = eval_points.map(|x| evaluate_le(polynomial, &x));
+        let folded_polynomials_evaluations = foldings
+            .iter()
+            .map(|folding| {
+                [
+                    evaluate_le(folding, &eval_points[1]),
+                    evaluate_le(folding, &eval_points[2]),
+                ]
+            })
+            .collect::<Vec<_>>();
+        base_polynomial_evaluations
+            .iter()
+            .chain(folded_polynomials_evaluations.iter().flatten())
+            .for_each(|e| transcript.append_serializable(b"eval", e));
+        let open_chal = transcript.get_challenge::<E::ScalarField>(b"open-chal");
+
+        let polynomial = polynomial.to_vec();
+        let mut all_polynomials = vec![&polynomial];
+        all_polynomials.extend(foldings.iter());
+        let evaluation_proof =
+            ck.batch_open_multi_points(&all_polynomials, &eval_points, &open_chal);
+
+        let proof = MultilinearProof {
+            folded_polynomials_commitments,
+            base_polynomial_evaluations,
+            folded_polynomials_evaluations,
+            evaluation_proof,
+        };
+        (evaluation, proof)
+    }
+
+    /// Verify that the multilinear polynomial committed in `commitment` evaluates to `evaluation` at `point`.
+    pub fn verify<V: MultiPointVerifier<E>>(
+        vk: &V,
+        transcript: &mut impl GeminiTranscript,
+        commitment: &Commitment<E>,
+        point: &[E::ScalarField],
+        evaluation: &E::ScalarField,
+        proof: &MultilinearProof<E>,
+    ) -> VerificationResult {
+        let rounds = point.len();
+        if rounds == 0
+            || proof.folded_polynomials_commitments.len() != rounds - 1
+            || proof.folded_polynomials_evaluations.len() != rounds - 1
+        {
+            return Err(VerificationError);
+        }
+        transcript.append_serializable(b"point", &point.to_vec());
+        transcript.append_serializable(b"evaluation", evaluation);
+        transcript.append_serializables(b"commitment", &proof.folded_polynomials_commitments);
+        let eval_chal = transcript.get_challenge::<E::ScalarField>(b"evaluation-chal");
+        let eval_points = [eval_chal.square(), eval_chal, -eval_chal];
+        proof
+            .base_polynomial_evaluations
+            .iter()
+            .chain(proof.folded_polynomials_evaluations.iter().flatten())
+            .for_each(|e| transcript.append_serializable(b"eval", e));
+        let open_chal = transcript.get_challenge::<E::ScalarField>(b"open-chal");
+
+        // derive the evaluations at \beta^2 of each folding from the evaluations of its parent.
+        let two_inv = E::ScalarField::one().double().inverse().unwrap();
+        let two_beta_inv = eval_chal.double().inverse().ok_or(VerificationError)?;
+        let base = proof.base_polynomial_evaluations;
+        let mut evaluations = vec![base.to_vec()];
+        let mut parent = [base[1], base[2]];
+        for (folded_evaluations, randomness) in
+            proof.folded_polynomials_evaluations.iter().zip(point)
+        {
+            let folded_evaluation =
+                evaluate_sq_fp(&parent[0], &parent[1], randomness, &two_inv, &two_beta_inv);
+            evaluations.push(vec![
+                folded_evaluation,
+                folded_evaluations[0],
+                folded_evaluations[1],
+            ]);
+            parent = *folded_evaluations;
+        }
+        let last_folding = evaluate_sq_fp(
+            &parent[0],
+            &parent[1],
+            &point[rounds - 1],
+            &two_inv,
+            &two_beta_inv,
+        );
+        if last_folding != *evaluation {
+            return Err(VerificationError);
+        }
+
+        let mut commitments = vec![*commitment];
+        commitments.extend(proof.folded_polynomials_commitments.iter());
+        vk.verify_multi_points(
+            &commitments,
+            &eval_points,
+            &evaluations,
+            &proof.evaluation_proof,
+            &open_chal,
+        )
+    }
+}
+
+#[test]
+fn test_multilinear_kzg() {
+    use crate::kzg::VerifierKey;
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+    use merlin::Transcript;
+
+    let rng = &mut ark_std::test_rng();
+    let num_vars = 5;
+    let ck = MultilinearKzg::<Bls12_381>::setup(num_vars, rng);
+    let vk = VerifierKey::from(&ck);
+    let polynomial = (0..1 << num_vars)
+        .map(|_| Fr::rand(rng))
+        .collect::<Vec<_>>();
+    let point = (0..num_vars).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+    let commitment = MultilinearKzg::commit(&ck, &polynomial);
+
+    let mut transcript = Transcript::new(b"test");
+    let (evaluation, proof) = MultilinearKzg::open(&ck, &mut transcript, &polynomial, &point);
+    // f(\rho) = \sum_b f_b \prod_j \rho_j^{b_j}
+    let expected = polynomial
+        .iter()
+        .enumerate()
+        .map(|(b, f_b)| {
+            (0..num_vars)
+                .filter(|j| b >> j & 1 == 1)
+                .fold(*f_b, |acc, j| acc * point[j])
+        })
+        .sum::<Fr>();
+    assert_eq!(evaluation, expected);
+
+    let mut transcript = Transcript::new(b"test");
+    assert!(MultilinearKzg::verify(
+        &vk,
+        &mut transcript,
+        &commitment,
+        &point,
+        &evaluation,
+        &proof
+    )
+    .is_ok());
+
+    let mut transcript = Transcript::new(b"test");
+    assert!(MultilinearKzg::verify(
+        &vk,
+        &mut transcript,
+        &commitment,
+        &point,
+        &(evaluation + Fr::one()),
+        &proof
+    )
+    .is_err());
+}
@@ -47,9 +47,7 @@ where
         .zip(cfg_iter_mut!(evaluations_w))
         .for_each(|(eval_point, dst)| *dst = evaluate_be(base_polynomial.iter(), eval_point));
 
-    evaluations_w
-        .iter()
-        .for_each(|e| transcript.append_serializable(b"eval", &e));
+    transcript.append_serializables(b"eval", &evaluations_w);
     evaluations_w
 }
 
@@ -57,6 +55,7 @@ impl<E: Pairing> Proof<E> {
     /// Given as input the _streaming_ R1CS instance `r1cs`
     /// and the _streaming_ committer key `ck`,
     /// return a new _preprocessing_ SNARK using the elastic prover.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(variables = r1cs.z.len(), max_msm_buffer = max_msm_buffer)))]
     pub fn new_elastic<SM, SG, SZ, SW>(
         ck: &CommitterKeyStream<E, SG>,
         r1cs: &R1csStream<SM, SZ, SW>,
@@ -316,7 +315,7 @@ impl<E: Pairing> Proof<E> {
             ),
             &open_chals
         );
-        let ralpha_star_acc_mu_proof = ck.open(&polynomial, &psi, max_msm_buffer).1;
+        let ralpha_star_acc_mu_proof = ck.open_with_buffer(&polynomial, &psi, max_msm_buffer).1;
 
         let ralpha_star_acc_mu_evals = vec![
             evaluate_be(ralpha_star.iter(), &psi),
@@ -337,9 +336,7 @@ impl<E: Pairing> Proof<E> {
         let r_val_chal_a = ip_unsafe(lhs_ralpha_star.iter(), val_a.iter());
         let r_val_chal_b = ip_unsafe(lhs_r_star.iter(), val_b.iter());
 
-        ralpha_star_acc_mu_evals
-            .iter()
-            .for_each(|e| transcript.append_serializable(b"ralpha_star_acc_mu", e));
+        transcript.append_serializables(b"ralpha_star_acc_mu", &ralpha_star_acc_mu_evals);
         transcript.append_serializable(b"ralpha_star_mu_proof", &ralpha_star_acc_mu_proof);
 
         // Add to the list of inner-products claims (obtained from the entry product)
@@ -446,9 +443,7 @@ impl<E: Pairing> Proof<E> {
             .extend(ck.commit_folding(&tensorcheck_foldings_3, max_msm_buffer));
 
         // add commitments to transcript
-        folded_polynomials_commitments
-            .iter()
-            .for_each(|c| transcript.append_serializable(b"commitment", c));
+        transcript.append_serializables(b"commitment", &folded_polynomials_commitments);
 
         let eval_chal = transcript.get_challenge::<E::ScalarField>(b"evaluation-chal");
 
@@ -1,9 +1,11 @@
 //! Elastic preprocessing SNARK for R1CS.
 //!
-//!
-//!
-//!
-//!
+//! The circuit is indexed once via [`Proof::index_verifier_key`],
+//! committing to the sparse representation of the matrices $A, B, C$.
+//! Proofs are then checked with [`Proof::verify_indexed`] against the index commitments only,
+//! with a number of pairings independent of the circuit size.
+//! On the prover side, many statements for the same circuit can be proven with [`Proof::prove_many`],
+//! which preprocesses the matrices once into a [`ProverIndex`].
 mod elastic_prover;
 mod time_prover;
 mod verifier;
@@ -16,7 +18,7 @@ mod tests;
 use ark_ec::pairing::Pairing;
 use ark_std::vec::Vec;
 
-use crate::kzg::{Commitment, EvaluationProof};
+use crate::kzg::{Commitment, EvaluationProof, IndexedCommitterKey, VerifierKey};
 use crate::subprotocols::entryproduct;
 use crate::subprotocols::sumcheck::prover::ProverMsgs;
 use crate::subprotocols::tensorcheck::TensorcheckProof;
@@ -24,8 +26,45 @@ use ark_serialize::*;
 
 pub type Index<E> = Vec<Commitment<E>>;
 
+/// The verifier key for an indexed circuit, produced once by [`Proof::index_verifier_key`].
+///
+/// It contains the commitments to the index polynomials, the sizes of the circuit,
+/// and the verifier key for the polynomial commitment,
+/// so that proofs for any statement can be checked without accessing the matrices.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct IndexVerifierKey<E: Pairing> {
+    /// The commitments to the index polynomials.
+    pub index: Index<E>,
+    /// The number of variables, i.e. the length of \\(\vec z\\).
+    pub num_variables: usize,
+    /// The number of non-zero entries in the joint matrix \\(A + B + C\\).
+    pub num_non_zero: usize,
+    /// The verifier key for the polynomial commitment.
+    pub vk: VerifierKey<E>,
+}
+
+/// The circuit-dependent state of the time-efficient prover, produced once by [`Proof::prover_index`].
+///
+/// It contains the sparse representation of the joint matrix \(A + B + C\),
+/// and the committer keys indexed by its rows, its columns, and the frequencies of their entries,
+/// so that many proofs for the same circuit can be produced with [`Proof::prove_many`]
+/// without preprocessing the matrices again.
+pub struct ProverIndex<E: Pairing> {
+    row: Vec<E::ScalarField>,
+    col: Vec<E::ScalarField>,
+    row_index: Vec<usize>,
+    col_index: Vec<usize>,
+    val_a: Vec<E::ScalarField>,
+    val_b: Vec<E::ScalarField>,
+    val_c: Vec<E::ScalarField>,
+    ck_row: IndexedCommitterKey<E>,
+    ck_col: IndexedCommitterKey<E>,
+    frequency: [Vec<usize>; 2],
+    ck_fre: [IndexedCommitterKey<E>; 2],
+}
+
 /// The preprocessing SNARK proof, containing all prover messages.
-#[derive(CanonicalSerialize, PartialEq, Eq)]
+#[derive(CanonicalSerialize, CanonicalDeserialize, PartialEq, Eq)]
 pub struct Proof<E: Pairing> {
     witness_commitment: Commitment<E>,
     zc_alpha: E::ScalarField,
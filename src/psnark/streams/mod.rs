@@ -6,8 +6,8 @@ mod val_stream;
 
 mod intofield_stream;
 
+pub use crate::iterable::Tensor;
 pub use hadamard_stream::HadamardStreamer;
-pub use tensor_stream::Tensor;
 // XXX. this struct should probably replace TensorStreamer.
 pub use algebraic_hash::AlgebraicHash;
 pub use intofield_stream::IntoField;
@@ -8,6 +8,7 @@ use crate::iterable::Reverse;
 use crate::kzg::{CommitterKey, CommitterKeyStream};
 use crate::misc::product_matrix_vector;
 use ark_std::test_rng;
+use ark_std::vec::Vec;
 use ark_test_curves::bls12_381::{Bls12_381, Fr};
 
 #[test]
@@ -143,3 +144,99 @@ fn test_psnark_correctness() {
 
     assert!(time_proof.verify(&r1cs, &vk, &index, num_non_zero).is_ok())
 }
+
+#[test]
+fn test_psnark_indexed_verifier() {
+    let rng = &mut test_rng();
+    let num_constraints = 1024;
+    let num_variables = 1024;
+
+    let circuit = random_circuit(rng, num_constraints, num_variables);
+    let r1cs = generate_relation(circuit);
+    let num_non_zero = 3 * num_constraints;
+
+    let ck = CommitterKey::<Bls12_381>::new(num_non_zero + num_variables + num_constraints, 5, rng);
+    let ivk = Proof::index_verifier_key(&ck, &r1cs);
+    assert_eq!(ivk.num_variables, r1cs.z.len());
+
+    let time_proof = Proof::new_time(&ck, &r1cs, &ivk.index);
+    assert!(time_proof.verify_indexed(&ivk, &r1cs.x).is_ok());
+
+    let mut wrong_statement = r1cs.x.clone();
+    wrong_statement[1] += wrong_statement[0];
+    assert!(time_proof.verify_indexed(&ivk, &wrong_statement).is_err());
+}
+
+#[test]
+fn test_psnark_bn254() {
+    use super::IndexVerifierKey;
+    use ark_bn254::Bn254;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+    let rng = &mut test_rng();
+    let num_constraints = 1024;
+    let num_variables = 1024;
+
+    let circuit = random_circuit(rng, num_constraints, num_variables);
+    let r1cs = generate_relation(circuit);
+    let num_non_zero = 3 * num_constraints;
+
+    let ck = CommitterKey::<Bn254>::new(num_non_zero + num_variables + num_constraints, 5, rng);
+    let ivk = Proof::index_verifier_key(&ck, &r1cs);
+    let time_proof = Proof::new_time(&ck, &r1cs, &ivk.index);
+    assert!(time_proof.verify_indexed(&ivk, &r1cs.x).is_ok());
+
+    let mut proof_bytes = Vec::new();
+    time_proof.serialize_compressed(&mut proof_bytes).unwrap();
+    let mut ivk_bytes = Vec::new();
+    ivk.serialize_compressed(&mut ivk_bytes).unwrap();
+    let proof = Proof::<Bn254>::deserialize_compressed(proof_bytes.as_slice()).unwrap();
+    let ivk = IndexVerifierKey::<Bn254>::deserialize_compressed(ivk_bytes.as_slice()).unwrap();
+    assert!(proof == time_proof);
+    assert!(proof.verify_indexed(&ivk, &r1cs.x).is_ok());
+}
+
+#[test]
+fn test_psnark_prove_many() {
+    let rng = &mut test_rng();
+    let num_constraints = 256;
+    let num_variables = 256;
+
+    let circuit = random_circuit(rng, num_constraints, num_variables);
+    let r1cs = generate_relation(circuit);
+    let num_non_zero = 3 * num_constraints;
+
+    let ck = CommitterKey::<Bls12_381>::new(num_non_zero + num_variables + num_constraints, 5, rng);
+    let ivk = Proof::index_verifier_key(&ck, &r1cs);
+
+    // the same circuit, with a different assignment.
+    let other_r1cs = generate_relation(random_circuit(rng, num_constraints, num_variables));
+    assert!(other_r1cs.a == r1cs.a && other_r1cs.b == r1cs.b && other_r1cs.c == r1cs.c);
+    assert_ne!(other_r1cs.x, r1cs.x);
+
+    // the proofs sharing the preprocessing are the same as the ones produced one by one.
+    let instances = [&r1cs, &other_r1cs];
+    let assignments = instances
+        .iter()
+        .map(|instance| (instance.x.clone(), instance.w.clone()))
+        .collect::<Vec<_>>();
+    let proofs = Proof::prove_many(&ck, &r1cs, &ivk.index, &assignments);
+    assert_eq!(proofs.len(), 2);
+    for (proof, instance) in proofs.iter().zip(instances) {
+        assert!(*proof == Proof::new_time(&ck, instance, &ivk.index));
+        assert!(proof.verify_indexed(&ivk, &instance.x).is_ok());
+    }
+    assert!(proofs[0].verify_indexed(&ivk, &other_r1cs.x).is_err());
+    assert!(proofs[1].verify_indexed(&ivk, &r1cs.x).is_err());
+}
+
+#[test]
+#[should_panic(expected = "the assignments must match the shape of the circuit")]
+fn test_psnark_prove_many_shape() {
+    let rng = &mut test_rng();
+    let r1cs = generate_relation(random_circuit(rng, 16, 16));
+    let ck = CommitterKey::<Bls12_381>::new(3 * 16 + 16 + 16, 5, rng);
+    let ivk = Proof::index_verifier_key(&ck, &r1cs);
+    let assignments = [(r1cs.x.clone(), r1cs.w[1..].to_vec())];
+    Proof::prove_many(&ck, &r1cs, &ivk.index, &assignments);
+}
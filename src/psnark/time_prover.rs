@@ -3,14 +3,15 @@
 use ark_ec::pairing::Pairing;
 use ark_ff::Field;
 use ark_std::boxed::Box;
+use ark_std::log2;
 use ark_std::vec::Vec;
 use ark_std::One;
 
 use crate::circuit::R1cs;
-use crate::kzg::CommitterKey;
+use crate::kzg::{CommitterKey, VerifierKey};
 use crate::misc::{
     evaluate_le, hadamard, ip, joint_matrices, linear_combination, powers, powers2,
-    product_matrix_vector, sum_matrices, tensor,
+    product_matrix_vector, sum_matrices, tensor, wipe,
 };
 
 use crate::subprotocols::entryproduct::time_prover::{accumulated_product, monic, right_rotation};
@@ -26,7 +27,7 @@ use crate::transcript::GeminiTranscript;
 
 use crate::PROTOCOL_NAME;
 
-use super::{Index, Proof};
+use super::{Index, IndexVerifierKey, Proof, ProverIndex};
 
 fn product3<F: Field>(v: &[Vec<F>; 3]) -> Vec<F> {
     vec![
@@ -63,20 +64,134 @@ impl<E: Pairing> Proof<E> {
         ck.batch_commit(&vec![row, col, val_a, val_b, val_c])
     }
 
+    /// Index the circuit `r1cs` once, and return the verifier key for all its statements.
+    ///
+    /// The number of variables is the length of the assignment `r1cs.z`.
+    pub fn index_verifier_key(
+        ck: &CommitterKey<E>,
+        r1cs: &R1cs<E::ScalarField>,
+    ) -> IndexVerifierKey<E> {
+        let num_variables = r1cs.z.len();
+        let num_non_zero = sum_matrices(&r1cs.a, &r1cs.b, &r1cs.c, num_variables)
+            .iter()
+            .map(|col| col.len())
+            .sum();
+        IndexVerifierKey {
+            index: Self::index(ck, r1cs),
+            num_variables,
+            num_non_zero,
+            vk: VerifierKey::from(ck),
+        }
+    }
+
     /// Given as input the R1CS instance `r1cs`
     /// and the committer key `ck`,
     /// return a new _preprocessing_ SNARK using the elastic prover.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(constraints = r1cs.a.len(), variables = r1cs.z.len())))]
     pub fn new_time(
         ck: &CommitterKey<E>,
         r1cs: &R1cs<E::ScalarField>,
         index: &Index<E>,
     ) -> Proof<E> {
-        let z_a = product_matrix_vector(&r1cs.a, &r1cs.z);
-        let z_b = product_matrix_vector(&r1cs.b, &r1cs.z);
-        let z_c = product_matrix_vector(&r1cs.c, &r1cs.z);
+        let prover_index = Self::prover_index(ck, r1cs);
+        Self::prove_assigned(ck, r1cs, &r1cs.z, &r1cs.w, index, &prover_index)
+    }
+
+    /// Preprocess the circuit `r1cs` for the time-efficient prover, see [`ProverIndex`].
+    ///
+    /// The number of variables is the length of the assignment `r1cs.z`.
+    pub fn prover_index(ck: &CommitterKey<E>, r1cs: &R1cs<E::ScalarField>) -> ProverIndex<E> {
+        let num_constraints = r1cs.a.len();
+        let num_variables = r1cs.z.len();
+
+        let joint_matrix = sum_matrices(&r1cs.a, &r1cs.b, &r1cs.c, num_variables);
+        let (row, col, row_index, col_index, val_a, val_b, val_c) = joint_matrices(
+            &joint_matrix,
+            num_constraints,
+            num_variables,
+            &r1cs.a,
+            &r1cs.b,
+            &r1cs.c,
+        );
+        let [ck_row, ck_col] = ck.index_by_many([&row_index[..], &col_index[..]]);
+
+        // the lookup tables are the tensor of the first sumcheck challenges, and z.
+        let frequency = [
+            compute_frequency(1 << log2(num_constraints), &row_index),
+            compute_frequency(num_variables, &col_index),
+        ];
+        let ext_fre = [
+            extend_frequency(&frequency[0]),
+            extend_frequency(&frequency[1]),
+        ];
+        let ck_fre = ck.index_by_many([&ext_fre[0][..], &ext_fre[1][..]]);
+
+        ProverIndex {
+            row,
+            col,
+            row_index,
+            col_index,
+            val_a,
+            val_b,
+            val_c,
+            ck_row,
+            ck_col,
+            frequency,
+            ck_fre,
+        }
+    }
+
+    /// Given as input the circuit `r1cs`, its index `index`, and many `assignments` to it,
+    /// each a pair of statement \(\vec x\) and witness \(\vec w\),
+    /// return a _preprocessing_ SNARK for each assignment using the time-efficient prover.
+    ///
+    /// The circuit is preprocessed once with [`Proof::prover_index`] for all the proofs,
+    /// rather than once per proof as in [`Proof::new_time`].
+    /// The assignment of `r1cs` itself is ignored.
+    ///
+    /// # Panics
+    /// If any statement or witness in `assignments` differs in length from the ones of `r1cs`.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(constraints = r1cs.a.len(), proofs = assignments.len())))]
+    pub fn prove_many(
+        ck: &CommitterKey<E>,
+        r1cs: &R1cs<E::ScalarField>,
+        index: &Index<E>,
+        assignments: &[(Vec<E::ScalarField>, Vec<E::ScalarField>)],
+    ) -> Vec<Proof<E>> {
+        assert!(
+            assignments
+                .iter()
+                .all(|(x, w)| x.len() == r1cs.x.len() && w.len() == r1cs.w.len()),
+            "the assignments must match the shape of the circuit"
+        );
+        let prover_index = Self::prover_index(ck, r1cs);
+        assignments
+            .iter()
+            .map(|(x, w)| {
+                let z = [x.as_slice(), w.as_slice()].concat();
+                Self::prove_assigned(ck, r1cs, &z, w, index, &prover_index)
+            })
+            .collect()
+    }
+
+    /// The time-efficient prover for the circuit `r1cs` with assignment `z`, of witness `w`,
+    /// preprocessed in `prover_index`.
+    // the tensorcheck takes the base polynomials as references to vectors.
+    #[allow(clippy::ptr_arg)]
+    fn prove_assigned(
+        ck: &CommitterKey<E>,
+        r1cs: &R1cs<E::ScalarField>,
+        z: &Vec<E::ScalarField>,
+        w: &Vec<E::ScalarField>,
+        index: &Index<E>,
+        prover_index: &ProverIndex<E>,
+    ) -> Proof<E> {
+        let mut z_a = product_matrix_vector(&r1cs.a, z);
+        let mut z_b = product_matrix_vector(&r1cs.b, z);
+        let mut z_c = product_matrix_vector(&r1cs.c, z);
         let mut transcript = merlin::Transcript::new(PROTOCOL_NAME);
         let witness_commitment_time = start_timer!(|| "Commitment to w");
-        let witness_commitment = ck.commit(&r1cs.w);
+        let witness_commitment = ck.commit(w);
         end_timer!(witness_commitment_time);
 
         transcript.append_serializable(b"witness", &witness_commitment);
@@ -89,39 +204,39 @@ impl<E: Pairing> Proof<E> {
 
         let first_sumcheck_time = start_timer!(|| "First sumcheck");
         let first_proof = Sumcheck::new_time(&mut transcript, &z_a, &z_b, &alpha);
+        for z in [&mut z_a, &mut z_b, &mut z_c] {
+            wipe(z);
+        }
         end_timer!(first_sumcheck_time);
 
         let b_challenges = tensor(&first_proof.challenges);
         let c_challenges = powers(alpha, b_challenges.len());
         let a_challenges = hadamard(&b_challenges, &c_challenges);
 
-        let num_constraints = r1cs.a.len();
-        let num_variables = r1cs.z.len();
-
-        let joint_matrix = sum_matrices(&r1cs.a, &r1cs.b, &r1cs.c, num_variables);
-        let (row, col, row_index, col_index, val_a, val_b, val_c) = joint_matrices(
-            &joint_matrix,
-            num_constraints,
-            num_variables,
-            &r1cs.a,
-            &r1cs.b,
-            &r1cs.c,
-        );
-
+        let ProverIndex {
+            row,
+            col,
+            row_index,
+            col_index,
+            val_a,
+            val_b,
+            val_c,
+            ck_row,
+            ck_col,
+            frequency,
+            ck_fre,
+        } = prover_index;
         let num_non_zero = row.len();
 
-        let ralpha_star = lookup(&a_challenges, &row_index);
-        let r_star = lookup(&b_challenges, &row_index);
-        let alpha_star = lookup(&c_challenges, &row_index);
-        let z_star = lookup(&r1cs.z, &col_index);
-
-        let ck_row = ck.index_by(&row_index[..]);
-        let ck_col = ck.index_by(&col_index[..]);
+        let ralpha_star = lookup(&a_challenges, row_index);
+        let r_star = lookup(&b_challenges, row_index);
+        let alpha_star = lookup(&c_challenges, row_index);
+        let z_star = lookup(z, col_index);
 
         let z_r_commitments_time = start_timer!(|| "Commitments to z* and r*");
         let mut z_r_commitments =
             ck_row.batch_commit(vec![&a_challenges, &b_challenges, &c_challenges]);
-        z_r_commitments.push(ck_col.commit(&r1cs.z));
+        z_r_commitments.push(ck_col.commit(z));
         // let z_r_commitments = ck.batch_commit(vec![&ralpha_star, &r_star, &alpha_star, &z_star]);
         end_timer!(z_r_commitments_time);
 
@@ -135,9 +250,9 @@ impl<E: Pairing> Proof<E> {
 
         let r_star_val = linear_combination(
             &[
-                hadamard(&ralpha_star, &val_a),
-                hadamard(&r_star, &val_b),
-                hadamard(&alpha_star, &val_c),
+                hadamard(&ralpha_star, val_a),
+                hadamard(&r_star, val_b),
+                hadamard(&alpha_star, val_c),
             ],
             &challenges,
         );
@@ -149,8 +264,7 @@ impl<E: Pairing> Proof<E> {
             &r_star_val,
             &E::ScalarField::one(),
         );
-        let second_challenges = tensor(&second_proof.challenges);
-        let second_challenges_head = &second_challenges[..num_non_zero];
+        let second_challenges_head = &tensor_prefix(&second_proof.challenges, num_non_zero);
         end_timer!(second_sumcheck_time);
 
         let zeta = transcript.get_challenge(b"zeta");
@@ -159,23 +273,16 @@ impl<E: Pairing> Proof<E> {
         let alg_hash_poly = [
             alg_hash(&b_challenges, 0..b_challenges.len(), &zeta),
             alg_hash(&c_challenges, 0..c_challenges.len(), &zeta),
-            alg_hash(&r1cs.z, 0..r1cs.z.len(), &zeta),
-        ];
-        let frequency = [
-            compute_frequency(alg_hash_poly[0].len(), &row_index),
-            compute_frequency(alg_hash_poly[2].len(), &col_index),
+            alg_hash(z, 0..z.len(), &zeta),
         ];
+        debug_assert_eq!(frequency[0].len(), alg_hash_poly[0].len());
+        debug_assert_eq!(frequency[1].len(), alg_hash_poly[2].len());
         let sorted_polynomials = [
             &sorted(&alg_hash_poly[0], &frequency[0]),
             &sorted(&alg_hash_poly[1], &frequency[0]),
             &sorted(&alg_hash_poly[2], &frequency[1]),
         ];
 
-        let ext_fre = [
-            extend_frequency(&frequency[0]),
-            extend_frequency(&frequency[1]),
-        ];
-        let ck_fre = [ck.index_by(&ext_fre[0]), ck.index_by(&ext_fre[1])];
         let mut sorted_commitments =
             ck_fre[0].batch_commit(vec![&alg_hash_poly[0], &alg_hash_poly[1]]);
         sorted_commitments.push(ck_fre[1].commit(&alg_hash_poly[2]));
@@ -190,15 +297,15 @@ impl<E: Pairing> Proof<E> {
         let chi = transcript.get_challenge(b"chi");
 
         // TODO: Make sorted vectors as input to the plookup function.
-        let r_lookup_vec = plookup(&r_star, &b_challenges, &row_index, &gamma, &chi, &zeta);
+        let r_lookup_vec = plookup(&r_star, &b_challenges, row_index, &gamma, &chi, &zeta);
         let r_prod_vec = product3(&r_lookup_vec);
         let r_accumulated_vec = accproduct3(&r_lookup_vec);
 
-        let alpha_lookup_vec = plookup(&alpha_star, &c_challenges, &row_index, &gamma, &chi, &zeta);
+        let alpha_lookup_vec = plookup(&alpha_star, &c_challenges, row_index, &gamma, &chi, &zeta);
         let alpha_prod_vec = product3(&alpha_lookup_vec);
         let alpha_accumulated_vec = accproduct3(&alpha_lookup_vec);
 
-        let z_lookup_vec = plookup(&z_star, &r1cs.z, &col_index, &gamma, &chi, &zeta);
+        let z_lookup_vec = plookup(&z_star, z, col_index, &gamma, &chi, &zeta);
         let z_prod_vec = product3(&z_lookup_vec);
         let z_accumulated_vec = accproduct3(&z_lookup_vec);
 
@@ -248,14 +355,12 @@ impl<E: Pairing> Proof<E> {
             ralpha_star_acc_mu_evals.push(evaluate_le(v, &psi));
         });
 
-        let s_0_prime = ip(&hadamard(&ralpha_star, &val_a), second_challenges_head);
-        let s_1_prime = ip(&hadamard(&r_star, &val_b), second_challenges_head);
-        // let s_2_prime = ip(&hadamard(&alpha_star, &val_c), &second_challenges_head);
+        let s_0_prime = ip(&hadamard(&ralpha_star, val_a), second_challenges_head);
+        let s_1_prime = ip(&hadamard(&r_star, val_b), second_challenges_head);
+        // let s_2_prime = ip(&hadamard(&alpha_star, val_c), &second_challenges_head);
         // transcript.append_serializable(b"r_val_chal_a", &s_0_prime);
         // transcript.append_serializable(b"r_val_chal_b", &s_1_prime);
-        ralpha_star_acc_mu_evals
-            .iter()
-            .for_each(|e| transcript.append_serializable(b"ralpha_star_acc_mu", e));
+        transcript.append_serializables(b"ralpha_star_acc_mu", &ralpha_star_acc_mu_evals);
         transcript.append_serializable(b"ralpha_star_mu_proof", &ralpha_star_acc_mu_proof);
 
         let mut provers = Vec::new();
@@ -263,17 +368,17 @@ impl<E: Pairing> Proof<E> {
 
         provers.push(Box::new(TimeProver::new(Witness::new(
             &hadamard(&ralpha_star, second_challenges_head),
-            &val_a,
+            val_a,
             &E::ScalarField::one(),
         ))));
         provers.push(Box::new(TimeProver::new(Witness::new(
             &hadamard(&r_star, second_challenges_head),
-            &val_b,
+            val_b,
             &E::ScalarField::one(),
         ))));
         provers.push(Box::new(TimeProver::new(Witness::new(
             &hadamard(&alpha_star, second_challenges_head),
-            &val_c,
+            val_c,
             &E::ScalarField::one(),
         ))));
         provers.push(Box::new(TimeProver::new(Witness::new(
@@ -287,16 +392,16 @@ impl<E: Pairing> Proof<E> {
         end_timer!(third_sumcheck_time);
 
         let tc_base_polynomials = [
-            &r1cs.w,
+            w,
             &ralpha_star,
             &r_star,
             &alpha_star,
             &z_star,
-            &row,
-            &col,
-            &val_a,
-            &val_b,
-            &val_c,
+            row,
+            col,
+            val_a,
+            val_b,
+            val_c,
             sorted_polynomials[0],
             sorted_polynomials[1],
             sorted_polynomials[2],
@@ -320,7 +425,7 @@ impl<E: Pairing> Proof<E> {
         let mut third_proof_vec = Vec::new();
 
         third_proof_vec.extend(&shift_monic_lookup_vec);
-        third_proof_vec.extend(&[&val_a, &val_b, &val_c, &alpha_star]);
+        third_proof_vec.extend(&[val_a, val_b, val_c, &alpha_star]);
 
         // third_proof.challenges might be longer than second_proof.challenges because of
         // the batched sumcheck involves entry products polynomials.
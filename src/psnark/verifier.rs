@@ -14,7 +14,7 @@ use crate::subprotocols::sumcheck::Subclaim;
 use crate::transcript::GeminiTranscript;
 use crate::PROTOCOL_NAME;
 
-use super::Index;
+use super::{Index, IndexVerifierKey};
 
 /// Given oracle access to a polynomial $f \in \FF[x]$ and a field element $\psi \in \FF$, returns $(f - \psi)(x)$.
 /// XXX. use for real
@@ -91,6 +91,39 @@ impl<E: Pairing> Proof<E> {
         vk: &VerifierKey<E>,
         index: &Index<E>,
         num_non_zero: usize,
+    ) -> VerificationResult {
+        self.verify_with_index(&r1cs.x, r1cs.z.len(), vk, index, num_non_zero)
+    }
+
+    /// Verification function for Preprocessing SNARK proof,
+    /// given the verifier key `ivk` produced once by the indexer,
+    /// and the statement `public_inputs` (including the constant term and the padding).
+    ///
+    /// The matrices are never accessed:
+    /// the verifier only reads the commitments in the index,
+    /// and performs a constant number of pairings.
+    pub fn verify_indexed(
+        &self,
+        ivk: &IndexVerifierKey<E>,
+        public_inputs: &[E::ScalarField],
+    ) -> VerificationResult {
+        self.verify_with_index(
+            public_inputs,
+            ivk.num_variables,
+            &ivk.vk,
+            &ivk.index,
+            ivk.num_non_zero,
+        )
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(variables = num_variables, public_inputs = public_inputs.len())))]
+    fn verify_with_index(
+        &self,
+        public_inputs: &[E::ScalarField],
+        num_variables: usize,
+        vk: &VerifierKey<E>,
+        index: &Index<E>,
+        num_non_zero: usize,
     ) -> VerificationResult {
         let mut transcript = merlin::Transcript::new(PROTOCOL_NAME);
         let witness_commitment = self.witness_commitment;
@@ -105,11 +138,6 @@ impl<E: Pairing> Proof<E> {
         let first_sumcheck_msgs = &self.first_sumcheck_msgs;
         let subclaim_1 = Subclaim::new(&mut transcript, first_sumcheck_msgs, self.zc_alpha)?;
 
-        /*
-        TODO: num_constraints should be the input.
-        */
-        // let num_constraints = r1cs.a.len();
-        let num_variables = r1cs.z.len();
         self.r_star_commitments
             .iter()
             .zip(vec![b"ra*", b"rb*", b"rc*"].iter())
@@ -195,13 +223,11 @@ impl<E: Pairing> Proof<E> {
             &self.ralpha_star_acc_mu_proof,
             &open_chal,
         )
-        .map_err(|_e| VerificationError)?;
+        .map_err(|_e| VerificationError::PairingCheck)?;
 
         // transcript.append_serializable(b"r_val_chal_a", &self.rstars_vals[0]);
         // transcript.append_serializable(b"r_val_chal_b", &self.rstars_vals[1]);
-        self.ralpha_star_acc_mu_evals
-            .iter()
-            .for_each(|e| transcript.append_serializable(b"ralpha_star_acc_mu", e));
+        transcript.append_serializables(b"ralpha_star_acc_mu", &self.ralpha_star_acc_mu_evals);
         transcript.append_serializable(b"ralpha_star_mu_proof", &self.ralpha_star_acc_mu_proof);
 
         // Verify the third sumcheck
@@ -219,10 +245,10 @@ impl<E: Pairing> Proof<E> {
 
         // Consistency check
         let batch_consistency = transcript.get_challenge::<E::ScalarField>(b"batch_challenge");
-        self.tensorcheck_proof
-            .folded_polynomials_commitments
-            .iter()
-            .for_each(|c| transcript.append_serializable(b"commitment", c));
+        transcript.append_serializables(
+            b"commitment",
+            &self.tensorcheck_proof.folded_polynomials_commitments,
+        );
         let beta = transcript.get_challenge(b"evaluation-chal");
 
         // asserted_res_vec
@@ -406,14 +432,14 @@ impl<E: Pairing> Proof<E> {
         tmp *= batch_consistency;
         //
         // lookup z*
-        let beta_power = E::ScalarField::pow(&beta, &[r1cs.x.len() as u64]);
-        let z_pos = evaluate_le(&r1cs.x, &beta)
+        let beta_power = E::ScalarField::pow(&beta, &[public_inputs.len() as u64]);
+        let z_pos = evaluate_le(public_inputs, &beta)
             + beta_power * self.tensorcheck_proof.base_polynomials_evaluations[0][1];
-        let z_neg = if (r1cs.x.len() & 1) == 0 {
-            evaluate_le(&r1cs.x, &-beta)
+        let z_neg = if (public_inputs.len() & 1) == 0 {
+            evaluate_le(public_inputs, &-beta)
                 + beta_power * self.tensorcheck_proof.base_polynomials_evaluations[0][2]
         } else {
-            evaluate_le(&r1cs.x, &-beta)
+            evaluate_le(public_inputs, &-beta)
                 - beta_power * self.tensorcheck_proof.base_polynomials_evaluations[0][2]
         };
         direct_base_polynomials_evaluations_2[0] += tmp
@@ -535,32 +561,30 @@ impl<E: Pairing> Proof<E> {
 
         let mu_powers2 = powers2(mu, subclaim_3.challenges.len());
         let subclaim_3_chal_leading = &subclaim_3.challenges[0..subclaim_2.challenges.len()];
-        self.tensorcheck_proof
-            .verify(
-                &mut transcript,
-                vk,
-                &[
-                    asserted_res_vec_1,
-                    asserted_res_vec_2,
-                    asserted_res_vec_3,
-                    asserted_res_vec_4,
-                ],
-                &base_polynomials_commitments,
-                &[
-                    direct_base_polynomials_evaluations_1,
-                    direct_base_polynomials_evaluations_2,
-                    direct_base_polynomials_evaluations_3,
-                    direct_base_polynomials_evaluations_4,
-                ],
-                &[
-                    hadamard(&subclaim_3.challenges, &mu_powers2),
-                    subclaim_3.challenges.clone(),
-                    subclaim_2.challenges.clone(),
-                    hadamard(&subclaim_2.challenges, subclaim_3_chal_leading),
-                ],
-                beta,
-                batch_consistency,
-            )
-            .map_err(|_| VerificationError)
+        self.tensorcheck_proof.verify(
+            &mut transcript,
+            vk,
+            &[
+                asserted_res_vec_1,
+                asserted_res_vec_2,
+                asserted_res_vec_3,
+                asserted_res_vec_4,
+            ],
+            &base_polynomials_commitments,
+            &[
+                direct_base_polynomials_evaluations_1,
+                direct_base_polynomials_evaluations_2,
+                direct_base_polynomials_evaluations_3,
+                direct_base_polynomials_evaluations_4,
+            ],
+            &[
+                hadamard(&subclaim_3.challenges, &mu_powers2),
+                subclaim_3.challenges.clone(),
+                subclaim_2.challenges.clone(),
+                hadamard(&subclaim_2.challenges, subclaim_3_chal_leading),
+            ],
+            beta,
+            batch_consistency,
+        )
     }
 }
@@ -0,0 +1,108 @@
+//! Aggregation of the evaluation proofs of many SNARK proofs for the same circuit.
+//!
+//! Each SNARK proof ends with a multi-point evaluation proof for the tensorcheck.
+//! [`Proof::aggregate_evaluation_proofs`] replaces the evaluation proofs of many SNARK proofs
+//! with a single [`AggregatedProof`] of size logarithmic in their number,
+//! using the inner-pairing-product argument of [`AggregationKey`].
+//! [`Proof::verify_aggregated_evaluation_proofs`] then checks the sumchecks and the tensorchecks of each proof,
+//! reading the matrices once as in [`Proof::batch_verify`],
+//! and all evaluation claims with a constant number of pairings.
+//!
+//! # Scope
+//!
+//! Only the evaluation proofs are aggregated, not the SNARK proofs themselves:
+//! the sumcheck messages and the tensorcheck commitments are still sent and checked for each proof,
+//! so that the size of the proofs and the time to verify them remain linear in their number.
+//! An aggregate of logarithmic size would also need to fold the sumchecks and the tensorchecks
+//! of many proofs, which this module does not attempt.
+use ark_ec::pairing::Pairing;
+use ark_std::vec::Vec;
+use merlin::Transcript;
+
+use crate::circuit::R1cs;
+use crate::errors::{VerificationError, VerificationResult};
+use crate::kzg::{
+    AggregatedProof, AggregationKey, AggregationVerifierKey, MultiPointVerifier, VerifierKey,
+};
+use crate::snark::config::transcript_domains;
+use crate::snark::{Proof, ProverConfig};
+use crate::subprotocols::tensorcheck::MultiPointClaim;
+
+/// The domain separator for the aggregation transcript.
+const AGGREGATION_LABEL: &[u8] = b"gemini-aggregation";
+
+/// Return, for each claim, the element \\(F - I\\) and the vanishing polynomial of the evaluation points.
+fn equations<E: Pairing>(
+    vk: &VerifierKey<E>,
+    claims: &[MultiPointClaim<E>],
+) -> Result<Vec<(E::G1, Vec<E::ScalarField>)>, VerificationError> {
+    claims
+        .iter()
+        .map(|claim| {
+            vk.multi_points_equation_coeffs(
+                &claim.commitments,
+                &claim.eval_points,
+                &claim.evaluations,
+                &claim.open_chal,
+            )
+            .map_err(|_| VerificationError::PairingCheck)
+        })
+        .collect()
+}
+
+impl<E: Pairing> Proof<E> {
+    /// Aggregate the evaluation proofs of the SNARK proofs `proofs` for the same circuit `r1cs`,
+    /// each one for the statement and produced with the configuration at the same position
+    /// in `public_inputs` and `configs` respectively.
+    ///
+    /// Fails if any of the sumchecks or tensorchecks in `proofs` is invalid.
+    pub fn aggregate_evaluation_proofs<V: MultiPointVerifier<E>>(
+        proofs: &[Self],
+        r1cs: &R1cs<E::ScalarField>,
+        public_inputs: &[&[E::ScalarField]],
+        vk: &V,
+        configs: &[ProverConfig],
+        ak: &AggregationKey<E>,
+    ) -> Result<AggregatedProof<E>, VerificationError> {
+        if proofs.is_empty() || proofs.len() > ak.max_proofs() {
+            return Err(VerificationError::InvalidInput);
+        }
+        let vk = vk.verifier_key();
+        let domains = transcript_domains(configs);
+        let claims = Self::multi_point_claims(proofs, r1cs, public_inputs, vk, &domains)?;
+        let evaluation_proofs = proofs
+            .iter()
+            .map(|proof| proof.tensorcheck_proof.evaluation_proof.clone())
+            .collect::<Vec<_>>();
+        Ok(ak.aggregate_equations(
+            &mut Transcript::new(AGGREGATION_LABEL),
+            &equations(vk, &claims)?,
+            &evaluation_proofs,
+        ))
+    }
+
+    /// Verify the SNARK proofs `proofs` for the same circuit `r1cs`,
+    /// whose evaluation proofs have been aggregated in `aggregated` with [`Proof::aggregate_evaluation_proofs`].
+    ///
+    /// The evaluation proofs within `proofs` are not read.
+    pub fn verify_aggregated_evaluation_proofs<V: MultiPointVerifier<E>>(
+        proofs: &[Self],
+        r1cs: &R1cs<E::ScalarField>,
+        public_inputs: &[&[E::ScalarField]],
+        vk: &V,
+        configs: &[ProverConfig],
+        avk: &AggregationVerifierKey<E>,
+        aggregated: &AggregatedProof<E>,
+    ) -> VerificationResult {
+        let vk = vk.verifier_key();
+        let domains = transcript_domains(configs);
+        let claims = Self::multi_point_claims(proofs, r1cs, public_inputs, vk, &domains)?;
+        avk.verify_equations(
+            vk,
+            &mut Transcript::new(AGGREGATION_LABEL),
+            &equations(vk, &claims)?,
+            aggregated,
+        )
+        .map_err(|_| VerificationError::PairingCheck)
+    }
+}
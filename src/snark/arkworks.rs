@@ -0,0 +1,130 @@
+//! Bindings for the [`ark_snark::SNARK`] trait.
+//!
+//! [`Gemini`] wraps the time-efficient prover and the verifier of [`Proof`]
+//! so that they can be used interchangeably with any other arkworks SNARK
+//! over circuits described through [`ConstraintSynthesizer`].
+use ark_ec::pairing::Pairing;
+use ark_ff::{One, Zero};
+use ark_relations::r1cs::{
+    ConstraintSynthesizer, ConstraintSystem, OptimizationGoal, SynthesisError, SynthesisMode,
+};
+use ark_serialize::*;
+use ark_snark::SNARK;
+use ark_std::marker::PhantomData;
+use ark_std::rand::{CryptoRng, RngCore};
+use ark_std::vec::Vec;
+
+use crate::circuit::{pad_input_for_indexer_and_prover, try_generate_relation, R1cs};
+use crate::kzg::{CommitterKey, VerifierKey};
+use crate::snark::Proof;
+
+/// The Gemini non-preprocessing SNARK, as an implementor of [`ark_snark::SNARK`].
+pub struct Gemini<E: Pairing>(PhantomData<E>);
+
+/// The proving key: the committer key for the witness and the intermediate polynomials.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ProvingKey<E: Pairing> {
+    /// The committer key.
+    pub ck: CommitterKey<E>,
+}
+
+/// The verifying key: the R1CS matrices of the circuit, together with the verifier key for KZG.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VerifyingKey<E: Pairing> {
+    /// The circuit description, without any assignment.
+    pub circuit: R1cs<E::ScalarField>,
+    /// The number of instance variables, including the constant and the padding.
+    pub num_instance_variables: usize,
+    /// The verifier key.
+    pub vk: VerifierKey<E>,
+}
+
+impl<E: Pairing> SNARK<E::ScalarField> for Gemini<E> {
+    type ProvingKey = ProvingKey<E>;
+    type VerifyingKey = VerifyingKey<E>;
+    type ProcessedVerifyingKey = VerifyingKey<E>;
+    type Proof = Proof<E>;
+    type Error = SynthesisError;
+
+    fn circuit_specific_setup<C: ConstraintSynthesizer<E::ScalarField>, R: RngCore + CryptoRng>(
+        circuit: C,
+        rng: &mut R,
+    ) -> Result<(Self::ProvingKey, Self::VerifyingKey), Self::Error> {
+        // synthesize the circuit exactly as the prover does in `try_generate_relation`,
+        // so that the matrices of the indexer and of the prover coincide.
+        let cs = ConstraintSystem::new_ref();
+        cs.set_optimization_goal(OptimizationGoal::Weight);
+        cs.set_mode(SynthesisMode::Setup);
+        circuit.generate_constraints(cs.clone())?;
+        pad_input_for_indexer_and_prover(cs.clone());
+        cs.finalize();
+        let matrices = cs.to_matrices().ok_or(SynthesisError::MissingCS)?;
+
+        let num_variables = matrices.num_instance_variables + matrices.num_witness_variables;
+        let max_degree = usize::max(num_variables, matrices.num_constraints).next_power_of_two();
+        let ck = CommitterKey::new(2 * max_degree, 3, rng);
+        let vk = VerifierKey::from(&ck);
+        let circuit_vk = VerifyingKey {
+            circuit: R1cs::from_matrices(matrices.a, matrices.b, matrices.c),
+            num_instance_variables: matrices.num_instance_variables,
+            vk,
+        };
+        Ok((ProvingKey { ck }, circuit_vk))
+    }
+
+    fn prove<C: ConstraintSynthesizer<E::ScalarField>, R: RngCore + CryptoRng>(
+        circuit_pk: &Self::ProvingKey,
+        circuit: C,
+        _rng: &mut R,
+    ) -> Result<Self::Proof, Self::Error> {
+        let r1cs = try_generate_relation(circuit)?;
+        Ok(Proof::new_time(&r1cs, &circuit_pk.ck))
+    }
+
+    fn process_vk(
+        circuit_vk: &Self::VerifyingKey,
+    ) -> Result<Self::ProcessedVerifyingKey, Self::Error> {
+        Ok(circuit_vk.clone())
+    }
+
+    fn verify_with_processed_vk(
+        circuit_pvk: &Self::ProcessedVerifyingKey,
+        public_input: &[E::ScalarField],
+        proof: &Self::Proof,
+    ) -> Result<bool, Self::Error> {
+        // the statement is the constant 1, followed by the public input and the padding.
+        if public_input.len() >= circuit_pvk.num_instance_variables {
+            return Ok(false);
+        }
+        let mut x = Vec::with_capacity(circuit_pvk.num_instance_variables);
+        x.push(E::ScalarField::one());
+        x.extend_from_slice(public_input);
+        x.resize(circuit_pvk.num_instance_variables, E::ScalarField::zero());
+        Ok(proof
+            .verify(&circuit_pvk.circuit, &x, &circuit_pvk.vk)
+            .is_ok())
+    }
+}
+
+#[test]
+fn test_snark_trait() {
+    use crate::circuit::random_circuit;
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+
+    let rng = &mut ark_std::test_rng();
+    let num_constraints = 8;
+    let num_variables = 8;
+
+    let circuit = random_circuit::<Fr>(rng, num_constraints, num_variables);
+    let statement = try_generate_relation(circuit).unwrap().x;
+    let (pk, vk) = Gemini::<Bls12_381>::circuit_specific_setup(circuit, rng).unwrap();
+    let proof = Gemini::<Bls12_381>::prove(&pk, circuit, rng).unwrap();
+
+    let public_input = &statement[1..];
+    assert!(Gemini::<Bls12_381>::verify(&vk, public_input, &proof).unwrap());
+
+    let mut wrong_input = public_input.to_vec();
+    wrong_input[0] += Fr::rand(rng);
+    assert!(!Gemini::<Bls12_381>::verify(&vk, &wrong_input, &proof).unwrap());
+}
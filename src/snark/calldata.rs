@@ -0,0 +1,185 @@
+//! Encoding of SNARK proofs as calldata for the Ethereum Virtual Machine.
+//!
+//! Every element is encoded in big-endian 32-byte words, as expected by the precompiled contracts for
+//! elliptic-curve arithmetic:
+//! field elements are left-padded to a multiple of 32 bytes
+//! (32 bytes for BN254, as in [EIP-196](https://eips.ethereum.org/EIPS/eip-196),
+//! 64 bytes for BLS12-381, as in [EIP-2537](https://eips.ethereum.org/EIPS/eip-2537)),
+//! points of \\(\GG_1\\) are encoded as the pair of their affine coordinates,
+//! with the point at infinity encoded as \\((0, 0)\\),
+//! and vectors are preceded by their length in a single word.
+//!
+//! # Scope
+//!
+//! This module only encodes proofs: it does not generate a Solidity or Yul verifier contract,
+//! and proofs cannot be verified on Ethereum yet.
+//! The encoding fixes the layout an on-chain verifier would read,
+//! so that it can be consumed by a contract written against it.
+//! The Fiat–Shamir challenges of the SNARK are derived from a [`merlin`] transcript,
+//! whose STROBE construction over Keccak-f\[1600\] cannot be replayed
+//! with the `keccak256` opcode, and re-implementing the permutation on-chain is prohibitively expensive.
+//! An on-chain verifier first requires the provers to support an EVM-friendly transcript.
+use ark_ec::pairing::Pairing;
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{Field, PrimeField, Zero};
+use ark_serialize::CanonicalSerialize;
+use ark_std::vec::Vec;
+
+use crate::kzg::{Commitment, EvaluationProof};
+use crate::snark::Proof;
+use crate::subprotocols::sumcheck::prover::ProverMsgs;
+
+/// The size of an EVM word, in bytes.
+pub const WORD_SIZE: usize = 32;
+
+/// Append to `calldata` the encoding of the prime field element `element`.
+fn encode_prime_field<F: PrimeField>(calldata: &mut Vec<u8>, element: &F) {
+    let mut bytes = Vec::new();
+    element
+        .serialize_uncompressed(&mut bytes)
+        .expect("serialization into a vector cannot fail");
+    bytes.reverse();
+    let padding = (WORD_SIZE - bytes.len() % WORD_SIZE) % WORD_SIZE;
+    calldata.extend(ark_std::iter::repeat(0u8).take(padding));
+    calldata.extend(bytes);
+}
+
+/// Append to `calldata` the encoding of the field element `element`,
+/// as the sequence of its coordinates over the base prime field.
+pub fn encode_field<F: Field>(calldata: &mut Vec<u8>, element: &F) {
+    element
+        .to_base_prime_field_elements()
+        .for_each(|coordinate| encode_prime_field(calldata, &coordinate));
+}
+
+/// Append to `calldata` the encoding of the point `point` as the pair of its affine coordinates.
+pub fn encode_point<G: AffineRepr>(calldata: &mut Vec<u8>, point: &G) {
+    match point.xy() {
+        Some((x, y)) => {
+            encode_field(calldata, x);
+            encode_field(calldata, y);
+        }
+        None => {
+            encode_field(calldata, &G::BaseField::zero());
+            encode_field(calldata, &G::BaseField::zero());
+        }
+    }
+}
+
+/// Append to `calldata` the length `len` in a single word.
+fn encode_len(calldata: &mut Vec<u8>, len: usize) {
+    calldata.extend([0u8; WORD_SIZE - 8]);
+    calldata.extend((len as u64).to_be_bytes());
+}
+
+fn encode_commitment<E: Pairing>(calldata: &mut Vec<u8>, commitment: &Commitment<E>) {
+    encode_point(calldata, &commitment.0.into_affine());
+}
+
+fn encode_evaluation_proof<E: Pairing>(calldata: &mut Vec<u8>, proof: &EvaluationProof<E>) {
+    encode_point(calldata, &proof.0.into_affine());
+}
+
+fn encode_sumcheck<F: Field>(calldata: &mut Vec<u8>, msgs: &ProverMsgs<F>) {
+    let ProverMsgs(messages, final_foldings) = msgs;
+    encode_len(calldata, messages.len());
+    for message in messages {
+        encode_field(calldata, &message.0);
+        encode_field(calldata, &message.1);
+    }
+    encode_len(calldata, final_foldings.len());
+    for element in final_foldings.iter().flatten() {
+        encode_field(calldata, element);
+    }
+}
+
+impl<E: Pairing> Proof<E> {
+    /// Return the encoding of the proof, followed by the statement `public_inputs`, as EVM calldata.
+    ///
+    /// Elements are encoded in the order they are sent by the prover throughout the protocol.
+    /// No verifier contract for this calldata is provided, see [`calldata`](crate::snark::calldata).
+    pub fn to_calldata(&self, public_inputs: &[E::ScalarField]) -> Vec<u8> {
+        let mut calldata = Vec::new();
+        encode_commitment(&mut calldata, &self.witness_commitment);
+        encode_field(&mut calldata, &self.zc_alpha);
+        encode_sumcheck(&mut calldata, &self.first_sumcheck_msgs);
+        encode_sumcheck(&mut calldata, &self.second_sumcheck_msgs);
+
+        let tensorcheck_proof = &self.tensorcheck_proof;
+        encode_len(
+            &mut calldata,
+            tensorcheck_proof.folded_polynomials_commitments.len(),
+        );
+        for commitment in &tensorcheck_proof.folded_polynomials_commitments {
+            encode_commitment(&mut calldata, commitment);
+        }
+        encode_len(
+            &mut calldata,
+            tensorcheck_proof.folded_polynomials_evaluations.len(),
+        );
+        for element in tensorcheck_proof
+            .folded_polynomials_evaluations
+            .iter()
+            .flatten()
+        {
+            encode_field(&mut calldata, element);
+        }
+        encode_len(
+            &mut calldata,
+            tensorcheck_proof.base_polynomials_evaluations.len(),
+        );
+        for element in tensorcheck_proof
+            .base_polynomials_evaluations
+            .iter()
+            .flatten()
+        {
+            encode_field(&mut calldata, element);
+        }
+        encode_evaluation_proof(&mut calldata, &tensorcheck_proof.evaluation_proof);
+
+        encode_len(&mut calldata, public_inputs.len());
+        for element in public_inputs {
+            encode_field(&mut calldata, element);
+        }
+        calldata
+    }
+}
+
+#[test]
+fn test_calldata_encoding() {
+    use crate::circuit::{generate_relation, random_circuit};
+    use crate::kzg::CommitterKey;
+    use ark_ff::One;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr, G1Affine};
+
+    let rng = &mut ark_std::test_rng();
+
+    // scalars take a single word, big-endian.
+    let mut calldata = Vec::new();
+    encode_field(&mut calldata, &Fr::one());
+    assert_eq!(calldata.len(), WORD_SIZE);
+    assert_eq!(calldata[WORD_SIZE - 1], 1);
+    assert!(calldata[..WORD_SIZE - 1].iter().all(|&b| b == 0));
+
+    // points over a 381-bit field take two words per coordinate.
+    let mut calldata = Vec::new();
+    encode_point(&mut calldata, &G1Affine::generator());
+    assert_eq!(calldata.len(), 4 * WORD_SIZE);
+    let mut calldata = Vec::new();
+    encode_point(&mut calldata, &G1Affine::zero());
+    assert!(calldata.iter().all(|&b| b == 0));
+
+    let r1cs = generate_relation(random_circuit(rng, 8, 8));
+    let ck = CommitterKey::<Bls12_381>::new(16, 5, rng);
+    let proof = Proof::new_time(&r1cs, &ck);
+    let calldata = proof.to_calldata(&r1cs.x);
+    assert_eq!(calldata.len() % WORD_SIZE, 0);
+
+    // the statement closes the calldata.
+    let statement_words = 1 + r1cs.x.len();
+    let offset = calldata.len() - statement_words * WORD_SIZE;
+    let mut expected = Vec::new();
+    encode_len(&mut expected, r1cs.x.len());
+    r1cs.x.iter().for_each(|x| encode_field(&mut expected, x));
+    assert_eq!(&calldata[offset..], &expected[..]);
+}
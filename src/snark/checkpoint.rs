@@ -0,0 +1,59 @@
+//! Checkpoints of the elastic prover.
+//!
+//! Proving huge instances with the elastic prover may take hours.
+//! [`Proof::new_elastic_resumable`](crate::snark::Proof::new_elastic_resumable)
+//! hands out a [`Checkpoint`] after each of its phases: the commitment to the witness,
+//! the first sumcheck, and the second sumcheck.
+//! Checkpoints can be serialized to disk, and proving resumed from the last phase completed
+//! after a crash or a preemption.
+//!
+//! A checkpoint holds the prover's messages only:
+//! the transcript is replayed from them upon resuming,
+//! and every phase reads the streams from their beginning.
+//!
+//! Checkpoints are deliberately taken at phase boundaries only, not after each sumcheck round,
+//! and the tensorcheck, the last phase, is never checkpointed:
+//! resuming a sumcheck mid-way would require saving the folded streams of the elastic prover,
+//! which are as large as the instance.
+//! A crash in the middle of a phase loses all the work done in that phase,
+//! which is computed again from its first round upon resuming.
+use ark_ec::pairing::Pairing;
+use ark_serialize::*;
+
+use crate::kzg::Commitment;
+use crate::subprotocols::sumcheck::prover::ProverMsgs;
+
+/// The messages sent by the elastic prover in the phases completed so far.
+///
+/// A checkpoint is bound to the instance it was produced for:
+/// resuming it with a different instance or committer key leads to an invalid proof.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Checkpoint<E: Pairing> {
+    pub(crate) witness_commitment: Option<Commitment<E>>,
+    pub(crate) first_sumcheck: Option<(E::ScalarField, ProverMsgs<E::ScalarField>)>,
+    pub(crate) second_sumcheck_msgs: Option<ProverMsgs<E::ScalarField>>,
+}
+
+impl<E: Pairing> Checkpoint<E> {
+    /// Create an empty checkpoint, from which proving starts over.
+    pub fn new() -> Self {
+        Checkpoint {
+            witness_commitment: None,
+            first_sumcheck: None,
+            second_sumcheck_msgs: None,
+        }
+    }
+
+    /// Return the number of phases completed.
+    pub fn completed_phases(&self) -> usize {
+        self.witness_commitment.is_some() as usize
+            + self.first_sumcheck.is_some() as usize
+            + self.second_sumcheck_msgs.is_some() as usize
+    }
+}
+
+impl<E: Pairing> Default for Checkpoint<E> {
+    fn default() -> Self {
+        Checkpoint::new()
+    }
+}
@@ -0,0 +1,232 @@
+//! Configuration of the SNARK provers.
+//!
+//! A [`ProverConfig`] collects the knobs of the time-efficient prover [`Proof::new_time_with_config`]
+//! and of the space-efficient, elastic prover [`Proof::new_elastic_with_config`].
+//! The defaults reproduce the behavior of [`Proof::new_time`] and [`Proof::new_elastic`].
+//! Invalid settings are reported as a [`ConfigError`],
+//! either when building the configuration or when running a prover that does not support them.
+//!
+//! [`Proof::new_time_with_config`]: crate::snark::Proof::new_time_with_config
+//! [`Proof::new_elastic_with_config`]: crate::snark::Proof::new_elastic_with_config
+//! [`Proof::new_time`]: crate::snark::Proof::new_time
+//! [`Proof::new_elastic`]: crate::snark::Proof::new_elastic
+use ark_ec::pairing::Pairing;
+use ark_std::fmt;
+use ark_std::mem::size_of;
+use ark_std::vec::Vec;
+
+use crate::kzg::SpaceProverConfig;
+use crate::transcript::TranscriptDomain;
+
+#[cfg(feature = "parallel")]
+use std::sync::Arc;
+
+/// The default memory budget for the buffers of the elastic prover, in bytes.
+pub const DEFAULT_MEMORY_BUDGET: usize = 1 << 30;
+
+/// Error type denoting an invalid configuration of the SNARK provers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigError {
+    /// The prover has been configured to run on zero threads.
+    NoThreads,
+    /// The streams have been configured to be read in empty chunks.
+    EmptyChunks,
+    /// The thread pool of the prover could not be built.
+    ThreadPool,
+    /// The elastic prover does not support the given option.
+    UnsupportedByElasticProver(&'static str),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoThreads => write!(f, "The prover needs at least one thread."),
+            Self::EmptyChunks => write!(f, "The streams must be read in non-empty chunks."),
+            Self::ThreadPool => write!(f, "Unable to build the prover thread pool."),
+            Self::UnsupportedByElasticProver(option) => {
+                write!(f, "The elastic prover does not support {}.", option)
+            }
+        }
+    }
+}
+
+/// Configuration of the SNARK provers.
+///
+/// The verifier must be configured with the same transcript domain,
+/// see [`Proof::verify_with_config`](crate::snark::Proof::verify_with_config).
+///
+/// The thread pool of the prover is built once, by [`ProverConfig::with_num_threads`],
+/// and shared by the clones of the configuration.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct ProverConfig {
+    /// The number of threads used by the prover, or `None` for the global thread pool.
+    num_threads: Option<usize>,
+    /// The thread pool with `num_threads` threads.
+    #[cfg(feature = "parallel")]
+    thread_pool: Option<Arc<rayon::ThreadPool>>,
+    /// The memory, in bytes, allotted to the multi-scalar multiplication buffers of the elastic prover.
+    pub memory_budget: usize,
+    /// Whether the witness is committed with fixed windows, see [`FixedWindowMsm`](crate::kzg::FixedWindowMsm).
+    /// This stops the prover from skipping the zero scalars and digits of the witness,
+    /// but is not a constant-time guarantee.
+    /// Only supported by the time-efficient prover.
+    pub fixed_window_commit: bool,
+    /// The number of elements read at once from the streams of the elastic prover.
+    chunk_size: usize,
+    /// The domain separation of the Fiat–Shamir transcript.
+    pub transcript_domain: TranscriptDomain,
+}
+
+impl ProverConfig {
+    /// Create a new configuration, with the same behavior as the default provers.
+    pub fn new() -> Self {
+        ProverConfig {
+            num_threads: None,
+            #[cfg(feature = "parallel")]
+            thread_pool: None,
+            memory_budget: DEFAULT_MEMORY_BUDGET,
+            fixed_window_commit: false,
+            chunk_size: SpaceProverConfig::default().chunk_size,
+            transcript_domain: TranscriptDomain::default(),
+        }
+    }
+
+    /// Run the prover on `num_threads` threads, building their thread pool.
+    /// Without the feature `parallel`, the prover runs on the current thread regardless.
+    pub fn with_num_threads(mut self, num_threads: usize) -> Result<Self, ConfigError> {
+        if num_threads == 0 {
+            return Err(ConfigError::NoThreads);
+        }
+        #[cfg(feature = "parallel")]
+        {
+            let thread_pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(num_threads)
+                .build()
+                .map_err(|_| ConfigError::ThreadPool)?;
+            self.thread_pool = Some(Arc::new(thread_pool));
+        }
+        self.num_threads = Some(num_threads);
+        Ok(self)
+    }
+
+    /// Return the number of threads used by the prover, or `None` for the global thread pool.
+    pub fn num_threads(&self) -> Option<usize> {
+        self.num_threads
+    }
+
+    /// Limit the buffers of the elastic prover to `memory_budget` bytes.
+    pub fn with_memory_budget(mut self, memory_budget: usize) -> Self {
+        self.memory_budget = memory_budget;
+        self
+    }
+
+    /// Enable or disable the fixed-window commitment to the witness.
+    ///
+    /// This does not change the proof, only the time to compute it.
+    pub fn with_fixed_window_commit(mut self, fixed_window_commit: bool) -> Self {
+        self.fixed_window_commit = fixed_window_commit;
+        self
+    }
+
+    /// Read the streams in chunks of `chunk_size` elements.
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Result<Self, ConfigError> {
+        if chunk_size == 0 {
+            return Err(ConfigError::EmptyChunks);
+        }
+        self.chunk_size = chunk_size;
+        Ok(self)
+    }
+
+    /// Return the number of elements read at once from the streams of the elastic prover.
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// Use `transcript_label` as domain separator for the Fiat–Shamir transcript.
+    pub fn with_transcript_label(mut self, transcript_label: &'static [u8]) -> Self {
+        self.transcript_domain = self.transcript_domain.with_label(transcript_label);
+        self
+    }
+
+    /// Bind the Fiat–Shamir transcript to the application-level domain separator `application`,
+    /// so that proofs are not valid across deployments.
+    pub fn with_domain_separator(mut self, application: &[u8]) -> Self {
+        self.transcript_domain = self.transcript_domain.with_application(application);
+        self
+    }
+
+    /// Bind the Fiat–Shamir transcript to the session identifier `session`.
+    pub fn with_session_id(mut self, session: &[u8]) -> Self {
+        self.transcript_domain = self.transcript_domain.with_session(session);
+        self
+    }
+
+    /// Return the number of base–scalar pairs fitting in the memory budget,
+    /// used as buffer size for the multi-scalar multiplications.
+    pub(crate) fn msm_buffer<E: Pairing>(&self) -> usize {
+        let element_size = size_of::<E::G1Affine>() + size_of::<E::ScalarField>();
+        usize::max(self.memory_budget / element_size, 1)
+    }
+
+    /// Return an error if the elastic prover does not support the configuration.
+    pub(crate) fn check_elastic(&self) -> Result<(), ConfigError> {
+        if self.fixed_window_commit {
+            Err(ConfigError::UnsupportedByElasticProver(
+                "fixed-window commitments",
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Run `op` within the thread pool of the configuration.
+    #[cfg(feature = "parallel")]
+    pub(crate) fn install<R, OP>(&self, op: OP) -> R
+    where
+        R: Send,
+        OP: FnOnce() -> R + Send,
+    {
+        match &self.thread_pool {
+            Some(thread_pool) => thread_pool.install(op),
+            None => op(),
+        }
+    }
+
+    /// Run `op` within a thread pool with the configured number of threads.
+    #[cfg(not(feature = "parallel"))]
+    pub(crate) fn install<R, OP>(&self, op: OP) -> R
+    where
+        R: Send,
+        OP: FnOnce() -> R + Send,
+    {
+        op()
+    }
+}
+
+/// Return the transcript domain of each configuration in `configs`.
+pub(crate) fn transcript_domains(configs: &[ProverConfig]) -> Vec<&TranscriptDomain> {
+    configs
+        .iter()
+        .map(|config| &config.transcript_domain)
+        .collect()
+}
+
+impl Default for ProverConfig {
+    fn default() -> Self {
+        ProverConfig::new()
+    }
+}
+
+/// Two configurations are equal if they have the same settings, regardless of their thread pools.
+impl PartialEq for ProverConfig {
+    fn eq(&self, other: &Self) -> bool {
+        self.num_threads == other.num_threads
+            && self.memory_budget == other.memory_budget
+            && self.fixed_window_commit == other.fixed_window_commit
+            && self.chunk_size == other.chunk_size
+            && self.transcript_domain == other.transcript_domain
+    }
+}
+
+impl Eq for ProverConfig {}
@@ -0,0 +1,198 @@
+//! Cost estimates for the SNARK provers.
+//!
+//! [`Proof::estimate`] predicts, from the shape of an R1CS instance alone,
+//! the size of the proof and the resources required by the time-efficient and by the elastic prover,
+//! so that the prover and the machine can be chosen before proving.
+//! The proof size is exact, the other figures are estimates up to small constant factors.
+use ark_ec::pairing::Pairing;
+use ark_ff::Zero;
+use ark_serialize::CanonicalSerialize;
+use ark_std::log2;
+use ark_std::mem::size_of;
+use ark_std::vec::Vec;
+
+use crate::circuit::R1cs;
+use crate::kzg::{Commitment, EvaluationProof};
+use crate::snark::Proof;
+use crate::subprotocols::sumcheck::prover::{ProverMsgs, RoundMsg};
+use crate::subprotocols::tensorcheck::TensorcheckProof;
+use crate::SPACE_TIME_THRESHOLD;
+
+/// The prover whose cost is estimated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProverMode {
+    /// The time-efficient prover, see [`Proof::new_time`].
+    Time,
+    /// The elastic prover with multi-scalar multiplication buffers of `max_msm_buffer` elements,
+    /// see [`Proof::new_elastic`].
+    Elastic {
+        /// The size of the multi-scalar multiplication buffers.
+        max_msm_buffer: usize,
+    },
+}
+
+/// The estimated cost of producing a SNARK proof.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CostReport {
+    /// The size of the compressed proof, in bytes.
+    pub proof_bytes: usize,
+    /// The peak memory of the prover, in bytes, excluding the instance and the committer key.
+    pub peak_memory: usize,
+    /// The number of multi-scalar multiplications.
+    pub msm_count: usize,
+    /// The number of field multiplications, excluding the ones within multi-scalar multiplications.
+    pub field_ops: usize,
+}
+
+impl<E: Pairing> Proof<E> {
+    /// Estimate the cost of proving the R1CS instance `r1cs` with the prover `mode`.
+    ///
+    /// Only the shape of `r1cs` is read: the number of constraints, variables, and non-zero entries.
+    pub fn estimate(r1cs: &R1cs<E::ScalarField>, mode: ProverMode) -> CostReport {
+        let num_constraints = r1cs.a.len();
+        let num_variables = r1cs.z.len();
+        let num_non_zero = [&r1cs.a, &r1cs.b, &r1cs.c]
+            .iter()
+            .flat_map(|matrix| matrix.iter())
+            .map(|row| row.len())
+            .sum::<usize>();
+        let first_rounds = log2(num_constraints) as usize;
+        let second_rounds = log2(num_variables) as usize;
+        let num_foldings = second_rounds.saturating_sub(1);
+
+        let field_size = size_of::<E::ScalarField>();
+        let proof_bytes = proof_shape::<E>(first_rounds, second_rounds).compressed_size();
+        // commitment to the witness, to the foldings, and the opening proofs.
+        let commitments = 1 + num_foldings;
+
+        // matrix-vector products, sumchecks (messages and foldings),
+        // the linear combination, and the evaluations of the foldings.
+        let sumchecks_ops = 5 * (num_constraints + num_variables);
+        let tensorcheck_ops = 6 * num_variables;
+
+        match mode {
+            ProverMode::Time => {
+                // z_a, z_b, z_c, the tensored challenges, the sumcheck witnesses,
+                // and the foldings of the tensorcheck.
+                let elements = 3 * num_constraints
+                    + 3 * (1 << first_rounds)
+                    + 2 * num_constraints
+                    + 6 * num_variables
+                    + 2 * num_variables;
+                CostReport {
+                    proof_bytes,
+                    peak_memory: elements * field_size,
+                    msm_count: commitments + 1,
+                    field_ops: 6 * num_non_zero + sumchecks_ops + tensorcheck_ops,
+                }
+            }
+            ProverMode::Elastic { max_msm_buffer } => {
+                // the sumcheck provers switch to the time-efficient prover
+                // in the last rounds, and each space-efficient round streams the instance again.
+                let time_rounds = usize::min(SPACE_TIME_THRESHOLD, second_rounds);
+                let space_rounds = second_rounds - time_rounds;
+                let time_elements = 4 * (1 << time_rounds);
+                let msm_elements = 2 * max_msm_buffer;
+                let peak_memory = time_elements * field_size
+                    + msm_elements * (size_of::<E::G1Affine>() + field_size);
+                CostReport {
+                    proof_bytes,
+                    peak_memory,
+                    msm_count: commitments + 2,
+                    field_ops: (space_rounds + 2) * 3 * num_non_zero
+                        + sumchecks_ops
+                        + tensorcheck_ops,
+                }
+            }
+        }
+    }
+}
+
+/// Return a proof with `first_rounds` rounds in the first sumcheck
+/// and `second_rounds` in the second one, with all elements set to zero.
+fn proof_shape<E: Pairing>(first_rounds: usize, second_rounds: usize) -> Proof<E> {
+    let zero = E::ScalarField::zero();
+    let sumcheck_msgs = |rounds| ProverMsgs(vec![RoundMsg(zero, zero); rounds], vec![[zero; 2]]);
+    let num_foldings = second_rounds.saturating_sub(1);
+    Proof {
+        witness_commitment: Commitment(E::G1::zero()),
+        zc_alpha: zero,
+        first_sumcheck_msgs: sumcheck_msgs(first_rounds),
+        second_sumcheck_msgs: sumcheck_msgs(second_rounds),
+        tensorcheck_proof: TensorcheckProof {
+            folded_polynomials_commitments: vec![Commitment(E::G1::zero()); num_foldings],
+            folded_polynomials_evaluations: vec![[zero; 2]; num_foldings],
+            evaluation_proof: EvaluationProof(E::G1::zero()),
+            base_polynomials_evaluations: Vec::from([[zero; 3]]),
+        },
+    }
+}
+
+#[test]
+fn test_estimate() {
+    use crate::circuit::{generate_relation, random_circuit};
+    use crate::kzg::CommitterKey;
+    use ark_test_curves::bls12_381::Bls12_381;
+
+    let rng = &mut ark_std::test_rng();
+    let num_constraints = 16;
+    let num_variables = 16;
+
+    let circuit = random_circuit(rng, num_constraints, num_variables);
+    let r1cs = generate_relation(circuit);
+    let ck = CommitterKey::<Bls12_381>::new(num_constraints + num_variables, 5, rng);
+    let proof = Proof::new_time(&r1cs, &ck);
+
+    let time_report = Proof::<Bls12_381>::estimate(&r1cs, ProverMode::Time);
+    let elastic_report = Proof::<Bls12_381>::estimate(
+        &r1cs,
+        ProverMode::Elastic {
+            max_msm_buffer: 1 << 4,
+        },
+    );
+    assert_eq!(time_report.proof_bytes, proof.compressed_size());
+    assert_eq!(elastic_report.proof_bytes, proof.compressed_size());
+    // one commitment to the witness and to each folding, and the opening proof;
+    // the elastic prover opens the witness on its own.
+    let commitments = 1 + proof.tensorcheck_proof.folded_polynomials_commitments.len();
+    assert_eq!(time_report.msm_count, commitments + 1);
+    assert_eq!(elastic_report.msm_count, commitments + 2);
+}
+
+#[test]
+fn test_estimate_known_instance() {
+    use ark_test_curves::bls12_381::{Bls12_381, Fr, G1Affine};
+
+    // 16 constraints over 32 variables, with one non-zero entry per row of each matrix.
+    let matrix = (0..16)
+        .map(|i| vec![(Fr::from(1u64), i)])
+        .collect::<Vec<_>>();
+    let mut r1cs = R1cs::from_matrices(matrix.clone(), matrix.clone(), matrix);
+    r1cs.z = vec![Fr::zero(); 32];
+
+    // 4 rounds in the first sumcheck, 5 in the second one, and 4 foldings.
+    let field_size = size_of::<Fr>();
+    assert_eq!(
+        Proof::<Bls12_381>::estimate(&r1cs, ProverMode::Time),
+        CostReport {
+            proof_bytes: proof_shape::<Bls12_381>(4, 5).compressed_size(),
+            peak_memory: 384 * field_size,
+            msm_count: 6,
+            field_ops: 720,
+        }
+    );
+    assert_eq!(
+        Proof::<Bls12_381>::estimate(
+            &r1cs,
+            ProverMode::Elastic {
+                max_msm_buffer: 1 << 4
+            }
+        ),
+        CostReport {
+            proof_bytes: proof_shape::<Bls12_381>(4, 5).compressed_size(),
+            peak_memory: 128 * field_size + 32 * (size_of::<G1Affine>() + field_size),
+            msm_count: 7,
+            field_ops: 720,
+        }
+    );
+}
@@ -0,0 +1,208 @@
+//! The circuit digest, binding the R1CS instance and the verifier key to the Fiat–Shamir transcript.
+//!
+//! Both the prover and the verifier start the transcript appending the digest
+//! of the matrices $A, B, C$, of the statement $\vec x$, and of the verifier key,
+//! so that a proof produced for an instance cannot be replayed against another one.
+//! The matrices are hashed in the same order they are produced by the row-major streams
+//! of the elastic prover, so that the digest can be computed either from the [`R1cs`] matrices
+//! or from its streaming counterpart.
+use ark_ec::pairing::Pairing;
+use ark_ff::Field;
+use ark_serialize::CanonicalSerialize;
+use ark_std::borrow::Borrow;
+use ark_std::iter;
+use ark_std::vec::Vec;
+use merlin::Transcript;
+
+use crate::circuit::{DataParallelR1cs, Matrix, R1cs, R1csStream};
+use crate::iterable::Iterable;
+use crate::kzg::VerifierKey;
+use crate::misc::MatrixElement;
+use crate::transcript::GeminiTranscript;
+
+/// The size of the buffer of serialized matrix elements absorbed at once into the hash.
+const DIGEST_BUFFER_SIZE: usize = 1 << 12;
+
+/// The digest of a circuit: the R1CS matrices, the statement, and the verifier key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CircuitDigest(pub [u8; 32]);
+
+impl CircuitDigest {
+    /// Compute the digest of the matrices of `r1cs`, the statement `x`, and the verifier key `vk`.
+    pub fn new<E: Pairing>(
+        r1cs: &R1cs<E::ScalarField>,
+        x: &[E::ScalarField],
+        vk: &VerifierKey<E>,
+    ) -> Self {
+        Self::from_matrices_digest(&matrices_digest(r1cs), x, vk)
+    }
+
+    /// Compute the digest of the data-parallel instance `r1cs`, the statement `x`, and the verifier key `vk`.
+    ///
+    /// The digest is the same as the one of the materialized instance [`DataParallelR1cs::to_r1cs`].
+    pub fn new_data_parallel<E: Pairing>(
+        r1cs: &DataParallelR1cs<E::ScalarField>,
+        x: &[E::ScalarField],
+        vk: &VerifierKey<E>,
+    ) -> Self {
+        let circuit = r1cs.circuit();
+        let matrices_digest = hash_matrices([
+            r1cs.rowmaj_elements(&circuit.a),
+            r1cs.rowmaj_elements(&circuit.b),
+            r1cs.rowmaj_elements(&circuit.c),
+        ]);
+        Self::from_matrices_digest(&matrices_digest, x, vk)
+    }
+
+    /// Compute the digest from the row-major streams of the matrices `a`, `b`, `c`,
+    /// the statement `x`, and the verifier key `vk`.
+    pub fn from_streams<E, SM>(
+        a: SM,
+        b: SM,
+        c: SM,
+        x: &[E::ScalarField],
+        vk: &VerifierKey<E>,
+    ) -> Self
+    where
+        E: Pairing,
+        SM: Iterable,
+        SM::Item: Borrow<MatrixElement<E::ScalarField>>,
+    {
+        let matrices_digest = hash_matrices([
+            a.iter().map(copy_element),
+            b.iter().map(copy_element),
+            c.iter().map(copy_element),
+        ]);
+        Self::from_matrices_digest(&matrices_digest, x, vk)
+    }
+
+    /// Compute the digest of the streaming instance `r1cs` and of the verifier key `vk`.
+    ///
+    /// The statement is read from the assignment stream, where it follows the witness.
+    pub fn from_r1cs_stream<E, SM, SZ, SW>(
+        r1cs: &R1csStream<SM, SZ, SW>,
+        vk: &VerifierKey<E>,
+    ) -> Self
+    where
+        E: Pairing,
+        SM: Iterable + Copy,
+        SZ: Iterable,
+        SW: Iterable,
+        SM::Item: Borrow<MatrixElement<E::ScalarField>>,
+        SZ::Item: Borrow<E::ScalarField>,
+    {
+        // the assignment is streamed in reverse order, the statement comes last.
+        let mut x = r1cs
+            .z
+            .iter()
+            .skip(r1cs.witness.len())
+            .map(|e| *e.borrow())
+            .collect::<Vec<_>>();
+        x.reverse();
+        Self::from_streams(r1cs.a_rowmaj, r1cs.b_rowmaj, r1cs.c_rowmaj, &x, vk)
+    }
+
+    /// Compute the digest from the digest of the matrices `matrices_digest`
+    /// (see [`matrices_digest`]), the statement `x`, and the verifier key `vk`.
+    ///
+    /// The matrices need to be hashed only once for many statements.
+    pub(crate) fn from_matrices_digest<E: Pairing>(
+        matrices_digest: &[u8; 32],
+        x: &[E::ScalarField],
+        vk: &VerifierKey<E>,
+    ) -> Self {
+        let mut hasher = Transcript::new(b"gemini-circuit-digest");
+        hasher.append_serializable(b"matrices", matrices_digest);
+        hasher.append_serializable(b"statement", &x.to_vec());
+        hasher.append_serializable(b"vk", vk);
+
+        let mut digest = [0u8; 32];
+        hasher.challenge_bytes(b"digest", &mut digest);
+        CircuitDigest(digest)
+    }
+
+    /// Append the digest to the transcript.
+    pub(crate) fn append_to(&self, transcript: &mut impl GeminiTranscript) {
+        transcript.append_serializable(b"circuit-digest", &self.0);
+    }
+}
+
+/// Return the digest of the matrices of `r1cs`.
+pub(crate) fn matrices_digest<F: Field>(r1cs: &R1cs<F>) -> [u8; 32] {
+    hash_matrices([
+        rowmaj_elements(&r1cs.a),
+        rowmaj_elements(&r1cs.b),
+        rowmaj_elements(&r1cs.c),
+    ])
+}
+
+fn hash_matrices<F, I>(matrices: [I; 3]) -> [u8; 32]
+where
+    F: Field,
+    I: Iterator<Item = MatrixElement<F>>,
+{
+    let mut hasher = Transcript::new(b"gemini-matrices-digest");
+    let mut buffer = Vec::with_capacity(DIGEST_BUFFER_SIZE);
+    for matrix in matrices {
+        for element in matrix {
+            match element {
+                MatrixElement::Element((val, col)) => {
+                    buffer.push(0u8);
+                    val.serialize_uncompressed(&mut buffer).unwrap();
+                    (col as u64).serialize_uncompressed(&mut buffer).unwrap();
+                }
+                MatrixElement::EOL => buffer.push(1u8),
+            }
+            if buffer.len() >= DIGEST_BUFFER_SIZE {
+                hasher.append_message(b"matrix", &buffer);
+                buffer.clear();
+            }
+        }
+        hasher.append_message(b"matrix", &buffer);
+        buffer.clear();
+    }
+
+    let mut digest = [0u8; 32];
+    hasher.challenge_bytes(b"digest", &mut digest);
+    digest
+}
+
+fn copy_element<F: Copy, T: Borrow<MatrixElement<F>>>(element: T) -> MatrixElement<F> {
+    *element.borrow()
+}
+
+/// Iterate over the elements of `matrix` in the same order as
+/// [`matrix_into_rowmaj`](crate::circuit::matrix_into_rowmaj).
+fn rowmaj_elements<F: Field>(matrix: &Matrix<F>) -> impl Iterator<Item = MatrixElement<F>> + '_ {
+    matrix.iter().rev().flat_map(|row| {
+        row.iter()
+            .rev()
+            .map(|&element| MatrixElement::Element(element))
+            .chain(iter::once(MatrixElement::EOL))
+    })
+}
+
+#[test]
+fn test_digest_consistency() {
+    use crate::circuit::{generate_relation, matrix_into_rowmaj, random_circuit};
+    use crate::kzg::CommitterKey;
+    use ark_test_curves::bls12_381::Bls12_381;
+
+    let rng = &mut ark_std::test_rng();
+    let circuit = random_circuit(rng, 8, 8);
+    let r1cs = generate_relation(circuit);
+    let ck = CommitterKey::<Bls12_381>::new(16, 3, rng);
+    let vk = VerifierKey::from(&ck);
+
+    let a = matrix_into_rowmaj(&r1cs.a);
+    let b = matrix_into_rowmaj(&r1cs.b);
+    let c = matrix_into_rowmaj(&r1cs.c);
+    let digest = CircuitDigest::new(&r1cs, &r1cs.x, &vk);
+    let stream_digest =
+        CircuitDigest::from_streams(a.as_slice(), b.as_slice(), c.as_slice(), &r1cs.x, &vk);
+    assert_eq!(digest, stream_digest);
+
+    let mut other_x = r1cs.x.clone();
+    other_x[0] += r1cs.x[0];
+    assert_ne!(digest, CircuitDigest::new(&r1cs, &other_x, &vk));
+}
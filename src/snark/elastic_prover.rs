@@ -2,22 +2,29 @@
 use ark_ec::pairing::Pairing;
 use ark_ff::Field;
 use ark_std::borrow::Borrow;
+use ark_std::mem::size_of;
 use ark_std::vec::Vec;
 use ark_std::One;
 use log::debug;
 use merlin::Transcript;
 
 use crate::circuit::R1csStream;
+use crate::errors::VerificationError;
 use crate::iterable::Iterable;
-use crate::kzg::CommitterKeyStream;
+use crate::kzg::{CommitterKeyStream, SpaceProverConfig, VerifierKey};
 use crate::misc::{evaluate_be, evaluate_le, hadamard, powers, powers2, strip_last, MatrixElement};
+use crate::snark::observer::{observe_round, observe_tensorcheck};
 use crate::snark::streams::MatrixTensor;
-use crate::snark::Proof;
+use crate::snark::{
+    Checkpoint, CircuitDigest, ConfigError, Proof, ProverConfig, ProverObserver, ProverPhase,
+};
 use crate::subprotocols::sumcheck::proof::Sumcheck;
 use crate::subprotocols::sumcheck::streams::FoldedPolynomialTree;
+use crate::subprotocols::sumcheck::ElasticProver;
 use crate::subprotocols::tensorcheck::{evaluate_folding, partially_foldtree, TensorcheckProof};
 use crate::transcript::GeminiTranscript;
-use crate::{lincomb, PROTOCOL_NAME, SPACE_TIME_THRESHOLD};
+use crate::transcript::TranscriptDomain;
+use crate::{lincomb, SPACE_TIME_THRESHOLD};
 
 #[allow(dead_code)]
 pub fn elastic_tensorcheck<F, E, SG, SB, SF1>(
@@ -45,9 +52,7 @@ where
     folded_polynomials_commitments.extend(time_ck.batch_commit(&tensorcheck_tfoldings));
 
     // add commitments to transcript
-    folded_polynomials_commitments
-        .iter()
-        .for_each(|c| transcript.append_serializable(b"commitment", c));
+    transcript.append_serializables(b"commitment", &folded_polynomials_commitments);
     let eval_chal = transcript.get_challenge::<E::ScalarField>(b"evaluation-chal");
     let eval_points = [eval_chal.square(), eval_chal, -eval_chal];
 
@@ -68,9 +73,7 @@ where
         evaluate_be(base_polynomial.iter(), &eval_points[1]),
         evaluate_be(base_polynomial.iter(), &eval_points[2]),
     ];
-    evaluations_w
-        .iter()
-        .for_each(|e| transcript.append_serializable(b"eval", &e));
+    transcript.append_serializables(b"eval", &evaluations_w);
     folded_polynomials_evaluations
         .iter()
         .flatten()
@@ -102,6 +105,7 @@ where
 }
 
 /// Streaming function for producing the tensor check proof.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(max_msm_buffer = max_msm_buffer)))]
 pub fn tensorcheck<F, E, SG, SB, SF1>(
     transcript: &mut Transcript,
     ck: CommitterKeyStream<E, SG>,
@@ -124,9 +128,7 @@ where
     let folded_polynomials_commitments = ck.commit_folding(&tensorcheck_foldings, max_msm_buffer);
 
     // add commitments to transcript
-    folded_polynomials_commitments
-        .iter()
-        .for_each(|c| transcript.append_serializable(b"commitment", c));
+    transcript.append_serializables(b"commitment", &folded_polynomials_commitments);
     let eval_chal = transcript.get_challenge::<E::ScalarField>(b"evaluation-chal");
     let eval_points = [eval_chal.square(), eval_chal, -eval_chal];
 
@@ -140,9 +142,7 @@ where
         evaluate_be(base_polynomial.iter(), &eval_points[1]),
         evaluate_be(base_polynomial.iter(), &eval_points[2]),
     ];
-    evaluations_w
-        .iter()
-        .for_each(|e| transcript.append_serializable(b"eval", &e));
+    transcript.append_serializables(b"eval", &evaluations_w);
     folded_polynomials_evaluations
         .iter()
         .flatten()
@@ -171,6 +171,10 @@ impl<E: Pairing> Proof<E> {
     /// Given as input the _streaming_ R1CS instance `r1cs`
     /// and the _streaming_ committer key `ck`,
     /// return a new SNARK using the elastic prover.
+    ///
+    /// The digest of the circuit is computed with an additional pass over the matrices,
+    /// see [`CircuitDigest::from_r1cs_stream`].
+    /// When proving many statements for the same circuit, use [`Proof::new_elastic_with_digest`].
     pub fn new_elastic<SM, SG, SZ, SW>(
         r1cs: R1csStream<SM, SZ, SW>,
         ck: CommitterKeyStream<E, SG>,
@@ -185,9 +189,195 @@ impl<E: Pairing> Proof<E> {
         SM::Item: Borrow<MatrixElement<E::ScalarField>>,
         SZ::Item: Borrow<E::ScalarField>,
         SW::Item: Borrow<E::ScalarField>,
+        SG::Item: Borrow<E::G1Affine>,
+    {
+        let digest = CircuitDigest::from_r1cs_stream(&r1cs, &VerifierKey::from(&ck));
+        Self::new_elastic_with_digest(r1cs, ck, &digest, max_msm_buffer)
+    }
+
+    /// Given as input the _streaming_ R1CS instance `r1cs`,
+    /// the _streaming_ committer key `ck`,
+    /// and the digest of the circuit `digest` (see [`CircuitDigest::from_streams`]),
+    /// return a new SNARK using the elastic prover.
+    pub fn new_elastic_with_digest<SM, SG, SZ, SW>(
+        r1cs: R1csStream<SM, SZ, SW>,
+        ck: CommitterKeyStream<E, SG>,
+        digest: &CircuitDigest,
+        max_msm_buffer: usize,
+    ) -> Proof<E>
+    where
+        E: Pairing,
+        SM: Iterable + Copy,
+        SZ: Iterable + Copy,
+        SW: Iterable,
+        SG: Iterable,
+        SM::Item: Borrow<MatrixElement<E::ScalarField>>,
         SZ::Item: Borrow<E::ScalarField>,
+        SW::Item: Borrow<E::ScalarField>,
+        SG::Item: Borrow<E::G1Affine>,
+    {
+        Self::prove_elastic(
+            r1cs,
+            ck,
+            digest,
+            max_msm_buffer,
+            &TranscriptDomain::default(),
+            Checkpoint::new(),
+            |_| (),
+            &mut (),
+        )
+        .expect("a new checkpoint has no message to replay")
+    }
+
+    /// Given as input the _streaming_ R1CS instance `r1cs`,
+    /// the _streaming_ committer key `ck`,
+    /// and the digest of the circuit `digest` (see [`CircuitDigest::from_streams`]),
+    /// return a new SNARK using the elastic prover configured with `config`.
+    ///
+    /// The buffers of the multi-scalar multiplications are sized after the memory budget of `config`,
+    /// and the chunk size of `config` overrides the one of `ck`.
+    /// The elastic prover does not support fixed-window commitments:
+    /// if `config` enables them, a [`ConfigError`] is returned.
+    pub fn new_elastic_with_config<SM, SG, SZ, SW>(
+        r1cs: R1csStream<SM, SZ, SW>,
+        ck: CommitterKeyStream<E, SG>,
+        digest: &CircuitDigest,
+        config: &ProverConfig,
+    ) -> Result<Proof<E>, ConfigError>
+    where
+        E: Pairing,
+        SM: Iterable + Copy + Send,
+        SZ: Iterable + Copy + Send,
+        SW: Iterable + Send,
+        SG: Iterable + Send,
+        SM::Item: Borrow<MatrixElement<E::ScalarField>>,
         SZ::Item: Borrow<E::ScalarField>,
+        SW::Item: Borrow<E::ScalarField>,
         SG::Item: Borrow<E::G1Affine>,
+    {
+        config.check_elastic()?;
+        let ck = ck.with_config(SpaceProverConfig::new(config.chunk_size()));
+        let max_msm_buffer = config.msm_buffer::<E>();
+        let proof = config.install(|| {
+            Self::prove_elastic(
+                r1cs,
+                ck,
+                digest,
+                max_msm_buffer,
+                &config.transcript_domain,
+                Checkpoint::new(),
+                |_| (),
+                &mut (),
+            )
+            .expect("a new checkpoint has no message to replay")
+        });
+        Ok(proof)
+    }
+
+    /// Given as input the _streaming_ R1CS instance `r1cs`,
+    /// the _streaming_ committer key `ck`,
+    /// and the digest of the circuit `digest` (see [`CircuitDigest::from_streams`]),
+    /// return a new SNARK using the elastic prover, resuming from `checkpoint`.
+    ///
+    /// After each phase but the tensorcheck, the prover calls `save` with the checkpoint updated so far,
+    /// see [`Checkpoint`].
+    /// The rounds of a phase that is interrupted are not saved, and are computed again upon resuming.
+    ///
+    /// Fails with [`VerificationError::MalformedProof`] if a sumcheck in `checkpoint`
+    /// does not hold exactly one pair of final foldings.
+    pub fn new_elastic_resumable<SM, SG, SZ, SW, C>(
+        r1cs: R1csStream<SM, SZ, SW>,
+        ck: CommitterKeyStream<E, SG>,
+        digest: &CircuitDigest,
+        max_msm_buffer: usize,
+        checkpoint: Checkpoint<E>,
+        save: C,
+    ) -> Result<Proof<E>, VerificationError>
+    where
+        E: Pairing,
+        SM: Iterable + Copy,
+        SZ: Iterable + Copy,
+        SW: Iterable,
+        SG: Iterable,
+        SM::Item: Borrow<MatrixElement<E::ScalarField>>,
+        SZ::Item: Borrow<E::ScalarField>,
+        SW::Item: Borrow<E::ScalarField>,
+        SG::Item: Borrow<E::G1Affine>,
+        C: FnMut(&Checkpoint<E>),
+    {
+        Self::prove_elastic(
+            r1cs,
+            ck,
+            digest,
+            max_msm_buffer,
+            &TranscriptDomain::default(),
+            checkpoint,
+            save,
+            &mut (),
+        )
+    }
+
+    /// Given as input the _streaming_ R1CS instance `r1cs`,
+    /// the _streaming_ committer key `ck`,
+    /// and the digest of the circuit `digest` (see [`CircuitDigest::from_streams`]),
+    /// return a new SNARK using the elastic prover, notifying `observer` of its progress.
+    pub fn new_elastic_observed<SM, SG, SZ, SW, O>(
+        r1cs: R1csStream<SM, SZ, SW>,
+        ck: CommitterKeyStream<E, SG>,
+        digest: &CircuitDigest,
+        max_msm_buffer: usize,
+        observer: &mut O,
+    ) -> Proof<E>
+    where
+        E: Pairing,
+        SM: Iterable + Copy,
+        SZ: Iterable + Copy,
+        SW: Iterable,
+        SG: Iterable,
+        SM::Item: Borrow<MatrixElement<E::ScalarField>>,
+        SZ::Item: Borrow<E::ScalarField>,
+        SW::Item: Borrow<E::ScalarField>,
+        SG::Item: Borrow<E::G1Affine>,
+        O: ProverObserver + ?Sized,
+    {
+        Self::prove_elastic(
+            r1cs,
+            ck,
+            digest,
+            max_msm_buffer,
+            &TranscriptDomain::default(),
+            Checkpoint::new(),
+            |_| (),
+            observer,
+        )
+        .expect("a new checkpoint has no message to replay")
+    }
+
+    /// The elastic prover, with transcript domain `domain`.
+    #[allow(clippy::too_many_arguments)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(variables = r1cs.z.len(), max_msm_buffer = max_msm_buffer)))]
+    fn prove_elastic<SM, SG, SZ, SW, C, O>(
+        r1cs: R1csStream<SM, SZ, SW>,
+        ck: CommitterKeyStream<E, SG>,
+        digest: &CircuitDigest,
+        max_msm_buffer: usize,
+        domain: &TranscriptDomain,
+        mut checkpoint: Checkpoint<E>,
+        mut save: C,
+        observer: &mut O,
+    ) -> Result<Proof<E>, VerificationError>
+    where
+        E: Pairing,
+        SM: Iterable + Copy,
+        SZ: Iterable + Copy,
+        SW: Iterable,
+        SG: Iterable,
+        SM::Item: Borrow<MatrixElement<E::ScalarField>>,
+        SZ::Item: Borrow<E::ScalarField>,
+        SW::Item: Borrow<E::ScalarField>,
+        SG::Item: Borrow<E::G1Affine>,
+        C: FnMut(&Checkpoint<E>),
+        O: ProverObserver + ?Sized,
     {
         let snark_time = start_timer!(|| module_path!());
 
@@ -199,28 +389,65 @@ impl<E: Pairing> Proof<E> {
             max_msm_buffer,
         );
 
-        let mut transcript = merlin::Transcript::new(PROTOCOL_NAME);
-        // transcript.append_serializable(b"r1cs-a", &r1cs.id);
-        // transcript.append_serializable(b"r1cs-b", &r1cs.id);
-        // transcript.append_serializable(b"r1cs-c", &r1cs.id);
+        let msm_element_size = size_of::<E::G1Affine>() + size_of::<E::ScalarField>();
+        observer.memory_in_use(max_msm_buffer * msm_element_size);
+
+        let mut transcript = domain.transcript();
+        digest.append_to(&mut transcript);
 
         // send the vector w
-        let witness_commitment_time = start_timer!(|| "Commitment to w");
-        let witness_commitment = ck.commit(&r1cs.witness);
-        end_timer!(witness_commitment_time);
+        let witness_commitment = match checkpoint.witness_commitment {
+            Some(witness_commitment) => witness_commitment,
+            None => {
+                observer.phase_started(ProverPhase::WitnessCommitment);
+                let witness_commitment_time = start_timer!(|| "Commitment to w");
+                let witness_commitment = ck.commit(&r1cs.witness);
+                end_timer!(witness_commitment_time);
+                observer.stream_pass_finished(ProverPhase::WitnessCommitment);
+                observer.msm_completed(r1cs.witness.len());
+                observer.phase_finished(ProverPhase::WitnessCommitment);
+                checkpoint.witness_commitment = Some(witness_commitment);
+                save(&checkpoint);
+                witness_commitment
+            }
+        };
 
         // send witness, receive challenge.
         transcript.append_serializable(b"witness", &witness_commitment);
         let alpha = transcript.get_challenge(b"alpha");
 
-        // send evaluation of zc(alpha)
-        let zc_alpha = evaluate_be(r1cs.z_c.iter(), &alpha);
-        transcript.append_serializable(b"zc(alpha)", &zc_alpha);
+        let (zc_alpha, first_proof) = match &checkpoint.first_sumcheck {
+            Some((zc_alpha, first_sumcheck_msgs)) => {
+                transcript.append_serializable(b"zc(alpha)", zc_alpha);
+                let first_proof = Sumcheck::replay(&mut transcript, first_sumcheck_msgs)?;
+                (*zc_alpha, first_proof)
+            }
+            None => {
+                observer.phase_started(ProverPhase::FirstSumcheck);
+                // send evaluation of zc(alpha)
+                let zc_alpha = evaluate_be(r1cs.z_c.iter(), &alpha);
+                transcript.append_serializable(b"zc(alpha)", &zc_alpha);
 
-        // run the sumcheck for z_a and z_b with twist alpha
-        let first_sumcheck_time = start_timer!(|| "First sumcheck");
-        let first_proof = Sumcheck::new_elastic(&mut transcript, r1cs.z_a, r1cs.z_b, alpha);
-        end_timer!(first_sumcheck_time);
+                // run the sumcheck for z_a and z_b with twist alpha
+                let first_sumcheck_time = start_timer!(|| "First sumcheck");
+                let first_proof = Sumcheck::prove_with(
+                    &mut transcript,
+                    ElasticProver::new(r1cs.z_a, r1cs.z_b, alpha),
+                    |prover| {
+                        observe_round::<E::ScalarField, _, _>(
+                            observer,
+                            ProverPhase::FirstSumcheck,
+                            prover,
+                        )
+                    },
+                );
+                end_timer!(first_sumcheck_time);
+                observer.phase_finished(ProverPhase::FirstSumcheck);
+                checkpoint.first_sumcheck = Some((zc_alpha, first_proof.prover_messages()));
+                save(&checkpoint);
+                (zc_alpha, first_proof)
+            }
+        };
 
         // after sumcheck, generate a new challenge
         let eta = transcript.get_challenge::<E::ScalarField>(b"eta");
@@ -236,13 +463,33 @@ impl<E: Pairing> Proof<E> {
         let sumcheck_batch_challenges = powers(eta, 3);
         let lhs = lincomb!((a_alpha, b_alpha, c_alpha), &sumcheck_batch_challenges);
 
-        let second_sumcheck_time = start_timer!(|| "Second sumcheck");
-        let second_proof =
-            Sumcheck::new_elastic(&mut transcript, lhs, r1cs.z, E::ScalarField::one());
-        end_timer!(second_sumcheck_time);
+        let second_proof = match &checkpoint.second_sumcheck_msgs {
+            Some(second_sumcheck_msgs) => Sumcheck::replay(&mut transcript, second_sumcheck_msgs)?,
+            None => {
+                observer.phase_started(ProverPhase::SecondSumcheck);
+                let second_sumcheck_time = start_timer!(|| "Second sumcheck");
+                let second_proof = Sumcheck::prove_with(
+                    &mut transcript,
+                    ElasticProver::new(lhs, r1cs.z, E::ScalarField::one()),
+                    |prover| {
+                        observe_round::<E::ScalarField, _, _>(
+                            observer,
+                            ProverPhase::SecondSumcheck,
+                            prover,
+                        )
+                    },
+                );
+                end_timer!(second_sumcheck_time);
+                observer.phase_finished(ProverPhase::SecondSumcheck);
+                checkpoint.second_sumcheck_msgs = Some(second_proof.prover_messages());
+                save(&checkpoint);
+                second_proof
+            }
+        };
 
         let batch_challenge = transcript.get_challenge::<E::ScalarField>(b"batch_challenge");
 
+        observer.phase_started(ProverPhase::Tensorcheck);
         let tensorcheck_time = start_timer!(|| "Tensorcheck");
         let tensorcheck_batch_challenges = powers(batch_challenge, 2);
         let tensorcheck_polynomials = lincomb!((lhs, r1cs.z), &tensorcheck_batch_challenges);
@@ -254,14 +501,16 @@ impl<E: Pairing> Proof<E> {
             max_msm_buffer,
         );
         end_timer!(tensorcheck_time);
+        observe_tensorcheck(observer, &tensorcheck_proof, r1cs.z.len());
+        observer.phase_finished(ProverPhase::Tensorcheck);
 
         end_timer!(snark_time);
-        Proof {
+        Ok(Proof {
             witness_commitment,
             zc_alpha,
             first_sumcheck_msgs: first_proof.prover_messages(),
             second_sumcheck_msgs: second_proof.prover_messages(),
             tensorcheck_proof,
-        }
+        })
     }
 }
@@ -0,0 +1,201 @@
+//! Peak-memory telemetry for the SNARK provers.
+//!
+//! The elastic prover is meant to run in space \\(O(\sqrt n)\\) for instances of size \\(n\\),
+//! and the [`ProverObserver::memory_in_use`] estimates only account for its largest buffers.
+//! To measure the actual footprint, install a [`TrackingAllocator`] as the global allocator,
+//! and observe the prover with a [`MemoryProfile`]:
+//!
+//! ```ignore
+//! use std::alloc::System;
+//! use ark_gemini::snark::{MemoryProfile, Proof, TrackingAllocator};
+//!
+//! #[global_allocator]
+//! static ALLOCATOR: TrackingAllocator<System> = TrackingAllocator::new(System);
+//!
+//! let mut profile = MemoryProfile::new(&ALLOCATOR);
+//! let proof = Proof::new_elastic_observed(r1cs_stream, ck_stream, &digest, max_msm_buffer, &mut profile);
+//! for (phase, peak) in profile.peaks() {
+//!     println!("{:?}: {} bytes", phase, peak);
+//! }
+//! ```
+//!
+//! The allocator counts the bytes allocated by the whole process, across all threads,
+//! hence the peaks include the memory held by the caller, such as the instance when stored in memory.
+use std::alloc::{GlobalAlloc, Layout};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::vec::Vec;
+
+use crate::snark::{ProverObserver, ProverPhase};
+
+/// A global allocator wrapping `A` that keeps track of the bytes currently allocated
+/// and of their peak.
+pub struct TrackingAllocator<A> {
+    inner: A,
+    current: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+impl<A> TrackingAllocator<A> {
+    /// Wrap the allocator `inner`.
+    pub const fn new(inner: A) -> Self {
+        Self {
+            inner,
+            current: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+        }
+    }
+
+    /// Return the bytes currently allocated.
+    pub fn current(&self) -> usize {
+        self.current.load(Ordering::Relaxed)
+    }
+
+    /// Return the largest number of bytes allocated at once since the last [`reset_peak`](Self::reset_peak).
+    pub fn peak(&self) -> usize {
+        self.peak.load(Ordering::Relaxed)
+    }
+
+    /// Reset the peak to the bytes currently allocated.
+    pub fn reset_peak(&self) {
+        self.peak.store(self.current(), Ordering::Relaxed);
+    }
+
+    fn allocated(&self, size: usize) {
+        let current = self.current.fetch_add(size, Ordering::Relaxed) + size;
+        self.peak.fetch_max(current, Ordering::Relaxed);
+    }
+
+    fn deallocated(&self, size: usize) {
+        self.current.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+unsafe impl<A: GlobalAlloc> GlobalAlloc for TrackingAllocator<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.inner.alloc(layout);
+        if !ptr.is_null() {
+            self.allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.inner.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            self.allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.inner.dealloc(ptr, layout);
+        self.deallocated(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = self.inner.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            self.allocated(new_size);
+            self.deallocated(layout.size());
+        }
+        new_ptr
+    }
+}
+
+/// A [`ProverObserver`] recording the peak memory of each phase of the prover,
+/// as measured by a [`TrackingAllocator`].
+pub struct MemoryProfile<'a, A> {
+    allocator: &'a TrackingAllocator<A>,
+    peaks: Vec<(ProverPhase, usize)>,
+}
+
+impl<'a, A> MemoryProfile<'a, A> {
+    /// Create a new profile reading from `allocator`,
+    /// that must be the global allocator for the measures to be meaningful.
+    pub fn new(allocator: &'a TrackingAllocator<A>) -> Self {
+        Self {
+            allocator,
+            peaks: Vec::new(),
+        }
+    }
+
+    /// Return the peak bytes allocated during each phase run by the prover, in order.
+    ///
+    /// Phases restored from a [`Checkpoint`](crate::snark::Checkpoint) are not run, hence not reported.
+    pub fn peaks(&self) -> &[(ProverPhase, usize)] {
+        &self.peaks
+    }
+
+    /// Return the peak bytes allocated during `phase`, if it was run.
+    pub fn peak(&self, phase: ProverPhase) -> Option<usize> {
+        self.peaks
+            .iter()
+            .find(|(observed, _)| *observed == phase)
+            .map(|&(_, peak)| peak)
+    }
+}
+
+impl<'a, A> ProverObserver for MemoryProfile<'a, A> {
+    fn phase_started(&mut self, _phase: ProverPhase) {
+        self.allocator.reset_peak();
+    }
+
+    fn phase_finished(&mut self, phase: ProverPhase) {
+        self.peaks.push((phase, self.allocator.peak()));
+    }
+}
+
+#[test]
+fn test_tracking_allocator() {
+    use std::alloc::System;
+
+    let allocator = TrackingAllocator::new(System);
+    let layout = Layout::array::<u64>(100).unwrap();
+    unsafe {
+        let ptr = allocator.alloc(layout);
+        assert_eq!(allocator.current(), 800);
+        let ptr = allocator.realloc(ptr, layout, 1600);
+        assert_eq!(allocator.current(), 1600);
+        assert_eq!(allocator.peak(), 1600);
+        allocator.dealloc(ptr, Layout::array::<u64>(200).unwrap());
+    }
+    assert_eq!(allocator.current(), 0);
+    assert_eq!(allocator.peak(), 1600);
+    allocator.reset_peak();
+    assert_eq!(allocator.peak(), 0);
+}
+
+#[test]
+fn test_memory_profile() {
+    use ark_test_curves::bls12_381::Bls12_381;
+    use std::alloc::System;
+
+    use crate::circuit::{generate_relation, random_circuit};
+    use crate::kzg::CommitterKey;
+    use crate::snark::Proof;
+
+    let rng = &mut ark_std::test_rng();
+    let r1cs = generate_relation(random_circuit(rng, 16, 16));
+    let ck = CommitterKey::<Bls12_381>::new(32, 5, rng);
+
+    // the allocator is not global, hence its peaks stay at zero.
+    let allocator = TrackingAllocator::new(System);
+    let mut profile = MemoryProfile::new(&allocator);
+    let proof = Proof::new_time_observed(&r1cs, &ck, &mut profile);
+    assert_eq!(proof, Proof::new_time(&r1cs, &ck));
+    let phases = profile
+        .peaks()
+        .iter()
+        .map(|&(phase, _)| phase)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        phases,
+        [
+            ProverPhase::WitnessCommitment,
+            ProverPhase::FirstSumcheck,
+            ProverPhase::SecondSumcheck,
+            ProverPhase::Tensorcheck
+        ]
+    );
+    assert_eq!(profile.peak(ProverPhase::Tensorcheck), Some(0));
+}
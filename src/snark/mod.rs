@@ -54,10 +54,47 @@
 //! Both claims can be checked via the tensorcheck protocol.
 //! The evaluations of the base polynomials are generated internally by the verifier, using the R1CS matrices
 //! and the statement provided as input.
+//!
+//! The verifier of this argument reads the matrices $A, B, C$ in full, and thus runs in time linear in their size.
+//! For verification sublinear in the circuit size, circuits can be indexed once
+//! with [`psnark::Proof::index_verifier_key`](crate::psnark::Proof::index_verifier_key):
+//! the preprocessing verifier then only reads the index commitments and performs a constant number of pairings.
+//! Many proofs for the same circuit can be checked together with [`Proof::batch_verify`],
+//! which reads the matrices once and performs a single multi-pairing.
+//! Their evaluation proofs can also be aggregated into a single proof of logarithmic size
+//! with [`Proof::aggregate_evaluation_proofs`], to be checked with [`Proof::verify_aggregated_evaluation_proofs`];
+//! the rest of each proof is still sent and checked on its own.
+//!
+//! Before sending any message, both the prover and the verifier bind the transcript
+//! to the instance $(A, B, C, \vec x)$ and to the verifier key through a [`CircuitDigest`].
+//!
+//! Both provers can be tuned with a [`ProverConfig`], via [`Proof::new_time_with_config`]
+//! and [`Proof::new_elastic_with_config`].
+//! The cost of either prover can be estimated beforehand with [`Proof::estimate`].
+//! Circuits made of many copies of the same sub-circuit, see [`DataParallelR1cs`](crate::circuit::DataParallelR1cs),
+//! can be proven with [`Proof::new_data_parallel`] without materializing the repeated matrices.
+//! The progress of either prover can be followed with a [`ProverObserver`],
+//! and, with the feature `memory-telemetry`, its peak memory with a [`MemoryProfile`](memory::MemoryProfile).
+//! Long runs of the elastic prover can be resumed from a [`Checkpoint`] with [`Proof::new_elastic_resumable`].
+//!
+//! Proofs can be encoded as calldata for the Ethereum Virtual Machine with [`Proof::to_calldata`],
+//! but no on-chain verifier is provided, see [`calldata`].
 
+mod aggregation;
+#[cfg(feature = "snark-trait")]
+mod arkworks;
+pub mod calldata;
+mod checkpoint;
+mod config;
+mod cost;
+mod digest;
 mod elastic_prover;
+#[cfg(feature = "memory-telemetry")]
+pub mod memory;
+mod observer;
 mod time_prover;
 mod verifier;
+mod wire;
 
 /// Utilities for producing streams in SNARK protocol.
 mod streams;
@@ -65,14 +102,27 @@ mod streams;
 mod tests;
 
 use ark_ec::pairing::Pairing;
-use ark_serialize::*;
 
 use crate::kzg::Commitment;
 use crate::subprotocols::sumcheck::prover::ProverMsgs;
 use crate::subprotocols::tensorcheck::TensorcheckProof;
 
+pub use checkpoint::Checkpoint;
+pub use config::{ConfigError, ProverConfig, DEFAULT_MEMORY_BUDGET};
+pub use cost::{CostReport, ProverMode};
+pub use digest::CircuitDigest;
+#[cfg(feature = "memory-telemetry")]
+pub use memory::{MemoryProfile, TrackingAllocator};
+pub use observer::{ProverObserver, ProverPhase};
+pub use wire::{PROOF_MAGIC, PROOF_VERSION};
+
+#[cfg(feature = "snark-trait")]
+pub use arkworks::{Gemini, ProvingKey, VerifyingKey};
+
 /// The SNARK proof, composed of all prover's messages sent throughout the protocol.
-#[derive(CanonicalSerialize, PartialEq, Eq)]
+///
+/// Proofs are serialized with a versioned header, see [`PROOF_VERSION`].
+#[derive(Clone, PartialEq, Eq)]
 pub struct Proof<E: Pairing> {
     witness_commitment: Commitment<E>,
     zc_alpha: E::ScalarField,
@@ -0,0 +1,85 @@
+//! Observers of the progress of the SNARK provers.
+//!
+//! A [`ProverObserver`] is notified by the time-efficient prover [`Proof::new_time_observed`]
+//! and by the elastic prover [`Proof::new_elastic_observed`] as they run,
+//! so that services can expose progress bars and metrics.
+//! All notifications default to no-ops, and `()` observes nothing.
+//! With the feature `memory-telemetry`, [`MemoryProfile`](crate::snark::MemoryProfile)
+//! records the peak heap usage of each phase.
+//!
+//! [`Proof::new_time_observed`]: crate::snark::Proof::new_time_observed
+//! [`Proof::new_elastic_observed`]: crate::snark::Proof::new_elastic_observed
+use ark_ec::pairing::Pairing;
+use ark_ff::Field;
+
+use crate::subprotocols::sumcheck::Prover;
+use crate::subprotocols::tensorcheck::TensorcheckProof;
+
+/// The phases of the SNARK provers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProverPhase {
+    /// The commitment to the witness.
+    WitnessCommitment,
+    /// The sumcheck over the rows of the matrices.
+    FirstSumcheck,
+    /// The sumcheck over the columns of the matrices.
+    SecondSumcheck,
+    /// The commitments to the foldings and the evaluation proof.
+    Tensorcheck,
+}
+
+/// Observer of the progress of the SNARK provers.
+pub trait ProverObserver {
+    /// The prover starts `phase`.
+    fn phase_started(&mut self, _phase: ProverPhase) {}
+
+    /// The prover completed `phase`.
+    fn phase_finished(&mut self, _phase: ProverPhase) {}
+
+    /// The prover starts the round `round` of the sumcheck `phase`.
+    fn round_started(&mut self, _phase: ProverPhase, _round: usize) {}
+
+    /// The prover completed a multi-scalar multiplication of `size` elements.
+    ///
+    /// The multi-scalar multiplications of the tensorcheck are reported as it completes.
+    fn msm_completed(&mut self, _size: usize) {}
+
+    /// The elastic prover completed a pass over its streams during `phase`.
+    fn stream_pass_finished(&mut self, _phase: ProverPhase) {}
+
+    /// The prover holds `bytes` bytes of memory, excluding the instance and the committer key.
+    fn memory_in_use(&mut self, _bytes: usize) {}
+}
+
+impl ProverObserver for () {}
+
+/// Notify `observer` of the state of the sumcheck `prover` in `phase`,
+/// as invoked before each round and once after the last one:
+/// the previous round was a pass over the streams if the prover is still streaming.
+pub(crate) fn observe_round<F, P, O>(observer: &mut O, phase: ProverPhase, prover: &P)
+where
+    F: Field,
+    P: Prover<F>,
+    O: ProverObserver + ?Sized,
+{
+    let round = prover.round();
+    if round > 0 && prover.is_streaming() {
+        observer.stream_pass_finished(phase);
+    }
+    if round < prover.rounds() {
+        observer.round_started(phase, round);
+    }
+}
+
+/// Notify `observer` of the multi-scalar multiplications of the tensorcheck `proof`
+/// for polynomials of length `len`: one for each folded polynomial, and one for the evaluation proof.
+pub(crate) fn observe_tensorcheck<E, O>(observer: &mut O, proof: &TensorcheckProof<E>, len: usize)
+where
+    E: Pairing,
+    O: ProverObserver + ?Sized,
+{
+    for i in 0..proof.folded_polynomials_commitments.len() {
+        observer.msm_completed(len >> (i + 1));
+    }
+    observer.msm_completed(len);
+}
@@ -1,14 +1,58 @@
 use ark_std::test_rng;
-use ark_test_curves::bls12_381::Bls12_381;
+use ark_std::vec::Vec;
+use ark_test_curves::bls12_381::{Bls12_381, Fr};
 
 use crate::circuit::matrix_into_colmaj;
 use crate::circuit::matrix_into_rowmaj;
-use crate::circuit::{generate_relation, random_circuit, R1csStream};
+use crate::circuit::{generate_relation, random_circuit, DataParallelR1cs, R1cs, R1csStream};
 use crate::iterable::Reverse;
 use crate::kzg::CommitterKey;
 use crate::kzg::CommitterKeyStream;
-use crate::misc::product_matrix_vector;
-use crate::snark::Proof;
+use crate::kzg::VerifierKey;
+use crate::misc::{product_matrix_vector, MatrixElement};
+use crate::snark::{CircuitDigest, Proof};
+
+/// The matrices of an R1CS instance in row- and column-major order,
+/// and the products \\(A\vec z, B\vec z, C\vec z\\), to be streamed to the elastic prover.
+struct StreamFixture<'a> {
+    r1cs: &'a R1cs<Fr>,
+    colmaj: [Vec<MatrixElement<Fr>>; 3],
+    rowmaj: [Vec<MatrixElement<Fr>>; 3],
+    products: [Vec<Fr>; 3],
+}
+
+impl<'a> StreamFixture<'a> {
+    fn new(r1cs: &'a R1cs<Fr>) -> Self {
+        let matrices = [&r1cs.a, &r1cs.b, &r1cs.c];
+        StreamFixture {
+            r1cs,
+            colmaj: matrices.map(|matrix| matrix_into_colmaj(matrix, r1cs.z.len())),
+            rowmaj: matrices.map(|matrix| matrix_into_rowmaj(matrix)),
+            products: matrices.map(|matrix| product_matrix_vector(matrix, &r1cs.z)),
+        }
+    }
+
+    fn stream(&self) -> R1csStream<&[MatrixElement<Fr>], Reverse<&[Fr]>, Reverse<&[Fr]>> {
+        let [a_colmaj, b_colmaj, c_colmaj] = &self.colmaj;
+        let [a_rowmaj, b_rowmaj, c_rowmaj] = &self.rowmaj;
+        let [z_a, z_b, z_c] = &self.products;
+        R1csStream {
+            z: Reverse(self.r1cs.z.as_slice()),
+            a_colmaj: a_colmaj.as_slice(),
+            b_colmaj: b_colmaj.as_slice(),
+            c_colmaj: c_colmaj.as_slice(),
+            a_rowmaj: a_rowmaj.as_slice(),
+            b_rowmaj: b_rowmaj.as_slice(),
+            c_rowmaj: c_rowmaj.as_slice(),
+            witness: Reverse(self.r1cs.w.as_slice()),
+            z_a: Reverse(z_a.as_slice()),
+            z_b: Reverse(z_b.as_slice()),
+            z_c: Reverse(z_c.as_slice()),
+            nonzero: self.r1cs.a.len(),
+            joint_len: self.r1cs.a.len(),
+        }
+    }
+}
 
 #[test]
 fn test_snark_consistency() {
@@ -21,41 +65,144 @@ fn test_snark_consistency() {
     let r1cs = generate_relation(circuit);
     let ck = CommitterKey::<Bls12_381>::new(num_constraints + num_variables, 3, rng);
 
-    let z_a = product_matrix_vector(&r1cs.a, &r1cs.z);
-    let z_b = product_matrix_vector(&r1cs.b, &r1cs.z);
-    let z_c = product_matrix_vector(&r1cs.c, &r1cs.z);
-
-    let rows = r1cs.z.len();
     let time_proof = Proof::new_time(&r1cs, &ck);
-
-    let a_rowm = matrix_into_colmaj(&r1cs.a, rows);
-    let b_rowm = matrix_into_colmaj(&r1cs.b, rows);
-    let c_rowm = matrix_into_colmaj(&r1cs.c, rows);
-    let a_colm = matrix_into_rowmaj(&r1cs.a);
-    let b_colm = matrix_into_rowmaj(&r1cs.b);
-    let c_colm = matrix_into_rowmaj(&r1cs.c);
-
-    let r1cs_stream = R1csStream {
-        z: Reverse(r1cs.z.as_slice()),
-        a_colmaj: a_rowm.as_slice(),
-        b_colmaj: b_rowm.as_slice(),
-        c_colmaj: c_rowm.as_slice(),
-        a_rowmaj: a_colm.as_slice(),
-        b_rowmaj: b_colm.as_slice(),
-        c_rowmaj: c_colm.as_slice(),
-        witness: Reverse(r1cs.w.as_slice()),
-        z_a: Reverse(z_a.as_slice()),
-        z_b: Reverse(z_b.as_slice()),
-        z_c: Reverse(z_c.as_slice()),
-        nonzero: num_constraints,
-        joint_len: num_constraints,
-    };
+    let fixture = StreamFixture::new(&r1cs);
+    let r1cs_stream = fixture.stream();
     let ck_stream = CommitterKeyStream::from(&ck);
+    let digest = CircuitDigest::new(&r1cs, &r1cs.x, &VerifierKey::from(&ck));
+    assert_eq!(
+        CircuitDigest::from_r1cs_stream(&r1cs_stream, &VerifierKey::from(&ck)),
+        digest
+    );
     let space_proof = Proof::new_elastic(r1cs_stream, ck_stream, max_msm_buffer);
 
     assert_eq!(time_proof, space_proof);
 }
 
+#[test]
+fn test_snark_checkpoint() {
+    use crate::errors::VerificationError;
+    use crate::snark::Checkpoint;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+    let rng = &mut test_rng();
+    let num_constraints = 8;
+    let num_variables = 8;
+    let max_msm_buffer = 20;
+
+    let circuit = random_circuit(rng, num_constraints, num_variables);
+    let r1cs = generate_relation(circuit);
+    let ck = CommitterKey::<Bls12_381>::new(num_constraints + num_variables, 3, rng);
+    let time_proof = Proof::new_time(&r1cs, &ck);
+
+    let fixture = StreamFixture::new(&r1cs);
+    let ck_stream = CommitterKeyStream::from(&ck);
+    let digest = CircuitDigest::new(&r1cs, &r1cs.x, &VerifierKey::from(&ck));
+
+    let mut checkpoints = Vec::new();
+    let proof = Proof::new_elastic_resumable(
+        fixture.stream(),
+        ck_stream.clone(),
+        &digest,
+        max_msm_buffer,
+        Checkpoint::new(),
+        |checkpoint| checkpoints.push(checkpoint.clone()),
+    )
+    .unwrap();
+    assert_eq!(proof, time_proof);
+    assert_eq!(checkpoints.len(), 3);
+
+    // a checkpoint whose sumcheck lacks the final foldings is rejected.
+    let mut malformed = checkpoints[2].clone();
+    if let Some(msgs) = &mut malformed.second_sumcheck_msgs {
+        msgs.1.clear();
+    }
+    assert_eq!(
+        Proof::new_elastic_resumable(
+            fixture.stream(),
+            ck_stream.clone(),
+            &digest,
+            max_msm_buffer,
+            malformed,
+            |_| (),
+        ),
+        Err(VerificationError::MalformedProof)
+    );
+
+    for (phases, checkpoint) in checkpoints.into_iter().enumerate() {
+        assert_eq!(checkpoint.completed_phases(), phases + 1);
+        let mut bytes = Vec::new();
+        checkpoint.serialize_compressed(&mut bytes).unwrap();
+        let checkpoint = Checkpoint::deserialize_compressed(&bytes[..]).unwrap();
+
+        let mut saved = 0;
+        let resumed_proof = Proof::new_elastic_resumable(
+            fixture.stream(),
+            ck_stream.clone(),
+            &digest,
+            max_msm_buffer,
+            checkpoint,
+            |_| saved += 1,
+        );
+        assert_eq!(resumed_proof, Ok(proof.clone()));
+        assert_eq!(saved, 2 - phases);
+    }
+}
+
+#[test]
+fn test_snark_observer() {
+    use crate::snark::{ProverObserver, ProverPhase};
+
+    #[derive(Default)]
+    struct Counter {
+        rounds: Vec<(ProverPhase, usize)>,
+        msms: usize,
+        stream_passes: usize,
+        peak_memory: usize,
+    }
+
+    impl ProverObserver for Counter {
+        fn round_started(&mut self, phase: ProverPhase, round: usize) {
+            self.rounds.push((phase, round));
+        }
+
+        fn msm_completed(&mut self, _size: usize) {
+            self.msms += 1;
+        }
+
+        fn stream_pass_finished(&mut self, _phase: ProverPhase) {
+            self.stream_passes += 1;
+        }
+
+        fn memory_in_use(&mut self, bytes: usize) {
+            self.peak_memory = usize::max(self.peak_memory, bytes);
+        }
+    }
+
+    let rng = &mut test_rng();
+    let num_constraints = 16;
+    let num_variables = 16;
+
+    let circuit = random_circuit(rng, num_constraints, num_variables);
+    let r1cs = generate_relation(circuit);
+    let ck = CommitterKey::<Bls12_381>::new(num_constraints + num_variables, 5, rng);
+
+    let mut counter = Counter::default();
+    let proof = Proof::new_time_observed(&r1cs, &ck, &mut counter);
+    assert_eq!(proof, Proof::new_time(&r1cs, &ck));
+
+    let first_rounds = (0..4).map(|round| (ProverPhase::FirstSumcheck, round));
+    let second_rounds = (0..4).map(|round| (ProverPhase::SecondSumcheck, round));
+    assert_eq!(
+        counter.rounds,
+        first_rounds.chain(second_rounds).collect::<Vec<_>>()
+    );
+    // the witness, three foldings, and the evaluation proof.
+    assert_eq!(counter.msms, 5);
+    assert_eq!(counter.stream_passes, 0);
+    assert!(counter.peak_memory > 0);
+}
+
 #[test]
 fn test_snark_correctness() {
     let rng = &mut test_rng();
@@ -65,8 +212,408 @@ fn test_snark_correctness() {
     let circuit = random_circuit(rng, num_constraints, num_variables);
     let r1cs = generate_relation(circuit);
     let ck = CommitterKey::<Bls12_381>::new(num_constraints + num_variables, 5, rng);
-    let vk = (&ck).into();
+    let vk = VerifierKey::from(&ck);
+
+    let time_proof = Proof::new_time(&r1cs, &ck);
+    assert!(time_proof.verify(&r1cs, &r1cs.x, &vk).is_ok());
+    assert!(time_proof.verify(&r1cs, &r1cs.x, &vk.prepare()).is_ok())
+}
+
+#[test]
+fn test_snark_ptau() {
+    use crate::kzg::write_ptau;
+
+    let rng = &mut test_rng();
+    let num_constraints = 20;
+    let num_variables = 20;
+    let max_degree = num_constraints + num_variables;
+
+    let circuit = random_circuit(rng, num_constraints, num_variables);
+    let r1cs = generate_relation(circuit);
+    // a ceremony of size 2^6, larger than the keys loaded from it.
+    let power = 6;
+    let ceremony = CommitterKey::<Bls12_381>::new((2 << power) - 2, (1 << power) - 1, rng);
+    let ptau = write_ptau(&ceremony, power);
+    let ck = CommitterKey::<Bls12_381>::from_ptau(&ptau[..], max_degree, 5).unwrap();
+    let vk = VerifierKey::<Bls12_381>::from_ptau(&ptau[..], max_degree, 5).unwrap();
+    assert_eq!(vk, VerifierKey::from(&ck));
+
+    let time_proof = Proof::new_time(&r1cs, &ck);
+    assert!(time_proof.verify(&r1cs, &r1cs.x, &vk).is_ok());
+}
+
+#[test]
+fn test_snark_binds_instance() {
+    let rng = &mut test_rng();
+    let num_constraints = 20;
+    let num_variables = 20;
+
+    let circuit = random_circuit(rng, num_constraints, num_variables);
+    let r1cs = generate_relation(circuit);
+    let ck = CommitterKey::<Bls12_381>::new(num_constraints + num_variables, 5, rng);
+    let vk = VerifierKey::from(&ck);
+    let proof = Proof::new_time(&r1cs, &ck);
+    assert!(proof.verify(&r1cs, &r1cs.x, &vk).is_ok());
+
+    // appending a trivial constraint does not change the relation, but changes the digest.
+    let mut other_r1cs = generate_relation(circuit);
+    other_r1cs.a.push(Vec::new());
+    other_r1cs.b.push(Vec::new());
+    other_r1cs.c.push(Vec::new());
+    assert!(proof.verify(&other_r1cs, &other_r1cs.x, &vk).is_err());
+}
+
+#[test]
+fn test_snark_data_parallel() {
+    let rng = &mut test_rng();
+    let num_constraints = 8;
+    let num_variables = 8;
+    let copies = 3;
+
+    let mut circuit = generate_relation(random_circuit(rng, num_constraints, num_variables));
+    circuit.pad();
+    let witnesses = vec![circuit.w.clone(); copies];
+    let data_parallel = DataParallelR1cs::new(circuit.clone(), circuit.x.clone(), &witnesses);
+    let r1cs = data_parallel.to_r1cs();
+    assert!(r1cs.is_satisfied().is_ok());
+
+    let ck = CommitterKey::<Bls12_381>::new(copies * (num_constraints + num_variables), 5, rng);
+    let vk = VerifierKey::from(&ck);
+    let proof = Proof::new_data_parallel(&data_parallel, &ck);
+    assert_eq!(proof, Proof::new_time(&r1cs, &ck));
+    assert!(proof.verify(&r1cs, &r1cs.x, &vk).is_ok());
+}
+
+#[test]
+fn test_snark_many_statements() {
+    let rng = &mut test_rng();
+    let num_constraints = 20;
+    let num_variables = 20;
+
+    let r1cs = generate_relation(random_circuit(rng, num_constraints, num_variables));
+    let other_r1cs = generate_relation(random_circuit(rng, num_constraints, num_variables));
+    let ck = CommitterKey::<Bls12_381>::new(num_constraints + num_variables, 5, rng);
+    let vk = VerifierKey::from(&ck);
+
+    // the circuit description is shared across statements.
+    let circuit = R1cs::from_matrices(r1cs.a.clone(), r1cs.b.clone(), r1cs.c.clone());
+    let proof = Proof::new_time(&r1cs, &ck);
+    let other_proof = Proof::new_time(&other_r1cs, &ck);
+    assert!(proof.verify(&circuit, &r1cs.x, &vk).is_ok());
+    assert!(other_proof.verify(&circuit, &other_r1cs.x, &vk).is_ok());
+    assert!(proof.verify(&circuit, &other_r1cs.x, &vk).is_err());
+}
+
+#[test]
+fn test_snark_batch_verify() {
+    use crate::snark::ProverConfig;
+
+    let rng = &mut test_rng();
+    let num_constraints = 20;
+    let num_variables = 20;
+
+    let relations = (0..3)
+        .map(|_| generate_relation(random_circuit(rng, num_constraints, num_variables)))
+        .collect::<Vec<_>>();
+    let ck = CommitterKey::<Bls12_381>::new(num_constraints + num_variables, 5, rng);
+    let vk = VerifierKey::from(&ck);
+
+    let circuit = R1cs::from_matrices(
+        relations[0].a.clone(),
+        relations[0].b.clone(),
+        relations[0].c.clone(),
+    );
+    let proofs = relations
+        .iter()
+        .map(|r1cs| Proof::new_time(r1cs, &ck))
+        .collect::<Vec<_>>();
+    let statements = relations
+        .iter()
+        .map(|r1cs| r1cs.x.as_slice())
+        .collect::<Vec<_>>();
+    assert!(Proof::batch_verify(&proofs, &circuit, &statements, &vk).is_ok());
+
+    let swapped_statements = [statements[1], statements[0], statements[2]];
+    assert!(Proof::batch_verify(&proofs, &circuit, &swapped_statements, &vk).is_err());
+    assert!(Proof::batch_verify(&proofs[..2], &circuit, &statements, &vk).is_err());
+
+    // proofs produced with different configurations are batched with their own configuration.
+    let configs = (0..3)
+        .map(|i| ProverConfig::new().with_session_id(&[i]))
+        .collect::<Vec<_>>();
+    let proofs = relations
+        .iter()
+        .zip(&configs)
+        .map(|(r1cs, config)| Proof::new_time_with_config(r1cs, &ck, config))
+        .collect::<Vec<_>>();
+    assert!(Proof::batch_verify_with_config(&proofs, &circuit, &statements, &vk, &configs).is_ok());
+    assert!(Proof::batch_verify(&proofs, &circuit, &statements, &vk).is_err());
+    assert!(Proof::batch_verify_with_config(
+        &proofs,
+        &circuit,
+        &statements,
+        &vk,
+        &[configs[1].clone(), configs[0].clone(), configs[2].clone()]
+    )
+    .is_err());
+    assert!(
+        Proof::batch_verify_with_config(&proofs, &circuit, &statements, &vk, &configs[..2])
+            .is_err()
+    );
+}
+
+#[test]
+fn test_snark_aggregation() {
+    use crate::kzg::{AggregationKey, AggregationVerifierKey};
+    use crate::snark::ProverConfig;
+
+    let rng = &mut test_rng();
+    let num_constraints = 16;
+    let num_variables = 16;
+
+    let relations = (0..3)
+        .map(|_| generate_relation(random_circuit(rng, num_constraints, num_variables)))
+        .collect::<Vec<_>>();
+    let ck = CommitterKey::<Bls12_381>::new(num_constraints + num_variables, 5, rng);
+    let vk = VerifierKey::from(&ck);
+    let ak = AggregationKey::<Bls12_381>::new(4, rng);
+    let avk = AggregationVerifierKey::from(&ak);
+
+    let circuit = R1cs::from_matrices(
+        relations[0].a.clone(),
+        relations[0].b.clone(),
+        relations[0].c.clone(),
+    );
+    let configs = (0..3)
+        .map(|i| ProverConfig::new().with_session_id(&[i]))
+        .collect::<Vec<_>>();
+    let proofs = relations
+        .iter()
+        .zip(&configs)
+        .map(|(r1cs, config)| Proof::new_time_with_config(r1cs, &ck, config))
+        .collect::<Vec<_>>();
+    let statements = relations
+        .iter()
+        .map(|r1cs| r1cs.x.as_slice())
+        .collect::<Vec<_>>();
+    let aggregated =
+        Proof::aggregate_evaluation_proofs(&proofs, &circuit, &statements, &vk, &configs, &ak)
+            .unwrap();
+    assert_eq!(aggregated.rounds.len(), 2);
+    assert!(Proof::verify_aggregated_evaluation_proofs(
+        &proofs,
+        &circuit,
+        &statements,
+        &vk,
+        &configs,
+        &avk,
+        &aggregated
+    )
+    .is_ok());
+    // the proofs are checked against the transcript domain of their own configuration.
+    let default_configs = vec![ProverConfig::default(); 3];
+    assert!(Proof::aggregate_evaluation_proofs(
+        &proofs,
+        &circuit,
+        &statements,
+        &vk,
+        &default_configs,
+        &ak
+    )
+    .is_err());
+
+    let swapped_statements = [statements[1], statements[0], statements[2]];
+    assert!(Proof::verify_aggregated_evaluation_proofs(
+        &proofs,
+        &circuit,
+        &swapped_statements,
+        &vk,
+        &configs,
+        &avk,
+        &aggregated
+    )
+    .is_err());
+    assert!(Proof::verify_aggregated_evaluation_proofs(
+        &proofs[..2],
+        &circuit,
+        &statements[..2],
+        &vk,
+        &configs[..2],
+        &avk,
+        &aggregated
+    )
+    .is_err());
+}
+
+#[test]
+fn test_snark_config() {
+    use crate::snark::{ConfigError, ProverConfig};
+
+    let rng = &mut test_rng();
+    let num_constraints = 8;
+    let num_variables = 8;
+
+    let circuit = random_circuit(rng, num_constraints, num_variables);
+    let r1cs = generate_relation(circuit);
+    let ck = CommitterKey::<Bls12_381>::new(8 * (num_constraints + num_variables), 5, rng);
+    let vk = VerifierKey::from(&ck);
+
+    // the default configuration reproduces the default prover.
+    let config = ProverConfig::default();
+    let proof = Proof::new_time_with_config(&r1cs, &ck, &config);
+    assert_eq!(proof, Proof::new_time(&r1cs, &ck));
+
+    let config = ProverConfig::new()
+        .with_num_threads(2)
+        .unwrap()
+        .with_transcript_label(b"custom-label");
+    let proof = Proof::new_time_with_config(&r1cs, &ck, &config);
+    assert!(proof
+        .verify_with_config(&r1cs, &r1cs.x, &vk, &config)
+        .is_ok());
+    assert!(proof.verify(&r1cs, &r1cs.x, &vk).is_err());
+
+    // the fixed-window commitment to the witness does not change the proof.
+    let fixed_window_config = config.clone().with_fixed_window_commit(true);
+    let fixed_window_proof = Proof::new_time_with_config(&r1cs, &ck, &fixed_window_config);
+    assert_eq!(fixed_window_proof, proof);
+
+    // the elastic prover, with small buffers and chunks.
+    let fixture = StreamFixture::new(&r1cs);
+    let elastic_config = config
+        .with_memory_budget(1 << 10)
+        .with_chunk_size(3)
+        .unwrap();
+    let digest = CircuitDigest::new(&r1cs, &r1cs.x, &vk);
+    let elastic_proof = Proof::new_elastic_with_config(
+        fixture.stream(),
+        CommitterKeyStream::from(&ck),
+        &digest,
+        &elastic_config,
+    );
+    assert!(elastic_proof == Ok(proof));
+
+    // invalid configurations are rejected.
+    assert_eq!(
+        ProverConfig::new().with_num_threads(0),
+        Err(ConfigError::NoThreads)
+    );
+    assert_eq!(
+        ProverConfig::new().with_chunk_size(0),
+        Err(ConfigError::EmptyChunks)
+    );
+    assert!(
+        Proof::new_elastic_with_config(
+            fixture.stream(),
+            CommitterKeyStream::from(&ck),
+            &digest,
+            &fixed_window_config,
+        ) == Err(ConfigError::UnsupportedByElasticProver(
+            "fixed-window commitments"
+        ))
+    );
+}
+
+#[test]
+fn test_snark_transcript_domain() {
+    use crate::snark::ProverConfig;
+
+    let rng = &mut test_rng();
+    let num_constraints = 8;
+    let num_variables = 8;
+
+    let circuit = random_circuit(rng, num_constraints, num_variables);
+    let r1cs = generate_relation(circuit);
+    let ck = CommitterKey::<Bls12_381>::new(8 * (num_constraints + num_variables), 5, rng);
+    let vk = VerifierKey::from(&ck);
+
+    let config = ProverConfig::new()
+        .with_domain_separator(b"deployment-a")
+        .with_session_id(b"session-0");
+    let proof = Proof::new_time_with_config(&r1cs, &ck, &config);
+    assert!(proof
+        .verify_with_config(&r1cs, &r1cs.x, &vk, &config)
+        .is_ok());
+    assert!(proof.verify(&r1cs, &r1cs.x, &vk).is_err());
+
+    // another deployment, or another session, rejects the proof.
+    let other_deployment = ProverConfig::new()
+        .with_domain_separator(b"deployment-b")
+        .with_session_id(b"session-0");
+    assert!(proof
+        .verify_with_config(&r1cs, &r1cs.x, &vk, &other_deployment)
+        .is_err());
+    let other_session = ProverConfig::new()
+        .with_domain_separator(b"deployment-a")
+        .with_session_id(b"session-1");
+    assert!(proof
+        .verify_with_config(&r1cs, &r1cs.x, &vk, &other_session)
+        .is_err());
+}
+
+#[test]
+fn test_snark_verification_errors() {
+    use crate::errors::VerificationError;
+
+    let rng = &mut test_rng();
+    let num_constraints = 8;
+    let num_variables = 8;
+
+    let r1cs = generate_relation(random_circuit(rng, num_constraints, num_variables));
+    let ck = CommitterKey::<Bls12_381>::new(num_constraints + num_variables, 5, rng);
+    let vk = VerifierKey::from(&ck);
+    let proof = Proof::new_time(&r1cs, &ck);
+
+    // a different statement changes the challenges of the first sumcheck.
+    let mut wrong_x = r1cs.x.clone();
+    wrong_x[0] += r1cs.x[0];
+    assert!(matches!(
+        proof.verify(&r1cs, &wrong_x, &vk),
+        Err(VerificationError::SumcheckRound(_))
+    ));
+
+    // a larger circuit requires more rounds in the first sumcheck.
+    let mut larger_r1cs = r1cs.clone();
+    larger_r1cs.a.resize(2 * num_constraints, Vec::new());
+    larger_r1cs.b.resize(2 * num_constraints, Vec::new());
+    larger_r1cs.c.resize(2 * num_constraints, Vec::new());
+    assert_eq!(
+        proof.verify(&larger_r1cs, &r1cs.x, &vk),
+        Err(VerificationError::TranscriptMismatch)
+    );
+
+    assert_eq!(
+        Proof::batch_verify(&[proof], &r1cs, &[], &vk),
+        Err(VerificationError::InvalidInput)
+    );
+}
+
+#[test]
+fn test_snark_bn254() {
+    use ark_bn254::Bn254;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+    let rng = &mut test_rng();
+    let num_constraints = 20;
+    let num_variables = 20;
+
+    let circuit = random_circuit(rng, num_constraints, num_variables);
+    let r1cs = generate_relation(circuit);
+    let ck = CommitterKey::<Bn254>::new(num_constraints + num_variables, 5, rng);
+    let vk = VerifierKey::from(&ck);
 
     let time_proof = Proof::new_time(&r1cs, &ck);
-    assert!(time_proof.verify(&r1cs, &vk).is_ok())
+    assert!(time_proof.verify(&r1cs, &r1cs.x, &vk).is_ok());
+
+    let mut bytes = Vec::new();
+    time_proof.serialize_compressed(&mut bytes).unwrap();
+    let estimate = Proof::<Bn254>::estimate(&r1cs, crate::snark::ProverMode::Time);
+    assert_eq!(bytes.len(), estimate.proof_bytes);
+    let decoded = Proof::<Bn254>::deserialize_compressed(bytes.as_slice()).unwrap();
+    assert!(decoded == time_proof);
+    assert!(decoded.verify(&r1cs, &r1cs.x, &vk).is_ok());
+
+    // field elements and points of BN254 fit in a single EVM word.
+    let calldata = time_proof.to_calldata(&r1cs.x);
+    assert_eq!(calldata.len() % crate::snark::calldata::WORD_SIZE, 0);
 }
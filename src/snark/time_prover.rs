@@ -1,17 +1,24 @@
 //! The Time prover for the algebraic proofs.
 use ark_ec::pairing::Pairing;
-use ark_ff::{Field, One, Zero};
+use ark_ff::{Field, One};
+use ark_std::mem::size_of;
+use ark_std::vec::Vec;
 use log::debug;
 
-use crate::circuit::R1cs;
-use crate::kzg::CommitterKey;
-use crate::misc::{evaluate_le, hadamard};
-use crate::misc::{powers, product_matrix_vector, tensor};
-use crate::snark::Proof;
+use crate::circuit::{DataParallelR1cs, R1cs};
+use crate::kzg::{CommitterKey, FixedWindowMsm, VerifierKey};
+use crate::misc::{evaluate_le, hadamard, join, wipe};
+use crate::misc::{powers, product_matrix_vector, tensor_prefix, vector_times_matrix};
+use crate::snark::observer::{observe_round, observe_tensorcheck};
+use crate::snark::{CircuitDigest, Proof, ProverConfig, ProverObserver, ProverPhase};
 use crate::subprotocols::sumcheck::proof::Sumcheck;
+use crate::subprotocols::sumcheck::time_prover::Witness;
+use crate::subprotocols::sumcheck::TimeProver;
 use crate::subprotocols::tensorcheck::TensorcheckProof;
 use crate::transcript::GeminiTranscript;
-use crate::PROTOCOL_NAME;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 impl<E: Pairing> Proof<E> {
     /// Given as input the R1CS instance `r1cs` and the committer key `ck` for the polynomial commitment scheme,
@@ -20,6 +27,53 @@ impl<E: Pairing> Proof<E> {
     where
         E: Pairing,
     {
+        Self::prove_time(r1cs, ck, &ProverConfig::default(), &mut ())
+    }
+
+    /// Given as input the R1CS instance `r1cs` and the committer key `ck` for the polynomial commitment scheme,
+    /// produce a new SNARK proof using the time-efficient prover, notifying `observer` of its progress.
+    pub fn new_time_observed<O: ProverObserver + ?Sized>(
+        r1cs: &R1cs<E::ScalarField>,
+        ck: &CommitterKey<E>,
+        observer: &mut O,
+    ) -> Proof<E> {
+        Self::prove_time(r1cs, ck, &ProverConfig::default(), observer)
+    }
+
+    /// Given as input the R1CS instance `r1cs` and the committer key `ck` for the polynomial commitment scheme,
+    /// produce a new SNARK proof using the time-efficient prover configured with `config`.
+    pub fn new_time_with_config(
+        r1cs: &R1cs<E::ScalarField>,
+        ck: &CommitterKey<E>,
+        config: &ProverConfig,
+    ) -> Proof<E> {
+        config.install(|| Self::prove_time(r1cs, ck, config, &mut ()))
+    }
+
+    /// Given as input the data-parallel R1CS instance `r1cs` and the committer key `ck`
+    /// for the polynomial commitment scheme,
+    /// produce a new SNARK proof using the time-efficient prover.
+    ///
+    /// The repeated matrices are never materialized:
+    /// the prover reads only the matrices of the sub-circuit,
+    /// exploiting the tensor structure of the sumcheck challenges across copies.
+    /// The proof is the same as the one produced by [`Proof::new_time`] for [`DataParallelR1cs::to_r1cs`],
+    /// against which it must be verified.
+    pub fn new_data_parallel(
+        r1cs: &DataParallelR1cs<E::ScalarField>,
+        ck: &CommitterKey<E>,
+    ) -> Proof<E> {
+        Self::prove_time(r1cs, ck, &ProverConfig::default(), &mut ())
+    }
+
+    /// The time-efficient prover, with the transcript domain and the witness commitment of `config`.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(constraints = r1cs.num_constraints(), variables = r1cs.z().len())))]
+    fn prove_time<O: ProverObserver + ?Sized>(
+        r1cs: &impl TimeRelation<E>,
+        ck: &CommitterKey<E>,
+        config: &ProverConfig,
+        observer: &mut O,
+    ) -> Proof<E> {
         let snark_time = start_timer!(|| module_path!());
 
         debug!(
@@ -29,18 +83,24 @@ impl<E: Pairing> Proof<E> {
             crate::misc::TENSOR_EXPANSION_LOG,
         );
 
-        let z_a = product_matrix_vector(&r1cs.a, &r1cs.z);
-        let z_b = product_matrix_vector(&r1cs.b, &r1cs.z);
-        let z_c = product_matrix_vector(&r1cs.c, &r1cs.z);
+        let [mut z_a, mut z_b, mut z_c] = r1cs.product_matrices_vector();
+        let field_size = size_of::<E::ScalarField>();
+        observer.memory_in_use(3 * z_a.len() * field_size);
 
-        let mut transcript = merlin::Transcript::new(PROTOCOL_NAME);
-        // transcript.append_serializable(b"r1cs-a", &r1cs.a);
-        // transcript.append_serializable(b"r1cs-b", &r1cs.b);
-        // transcript.append_serializable(b"r1cs-c", &r1cs.c);
+        let mut transcript = config.transcript_domain.transcript();
+        r1cs.digest(&VerifierKey::from(ck))
+            .append_to(&mut transcript);
 
+        observer.phase_started(ProverPhase::WitnessCommitment);
         let witness_commitment_time = start_timer!(|| "Commitment to w");
-        let witness_commitment = ck.commit(&r1cs.w);
+        let witness_commitment = if config.fixed_window_commit {
+            ck.commit_with(&FixedWindowMsm, r1cs.w())
+        } else {
+            ck.commit(r1cs.w())
+        };
         end_timer!(witness_commitment_time);
+        observer.msm_completed(r1cs.w().len());
+        observer.phase_finished(ProverPhase::WitnessCommitment);
 
         transcript.append_serializable(b"witness", &witness_commitment);
         let alpha = transcript.get_challenge(b"alpha");
@@ -48,55 +108,55 @@ impl<E: Pairing> Proof<E> {
         let zc_alpha = evaluate_le(&z_c, &alpha);
         transcript.append_serializable(b"zc(alpha)", &zc_alpha);
 
+        observer.phase_started(ProverPhase::FirstSumcheck);
         let first_sumcheck_time = start_timer!(|| "First sumcheck");
-        let first_proof = Sumcheck::new_time(&mut transcript, &z_a, &z_b, &alpha);
+        // the sumcheck prover holds a copy of z_a, z_b.
+        observer.memory_in_use(5 * z_a.len() * field_size);
+        let first_proof = Sumcheck::prove_with(
+            &mut transcript,
+            TimeProver::new(Witness::new(&z_a, &z_b, &alpha)),
+            |prover| {
+                observe_round::<E::ScalarField, _, _>(observer, ProverPhase::FirstSumcheck, prover)
+            },
+        );
         let first_sumcheck_msgs = first_proof.prover_messages();
         end_timer!(first_sumcheck_time);
-
-        let b_challenges = tensor(&first_proof.challenges);
-        let c_challenges = powers(alpha, b_challenges.len());
-        let a_challenges = hadamard(&b_challenges, &c_challenges);
+        observer.phase_finished(ProverPhase::FirstSumcheck);
 
         let eta = transcript.get_challenge::<E::ScalarField>(b"eta");
-        let eta2 = eta.square();
-
-        let mut abc_tensored = vec![E::ScalarField::zero(); r1cs.z.len()];
-
-        for (i, row_a) in r1cs.a.iter().enumerate() {
-            for &(val, col) in row_a {
-                abc_tensored[col] += a_challenges[i] * val;
-            }
-        }
-
-        for (i, row_b) in r1cs.b.iter().enumerate() {
-            for &(val, col) in row_b {
-                abc_tensored[col] += eta * b_challenges[i] * val;
-            }
-        }
-
-        for (i, row_c) in r1cs.c.iter().enumerate() {
-            for &(val, col) in row_c {
-                abc_tensored[col] += eta2 * c_challenges[i] * val;
-            }
-        }
+        observer.phase_started(ProverPhase::SecondSumcheck);
+        let abc_tensored = r1cs.tensored_matrices(&first_proof.challenges, alpha, eta);
 
         let second_sumcheck_time = start_timer!(|| "Second sumcheck");
-        let second_proof = Sumcheck::new_time(
+        // the vector z_c, the challenges, the tensored matrices,
+        // and the copy of the sumcheck prover.
+        observer.memory_in_use(
+            (z_c.len() + 3 * (1 << first_proof.challenges.len()) + 3 * abc_tensored.len())
+                * field_size,
+        );
+        let second_proof = Sumcheck::prove_with(
             &mut transcript,
-            &abc_tensored,
-            &r1cs.z,
-            &E::ScalarField::one(),
+            TimeProver::new(Witness::new(
+                &abc_tensored,
+                r1cs.z(),
+                &E::ScalarField::one(),
+            )),
+            |prover| {
+                observe_round::<E::ScalarField, _, _>(observer, ProverPhase::SecondSumcheck, prover)
+            },
         );
         let second_sumcheck_msgs = second_proof.prover_messages();
         end_timer!(second_sumcheck_time);
+        observer.phase_finished(ProverPhase::SecondSumcheck);
 
         // derive the points needed from the challenges
-        let tc_base_polynomials = [&r1cs.w];
-        let second_sumcheck_polynomials = [&abc_tensored, &r1cs.z];
+        let tc_base_polynomials = [r1cs.w()];
+        let second_sumcheck_polynomials = [&abc_tensored, r1cs.z()];
         let tc_body_polynomials = [(
             &second_sumcheck_polynomials[..],
             &second_proof.challenges[..],
         )];
+        observer.phase_started(ProverPhase::Tensorcheck);
         let tensorcheck_time = start_timer!(|| "Tensorcheck");
         let tensorcheck_proof = TensorcheckProof::new_time(
             &mut transcript,
@@ -105,7 +165,12 @@ impl<E: Pairing> Proof<E> {
             tc_body_polynomials,
         );
         end_timer!(tensorcheck_time);
+        observe_tensorcheck(observer, &tensorcheck_proof, abc_tensored.len());
+        observer.phase_finished(ProverPhase::Tensorcheck);
 
+        for z in [&mut z_a, &mut z_b, &mut z_c] {
+            wipe(z);
+        }
         end_timer!(snark_time);
         Proof {
             witness_commitment,
@@ -116,3 +181,103 @@ impl<E: Pairing> Proof<E> {
         }
     }
 }
+
+/// An R1CS instance the time-efficient prover can work with.
+trait TimeRelation<E: Pairing> {
+    fn num_constraints(&self) -> usize;
+    fn w(&self) -> &Vec<E::ScalarField>;
+    fn z(&self) -> &Vec<E::ScalarField>;
+    fn digest(&self, vk: &VerifierKey<E>) -> CircuitDigest;
+    /// Compute \(A \vec z, B \vec z, C \vec z\).
+    fn product_matrices_vector(&self) -> [Vec<E::ScalarField>; 3];
+    /// Compute \(\vec r_A A + \eta \vec r_B B + \eta^2 \vec r_C C\)
+    /// for the first sumcheck `challenges`, see [`crate::snark`].
+    fn tensored_matrices(
+        &self,
+        challenges: &[E::ScalarField],
+        alpha: E::ScalarField,
+        eta: E::ScalarField,
+    ) -> Vec<E::ScalarField>;
+}
+
+impl<E: Pairing> TimeRelation<E> for R1cs<E::ScalarField> {
+    fn num_constraints(&self) -> usize {
+        self.a.len()
+    }
+
+    fn w(&self) -> &Vec<E::ScalarField> {
+        &self.w
+    }
+
+    fn z(&self) -> &Vec<E::ScalarField> {
+        &self.z
+    }
+
+    fn digest(&self, vk: &VerifierKey<E>) -> CircuitDigest {
+        CircuitDigest::new(self, &self.x, vk)
+    }
+
+    fn product_matrices_vector(&self) -> [Vec<E::ScalarField>; 3] {
+        [&self.a, &self.b, &self.c].map(|matrix| product_matrix_vector(matrix, &self.z))
+    }
+
+    fn tensored_matrices(
+        &self,
+        challenges: &[E::ScalarField],
+        alpha: E::ScalarField,
+        eta: E::ScalarField,
+    ) -> Vec<E::ScalarField> {
+        // only the entries of the constraints are needed.
+        let b_challenges = tensor_prefix(challenges, self.a.len());
+        let c_challenges = powers(alpha, b_challenges.len());
+        let a_challenges = hadamard(&b_challenges, &c_challenges);
+        let eta2 = eta.square();
+
+        let num_columns = self.z.len();
+        let (a_tensored, (b_tensored, c_tensored)) = join(
+            || vector_times_matrix(&a_challenges, &self.a, num_columns),
+            || {
+                join(
+                    || vector_times_matrix(&b_challenges, &self.b, num_columns),
+                    || vector_times_matrix(&c_challenges, &self.c, num_columns),
+                )
+            },
+        );
+        cfg_into_iter!(a_tensored)
+            .zip(b_tensored)
+            .zip(c_tensored)
+            .map(|((a, b), c)| a + eta * b + eta2 * c)
+            .collect::<Vec<_>>()
+    }
+}
+
+impl<E: Pairing> TimeRelation<E> for DataParallelR1cs<E::ScalarField> {
+    fn num_constraints(&self) -> usize {
+        DataParallelR1cs::num_constraints(self)
+    }
+
+    fn w(&self) -> &Vec<E::ScalarField> {
+        &self.w
+    }
+
+    fn z(&self) -> &Vec<E::ScalarField> {
+        &self.z
+    }
+
+    fn digest(&self, vk: &VerifierKey<E>) -> CircuitDigest {
+        CircuitDigest::new_data_parallel(self, self.x(), vk)
+    }
+
+    fn product_matrices_vector(&self) -> [Vec<E::ScalarField>; 3] {
+        DataParallelR1cs::product_matrices_vector(self)
+    }
+
+    fn tensored_matrices(
+        &self,
+        challenges: &[E::ScalarField],
+        alpha: E::ScalarField,
+        eta: E::ScalarField,
+    ) -> Vec<E::ScalarField> {
+        DataParallelR1cs::tensored_matrices(self, challenges, alpha, eta)
+    }
+}
@@ -1,43 +1,288 @@
 //! The verifier for the algebraic proofs.
 use ark_ec::pairing::Pairing;
+use ark_ff::Field;
+use ark_std::log2;
+use ark_std::vec::Vec;
+use merlin::Transcript;
 
 use crate::circuit::R1cs;
 use crate::errors::{VerificationError, VerificationResult};
-use crate::kzg::VerifierKey;
-use crate::misc::{
-    evaluate_le, hadamard_unsafe, ip, ip_unsafe, powers, product_matrix_vector, tensor,
-};
-use crate::snark::Proof;
+use crate::kzg::{MultiPointVerifier, VerifierKey};
+use crate::misc::{evaluate_be, hadamard_unsafe, ip, powers, tensor_prefix};
+use crate::snark::config::transcript_domains;
+use crate::snark::digest::matrices_digest;
+use crate::snark::{CircuitDigest, Proof, ProverConfig};
 use crate::subprotocols::sumcheck::Subclaim;
-use crate::transcript::GeminiTranscript;
-use crate::PROTOCOL_NAME;
+use crate::subprotocols::tensorcheck::MultiPointClaim;
+use crate::transcript::{GeminiTranscript, TranscriptDomain};
+
+/// The state of the verifier right before the evaluation of the matrices.
+struct VerifierState<F: Field> {
+    transcript: Transcript,
+    /// The randomness for the rows of \\(A, B, C\\), already batched with \\(\eta\\).
+    row_randomness: [Vec<F>; 3],
+    /// The subclaim of the second sumcheck.
+    subclaim: Subclaim<F>,
+    /// The batching challenge of the tensorcheck.
+    gamma: F,
+    /// The evaluation challenge of the tensorcheck.
+    beta: F,
+}
+
+/// Evaluate, in a single pass over the matrices of `r1cs`,
+/// the polynomial \\(M(X) = \sum_{i, j} (\vec r_A A + \eta \vec r_B B + \eta^2 \vec r_C C)_{i, j} X^j \\)
+/// at \\(\beta\\) and \\(-\beta\\) for each of the verifier `states`.
+///
+/// The polynomial is split in its even and odd parts \\(M(X) = M_e(X^2) + X M_o(X^2)\\),
+/// each one evaluated once at \\(\beta^2\\), so that
+/// \\(M(\pm \beta) = M_e(\beta^2) \pm \beta M_o(\beta^2)\\).
+fn evaluate_matrices<F: Field>(r1cs: &R1cs<F>, states: &[VerifierState<F>]) -> Vec<[F; 2]> {
+    // the matrices may have more columns than rows.
+    let num_constraints = r1cs.a.len();
+    let num_columns = r1cs
+        .a
+        .iter()
+        .chain(&r1cs.b)
+        .chain(&r1cs.c)
+        .flatten()
+        .map(|&(_, col)| col + 1)
+        .fold(num_constraints, usize::max);
+    let beta2_powers = states
+        .iter()
+        .map(|state| powers(state.beta.square(), (num_columns + 1) / 2))
+        .collect::<Vec<_>>();
+
+    let mut even_odd = vec![[F::zero(); 2]; states.len()];
+    for (m, matrix) in [&r1cs.a, &r1cs.b, &r1cs.c].into_iter().enumerate() {
+        for (i, row) in matrix.iter().enumerate() {
+            for &(val, col) in row {
+                for ((parts, state), beta2_powers) in
+                    even_odd.iter_mut().zip(states).zip(&beta2_powers)
+                {
+                    parts[col & 1] += val * state.row_randomness[m][i] * beta2_powers[col >> 1];
+                }
+            }
+        }
+    }
+    even_odd
+        .into_iter()
+        .zip(states)
+        .map(|([even, odd], state)| even_odd_evaluations(even, odd, state.beta))
+        .collect()
+}
+
+/// Evaluate the polynomial `polynomial` at \\(\beta\\) and \\(-\beta\\),
+/// computing its even and odd parts once.
+fn evaluate_pm<F: Field>(polynomial: &[F], beta: F) -> [F; 2] {
+    let beta2 = beta.square();
+    let even = evaluate_be(polynomial.iter().step_by(2).rev(), &beta2);
+    let odd = evaluate_be(polynomial.iter().skip(1).step_by(2).rev(), &beta2);
+    even_odd_evaluations(even, odd, beta)
+}
+
+/// Given the evaluations `even`, `odd` of the even and odd parts of a polynomial at \\(\beta^2\\),
+/// return the evaluations of the polynomial at \\(\beta\\) and \\(-\beta\\).
+#[inline]
+fn even_odd_evaluations<F: Field>(even: F, odd: F, beta: F) -> [F; 2] {
+    let odd = beta * odd;
+    [even + odd, even - odd]
+}
 
 impl<E: Pairing> Proof<E> {
     /// Verification function for SNARK proof.
-    /// The input contains the R1CS instance and the verification key
-    /// of polynomial commitment.
-    pub fn verify(&self, r1cs: &R1cs<E::ScalarField>, vk: &VerifierKey<E>) -> VerificationResult {
-        let mut transcript = merlin::Transcript::new(PROTOCOL_NAME);
-        // transcript.append_serializable(b"r1cs-a", &r1cs.a);
-        // transcript.append_serializable(b"r1cs-b", &r1cs.b);
-        // transcript.append_serializable(b"r1cs-c", &r1cs.c);
+    /// The input contains the circuit description `r1cs`, of which only the matrices are read,
+    /// the statement `public_inputs` (including the constant term and the padding),
+    /// and the verification key of polynomial commitment,
+    /// either as a [`VerifierKey`](crate::kzg::VerifierKey)
+    /// or as a [`PreparedVerifierKey`](crate::kzg::PreparedVerifierKey).
+    pub fn verify<V: MultiPointVerifier<E>>(
+        &self,
+        r1cs: &R1cs<E::ScalarField>,
+        public_inputs: &[E::ScalarField],
+        vk: &V,
+    ) -> VerificationResult {
+        self.verify_with_domain(r1cs, public_inputs, vk, &TranscriptDomain::default())
+    }
+
+    /// Verification function for SNARK proofs produced with the configuration `config`,
+    /// see [`Proof::new_time_with_config`] and [`Proof::new_elastic_with_config`].
+    ///
+    /// Only the transcript domain of `config` is read.
+    pub fn verify_with_config<V: MultiPointVerifier<E>>(
+        &self,
+        r1cs: &R1cs<E::ScalarField>,
+        public_inputs: &[E::ScalarField],
+        vk: &V,
+        config: &ProverConfig,
+    ) -> VerificationResult {
+        self.verify_with_domain(r1cs, public_inputs, vk, &config.transcript_domain)
+    }
+
+    /// Verify the proof against a transcript with domain `domain`.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(constraints = r1cs.a.len(), public_inputs = public_inputs.len())))]
+    fn verify_with_domain<V: MultiPointVerifier<E>>(
+        &self,
+        r1cs: &R1cs<E::ScalarField>,
+        public_inputs: &[E::ScalarField],
+        vk: &V,
+        domain: &TranscriptDomain,
+    ) -> VerificationResult {
+        let digest = CircuitDigest::new(r1cs, public_inputs, vk.verifier_key());
+        let states = [self.verifier_state(r1cs, &digest, domain)?];
+        let matrix_evaluations = evaluate_matrices(r1cs, &states);
+        let [state] = states;
+        let claim = self.multi_point_claim(state, public_inputs, matrix_evaluations[0])?;
+
+        vk.verify_multi_points(
+            &claim.commitments,
+            &claim.eval_points,
+            &claim.evaluations,
+            &self.tensorcheck_proof.evaluation_proof,
+            &claim.open_chal,
+        )
+        .map_err(|_| VerificationError::PairingCheck)
+    }
+
+    /// Batch verification of many SNARK proofs `proofs` for the same circuit `r1cs`,
+    /// each one for the statement at the same position in `public_inputs`.
+    ///
+    /// The matrices are hashed and evaluated in a single pass for all proofs,
+    /// and the final pairing checks are combined with random scalars into a single multi-pairing.
+    /// The verifier fails if any of the proofs is invalid.
+    pub fn batch_verify<V: MultiPointVerifier<E>>(
+        proofs: &[Self],
+        r1cs: &R1cs<E::ScalarField>,
+        public_inputs: &[&[E::ScalarField]],
+        vk: &V,
+    ) -> VerificationResult {
+        let domain = TranscriptDomain::default();
+        let domains = vec![&domain; proofs.len()];
+        Self::batch_verify_with_domains(proofs, r1cs, public_inputs, vk, &domains)
+    }
 
-        let witness_commitment = self.witness_commitment;
+    /// Batch verification of many SNARK proofs `proofs` for the same circuit `r1cs`,
+    /// each one for the statement and produced with the configuration at the same position
+    /// in `public_inputs` and `configs` respectively, see [`Proof::verify_with_config`].
+    pub fn batch_verify_with_config<V: MultiPointVerifier<E>>(
+        proofs: &[Self],
+        r1cs: &R1cs<E::ScalarField>,
+        public_inputs: &[&[E::ScalarField]],
+        vk: &V,
+        configs: &[ProverConfig],
+    ) -> VerificationResult {
+        let domains = transcript_domains(configs);
+        Self::batch_verify_with_domains(proofs, r1cs, public_inputs, vk, &domains)
+    }
+
+    /// Batch verification of many SNARK proofs `proofs`,
+    /// each one against a transcript with the domain at the same position in `domains`.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(proofs = proofs.len(), constraints = r1cs.a.len())))]
+    fn batch_verify_with_domains<V: MultiPointVerifier<E>>(
+        proofs: &[Self],
+        r1cs: &R1cs<E::ScalarField>,
+        public_inputs: &[&[E::ScalarField]],
+        vk: &V,
+        domains: &[&TranscriptDomain],
+    ) -> VerificationResult {
+        let vk = vk.verifier_key();
+        let claims = Self::multi_point_claims(proofs, r1cs, public_inputs, vk, domains)?;
+
+        let mut batch_transcript = Transcript::new(b"gemini-batch-verify");
+        let mut equations = Vec::with_capacity(proofs.len());
+        for (proof, claim) in proofs.iter().zip(claims) {
+            let equation = vk
+                .multi_points_equation(
+                    &claim.commitments,
+                    &claim.eval_points,
+                    &claim.evaluations,
+                    &claim.open_chal,
+                )
+                .map_err(|_| VerificationError::PairingCheck)?;
+            batch_transcript.append_serializable(b"equation-lhs", &equation.0);
+            batch_transcript.append_serializable(b"equation-rhs", &equation.1);
+            batch_transcript
+                .append_serializable(b"proof", &proof.tensorcheck_proof.evaluation_proof);
+            equations.push(equation);
+        }
+        let randomizers = (0..proofs.len())
+            .map(|_| batch_transcript.get_challenge::<E::ScalarField>(b"randomizer"))
+            .collect::<Vec<_>>();
+        let evaluation_proofs = proofs
+            .iter()
+            .map(|proof| proof.tensorcheck_proof.evaluation_proof.clone())
+            .collect::<Vec<_>>();
+        vk.verify_multi_points_equations(&equations, &evaluation_proofs, &randomizers)
+            .map_err(|_| VerificationError::PairingCheck)
+    }
 
-        transcript.append_serializable(b"witness", &witness_commitment);
+    /// Verify the sumchecks and the tensorchecks of `proofs` for the same circuit `r1cs`,
+    /// each one for the statement and against the transcript domain at the same position
+    /// in `public_inputs` and `domains` respectively,
+    /// and return the multi-point evaluation claims left to check.
+    ///
+    /// The matrices are hashed and evaluated in a single pass for all proofs.
+    pub(super) fn multi_point_claims(
+        proofs: &[Self],
+        r1cs: &R1cs<E::ScalarField>,
+        public_inputs: &[&[E::ScalarField]],
+        vk: &VerifierKey<E>,
+        domains: &[&TranscriptDomain],
+    ) -> Result<Vec<MultiPointClaim<E>>, VerificationError> {
+        if proofs.len() != public_inputs.len() || proofs.len() != domains.len() {
+            return Err(VerificationError::InvalidInput);
+        }
+        let matrices_digest = matrices_digest(r1cs);
+        let digests = public_inputs
+            .iter()
+            .map(|x| CircuitDigest::from_matrices_digest(&matrices_digest, x, vk))
+            .collect::<Vec<_>>();
+
+        let states = proofs
+            .iter()
+            .zip(&digests)
+            .zip(domains)
+            .map(|((proof, digest), domain)| proof.verifier_state(r1cs, digest, domain))
+            .collect::<Result<Vec<_>, _>>()?;
+        let matrix_evaluations = evaluate_matrices(r1cs, &states);
+        proofs
+            .iter()
+            .zip(states)
+            .zip(public_inputs)
+            .zip(matrix_evaluations)
+            .map(|(((proof, state), x), matrix_evaluation)| {
+                proof.multi_point_claim(state, x, matrix_evaluation)
+            })
+            .collect()
+    }
+
+    /// Replay the transcript up to the evaluation challenge of the tensorcheck,
+    /// checking both sumchecks along the way.
+    fn verifier_state(
+        &self,
+        r1cs: &R1cs<E::ScalarField>,
+        digest: &CircuitDigest,
+        domain: &TranscriptDomain,
+    ) -> Result<VerifierState<E::ScalarField>, VerificationError> {
+        let mut transcript = domain.transcript();
+        digest.append_to(&mut transcript);
+
+        // the first sumcheck runs over the rows of the matrices.
+        let num_constraints = r1cs.a.len();
+        if self.first_sumcheck_msgs.0.len() != log2(num_constraints) as usize {
+            return Err(VerificationError::TranscriptMismatch);
+        }
+
+        transcript.append_serializable(b"witness", &self.witness_commitment);
         let alpha = transcript.get_challenge(b"alpha");
-        let first_sumcheck_msgs = &self.first_sumcheck_msgs;
 
         // First sumcheck
         transcript.append_serializable(b"zc(alpha)", &self.zc_alpha);
-
-        let subclaim_1 = Subclaim::new(&mut transcript, first_sumcheck_msgs, self.zc_alpha)?;
+        let subclaim_1 = Subclaim::new(&mut transcript, &self.first_sumcheck_msgs, self.zc_alpha)?;
 
         let eta = transcript.get_challenge::<E::ScalarField>(b"eta");
         let etas = powers(eta, 3);
 
-        let num_constraints = r1cs.a.len();
-        let tensor_challenges = tensor(&subclaim_1.challenges);
+        let tensor_challenges = tensor_prefix(&subclaim_1.challenges, num_constraints);
         let alpha_powers = powers(alpha, num_constraints);
         let hadamard_randomness = hadamard_unsafe(&tensor_challenges, &alpha_powers);
 
@@ -50,50 +295,51 @@ impl<E: Pairing> Proof<E> {
             ],
             &etas,
         );
-
         let subclaim_2 =
             Subclaim::new(&mut transcript, &self.second_sumcheck_msgs, asserted_sum_2)?;
 
         // Tensorcheck
         let gamma = transcript.get_challenge::<E::ScalarField>(b"batch_challenge");
-        self.tensorcheck_proof
-            .folded_polynomials_commitments
-            .iter()
-            .for_each(|c| transcript.append_serializable(b"commitment", c));
+        transcript.append_serializables(
+            b"commitment",
+            &self.tensorcheck_proof.folded_polynomials_commitments,
+        );
         let beta = transcript.get_challenge::<E::ScalarField>(b"evaluation-chal");
-        let beta_powers = powers(beta, num_constraints);
-        let minus_beta_powers = powers(-beta, num_constraints);
 
-        let a_beta_powers = product_matrix_vector(&r1cs.a, &beta_powers);
-        let b_beta_powers = product_matrix_vector(&r1cs.b, &beta_powers);
-        let c_beta_powers = product_matrix_vector(&r1cs.c, &beta_powers);
-        let a_minus_beta_powers = product_matrix_vector(&r1cs.a, &minus_beta_powers);
-        let b_minus_beta_powers = product_matrix_vector(&r1cs.b, &minus_beta_powers);
-        let c_minus_beta_powers = product_matrix_vector(&r1cs.c, &minus_beta_powers);
+        let batch = |randomness: &[E::ScalarField], eta: &E::ScalarField| {
+            randomness.iter().map(|r| *r * eta).collect::<Vec<_>>()
+        };
+        let row_randomness = [
+            batch(&hadamard_randomness, &etas[0]),
+            batch(&tensor_challenges, &etas[1]),
+            batch(&alpha_powers, &etas[2]),
+        ];
+        Ok(VerifierState {
+            transcript,
+            row_randomness,
+            subclaim: subclaim_2,
+            gamma,
+            beta,
+        })
+    }
 
-        let m_pos = ip(
-            &[
-                ip(&a_beta_powers, &hadamard_randomness),
-                ip_unsafe(&b_beta_powers, &tensor_challenges),
-                ip(&c_beta_powers, &alpha_powers),
-            ],
-            &etas,
-        );
-        let m_neg = ip(
-            &[
-                ip(&a_minus_beta_powers, &hadamard_randomness),
-                ip_unsafe(&b_minus_beta_powers, &tensor_challenges),
-                ip(&c_minus_beta_powers, &alpha_powers),
-            ],
-            &etas,
-        );
+    /// Given the evaluations of the matrices at \\(\beta, -\beta\\),
+    /// complete the verification of the tensorcheck
+    /// up to the final multi-point evaluation claim.
+    fn multi_point_claim(
+        &self,
+        mut state: VerifierState<E::ScalarField>,
+        public_inputs: &[E::ScalarField],
+        [m_pos, m_neg]: [E::ScalarField; 2],
+    ) -> Result<MultiPointClaim<E>, VerificationError> {
+        let beta = state.beta;
+        let gamma = state.gamma;
 
-        let beta_power = beta_powers[r1cs.x.len()];
-        let x_beta = evaluate_le(&r1cs.x, &beta);
-        let x_minus_beta = evaluate_le(&r1cs.x, &-beta);
+        let beta_power = beta.pow([public_inputs.len() as u64]);
+        let [x_beta, x_minus_beta] = evaluate_pm(public_inputs, beta);
         let z_pos = x_beta + beta_power * self.tensorcheck_proof.base_polynomials_evaluations[0][1];
 
-        let beta_power = if (r1cs.x.len() & 1) == 0 {
+        let beta_power = if (public_inputs.len() & 1) == 0 {
             beta_power
         } else {
             -beta_power
@@ -104,17 +350,14 @@ impl<E: Pairing> Proof<E> {
         let direct_base_polynomials_evaluations =
             vec![[m_pos + gamma * z_pos, m_neg + gamma * z_neg]];
 
-        self.tensorcheck_proof
-            .verify(
-                &mut transcript,
-                vk,
-                &[subclaim_2.final_foldings[0].to_vec()],
-                &[self.witness_commitment],
-                &direct_base_polynomials_evaluations,
-                &[subclaim_2.challenges],
-                beta,
-                gamma,
-            )
-            .map_err(|_| VerificationError)
+        self.tensorcheck_proof.reduce(
+            &mut state.transcript,
+            &[state.subclaim.final_foldings[0].to_vec()],
+            &[self.witness_commitment],
+            &direct_base_polynomials_evaluations,
+            &[state.subclaim.challenges],
+            beta,
+            gamma,
+        )
     }
 }
@@ -0,0 +1,143 @@
+//! The wire format of SNARK proofs.
+//!
+//! A serialized [`Proof`] starts with the magic bytes [`PROOF_MAGIC`] and a version byte,
+//! followed by the prover's messages in the order they are sent throughout the protocol.
+//! The version byte is incremented whenever the layout of the proof changes,
+//! and deserialization dispatches on it so that proofs serialized by earlier versions can still be read.
+use ark_ec::pairing::Pairing;
+use ark_serialize::*;
+
+use crate::snark::Proof;
+
+/// The magic bytes heading every serialized proof.
+pub const PROOF_MAGIC: [u8; 4] = *b"GMNI";
+/// The current version of the wire format.
+pub const PROOF_VERSION: u8 = 1;
+
+impl<E: Pairing> Proof<E> {
+    /// Return the size, in bytes, of the compressed proof, including the header.
+    pub fn serialized_size(&self) -> usize {
+        CanonicalSerialize::serialized_size(self, Compress::Yes)
+    }
+}
+
+impl<E: Pairing> CanonicalSerialize for Proof<E> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        writer.write_all(&PROOF_MAGIC)?;
+        writer.write_all(&[PROOF_VERSION])?;
+        self.witness_commitment
+            .serialize_with_mode(&mut writer, compress)?;
+        self.zc_alpha.serialize_with_mode(&mut writer, compress)?;
+        self.first_sumcheck_msgs
+            .serialize_with_mode(&mut writer, compress)?;
+        self.second_sumcheck_msgs
+            .serialize_with_mode(&mut writer, compress)?;
+        self.tensorcheck_proof
+            .serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        PROOF_MAGIC.len()
+            + 1
+            + self.witness_commitment.serialized_size(compress)
+            + self.zc_alpha.serialized_size(compress)
+            + self.first_sumcheck_msgs.serialized_size(compress)
+            + self.second_sumcheck_msgs.serialized_size(compress)
+            + self.tensorcheck_proof.serialized_size(compress)
+    }
+}
+
+impl<E: Pairing> Valid for Proof<E> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.witness_commitment.check()?;
+        self.zc_alpha.check()?;
+        self.first_sumcheck_msgs.check()?;
+        self.second_sumcheck_msgs.check()?;
+        self.tensorcheck_proof.check()
+    }
+}
+
+impl<E: Pairing> CanonicalDeserialize for Proof<E> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if magic != PROOF_MAGIC {
+            return Err(SerializationError::InvalidData);
+        }
+        let mut version = [0u8; 1];
+        reader.read_exact(&mut version)?;
+        match version[0] {
+            1 => Ok(Proof {
+                witness_commitment: CanonicalDeserialize::deserialize_with_mode(
+                    &mut reader,
+                    compress,
+                    validate,
+                )?,
+                zc_alpha: CanonicalDeserialize::deserialize_with_mode(
+                    &mut reader,
+                    compress,
+                    validate,
+                )?,
+                first_sumcheck_msgs: CanonicalDeserialize::deserialize_with_mode(
+                    &mut reader,
+                    compress,
+                    validate,
+                )?,
+                second_sumcheck_msgs: CanonicalDeserialize::deserialize_with_mode(
+                    &mut reader,
+                    compress,
+                    validate,
+                )?,
+                tensorcheck_proof: CanonicalDeserialize::deserialize_with_mode(
+                    &mut reader,
+                    compress,
+                    validate,
+                )?,
+            }),
+            _ => Err(SerializationError::InvalidData),
+        }
+    }
+}
+
+#[test]
+fn test_proof_wire_format() {
+    use crate::circuit::{generate_relation, random_circuit};
+    use crate::kzg::{CommitterKey, VerifierKey};
+    use ark_std::vec::Vec;
+    use ark_test_curves::bls12_381::Bls12_381;
+
+    let rng = &mut ark_std::test_rng();
+    let num_constraints = 8;
+    let num_variables = 8;
+
+    let r1cs = generate_relation(random_circuit(rng, num_constraints, num_variables));
+    let ck = CommitterKey::<Bls12_381>::new(num_constraints + num_variables, 5, rng);
+    let vk = VerifierKey::from(&ck);
+    let proof = Proof::new_time(&r1cs, &ck);
+
+    let mut bytes = Vec::new();
+    proof.serialize_compressed(&mut bytes).unwrap();
+    assert_eq!(bytes.len(), proof.serialized_size());
+    assert_eq!(&bytes[..4], &PROOF_MAGIC);
+    assert_eq!(bytes[4], PROOF_VERSION);
+
+    let deserialized = Proof::<Bls12_381>::deserialize_compressed(&bytes[..]).unwrap();
+    assert_eq!(deserialized, proof);
+    assert!(deserialized.verify(&r1cs, &r1cs.x, &vk).is_ok());
+
+    // unknown versions and foreign data are rejected.
+    let mut future = bytes.clone();
+    future[4] = PROOF_VERSION + 1;
+    assert!(Proof::<Bls12_381>::deserialize_compressed(&future[..]).is_err());
+    let mut foreign = bytes;
+    foreign[0] ^= 1;
+    assert!(Proof::<Bls12_381>::deserialize_compressed(&foreign[..]).is_err());
+}
@@ -5,8 +5,6 @@ use ark_std::boxed::Box;
 use ark_std::vec::Vec;
 use ark_std::One;
 
-use merlin::Transcript;
-
 use crate::iterable::Iterable;
 use crate::kzg::CommitterKeyStream;
 use crate::misc::evaluate_be;
@@ -32,7 +30,7 @@ impl<'a, E: Pairing, S: Iterable<Item = E::ScalarField>>
     /// Create a new (single) entry product arugment
     /// using the commiter key `ck` and the stream `v`, whose grand product is `claimed_product`.
     pub fn new_elastic<SG>(
-        transcript: &mut Transcript,
+        transcript: &mut impl GeminiTranscript,
         ck: &CommitterKeyStream<E, SG>,
         v: &'a S,
         claimed_product: E::ScalarField,
@@ -70,7 +68,7 @@ macro_rules! impl_elastic_batch {
         #[allow(non_snake_case)]
         #[allow(unused_assignments)]
         pub fn $name<SG, $($B),*>(
-            transcript: &mut Transcript,
+            transcript: &mut impl GeminiTranscript,
             ck: &CommitterKeyStream<E, SG>,
             vs: ($(&'a $B,)*),
             claimed_products: &[E::ScalarField],
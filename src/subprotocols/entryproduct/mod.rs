@@ -45,7 +45,7 @@ mod tests;
 /// Sometimes the verifier already knows the entry product result.
 /// For this reason, the product $t$ is never sent or added to the transcript.
 /// It is expected that the developer takes care of it in the upper protocol layer.
-#[derive(CanonicalSerialize, Debug, PartialEq, Eq)]
+#[derive(CanonicalSerialize, CanonicalDeserialize, Debug, PartialEq, Eq)]
 pub struct ProverMsgs<E: Pairing> {
     pub acc_v_commitments: Vec<Commitment<E>>,
     pub claimed_sumchecks: Vec<E::ScalarField>,
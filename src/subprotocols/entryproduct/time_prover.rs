@@ -2,7 +2,6 @@ use ark_ec::pairing::Pairing;
 use ark_ff::Field;
 use ark_std::boxed::Box;
 use ark_std::vec::Vec;
-use merlin::Transcript;
 
 use super::{EntryProduct, ProverMsgs};
 use crate::kzg::CommitterKey;
@@ -59,7 +58,7 @@ impl<E: Pairing> EntryProduct<E, Box<dyn Prover<E::ScalarField>>> {
     /// # Panics
     /// If the length of the claimed products differs from the length of `vs`.
     pub fn new_time_batch(
-        transcript: &mut Transcript,
+        transcript: &mut impl GeminiTranscript,
         ck: &CommitterKey<E>,
         vs: &[Vec<E::ScalarField>],
         claimed_products: &[E::ScalarField],
@@ -115,7 +114,7 @@ impl<E: Pairing> EntryProduct<E, Box<dyn Prover<E::ScalarField>>> {
 
     /// Creates a new grand product argument using the time prover.
     pub fn new_time(
-        transcript: &mut Transcript,
+        transcript: &mut impl GeminiTranscript,
         ck: &CommitterKey<E>,
         v: &[E::ScalarField],
         claimed_product: E::ScalarField,
@@ -3,7 +3,7 @@ mod init_stream;
 mod read_stream;
 mod write_stream;
 
-use std::borrow::Borrow;
+use ark_std::borrow::Borrow;
 
 use ark_ff::Field;
 pub use audit_stream::AuditStream;
@@ -5,6 +5,10 @@
 //!     an argument for claims of the form \\(\langle f, \otimes_j (1, \rho_j) \rangle = t\\).
 //!     This can be used for proving batches of multivariate evaluations claims using
 //!     univariate polynomial commitments.
+//!     [`tensorcheck::Tensorcheck`] exposes it as a standalone protocol,
+//!     [`tensorcheck::KaryTensorcheckProof`] folds by a configurable arity,
+//!     [`tensorcheck::PackedTensorcheckProof`] commits to all folded polynomials at once,
+//!     and [`tensorcheck::MultiPointTensorcheckProof`] checks each instance on its own evaluation point.
 //! - [`sumcheck::proof::Sumcheck`],
 //!    the multivariate sumcheck implementation, implemented in 3 flavours: linear-time, log-space, and elastic.
 //! - [`entryproduct::EntryProduct`],
@@ -0,0 +1,168 @@
+//! Constraints for the sumcheck verifier.
+//!
+//! Recursive verification of Gemini proofs requires checking the sumcheck within a circuit.
+//! Since [`merlin`] transcripts are expensive to express as constraints,
+//! the recursion-friendly flavour of the protocol derives its challenges from the Poseidon sponge:
+//! [`prove_with_sponge`] runs the sumcheck absorbing the prover's messages into a [`PoseidonSponge`],
+//! and [`enforce_sumcheck`] verifies the same messages within an [`R1csBuilder`],
+//! absorbing them into the corresponding [`PoseidonSpongeVar`].
+//!
+//! As for [`Subclaim`](super::Subclaim), the gadget returns the challenges and the final foldings,
+//! leaving the sub-claims to the caller.
+//!
+//! # Scope
+//!
+//! The gadget is written against the crate's own [`R1csBuilder`], not [`ark_r1cs_std`](https://docs.rs/ark-r1cs-std):
+//! it cannot be composed with `FpVar`s or other `ark_r1cs_std` gadgets,
+//! and the circuit it produces is consumed through [`R1csBuilder`] only.
+//! `ark-r1cs-std` requires an `ark-relations` and `ark-ff` that do not unify with the ones this crate is built on.
+//! Nor does this module verify a whole Gemini proof recursively:
+//! it only checks the sumcheck rounds, as a building block for such a verifier.
+use ark_ff::Field;
+use ark_std::vec::Vec;
+
+use crate::circuit::gadgets::{LinearCombination, PoseidonSponge, PoseidonSpongeVar, R1csBuilder};
+use crate::subprotocols::sumcheck::prover::{Prover, ProverMsgs, RoundMsg};
+
+/// Run the sumcheck with `prover`, deriving the challenges from `sponge`,
+/// and return the prover's messages.
+pub fn prove_with_sponge<F: Field, P: Prover<F>>(
+    sponge: &mut PoseidonSponge<F>,
+    mut prover: P,
+) -> ProverMsgs<F> {
+    let mut messages = Vec::with_capacity(prover.rounds());
+    let mut verifier_message = None;
+    while let Some(message) = prover.next_message(verifier_message) {
+        sponge.absorb(&[message.0, message.1]);
+        verifier_message = Some(sponge.squeeze());
+        messages.push(message);
+    }
+    let final_foldings = prover.final_foldings().unwrap();
+    sponge.absorb(&final_foldings);
+    ProverMsgs(messages, ark_std::vec![final_foldings])
+}
+
+/// The prover's messages of a single sumcheck instance, allocated in the circuit.
+#[derive(Clone, Debug)]
+pub struct ProverMsgsVar<F: Field> {
+    /// The coefficients \\(a, b\\) of each round polynomial.
+    pub messages: Vec<(LinearCombination<F>, LinearCombination<F>)>,
+    /// The final foldings \\(t_0, t_1\\).
+    pub final_folding: [LinearCombination<F>; 2],
+}
+
+impl<F: Field> ProverMsgsVar<F> {
+    /// Allocate the messages `prover_messages` of a single sumcheck instance as witness variables.
+    ///
+    /// # Panics
+    /// If `prover_messages` lacks the final foldings.
+    pub fn new_witness(builder: &mut R1csBuilder<F>, prover_messages: &ProverMsgs<F>) -> Self {
+        let ProverMsgs(messages, final_foldings) = prover_messages;
+        let [t0, t1] = final_foldings[0];
+        let messages = messages
+            .iter()
+            .map(|&RoundMsg(a, b)| (builder.new_witness(a).into(), builder.new_witness(b).into()))
+            .collect();
+        let final_folding = [
+            builder.new_witness(t0).into(),
+            builder.new_witness(t1).into(),
+        ];
+        ProverMsgsVar {
+            messages,
+            final_folding,
+        }
+    }
+}
+
+/// The subclaim of the sumcheck, allocated in the circuit.
+#[derive(Clone, Debug)]
+pub struct SubclaimVar<F: Field> {
+    /// The verifier's challenges \\(\rho_0, \dots, \rho_{n-1}\\).
+    pub challenges: Vec<LinearCombination<F>>,
+    /// The subclaim \\(t_0, t_1\\).
+    pub final_folding: [LinearCombination<F>; 2],
+}
+
+/// Enforce that `prover_messages` are a valid sumcheck proof for the claim `asserted_sum`,
+/// as produced by [`prove_with_sponge`] over the native counterpart of `sponge`,
+/// and return the subclaim.
+///
+/// Besides the sponge, each round costs two multiplications, and the final check one more.
+pub fn enforce_sumcheck<F: Field>(
+    builder: &mut R1csBuilder<F>,
+    sponge: &mut PoseidonSpongeVar<F>,
+    prover_messages: &ProverMsgsVar<F>,
+    asserted_sum: impl Into<LinearCombination<F>>,
+) -> SubclaimVar<F> {
+    let mut reduced_claim = asserted_sum.into();
+    let mut challenges = Vec::with_capacity(prover_messages.messages.len());
+    for (a, b) in &prover_messages.messages {
+        sponge.absorb(builder, &[a.clone(), b.clone()]);
+        let r = sponge.squeeze(builder);
+
+        let c = reduced_claim - a.clone();
+        // evaluate (a + bx + cx2) at r
+        let cr = builder.mul(c, r.clone());
+        let bcr = builder.mul(b.clone() + cr, r.clone());
+        reduced_claim = a.clone() + bcr;
+        challenges.push(r);
+    }
+
+    let [t0, t1] = &prover_messages.final_folding;
+    sponge.absorb(builder, &[t0.clone(), t1.clone()]);
+    builder.enforce(t0.clone(), t1.clone(), reduced_claim);
+
+    SubclaimVar {
+        challenges,
+        final_folding: prover_messages.final_folding.clone(),
+    }
+}
+
+#[test]
+fn test_sumcheck_verifier_gadget() {
+    use ark_ff::{One, Zero};
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::Fr;
+
+    use crate::circuit::gadgets::PoseidonParameters;
+    use crate::misc::{ip, tensor};
+    use crate::subprotocols::sumcheck::time_prover::{TimeProver, Witness};
+
+    let mds = vec![
+        vec![Fr::one(), Fr::zero(), Fr::one()],
+        vec![Fr::one(), Fr::one(), Fr::zero()],
+        vec![Fr::zero(), Fr::one(), Fr::one()],
+    ];
+    let ark = vec![vec![Fr::one(); 3]; 39];
+    let parameters = PoseidonParameters::new(8, 31, 17, mds, ark, 2, 1);
+
+    let rng = &mut ark_std::test_rng();
+    let d = 1 << 4;
+    let f = (0..d).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+    let g = (0..d).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+    let asserted_sum = ip(&f, &g);
+
+    let prover = TimeProver::new(Witness::new(&f, &g, &Fr::one()));
+    let prover_messages = prove_with_sponge(&mut PoseidonSponge::new(&parameters), prover);
+
+    for (sum, satisfied) in [(asserted_sum, true), (asserted_sum + Fr::one(), false)] {
+        let mut builder = R1csBuilder::new();
+        let messages_var = ProverMsgsVar::new_witness(&mut builder, &prover_messages);
+        let sum_var = builder.new_input(sum);
+        let mut sponge = PoseidonSpongeVar::new(&parameters);
+        let subclaim = enforce_sumcheck(&mut builder, &mut sponge, &messages_var, sum_var);
+
+        // the challenges match the ones of the native sponge.
+        let challenges = subclaim
+            .challenges
+            .iter()
+            .map(|r| builder.value(r))
+            .collect::<Vec<_>>();
+        let rhos = tensor(&challenges);
+        assert_eq!(
+            prover_messages.final_foldings()[0],
+            [ip(&f, &rhos), ip(&g, &rhos)]
+        );
+        assert_eq!(builder.build().is_satisfied().is_ok(), satisfied);
+    }
+}
@@ -0,0 +1,295 @@
+//! The sumcheck prover for witnesses sharded across multiple machines.
+//!
+//! The witness \\(f, g\\) of length \\(n\\) is split by a [`Sharding`] into contiguous shards
+//! of \\(2^s\\) coefficients each, and every shard is held by a [`Worker`].
+//! In each of the first \\(s\\) rounds, every worker computes the round message over its own shard,
+//! and the coordinator [`DistributedProver`] sums the partial messages into the message of the round;
+//! the challenge is then broadcast to the workers, which fold their shards locally.
+//! Shards are aligned to their size, so the pairs of coefficients folded together never cross two shards.
+//! After \\(s\\) rounds each shard is folded into a single coefficient,
+//! and the coordinator gathers them and completes the remaining \\(\log n - s\\) rounds on its own.
+//!
+//! The messages are the same as the ones of [`TimeProver`] over the whole witness.
+//! The commitments to the witness are split across the same workers via
+//! [`CommitterKey::commit_shard`](crate::kzg::CommitterKey::commit_shard).
+//!
+//! The [`Worker`] trait leaves the transport to the caller:
+//! [`ShardProver`] is the worker computing on a local shard,
+//! and a remote worker forwards the same calls to the machine holding the shard.
+use ark_ff::Field;
+use ark_std::log2;
+use ark_std::ops::Range;
+use ark_std::vec::Vec;
+
+use crate::misc::{ceil_div, fold_polynomial, BufferPool};
+use crate::subprotocols::sumcheck::prover::{Prover, RoundMsg};
+use crate::subprotocols::sumcheck::TimeProver;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// The split of a witness of length `len` into shards of `shard_len` coefficients.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Sharding {
+    /// The length of the witness.
+    pub len: usize,
+    /// The length of each shard, a power of two. The last shard may be shorter.
+    pub shard_len: usize,
+}
+
+impl Sharding {
+    /// Split a witness of length `len` into at most `max_shards` shards of the same length.
+    pub fn new(len: usize, max_shards: usize) -> Self {
+        assert!(max_shards > 0, "At least one shard is needed.");
+        let shard_len = usize::max(ceil_div(len, max_shards), 1).next_power_of_two();
+        Sharding { len, shard_len }
+    }
+
+    /// Return the number of shards.
+    pub fn num_shards(&self) -> usize {
+        ceil_div(self.len, self.shard_len)
+    }
+
+    /// Return the range of the coefficients in the `index`-th shard.
+    pub fn range(&self, index: usize) -> Range<usize> {
+        let start = index * self.shard_len;
+        start..usize::min(start + self.shard_len, self.len)
+    }
+
+    /// Return the number of rounds run by the workers, before the shards are gathered.
+    pub fn sharded_rounds(&self) -> usize {
+        log2(self.shard_len) as usize
+    }
+}
+
+/// A worker holding a shard of the witness, on behalf of a [`DistributedProver`].
+pub trait Worker<F: Field> {
+    /// Return the partial message of the current round, computed over the shard.
+    fn round_message(&mut self) -> RoundMsg<F>;
+    /// Fold the shard with the challenge `challenge`.
+    fn fold(&mut self, challenge: F);
+    /// Return the coefficients of \\(f, g\\) the shard has been folded into.
+    fn final_foldings(&self) -> [F; 2];
+}
+
+/// A worker computing on a shard of the witness held in memory.
+pub struct ShardProver<F: Field> {
+    /// The shard of `f`.
+    f: Vec<F>,
+    /// The shard of `g`.
+    g: Vec<F>,
+    /// The twist, squared at each folding.
+    twist: F,
+    /// The twist raised to the offset of the shard.
+    ///
+    /// At each folding the twist is squared and the offset halved, hence the shift stays the same.
+    shift: F,
+}
+
+impl<F: Field> ShardProver<F> {
+    /// Create a new worker for the shards `f`, `g` of the witness starting at `offset`,
+    /// of the scalar product twisted by `twist`.
+    /// This will cause a copy of the shard.
+    pub fn new(f: &[F], g: &[F], twist: F, offset: usize) -> Self {
+        ShardProver {
+            f: f.to_vec(),
+            g: g.to_vec(),
+            twist,
+            shift: twist.pow([offset as u64]),
+        }
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<F: Field> Drop for ShardProver<F> {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.f);
+        zeroize::Zeroize::zeroize(&mut self.g);
+    }
+}
+
+impl<F: Field> Worker<F> for ShardProver<F> {
+    fn round_message(&mut self) -> RoundMsg<F> {
+        let zero = F::zero();
+        let twist2 = self.twist.square();
+        let mut twist_runner = self.shift;
+        let mut a = F::zero();
+        let mut b = F::zero();
+        for (f_pair, g_pair) in self.f.chunks(2).zip(self.g.chunks(2)) {
+            let f_odd = f_pair.get(1).unwrap_or(&zero);
+            let g_odd = g_pair.get(1).unwrap_or(&zero);
+            a += f_pair[0] * g_pair[0] * twist_runner;
+            b += (f_pair[0] * g_odd + g_pair[0] * f_odd * self.twist) * twist_runner;
+            twist_runner *= twist2;
+        }
+        RoundMsg(a, b)
+    }
+
+    fn fold(&mut self, challenge: F) {
+        self.f = fold_polynomial(&self.f, challenge * self.twist);
+        self.g = fold_polynomial(&self.g, challenge);
+        self.twist.square_in_place();
+    }
+
+    fn final_foldings(&self) -> [F; 2] {
+        [
+            self.f.first().copied().unwrap_or_default(),
+            self.g.first().copied().unwrap_or_default(),
+        ]
+    }
+}
+
+/// The state of a [`DistributedProver`]:
+/// first the shards are folded by the workers, then gathered by the coordinator.
+enum State<F: Field, W> {
+    Sharded { workers: Vec<W>, round: usize },
+    Gathered(TimeProver<F>),
+}
+
+/// The coordinator of the workers proving a twisted scalar product over a sharded witness.
+pub struct DistributedProver<F: Field, W: Worker<F>> {
+    state: State<F, W>,
+    /// The twist, squared at each folding.
+    twist: F,
+    /// The number of rounds run by the workers.
+    sharded_rounds: usize,
+    /// Total number of rounds.
+    tot_rounds: usize,
+}
+
+impl<F: Field, W: Worker<F> + Send + Sync> DistributedProver<F, W> {
+    /// Create a new coordinator for the scalar product twisted by `twist`,
+    /// whose witness is split by `sharding` across `workers`, in the order of the shards.
+    pub fn new(sharding: Sharding, twist: F, workers: Vec<W>) -> Self {
+        assert_eq!(
+            workers.len(),
+            sharding.num_shards(),
+            "One worker per shard."
+        );
+        let mut prover = DistributedProver {
+            state: State::Sharded { workers, round: 0 },
+            twist,
+            sharded_rounds: sharding.sharded_rounds(),
+            tot_rounds: log2(sharding.len) as usize,
+        };
+        prover.gather_if_folded();
+        prover
+    }
+
+    /// Return true if the shards are still held by the workers.
+    pub fn is_sharded(&self) -> bool {
+        matches!(self.state, State::Sharded { .. })
+    }
+
+    /// Gather the shards from the workers once they are folded into a single coefficient.
+    fn gather_if_folded(&mut self) {
+        let (workers, round) = match &self.state {
+            State::Sharded { workers, round } if *round == self.sharded_rounds => (workers, *round),
+            _ => return,
+        };
+        let (f, g) = workers
+            .iter()
+            .map(|worker| {
+                let [f, g] = worker.final_foldings();
+                (f, g)
+            })
+            .unzip();
+        self.state = State::Gathered(TimeProver {
+            f,
+            g,
+            round,
+            twist: self.twist,
+            tot_rounds: self.tot_rounds,
+            buffers: BufferPool::default(),
+        });
+    }
+}
+
+impl<F: Field, W: Worker<F> + Send + Sync> Prover<F> for DistributedProver<F, W> {
+    /// Broadcast the challenge to the workers, or fold the gathered instance.
+    fn fold(&mut self, challenge: F) {
+        match &mut self.state {
+            State::Sharded { workers, .. } => {
+                cfg_iter_mut!(workers).for_each(|worker| worker.fold(challenge));
+                self.twist.square_in_place();
+                self.gather_if_folded();
+            }
+            State::Gathered(prover) => prover.fold(challenge),
+        }
+    }
+
+    /// Sum the partial messages of the workers.
+    fn next_message(&mut self, verifier_message: Option<F>) -> Option<RoundMsg<F>> {
+        if let Some(challenge) = verifier_message {
+            self.fold(challenge);
+        }
+        let (workers, round) = match &mut self.state {
+            State::Sharded { workers, round } => (workers, round),
+            State::Gathered(prover) => return prover.next_message(None),
+        };
+        assert!(*round < self.tot_rounds, "More rounds than needed.");
+
+        let message = cfg_iter_mut!(workers)
+            .map(|worker| worker.round_message())
+            .sum();
+        *round += 1;
+        Some(message)
+    }
+
+    #[inline]
+    fn rounds(&self) -> usize {
+        self.tot_rounds
+    }
+
+    fn round(&self) -> usize {
+        match &self.state {
+            State::Sharded { round, .. } => *round,
+            State::Gathered(prover) => prover.round(),
+        }
+    }
+
+    fn final_foldings(&self) -> Option<[F; 2]> {
+        match &self.state {
+            State::Sharded { .. } => None,
+            State::Gathered(prover) => prover.final_foldings(),
+        }
+    }
+}
+
+#[test]
+fn test_distributed_prover() {
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::Fr;
+    use merlin::Transcript;
+
+    use crate::subprotocols::sumcheck::time_prover::Witness;
+    use crate::subprotocols::sumcheck::Sumcheck;
+
+    let rng = &mut ark_std::test_rng();
+    for (len, max_shards) in [(1, 1), (16, 1), (16, 4), (29, 4), (64, 64), (100, 3)] {
+        let f = (0..len).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+        let g = (0..len - len / 3)
+            .map(|_| Fr::rand(rng))
+            .collect::<Vec<_>>();
+        let twist = Fr::rand(rng);
+
+        let sharding = Sharding::new(len, max_shards);
+        assert!(sharding.num_shards() <= max_shards);
+        let workers = (0..sharding.num_shards())
+            .map(|i| {
+                let range = sharding.range(i);
+                let g_range = usize::min(range.start, g.len())..usize::min(range.end, g.len());
+                ShardProver::new(&f[range.clone()], &g[g_range], twist, range.start)
+            })
+            .collect::<Vec<_>>();
+        let prover = DistributedProver::new(sharding, twist, workers);
+        assert_eq!(prover.is_sharded(), sharding.sharded_rounds() > 0);
+
+        let sumcheck = Sumcheck::prove(&mut Transcript::new(b"distributed"), prover);
+        let expected = Sumcheck::prove(
+            &mut Transcript::new(b"distributed"),
+            TimeProver::new(Witness::new(&f, &g, &twist)),
+        );
+        assert_eq!(sumcheck, expected);
+    }
+}
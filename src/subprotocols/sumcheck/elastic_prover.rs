@@ -1,17 +1,30 @@
+use crate::transcript::GeminiTranscript;
 use ark_ff::Field;
 use ark_std::borrow::Borrow;
+use ark_std::mem::size_of;
+use ark_std::vec::Vec;
 
 use crate::{iterable::Iterable, SPACE_TIME_THRESHOLD};
 
-use super::prover::RoundMsg;
+use super::proof::Sumcheck;
+use super::prover::{ProverMsgs, RoundMsg};
 use super::{Prover, SpaceProver, TimeProver};
 
 /// Specifier of the prover type (time-efficient or space-efficient).
+///
+/// The space-efficient prover carries the memory budget, in bytes,
+/// within which the folded instance is handed over to the time-efficient prover.
 pub enum ElasticProver<S, T> {
-    Space(S),
+    Space(S, usize),
     Time(T),
 }
 
+/// Return the default memory budget of the elastic prover for elements of `F`,
+/// switching to the time-efficient prover for the last [`SPACE_TIME_THRESHOLD`] rounds.
+fn default_memory_budget<F: Field>() -> usize {
+    size_of::<F>() << SPACE_TIME_THRESHOLD
+}
+
 impl<F, S1, S2, T> ElasticProver<SpaceProver<F, S1, S2>, T>
 where
     F: Field,
@@ -22,10 +35,50 @@ where
 {
     /// Initialize the elastic prover.
     pub fn new(f: S1, g: S2, twist: F) -> Self {
-        Self::Space(SpaceProver::new(f, g, twist))
+        Self::Space(SpaceProver::new(f, g, twist), default_memory_budget::<F>())
+    }
+
+    /// Switch to the time-efficient prover as soon as the folded instance
+    /// fits within `memory_budget` bytes, instead of for the last [`SPACE_TIME_THRESHOLD`] rounds.
+    pub fn with_memory_budget(self, memory_budget: usize) -> Self {
+        match self {
+            Self::Space(p, _) => Self::Space(p, memory_budget),
+            time => time,
+        }
+    }
+
+    /// Switch to the time-efficient prover from round `round` on, regardless of the memory budget.
+    ///
+    /// The first round always reads the streams (as would the conversion to the time-efficient prover),
+    /// thus a crossover round of zero behaves as one.
+    /// With a crossover round past the last round, the prover streams throughout the protocol.
+    pub fn with_crossover_round(self, round: usize) -> Self {
+        match self {
+            Self::Space(p, _) => {
+                let rounds = p.rounds();
+                let round = usize::max(round, 1);
+                // the memory budget fitting the folded instance at the beginning of `round`.
+                let memory_budget = if round <= rounds {
+                    (2 * size_of::<F>()) << (rounds - round)
+                } else {
+                    0
+                };
+                Self::Space(p, memory_budget)
+            }
+            time => time,
+        }
     }
 }
 
+/// The mode in which the elastic prover computed the message of a round.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoundMode {
+    /// The message was computed with a pass over the streams.
+    Streaming,
+    /// The message was computed over the folded instance held in memory.
+    InMemory,
+}
+
 impl<F, S1, S2> Prover<F> for ElasticProver<SpaceProver<F, S1, S2>, TimeProver<F>>
 where
     F: Field,
@@ -35,16 +88,22 @@ where
     S2::Item: Borrow<F>,
 {
     fn next_message(&mut self, verifier_message: Option<F>) -> Option<RoundMsg<F>> {
+        // fold here, so that the prover can switch to the time-efficient one.
+        if let Some(challenge) = verifier_message {
+            self.fold(challenge);
+        }
         match self {
-            Self::Space(p) => p.next_message(verifier_message),
-            Self::Time(p) => p.next_message(verifier_message),
+            Self::Space(p, _) => p.next_message(None),
+            Self::Time(p) => p.next_message(None),
         }
     }
 
     fn fold(&mut self, challenge: F) {
         match self {
-            Self::Space(p) => {
-                if p.rounds() - p.round() < SPACE_TIME_THRESHOLD {
+            Self::Space(p, memory_budget) => {
+                // the time-efficient prover stores both vectors before folding them.
+                let folded_size = (2 * size_of::<F>()) << (p.rounds() - p.round());
+                if folded_size <= *memory_budget {
                     let mut time_prover = TimeProver::from(&*p);
                     time_prover.fold(challenge);
                     *self = Self::Time(time_prover);
@@ -58,22 +117,130 @@ where
 
     fn rounds(&self) -> usize {
         match self {
-            Self::Space(p) => p.rounds(),
+            Self::Space(p, _) => p.rounds(),
             Self::Time(p) => p.rounds(),
         }
     }
 
     fn round(&self) -> usize {
         match self {
-            Self::Space(p) => p.round(),
+            Self::Space(p, _) => p.round(),
             Self::Time(p) => p.round(),
         }
     }
 
     fn final_foldings(&self) -> Option<[F; 2]> {
         match self {
-            Self::Space(p) => p.final_foldings(),
+            Self::Space(p, _) => p.final_foldings(),
             Self::Time(p) => p.final_foldings(),
         }
     }
+
+    fn is_streaming(&self) -> bool {
+        matches!(self, Self::Space(..))
+    }
+}
+
+/// The sumcheck prover for the scalar product of two streams, to be used as a standalone protocol.
+///
+/// The prover starts reading the streams in space-efficient mode,
+/// and switches to the time-efficient prover once the folded instance fits in memory
+/// (see [`ElasticProver`]).
+/// Its messages are verified with [`Subclaim::new`](super::Subclaim::new).
+pub struct SumcheckProver<F, SF, SG>(ElasticProver<SpaceProver<F, SF, SG>, TimeProver<F>>)
+where
+    F: Field,
+    SF: Iterable,
+    SG: Iterable,
+    SF::Item: Borrow<F>,
+    SG::Item: Borrow<F>;
+
+impl<F, SF, SG> SumcheckProver<F, SF, SG>
+where
+    F: Field,
+    SF: Iterable,
+    SG: Iterable,
+    SF::Item: Borrow<F>,
+    SG::Item: Borrow<F>,
+{
+    /// Create a new prover for the scalar product \\(\langle f, g \rangle\\)
+    /// of the polynomials `f` and `g`, streamed starting from their leading coefficient.
+    pub fn new(f: SF, g: SG) -> Self {
+        Self::new_twisted(f, g, F::one())
+    }
+
+    /// Create a new prover for the twisted scalar product \\(\langle f \circ (1, \tau, \tau^2, \dots), g \rangle\\)
+    /// of the polynomials `f` and `g`, streamed starting from their leading coefficient.
+    pub fn new_twisted(f: SF, g: SG, twist: F) -> Self {
+        SumcheckProver(ElasticProver::new(f, g, twist))
+    }
+
+    /// Switch to the time-efficient prover as soon as the folded instance fits within `memory_budget` bytes.
+    pub fn with_memory_budget(self, memory_budget: usize) -> Self {
+        SumcheckProver(self.0.with_memory_budget(memory_budget))
+    }
+
+    /// Switch to the time-efficient prover from round `round` on,
+    /// see [`ElasticProver::with_crossover_round`].
+    pub fn with_crossover_round(self, round: usize) -> Self {
+        SumcheckProver(self.0.with_crossover_round(round))
+    }
+
+    /// Run the sumcheck protocol over `transcript`, and return the prover's messages.
+    pub fn prove(self, transcript: &mut impl GeminiTranscript) -> ProverMsgs<F> {
+        Sumcheck::prove(transcript, self).prover_messages()
+    }
+
+    /// Run the sumcheck protocol over `transcript`, and return the prover's messages
+    /// together with the mode used in each round.
+    pub fn prove_with_modes(
+        self,
+        transcript: &mut impl GeminiTranscript,
+    ) -> (ProverMsgs<F>, Vec<RoundMode>) {
+        let mut modes = Vec::with_capacity(self.rounds());
+        let sumcheck = Sumcheck::prove_with(transcript, self, |p| {
+            // invoked once before the first round, and after computing each message.
+            if p.round() > modes.len() {
+                modes.push(if p.is_streaming() {
+                    RoundMode::Streaming
+                } else {
+                    RoundMode::InMemory
+                });
+            }
+        });
+        (sumcheck.prover_messages(), modes)
+    }
+}
+
+impl<F, SF, SG> Prover<F> for SumcheckProver<F, SF, SG>
+where
+    F: Field,
+    SF: Iterable,
+    SG: Iterable,
+    SF::Item: Borrow<F>,
+    SG::Item: Borrow<F>,
+{
+    fn next_message(&mut self, verifier_message: Option<F>) -> Option<RoundMsg<F>> {
+        self.0.next_message(verifier_message)
+    }
+
+    fn fold(&mut self, challenge: F) {
+        self.0.fold(challenge)
+    }
+
+    fn rounds(&self) -> usize {
+        self.0.rounds()
+    }
+
+    fn round(&self) -> usize {
+        self.0.round()
+    }
+
+    fn final_foldings(&self) -> Option<[F; 2]> {
+        self.0.final_foldings()
+    }
+
+    fn is_streaming(&self) -> bool {
+        self.0.is_streaming()
+    }
 }
@@ -10,9 +10,57 @@
 //!
 //! for some random challenges $\rho_0, \dots, \rho_{n-1}$ sent by the verifier
 //! and some $t_0, t_1 \in \FF$.
+//!
+//! The protocol can be used on its own:
+//! the prover [`SumcheckProver`] reads $f$ and $g$ as streams, starting from their leading coefficient,
+//! and returns the messages [`ProverMsgs`], which can be serialized with [`ark_serialize`].
+//! The verifier [`Subclaim::new`] checks the messages against the claim $u$,
+//! and returns the challenges $\rho_0, \dots, \rho_{n-1}$ together with $t_0, t_1$:
+//! it is then up to the caller to check the sub-claims.
+//!
+//! ```
+//! use ark_gemini::iterable::Reverse;
+//! use ark_gemini::misc::{ip, tensor};
+//! use ark_gemini::subprotocols::sumcheck::{ProverMsgs, Subclaim, SumcheckProver};
+//! use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+//! use ark_std::UniformRand;
+//! use ark_test_curves::bls12_381::Fr;
+//! use merlin::Transcript;
+//!
+//! let rng = &mut ark_std::test_rng();
+//! let f = (0..16).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+//! let g = (0..16).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+//! let u = ip(&f, &g);
+//!
+//! let prover = SumcheckProver::new(Reverse(f.as_slice()), Reverse(g.as_slice()));
+//! let messages = prover.prove(&mut Transcript::new(b"example"));
+//! let mut bytes = Vec::new();
+//! messages.serialize_compressed(&mut bytes).unwrap();
+//!
+//! let messages = ProverMsgs::<Fr>::deserialize_compressed(&bytes[..]).unwrap();
+//! let subclaim = Subclaim::new(&mut Transcript::new(b"example"), &messages, u).unwrap();
+//! let rhos = tensor(&subclaim.challenges);
+//! assert_eq!(subclaim.final_foldings[0], [ip(&f, &rhos), ip(&g, &rhos)]);
+//! ```
+//!
+//! Claims over the entry-wise product of more than two vectors, such as $\langle f \circ g, h \rangle = u$,
+//! are proven with [`ProductSumcheck`](self::product::ProductSumcheck),
+//! whose round polynomials have degree equal to the number of vectors.
+//! Round messages can be masked with [`ZkSumcheckProof`](self::zk::ZkSumcheckProof),
+//! so that they do not leak partial sums of the witness;
+//! the final foldings are still sent in the clear.
+//! Witnesses over a small field can be proven with challenges from an extension field
+//! via [`SmallFieldProver`], see [`small_field`].
+//! Witnesses sharded across multiple machines are proven by a [`DistributedProver`]
+//! coordinating one [`Worker`] per shard, see [`distributed`].
+pub mod constraints;
+pub mod distributed;
+pub mod product;
 pub mod proof;
 pub mod prover;
+pub mod small_field;
 pub mod streams;
+pub mod zk;
 
 /// The elastic prover implementation
 pub mod elastic_prover;
@@ -23,11 +71,16 @@ pub mod time_prover;
 
 mod subclaim;
 
-pub use elastic_prover::ElasticProver;
-pub use prover::{Prover, ProverMsgs};
+pub use distributed::{DistributedProver, ShardProver, Sharding, Worker};
+pub use elastic_prover::{ElasticProver, RoundMode, SumcheckProver};
+pub use product::{ProductProverMsgs, ProductSubclaim, ProductSumcheck};
+pub use proof::Sumcheck;
+pub use prover::{Prover, ProverMsgs, RoundMsg};
+pub use small_field::SmallFieldProver;
 pub use space_prover::SpaceProver;
 pub use subclaim::Subclaim;
 pub use time_prover::TimeProver;
+pub use zk::ZkSumcheckProof;
 
 #[cfg(test)]
 mod tests;
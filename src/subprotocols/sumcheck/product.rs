@@ -0,0 +1,357 @@
+//! The sumcheck protocol for the entry-wise product of many vectors.
+//!
+//! [`ProductSumcheck`] reduces a claim
+//! \\(\sum_i f_0[i] \cdot f_1[i] \cdots f_{d-1}[i] = u\\)
+//! over \\(d \geq 2\\) vectors of the same length
+//! (e.g., \\(\langle f \circ g, h \rangle = u\\) for custom gates and lookups)
+//! to the \\(d\\) sub-claims
+//! \\(\langle f_k, \otimes_j (1, \rho_j) \rangle = t_k\\).
+//!
+//! In each round, the vectors are split into their even and odd entries
+//! \\(f_k = (e_k, o_k)\\) and the prover sends the round polynomial
+//! \\(q(x) = \sum_i \prod_k (e_k[i] + x \cdot o_k[i])\\), of degree \\(d\\).
+//! The claim equals \\(q(0)\\) plus the leading coefficient of \\(q\\),
+//! thus the prover only sends its \\(d\\) lower coefficients, and the verifier infers the last one.
+//! The vectors are then folded as \\(e_k + \rho \cdot o_k\\), and the claim reduced to \\(q(\rho)\\).
+//! For \\(d = 2\\), this is the (untwisted) protocol of [`Sumcheck`](super::Sumcheck).
+use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::borrow::Borrow;
+use ark_std::log2;
+use ark_std::vec::Vec;
+
+use crate::errors::VerificationError;
+use crate::iterable::Iterable;
+use crate::misc::fold_polynomial;
+use crate::subprotocols::sumcheck::streams::FoldedPolynomialStream;
+use crate::transcript::GeminiTranscript;
+
+/// Messages sent by the prover throughout the product sumcheck:
+/// the lower coefficients of each round polynomial, and the final foldings \\(t_k\\).
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ProductProverMsgs<F: Field>(pub(crate) Vec<Vec<F>>, pub(crate) Vec<F>);
+
+impl<F: Field> ProductProverMsgs<F> {
+    /// Return the coefficients of the round polynomials, except their leading one.
+    pub fn messages(&self) -> &[Vec<F>] {
+        &self.0
+    }
+
+    /// Return the final foldings \\(t_k\\), one for each vector.
+    pub fn final_foldings(&self) -> &[F] {
+        &self.1
+    }
+}
+
+/// Prover interface for the product sumcheck.
+pub trait ProductProver<F: Field> {
+    /// Return the number of vectors in the product, i.e., the degree of the round polynomials.
+    fn degree(&self) -> usize;
+    /// Return the total number of rounds in the protocol.
+    fn rounds(&self) -> usize;
+    /// Fold the instance with `verifier_message` (if any),
+    /// and return the lower coefficients of the next round polynomial (if any).
+    fn next_message(&mut self, verifier_message: Option<F>) -> Option<Vec<F>>;
+    /// Return the fully-folded vectors if at the final round, otherwise return None.
+    fn final_foldings(&self) -> Option<Vec<F>>;
+}
+
+/// Add to `acc` the coefficients of \\(\prod_k (e_k + x \cdot o_k)\\), for the pairs \\((e_k, o_k)\\) in `pairs`.
+fn add_round_product<F, I>(acc: &mut [F], pairs: I)
+where
+    F: Field,
+    I: IntoIterator<Item = (F, F)>,
+{
+    let mut product = ark_std::vec![F::zero(); acc.len()];
+    product[0] = F::one();
+    for (degree, (even, odd)) in pairs.into_iter().enumerate() {
+        for t in (1..degree + 2).rev() {
+            product[t] = product[t] * even + product[t - 1] * odd;
+        }
+        product[0] *= even;
+    }
+    acc.iter_mut().zip(product).for_each(|(a, p)| *a += p);
+}
+
+/// Return the number of rounds for vectors of length `len`.
+fn required_rounds(len: usize) -> usize {
+    log2(len) as usize
+}
+
+/// The time-efficient prover, holding the vectors in memory.
+pub struct ProductTimeProver<F: Field> {
+    polynomials: Vec<Vec<F>>,
+    round: usize,
+    rounds: usize,
+}
+
+impl<F: Field> ProductTimeProver<F> {
+    /// Create a new prover for the product of `polynomials`, which must have the same length.
+    pub fn new(polynomials: &[&[F]]) -> Self {
+        assert!(polynomials.len() >= 2, "at least two vectors are expected");
+        let len = polynomials[0].len();
+        assert!(
+            polynomials.iter().all(|p| p.len() == len),
+            "vectors must have the same length"
+        );
+        ProductTimeProver {
+            polynomials: polynomials.iter().map(|p| p.to_vec()).collect(),
+            round: 0,
+            rounds: required_rounds(len),
+        }
+    }
+}
+
+impl<F: Field> ProductProver<F> for ProductTimeProver<F> {
+    fn degree(&self) -> usize {
+        self.polynomials.len()
+    }
+
+    fn rounds(&self) -> usize {
+        self.rounds
+    }
+
+    fn next_message(&mut self, verifier_message: Option<F>) -> Option<Vec<F>> {
+        if let Some(challenge) = verifier_message {
+            self.polynomials
+                .iter_mut()
+                .for_each(|p| *p = fold_polynomial(p, challenge));
+        }
+        if self.round == self.rounds {
+            return None;
+        }
+
+        let zero = F::zero();
+        let mut acc = ark_std::vec![F::zero(); self.degree() + 1];
+        let pairs = (self.polynomials[0].len() + 1) / 2;
+        for i in 0..pairs {
+            add_round_product(
+                &mut acc,
+                self.polynomials
+                    .iter()
+                    .map(|p| (p[2 * i], *p.get(2 * i + 1).unwrap_or(&zero))),
+            );
+        }
+        acc.pop();
+        self.round += 1;
+        Some(acc)
+    }
+
+    fn final_foldings(&self) -> Option<Vec<F>> {
+        (self.round == self.rounds).then(|| self.polynomials.iter().map(|p| p[0]).collect())
+    }
+}
+
+/// The space-efficient prover, reading the vectors from streams
+/// starting from their last entry.
+pub struct ProductSpaceProver<F: Field, S: Iterable> {
+    streams: Vec<S>,
+    challenges: Vec<F>,
+    round: usize,
+    rounds: usize,
+}
+
+impl<F, S> ProductSpaceProver<F, S>
+where
+    F: Field,
+    S: Iterable,
+    S::Item: Borrow<F>,
+{
+    /// Create a new prover for the product of `streams`, which must have the same length.
+    pub fn new(streams: Vec<S>) -> Self {
+        assert!(streams.len() >= 2, "at least two vectors are expected");
+        let len = streams[0].len();
+        assert!(
+            streams.iter().all(|s| s.len() == len),
+            "vectors must have the same length"
+        );
+        let rounds = required_rounds(len);
+        ProductSpaceProver {
+            streams,
+            challenges: Vec::with_capacity(rounds),
+            round: 0,
+            rounds,
+        }
+    }
+}
+
+impl<F, S> ProductProver<F> for ProductSpaceProver<F, S>
+where
+    F: Field,
+    S: Iterable,
+    S::Item: Borrow<F>,
+{
+    fn degree(&self) -> usize {
+        self.streams.len()
+    }
+
+    fn rounds(&self) -> usize {
+        self.rounds
+    }
+
+    fn next_message(&mut self, verifier_message: Option<F>) -> Option<Vec<F>> {
+        if let Some(challenge) = verifier_message {
+            self.challenges.push(challenge);
+        }
+        if self.round == self.rounds {
+            return None;
+        }
+
+        let folded = self
+            .streams
+            .iter()
+            .map(|s| FoldedPolynomialStream::new(s, &self.challenges))
+            .collect::<Vec<_>>();
+        let len = folded[0].len();
+        let mut iterators = folded.iter().map(|f| f.iter()).collect::<Vec<_>>();
+        let mut acc = ark_std::vec![F::zero(); self.degree() + 1];
+
+        // the streams start from the last entry: if their length is odd,
+        // it is an even entry without its odd counterpart.
+        if len % 2 == 1 {
+            let pairs = iterators
+                .iter_mut()
+                .map(|it| (it.next().unwrap(), F::zero()))
+                .collect::<Vec<_>>();
+            add_round_product(&mut acc, pairs);
+        }
+        for _ in 0..len / 2 {
+            let pairs = iterators
+                .iter_mut()
+                .map(|it| {
+                    let odd = it.next().unwrap();
+                    let even = it.next().unwrap();
+                    (even, odd)
+                })
+                .collect::<Vec<_>>();
+            add_round_product(&mut acc, pairs);
+        }
+        acc.pop();
+        self.round += 1;
+        Some(acc)
+    }
+
+    fn final_foldings(&self) -> Option<Vec<F>> {
+        if self.round != self.rounds {
+            return None;
+        }
+        self.streams
+            .iter()
+            .map(|s| {
+                FoldedPolynomialStream::new(s, &self.challenges)
+                    .iter()
+                    .next()
+            })
+            .collect()
+    }
+}
+
+/// A proof for the product sumcheck.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ProductSumcheck<F: Field> {
+    /// The lower coefficients of the round polynomials sent throughout the protocol.
+    pub messages: Vec<Vec<F>>,
+    /// The challenges sent throughout the protocol.
+    pub challenges: Vec<F>,
+    final_foldings: Vec<F>,
+}
+
+impl<F: Field> ProductSumcheck<F> {
+    /// Run the product sumcheck with `prover` over `transcript`.
+    pub fn prove<P: ProductProver<F>>(
+        transcript: &mut impl GeminiTranscript,
+        mut prover: P,
+    ) -> Self {
+        let rounds = prover.rounds();
+        let mut messages = Vec::with_capacity(rounds);
+        let mut challenges = Vec::with_capacity(rounds);
+
+        let mut verifier_message = None;
+        while let Some(message) = prover.next_message(verifier_message) {
+            transcript.append_serializable(b"evaluations", &message);
+            let challenge = transcript.get_challenge(b"challenge");
+            verifier_message = Some(challenge);
+
+            messages.push(message);
+            challenges.push(challenge);
+        }
+
+        let final_foldings = prover.final_foldings().unwrap();
+        transcript.append_serializable(b"final-folding", &final_foldings);
+
+        ProductSumcheck {
+            messages,
+            challenges,
+            final_foldings,
+        }
+    }
+
+    /// Prove the product of `polynomials` using the time-efficient prover.
+    pub fn new_time(transcript: &mut impl GeminiTranscript, polynomials: &[&[F]]) -> Self {
+        Self::prove(transcript, ProductTimeProver::new(polynomials))
+    }
+
+    /// Prove the product of `streams` using the space-efficient prover.
+    pub fn new_space<S>(transcript: &mut impl GeminiTranscript, streams: Vec<S>) -> Self
+    where
+        S: Iterable,
+        S::Item: Borrow<F>,
+    {
+        Self::prove(transcript, ProductSpaceProver::new(streams))
+    }
+
+    /// Return the prover's messages.
+    pub fn prover_messages(&self) -> ProductProverMsgs<F> {
+        ProductProverMsgs(self.messages.clone(), self.final_foldings.clone())
+    }
+}
+
+/// The subclaim of the product sumcheck.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ProductSubclaim<F: Field> {
+    /// The verifier's challenges \\(\rho_0, \dots, \rho_{n-1}\\).
+    pub challenges: Vec<F>,
+    /// The subclaims \\(t_0, \dots, t_{d-1}\\).
+    pub final_foldings: Vec<F>,
+}
+
+impl<F: Field> ProductSubclaim<F> {
+    /// Verify the messages `prover_messages` for the claim `asserted_sum`,
+    /// and return the subclaim.
+    pub fn new(
+        transcript: &mut impl GeminiTranscript,
+        prover_messages: &ProductProverMsgs<F>,
+        asserted_sum: F,
+    ) -> Result<Self, VerificationError> {
+        let ProductProverMsgs(messages, final_foldings) = prover_messages;
+        let degree = final_foldings.len();
+        if degree < 2 || messages.iter().any(|message| message.len() != degree) {
+            return Err(VerificationError::MalformedProof);
+        }
+
+        let mut reduced_claim = asserted_sum;
+        let mut challenges = Vec::with_capacity(messages.len());
+        for message in messages {
+            transcript.append_serializable(b"evaluations", message);
+            let r = transcript.get_challenge::<F>(b"challenge");
+            challenges.push(r);
+
+            // the leading coefficient is implied by the claim.
+            let leading = reduced_claim - message[0];
+            reduced_claim = message
+                .iter()
+                .rev()
+                .fold(leading, |acc, coefficient| acc * r + coefficient);
+        }
+        transcript.append_serializable(b"final-folding", final_foldings);
+
+        if final_foldings.iter().product::<F>() == reduced_claim {
+            Ok(ProductSubclaim {
+                challenges,
+                final_foldings: final_foldings.clone(),
+            })
+        } else {
+            Err(VerificationError::SumcheckRound(messages.len()))
+        }
+    }
+}
@@ -4,12 +4,15 @@ use ark_std::borrow::Borrow;
 use ark_std::boxed::Box;
 use ark_std::vec::Vec;
 
-use merlin::Transcript;
 #[cfg(feature = "parallel")]
 use rayon::iter::{IndexedParallelIterator, IntoParallelRefMutIterator, ParallelIterator};
 
+use crate::errors::VerificationError;
 use crate::iterable::Iterable;
-use crate::subprotocols::sumcheck::{time_prover::Witness, ElasticProver, SpaceProver, TimeProver};
+use crate::misc::powers;
+use crate::subprotocols::sumcheck::{
+    time_prover::Witness, ElasticProver, SmallFieldProver, SpaceProver, TimeProver,
+};
 use crate::transcript::GeminiTranscript;
 
 use crate::subprotocols::sumcheck::prover::{ProverMsgs, RoundMsg};
@@ -33,13 +36,30 @@ impl<F: Field> Sumcheck<F> {
     /// The input contains a randomness generator and a prover struct.
     /// The prover struct can be either time-efficient or space-efficient
     /// depending on the configuration.
-    pub fn prove<P: Prover<F>>(transcript: &mut Transcript, mut prover: P) -> Self {
+    pub fn prove<P: Prover<F>>(transcript: &mut impl GeminiTranscript, prover: P) -> Self {
+        Self::prove_with(transcript, prover, |_| ())
+    }
+
+    /// Prove function for the scalar product,
+    /// calling `on_round` with the prover before each round and once after the last one.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(rounds = prover.rounds())))]
+    pub(crate) fn prove_with<P, C>(
+        transcript: &mut impl GeminiTranscript,
+        mut prover: P,
+        mut on_round: C,
+    ) -> Self
+    where
+        P: Prover<F>,
+        C: FnMut(&P),
+    {
         let rounds = prover.rounds();
         let mut messages = Vec::with_capacity(rounds);
         let mut challenges = Vec::with_capacity(rounds);
 
         let mut verifier_message = None;
+        on_round(&prover);
         while let Some(message) = prover.next_message(verifier_message) {
+            on_round(&prover);
             // add the message sent to the transcript
             transcript.append_serializable(b"evaluations", &message);
             // compute the challenge for the next round
@@ -66,8 +86,15 @@ impl<F: Field> Sumcheck<F> {
     }
 
     /// Prove function for a batch of scalar product instances.
+    ///
+    /// The instances are combined with the powers of a single challenge,
+    /// and proven in a single run of the protocol.
+    /// Instances may have different sizes:
+    /// an instance requiring \\(k\\) rounds is zero-padded in its last variables,
+    /// i.e., it is folded with the first \\(k\\) challenges only,
+    /// and sends the constant round polynomial \\(t_0 t_1\\) afterwards.
     pub fn prove_batch<'a>(
-        transcript: &mut Transcript,
+        transcript: &mut impl GeminiTranscript,
         mut provers: Vec<Box<dyn Prover<F> + 'a>>,
     ) -> Sumcheck<F> {
         // +1 to get the final foldings
@@ -75,9 +102,8 @@ impl<F: Field> Sumcheck<F> {
         let mut messages = Vec::with_capacity(rounds);
         let mut challenges = Vec::with_capacity(rounds);
 
-        let coefficients = (0..provers.len())
-            .map(|_| transcript.get_challenge::<F>(b"batch-sumcheck"))
-            .collect::<Vec<_>>();
+        let batch_challenge = transcript.get_challenge::<F>(b"batch-sumcheck");
+        let coefficients = powers(batch_challenge, provers.len());
 
         let mut verifier_message = None;
         for _ in 0..rounds {
@@ -122,15 +148,31 @@ impl<F: Field> Sumcheck<F> {
     }
 
     /// Create a new Proof using the Time prover.
-    pub fn new_time(transcript: &mut Transcript, f: &[F], g: &[F], twist: &F) -> Self {
+    pub fn new_time(transcript: &mut impl GeminiTranscript, f: &[F], g: &[F], twist: &F) -> Self {
         let witness = Witness::new(f, g, twist);
         let prover = TimeProver::new(witness);
 
         Self::prove(transcript, prover)
     }
 
+    /// Create a new Proof using the small-field prover [`SmallFieldProver`],
+    /// for a witness over the base prime field of `F`.
+    pub fn new_small_field(
+        transcript: &mut impl GeminiTranscript,
+        f: &[F::BasePrimeField],
+        g: &[F::BasePrimeField],
+        twist: F::BasePrimeField,
+    ) -> Self {
+        Self::prove(transcript, SmallFieldProver::new(f, g, twist))
+    }
+
     /// Construct a new Proof using the space prover.
-    pub fn new_space<SF1, SF2>(transcript: &mut Transcript, f: SF1, g: SF2, twist: F) -> Self
+    pub fn new_space<SF1, SF2>(
+        transcript: &mut impl GeminiTranscript,
+        f: SF1,
+        g: SF2,
+        twist: F,
+    ) -> Self
     where
         SF1: Iterable,
         SF2: Iterable,
@@ -142,7 +184,12 @@ impl<F: Field> Sumcheck<F> {
     }
 
     /// Construct a new Proof using the Elastic prover
-    pub fn new_elastic<SF1, SF2>(transcript: &mut Transcript, f: SF1, g: SF2, twist: F) -> Self
+    pub fn new_elastic<SF1, SF2>(
+        transcript: &mut impl GeminiTranscript,
+        f: SF1,
+        g: SF2,
+        twist: F,
+    ) -> Self
     where
         SF1: Iterable,
         SF2: Iterable,
@@ -153,6 +200,36 @@ impl<F: Field> Sumcheck<F> {
         Self::prove(transcript, prover)
     }
 
+    /// Replay on `transcript` the sumcheck proof with messages `prover_messages`,
+    /// as produced by [`Sumcheck::prove`], and return it.
+    ///
+    /// Fails if `prover_messages` does not hold exactly one pair of final foldings.
+    pub(crate) fn replay(
+        transcript: &mut impl GeminiTranscript,
+        prover_messages: &ProverMsgs<F>,
+    ) -> Result<Self, VerificationError> {
+        let ProverMsgs(messages, final_foldings) = prover_messages;
+        if final_foldings.len() != 1 {
+            return Err(VerificationError::MalformedProof);
+        }
+        let challenges = messages
+            .iter()
+            .map(|message| {
+                transcript.append_serializable(b"evaluations", message);
+                transcript.get_challenge(b"challenge")
+            })
+            .collect();
+        transcript.append_serializable(b"final-folding", &final_foldings[0][0]);
+        transcript.append_serializable(b"final-folding", &final_foldings[0][1]);
+
+        Ok(Sumcheck {
+            messages: messages.clone(),
+            challenges,
+            rounds: messages.len(),
+            final_foldings: final_foldings.clone(),
+        })
+    }
+
     /// Return the prover's messages.
     pub fn prover_messages(&self) -> ProverMsgs<F> {
         ProverMsgs(self.messages.clone(), self.final_foldings.clone())
@@ -1,18 +1,30 @@
 //! Common data structures for the prover algorith in the scalar-product sub-argument.
 use ark_ff::Field;
-use ark_serialize::CanonicalSerialize;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::boxed::Box;
 use ark_std::iter::Sum;
 use ark_std::vec::Vec;
 
 /// Each message from the prover in a sumcheck protocol is a pair of FF-elements.
-#[derive(CanonicalSerialize, Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(CanonicalSerialize, CanonicalDeserialize, Copy, Clone, Debug, PartialEq, Eq)]
 pub struct RoundMsg<F: Field>(pub(crate) F, pub(crate) F);
 
 /// Messages sent by the prover throughout the protocol.
-#[derive(CanonicalSerialize, Clone, Debug, PartialEq, Eq)]
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
 pub struct ProverMsgs<F: Field>(pub(crate) Vec<RoundMsg<F>>, pub(crate) Vec<[F; 2]>);
 
+impl<F: Field> ProverMsgs<F> {
+    /// Return the messages sent in each round.
+    pub fn messages(&self) -> &[RoundMsg<F>] {
+        &self.0
+    }
+
+    /// Return the final foldings \\(t_0, t_1\\), one pair for each instance.
+    pub fn final_foldings(&self) -> &[[F; 2]] {
+        &self.1
+    }
+}
+
 impl<F: Field> Sum for RoundMsg<F> {
     fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
         iter.reduce(|fst, snd| RoundMsg(fst.0 + snd.0, fst.1 + snd.1))
@@ -21,6 +33,12 @@ impl<F: Field> Sum for RoundMsg<F> {
 }
 
 impl<F: Field> RoundMsg<F> {
+    /// Return the coefficients \\(a, b\\) of the round polynomial \\(a + bx + cx^2\\).
+    /// The quadratic coefficient \\(c\\) is implied by the claim of the round.
+    pub fn coefficients(&self) -> (F, F) {
+        (self.0, self.1)
+    }
+
     pub(crate) fn mul(self, rhs: &F) -> Self {
         RoundMsg(self.0 * rhs, self.1 * rhs)
     }
@@ -42,6 +60,10 @@ where
     /// Return the fully-folded isntances if at the final round,
     /// otherwise return None.
     fn final_foldings(&self) -> Option<[F; 2]>;
+    /// Whether the prover reads its instance from streams at the current round.
+    fn is_streaming(&self) -> bool {
+        false
+    }
 }
 
 impl<'a, F: Field> Prover<F> for Box<dyn Prover<F> + 'a> {
@@ -64,4 +86,8 @@ impl<'a, F: Field> Prover<F> for Box<dyn Prover<F> + 'a> {
     fn final_foldings(&self) -> Option<[F; 2]> {
         (**self).final_foldings()
     }
+
+    fn is_streaming(&self) -> bool {
+        (**self).is_streaming()
+    }
 }
@@ -0,0 +1,261 @@
+//! The sumcheck prover for witnesses over a small field, with challenges from an extension.
+//!
+//! Circuits naturally defined over a small prime field \\(\FF\\)
+//! cannot draw the Fiat–Shamir challenges from \\(\FF\\) itself, as the soundness error would be too large.
+//! Instead, the challenges are drawn from a degree-\\(k\\) extension \\(\mathbb{E}\\) of \\(\FF\\),
+//! and the claim \\(\langle f, g \rangle = u\\) is proven over \\(\mathbb{E}\\).
+//!
+//! [`SmallFieldProver`] keeps the witness over \\(\FF\\) for as long as possible:
+//! the first round message is computed entirely with base-field arithmetic,
+//! and the witness is lifted to \\(\mathbb{E}\\) only when folded with the first challenge,
+//! which halves its length.
+//! The messages are the same as the ones of [`TimeProver`] over the lifted witness,
+//! so they can be checked with [`Subclaim::new`](super::Subclaim::new) over \\(\mathbb{E}\\),
+//! and the sub-claims with [`evaluate_lifted`].
+//!
+//! When the base field is the scalar field of a pairing-friendly curve,
+//! the sub-claims are proven with [`SmallFieldTensorcheckProof`](crate::subprotocols::tensorcheck::SmallFieldTensorcheckProof),
+//! committing to the foldings over the extension coordinate-wise.
+//! Smaller base fields are out of reach of the tensorcheck,
+//! as KZG commits to polynomials over the scalar field of the curve only.
+use ark_ff::{Field, PrimeField};
+use ark_std::log2;
+use ark_std::vec::Vec;
+
+use crate::misc::{fold_polynomial, wipe, BufferPool};
+use crate::subprotocols::sumcheck::prover::{Prover, RoundMsg};
+use crate::subprotocols::sumcheck::TimeProver;
+
+/// The state of a [`SmallFieldProver`]:
+/// before the first challenge, the witness is over the base field,
+/// afterwards it is folded over the extension.
+enum State<F: PrimeField, E: Field<BasePrimeField = F>> {
+    Base { f: Vec<F>, g: Vec<F>, round: usize },
+    Extension(TimeProver<E>),
+}
+
+/// The linear-time prover for a twisted scalar product
+/// with witness over the base prime field `F` and challenges over the extension `E`.
+pub struct SmallFieldProver<F: PrimeField, E: Field<BasePrimeField = F>> {
+    state: State<F, E>,
+    /// The twist, in the base field.
+    twist: F,
+    /// Total number of rounds.
+    tot_rounds: usize,
+}
+
+impl<F: PrimeField, E: Field<BasePrimeField = F>> SmallFieldProver<F, E> {
+    /// Create a new prover for the scalar product of `f` and `g` twisted by `twist`.
+    /// This will cause a copy of the witness.
+    pub fn new(f: &[F], g: &[F], twist: F) -> Self {
+        let max_len = usize::max(f.len(), g.len());
+        SmallFieldProver {
+            state: State::Base {
+                f: f.to_vec(),
+                g: g.to_vec(),
+                round: 0,
+            },
+            twist,
+            tot_rounds: log2(max_len) as usize,
+        }
+    }
+
+    /// Return true if the witness is still over the base field.
+    pub fn is_base(&self) -> bool {
+        matches!(self.state, State::Base { .. })
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<F: PrimeField, E: Field<BasePrimeField = F>> Drop for SmallFieldProver<F, E> {
+    fn drop(&mut self) {
+        // once lifted, the witness is wiped by the time prover.
+        if let State::Base { f, g, .. } = &mut self.state {
+            zeroize::Zeroize::zeroize(f);
+            zeroize::Zeroize::zeroize(g);
+        }
+    }
+}
+
+/// Fold `f` with the challenge `r`, lifting its coefficients to the extension `E`.
+pub(crate) fn fold_lift<F: PrimeField, E: Field<BasePrimeField = F>>(f: &[F], r: E) -> Vec<E> {
+    f.chunks(2)
+        .map(|pair| {
+            let even = E::from_base_prime_field(pair[0]);
+            let odd = pair
+                .get(1)
+                .map_or_else(E::zero, |&odd| E::from_base_prime_field(odd));
+            even + r * odd
+        })
+        .collect()
+}
+
+impl<F: PrimeField, E: Field<BasePrimeField = F>> Prover<E> for SmallFieldProver<F, E> {
+    /// Fold the sumcheck instance (inplace), lifting it to the extension in the first round.
+    fn fold(&mut self, r: E) {
+        match &mut self.state {
+            State::Base { f, g, round } => {
+                let twist = E::from_base_prime_field(self.twist);
+                let prover = TimeProver {
+                    f: fold_lift(f, r * twist),
+                    g: fold_lift(g, r),
+                    round: *round,
+                    twist: twist.square(),
+                    tot_rounds: self.tot_rounds,
+                    buffers: BufferPool::default(),
+                };
+                // the base-field witness is dropped with the state: wipe it first.
+                wipe(f);
+                wipe(g);
+                self.state = State::Extension(prover);
+            }
+            State::Extension(prover) => prover.fold(r),
+        }
+    }
+
+    /// Time-efficient, next-message function.
+    fn next_message(&mut self, verifier_message: Option<E>) -> Option<RoundMsg<E>> {
+        if let Some(challenge) = verifier_message {
+            self.fold(challenge);
+        }
+        let (f, g, round) = match &mut self.state {
+            State::Base { f, g, round } => (f, g, round),
+            State::Extension(prover) => return prover.next_message(None),
+        };
+        assert!(*round <= self.tot_rounds, "More rounds than needed.");
+        if *round == self.tot_rounds {
+            return None;
+        }
+
+        // compute the coefficients a, b of the round polynomial over the base field.
+        let zero = F::zero();
+        let twist2 = self.twist.square();
+        let mut twist_runner = F::one();
+        let mut a = F::zero();
+        let mut b = F::zero();
+        for (f_pair, g_pair) in f.chunks(2).zip(g.chunks(2)) {
+            let f_odd = f_pair.get(1).unwrap_or(&zero);
+            let g_odd = g_pair.get(1).unwrap_or(&zero);
+            a += f_pair[0] * g_pair[0] * twist_runner;
+            b += (f_pair[0] * g_odd + g_pair[0] * f_odd * self.twist) * twist_runner;
+            twist_runner *= twist2;
+        }
+        *round += 1;
+
+        Some(RoundMsg(
+            E::from_base_prime_field(a),
+            E::from_base_prime_field(b),
+        ))
+    }
+
+    /// The number of rounds this prover is supposed to run on.
+    #[inline]
+    fn rounds(&self) -> usize {
+        self.tot_rounds
+    }
+
+    fn round(&self) -> usize {
+        match &self.state {
+            State::Base { round, .. } => *round,
+            State::Extension(prover) => prover.round(),
+        }
+    }
+
+    fn final_foldings(&self) -> Option<[E; 2]> {
+        match &self.state {
+            State::Base { f, g, round } => (*round == self.tot_rounds).then(|| {
+                [
+                    E::from_base_prime_field(f.first().copied().unwrap_or_default()),
+                    E::from_base_prime_field(g.first().copied().unwrap_or_default()),
+                ]
+            }),
+            State::Extension(prover) => prover.final_foldings(),
+        }
+    }
+}
+
+/// Return the scalar product \\(\langle f, \otimes_j (1, \rho_j) \rangle\\)
+/// of the vector `f` over the base field with the tensor of the challenges `challenges` over the extension,
+/// i.e., the sub-claim of the sumcheck over `f`.
+///
+/// The first challenge is folded with mixed arithmetic, without lifting `f` first.
+pub fn evaluate_lifted<F: PrimeField, E: Field<BasePrimeField = F>>(
+    f: &[F],
+    challenges: &[E],
+) -> E {
+    let (first, challenges) = match challenges.split_first() {
+        Some(split) => split,
+        None => return E::from_base_prime_field(f.first().copied().unwrap_or_default()),
+    };
+    let folded = challenges
+        .iter()
+        .fold(fold_lift(f, *first), |folded, &challenge| {
+            fold_polynomial(&folded, challenge)
+        });
+    folded.first().copied().unwrap_or_default()
+}
+
+#[test]
+fn test_small_field_prover() {
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::{Fq, Fq2};
+    use merlin::Transcript;
+
+    use crate::misc::{hadamard, ip, powers};
+    use crate::subprotocols::sumcheck::time_prover::Witness;
+    use crate::subprotocols::sumcheck::{Subclaim, Sumcheck};
+
+    let rng = &mut ark_std::test_rng();
+    for len in [1, 2, 13, 16] {
+        let f = (0..len).map(|_| Fq::rand(rng)).collect::<Vec<_>>();
+        let g = (0..len).map(|_| Fq::rand(rng)).collect::<Vec<_>>();
+        let twist = Fq::rand(rng);
+        let lift = |v: &[Fq]| {
+            v.iter()
+                .map(|&x| Fq2::from_base_prime_field(x))
+                .collect::<Vec<_>>()
+        };
+
+        // the messages are the same as the ones over the lifted witness.
+        let prover = SmallFieldProver::<Fq, Fq2>::new(&f, &g, twist);
+        assert!(prover.is_base());
+        let sumcheck = Sumcheck::prove(&mut Transcript::new(b"small-field"), prover);
+        assert_eq!(
+            Sumcheck::<Fq2>::new_small_field(&mut Transcript::new(b"small-field"), &f, &g, twist),
+            sumcheck
+        );
+        let lifted_twist = Fq2::from_base_prime_field(twist);
+        let expected = Sumcheck::prove(
+            &mut Transcript::new(b"small-field"),
+            TimeProver::new(Witness::new(&lift(&f), &lift(&g), &lifted_twist)),
+        );
+        assert_eq!(sumcheck, expected);
+
+        let twisted_f = hadamard(&powers(twist, f.len()), &f);
+        let asserted_sum = Fq2::from_base_prime_field(ip(&twisted_f, &g));
+        let subclaim = Subclaim::new(
+            &mut Transcript::new(b"small-field"),
+            &sumcheck.prover_messages(),
+            asserted_sum,
+        )
+        .unwrap();
+        // f is folded with the challenges multiplied by the squarings of the twist.
+        let mut twist_runner = lifted_twist;
+        let twisted_challenges = subclaim
+            .challenges
+            .iter()
+            .map(|&challenge| {
+                let twisted_challenge = challenge * twist_runner;
+                twist_runner.square_in_place();
+                twisted_challenge
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            subclaim.final_foldings[0],
+            [
+                evaluate_lifted(&f, &twisted_challenges),
+                evaluate_lifted(&g, &subclaim.challenges)
+            ]
+        );
+    }
+}
@@ -1,9 +1,3 @@
-// #[cfg(feature = "parallel")]
-// use rayon::{
-//     iter::{IndexedParallelIterator, ParallelIterator},
-//     slice::ParallelSlice,
-// };
-
 use ark_ff::Field;
 use ark_std::borrow::Borrow;
 use ark_std::cmp::Ordering;
@@ -12,9 +6,13 @@ use ark_std::vec::Vec;
 
 use super::{prover::Prover, time_prover::TimeProver};
 use crate::iterable::Iterable;
+use crate::misc::BufferPool;
 use crate::subprotocols::sumcheck::prover::RoundMsg;
 use crate::subprotocols::sumcheck::streams::FoldedPolynomialStream;
-// use crate::{misc::ceil_div, SUMCHECK_BUF_SIZE};
+#[cfg(feature = "parallel")]
+use crate::{misc::ceil_div, PARALLEL_THRESHOLD, SUMCHECK_BUF_SIZE};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 /// This is the streaming alter-ego of `Witness`.
 /// The witness for the twisted scalar product, where the vectors are stored as streams.
@@ -106,6 +104,28 @@ where
     }
 }
 
+/// Sum the contributions of the pairs `[f_odd, g_odd, f_even, g_even]` in `buf` to the round message,
+/// where the i-th pair is twisted by `twist_runner * twist2inv^i`.
+/// Blocks of [`PARALLEL_THRESHOLD`] pairs are summed in parallel.
+#[cfg(feature = "parallel")]
+fn partial_sums<F: Field>(buf: &[[F; 4]], twist: F, twist_runner: F, twist2inv: F) -> (F, F) {
+    buf.par_chunks(PARALLEL_THRESHOLD)
+        .enumerate()
+        .map(|(block, pairs)| {
+            let mut a = F::zero();
+            let mut b = F::zero();
+            let mut twist_runner =
+                twist_runner * twist2inv.pow([(block * PARALLEL_THRESHOLD) as u64]);
+            for [f_odd, g_odd, f_even, g_even] in pairs {
+                a += *f_even * g_even * twist_runner;
+                b += (*f_even * g_odd + *f_odd * g_even * twist) * twist_runner;
+                twist_runner *= twist2inv;
+            }
+            (a, b)
+        })
+        .reduce(|| (F::zero(), F::zero()), |(a, b), (x, y)| (a + x, b + y))
+}
+
 impl<F, S1, S2> Prover<F> for SpaceProver<F, S1, S2>
 where
     F: Field,
@@ -182,7 +202,7 @@ where
         let mut b = (f_even * g_odd + f_odd * g_even * self.twist) * twist_runner;
         twist_runner *= twist2inv;
 
-        // #[cfg(not(feature = "parallel"))]
+        #[cfg(not(feature = "parallel"))]
         for _i in 0..f_pairs {
             let f_odd = f_it.next().unwrap();
             let g_odd = g_it.next().unwrap();
@@ -196,43 +216,24 @@ where
             twist_runner *= twist2inv;
         }
 
-        // #[cfg(feature = "parallel")]
-        // for _i in 0..ceil_div(f_pairs, SUMCHECK_BUF_SIZE) {
-        //     let f_buf = (&mut f_it).take(SUMCHECK_BUF_SIZE).collect::<Vec<_>>();
-        //     let g_buf = (&mut g_it).take(SUMCHECK_BUF_SIZE).collect::<Vec<_>>();
-        //     let mut twist_runner_a = twist_runner;
-        //     let twist = self.twist;
-        //     a += f_buf
-        //         .par_chunks(2)
-        //         .zip(g_buf.par_chunks(2))
-        //         .map(|(f_chunk, g_chunk)| {
-        //             let _f_odd = f_chunk[0];
-        //             let f_even = f_chunk[1];
-        //             let _g_odd = g_chunk[0];
-        //             let g_even = g_chunk[1];
-
-        //             let result = f_even * g_even * twist_runner;
-        //             twist_runner_a *= twist2inv;
-        //             result
-        //         })
-        //         .sum::<F>();
-
-        //     let mut twist_runner_b = twist_runner;
-        //     b += f_buf
-        //         .par_chunks(2)
-        //         .zip(g_buf.par_chunks(2))
-        //         .map(|(f_chunk, g_chunk)| {
-        //             let f_odd = f_chunk[0];
-        //             let f_even = f_chunk[1];
-        //             let g_odd = g_chunk[0];
-        //             let g_even = g_chunk[1];
-
-        //             let result = (f_even * g_odd + f_odd * g_even * twist) * twist_runner;
-        //             twist_runner_b *= twist2inv;
-        //             result
-        //         })
-        //         .sum::<F>();
-        // }
+        // Read the pairs in buffers, and sum each buffer in parallel.
+        #[cfg(feature = "parallel")]
+        for i in 0..ceil_div(f_pairs, SUMCHECK_BUF_SIZE) {
+            let buf_size = usize::min(SUMCHECK_BUF_SIZE, f_pairs - i * SUMCHECK_BUF_SIZE);
+            let buf = (0..buf_size)
+                .map(|_| {
+                    let f_odd = f_it.next().unwrap();
+                    let g_odd = g_it.next().unwrap();
+                    let f_even = f_it.next().unwrap();
+                    let g_even = g_it.next().unwrap();
+                    [f_odd, g_odd, f_even, g_even]
+                })
+                .collect::<Vec<_>>();
+            let (buf_a, buf_b) = partial_sums(&buf, self.twist, twist_runner, twist2inv);
+            a += buf_a;
+            b += buf_b;
+            twist_runner *= twist2inv.pow([buf_size as u64]);
+        }
 
         // Increment the round counter.
         self.round += 1;
@@ -264,6 +265,10 @@ where
         let rhs = folded_g.iter().next()?;
         (self.round == self.tot_rounds).then_some([lhs, rhs])
     }
+
+    fn is_streaming(&self) -> bool {
+        true
+    }
 }
 
 impl<'a, F, S1, S2> From<&SpaceProver<F, S1, S2>> for TimeProver<F>
@@ -302,6 +307,7 @@ where
             round,
             twist,
             tot_rounds,
+            buffers: BufferPool::default(),
         }
     }
 }
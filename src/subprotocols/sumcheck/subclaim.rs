@@ -1,15 +1,20 @@
 use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::log2;
 use ark_std::vec::Vec;
-use merlin::Transcript;
 
 use crate::errors::VerificationError;
-use crate::misc::ip;
+use crate::misc::{ip, powers};
 use crate::subprotocols::sumcheck::prover::ProverMsgs;
 use crate::transcript::GeminiTranscript;
 
 use crate::subprotocols::sumcheck::prover::RoundMsg;
 
 /// The subclaim of the sumcheck.
+///
+/// Subclaims can be serialized, e.g. to hand them over to the process checking the sub-claims,
+/// which can re-derive them from the prover's messages with [`Subclaim::from_messages`].
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
 pub struct Subclaim<F: Field> {
     /// The verifier's challenges \\(\rho_0, \dots, \rho_{n-1}\\)
     pub challenges: Vec<F>,
@@ -20,37 +25,52 @@ pub struct Subclaim<F: Field> {
 impl<F: Field> Subclaim<F> {
     /// Generate a new subclaim
     /// from the non-oracle messages from the prover.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(rounds = prover_messages.0.len())))]
     pub fn new(
-        transcript: &mut Transcript,
+        transcript: &mut impl GeminiTranscript,
         prover_messages: &ProverMsgs<F>,
         asserted_sum: F,
     ) -> Result<Self, VerificationError> {
         let ProverMsgs(messages, final_foldings) = prover_messages;
+        let final_folding = final_foldings
+            .first()
+            .ok_or(VerificationError::MalformedProof)?;
         let (challenges, reduced_claim) = Self::reduce(transcript, messages, asserted_sum);
 
         // Add the final foldings to the transcript
-        transcript.append_serializable(b"final-folding", &final_foldings[0][0]);
-        transcript.append_serializable(b"final-folding", &final_foldings[0][1]);
+        transcript.append_serializable(b"final-folding", &final_folding[0]);
+        transcript.append_serializable(b"final-folding", &final_folding[1]);
 
-        if final_foldings[0][0] * final_foldings[0][1] == reduced_claim {
+        if final_folding[0] * final_folding[1] == reduced_claim {
             Ok(Self {
                 challenges,
                 final_foldings: final_foldings.to_vec(),
             })
         } else {
-            Err(VerificationError)
+            Err(VerificationError::SumcheckRound(messages.len()))
         }
     }
 
+    /// Generate a new subclaim
+    /// from the non-oracle messages of a batch of instances, as produced by [`Sumcheck::prove_batch`],
+    /// for the claims `asserted_sums`.
+    ///
+    /// The sub-claims of an instance requiring \\(k\\) rounds
+    /// are over the first \\(k\\) challenges, see [`Subclaim::instance_challenges`].
+    ///
+    /// [`Sumcheck::prove_batch`]: super::Sumcheck::prove_batch
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(rounds = prover_messages.0.len(), instances = asserted_sums.len())))]
     pub fn new_batch(
-        transcript: &mut Transcript,
+        transcript: &mut impl GeminiTranscript,
         prover_messages: &ProverMsgs<F>,
         asserted_sums: &[F],
     ) -> Result<Self, VerificationError> {
         let ProverMsgs(messages, final_foldings) = prover_messages;
-        let coefficients = (0..asserted_sums.len())
-            .map(|_| transcript.get_challenge::<F>(b"batch-sumcheck"))
-            .collect::<Vec<_>>();
+        if final_foldings.len() != asserted_sums.len() {
+            return Err(VerificationError::MalformedProof);
+        }
+        let batch_challenge = transcript.get_challenge::<F>(b"batch-sumcheck");
+        let coefficients = powers(batch_challenge, asserted_sums.len());
         let asserted_sum = ip(&coefficients, asserted_sums);
         let (challenges, reduced_claim) = Self::reduce(transcript, messages, asserted_sum);
 
@@ -70,12 +90,35 @@ impl<F: Field> Subclaim<F> {
                 final_foldings: final_foldings.to_vec(),
             })
         } else {
-            Err(VerificationError)
+            Err(VerificationError::SumcheckRound(messages.len()))
+        }
+    }
+
+    /// Re-derive the subclaim from the (possibly deserialized) messages `prover_messages`
+    /// for the claims `asserted_sums`, one for each instance.
+    ///
+    /// This is [`Subclaim::new`] for a single instance, and [`Subclaim::new_batch`] otherwise.
+    pub fn from_messages(
+        transcript: &mut impl GeminiTranscript,
+        prover_messages: &ProverMsgs<F>,
+        asserted_sums: &[F],
+    ) -> Result<Self, VerificationError> {
+        match asserted_sums {
+            [asserted_sum] if prover_messages.1.len() == 1 => {
+                Self::new(transcript, prover_messages, *asserted_sum)
+            }
+            _ => Self::new_batch(transcript, prover_messages, asserted_sums),
         }
     }
 
-    fn reduce(
-        transcript: &mut Transcript,
+    /// Return the challenges folding an instance of `len` elements within a batch.
+    pub fn instance_challenges(&self, len: usize) -> &[F] {
+        let rounds = usize::min(log2(len) as usize, self.challenges.len());
+        &self.challenges[..rounds]
+    }
+
+    pub(super) fn reduce(
+        transcript: &mut impl GeminiTranscript,
         messages: &[RoundMsg<F>],
         asserted_sum: F,
     ) -> (Vec<F>, F) {
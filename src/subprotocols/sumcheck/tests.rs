@@ -109,6 +109,40 @@ fn test_consistency_elastic() {
     assert_eq!(time_proof.messages, elastic_proof.messages);
 }
 
+#[test]
+fn test_elastic_memory_budget() {
+    use crate::subprotocols::sumcheck::ElasticProver;
+    use ark_std::mem::size_of;
+
+    let rng = &mut ark_std::test_rng();
+    let twist = F::one();
+
+    let f = DensePolynomial::<F>::rand(63, rng).coeffs().to_vec();
+    let g = DensePolynomial::<F>::rand(63, rng).coeffs().to_vec();
+    let mut rev_f = f.clone();
+    let mut rev_g = g.clone();
+    rev_f.reverse();
+    rev_g.reverse();
+
+    // the folded instance of 16 elements fits after the second round.
+    let memory_budget = 2 * 16 * size_of::<F>();
+    let mut prover = ElasticProver::new(rev_f.as_slice(), rev_g.as_slice(), twist)
+        .with_memory_budget(memory_budget);
+    prover.next_message(None);
+    prover.fold(F::one());
+    assert!(matches!(prover, ElasticProver::Space(..)));
+    prover.next_message(None);
+    prover.fold(F::one());
+    assert!(matches!(prover, ElasticProver::Time(..)));
+
+    let prover = ElasticProver::new(rev_f.as_slice(), rev_g.as_slice(), twist)
+        .with_memory_budget(memory_budget);
+    let elastic_proof = Sumcheck::prove(&mut Transcript::new(crate::PROTOCOL_NAME), prover);
+    let time_proof =
+        Sumcheck::<F>::new_time(&mut Transcript::new(crate::PROTOCOL_NAME), &f, &g, &twist);
+    assert_eq!(time_proof.messages, elastic_proof.messages);
+}
+
 #[test]
 fn test_messages_consistency_with_different_lengths() {
     let rng = &mut ark_std::test_rng();
@@ -266,3 +300,288 @@ fn test_batch_sumcheck_correctness() {
     );
     assert!(subclaim.is_ok());
 }
+
+#[test]
+fn test_standalone_sumcheck() {
+    use crate::subprotocols::sumcheck::SumcheckProver;
+
+    let rng = &mut ark_std::test_rng();
+    let d = 1 << 8;
+    let f = DensePolynomial::<F>::rand(d - 1, rng).coeffs().to_vec();
+    let g = DensePolynomial::<F>::rand(d - 1, rng).coeffs().to_vec();
+    let twist = F::rand(rng);
+    let asserted_sum = ip(&hadamard(&powers(twist, d), &f), &g);
+
+    let prover = SumcheckProver::new_twisted(Reverse(f.as_slice()), Reverse(g.as_slice()), twist)
+        .with_memory_budget(0);
+    let messages = prover.prove(&mut Transcript::new(crate::PROTOCOL_NAME));
+    let expected = Sumcheck::new_time(&mut Transcript::new(crate::PROTOCOL_NAME), &f, &g, &twist);
+    assert_eq!(messages, expected.prover_messages());
+    assert_eq!(messages.messages().len(), 8);
+
+    let subclaim = Subclaim::new(
+        &mut Transcript::new(crate::PROTOCOL_NAME),
+        &messages,
+        asserted_sum,
+    );
+    assert!(subclaim.is_ok());
+}
+
+#[test]
+fn test_product_sumcheck() {
+    use crate::misc::tensor;
+    use crate::subprotocols::sumcheck::{ProductProverMsgs, ProductSubclaim, ProductSumcheck};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+    let rng = &mut ark_std::test_rng();
+    for d in [1 << 6, 100] {
+        let f = (0..d).map(|_| F::rand(rng)).collect::<Vec<_>>();
+        let g = (0..d).map(|_| F::rand(rng)).collect::<Vec<_>>();
+        let h = (0..d).map(|_| F::rand(rng)).collect::<Vec<_>>();
+        let asserted_sum = ip(&hadamard(&f, &g), &h);
+
+        let time_sumcheck =
+            ProductSumcheck::new_time(&mut Transcript::new(crate::PROTOCOL_NAME), &[&f, &g, &h]);
+        let space_sumcheck = ProductSumcheck::new_space(
+            &mut Transcript::new(crate::PROTOCOL_NAME),
+            ark_std::vec![
+                Reverse(f.as_slice()),
+                Reverse(g.as_slice()),
+                Reverse(h.as_slice()),
+            ],
+        );
+        assert_eq!(time_sumcheck, space_sumcheck);
+        assert!(time_sumcheck.messages.iter().all(|m| m.len() == 3));
+
+        let mut bytes = Vec::new();
+        time_sumcheck
+            .prover_messages()
+            .serialize_compressed(&mut bytes)
+            .unwrap();
+        let messages = ProductProverMsgs::<F>::deserialize_compressed(&bytes[..]).unwrap();
+        let subclaim = ProductSubclaim::new(
+            &mut Transcript::new(crate::PROTOCOL_NAME),
+            &messages,
+            asserted_sum,
+        )
+        .unwrap();
+        let rhos = &tensor(&subclaim.challenges)[..d];
+        assert_eq!(
+            subclaim.final_foldings,
+            ark_std::vec![ip(&f, rhos), ip(&g, rhos), ip(&h, rhos)]
+        );
+
+        let subclaim = ProductSubclaim::new(
+            &mut Transcript::new(crate::PROTOCOL_NAME),
+            &messages,
+            asserted_sum + F::one(),
+        );
+        assert!(subclaim.is_err());
+    }
+}
+
+#[test]
+fn test_batch_sumcheck_sizes() {
+    use crate::misc::tensor;
+
+    let rng = &mut ark_std::test_rng();
+    let sizes = [8, 100, 1 << 9];
+    let instances = sizes
+        .iter()
+        .map(|&d| {
+            let f = (0..d).map(|_| F::rand(rng)).collect::<Vec<_>>();
+            let g = (0..d).map(|_| F::rand(rng)).collect::<Vec<_>>();
+            (f, g)
+        })
+        .collect::<Vec<_>>();
+    let asserted_sums = instances.iter().map(|(f, g)| ip(f, g)).collect::<Vec<_>>();
+
+    let provers = instances
+        .iter()
+        .map(|(f, g)| {
+            Box::new(TimeProver::new(Witness::new(f, g, &F::one()))) as Box<dyn Prover<F>>
+        })
+        .collect();
+    let sumcheck = Sumcheck::prove_batch(&mut Transcript::new(crate::PROTOCOL_NAME), provers);
+    let prover_messages = sumcheck.prover_messages();
+    let subclaim = Subclaim::new_batch(
+        &mut Transcript::new(crate::PROTOCOL_NAME),
+        &prover_messages,
+        &asserted_sums,
+    )
+    .unwrap();
+
+    // each instance is folded with as many challenges as it requires.
+    for ((f, g), final_folding) in instances.iter().zip(&subclaim.final_foldings) {
+        let challenges = subclaim.instance_challenges(f.len());
+        let rhos = &tensor(challenges)[..f.len()];
+        assert_eq!(*final_folding, [ip(f, rhos), ip(g, rhos)]);
+    }
+
+    let mut wrong_sums = asserted_sums.clone();
+    wrong_sums[0] += F::one();
+    let subclaim = Subclaim::new_batch(
+        &mut Transcript::new(crate::PROTOCOL_NAME),
+        &prover_messages,
+        &wrong_sums,
+    );
+    assert!(subclaim.is_err());
+}
+
+#[test]
+fn test_zk_sumcheck() {
+    use crate::kzg::{CommitterKey, VerifierKey};
+    use crate::misc::tensor;
+    use crate::subprotocols::sumcheck::ZkSumcheckProof;
+    use ark_test_curves::bls12_381::Bls12_381;
+
+    let rng = &mut ark_std::test_rng();
+    let d = 1 << 6;
+    let f = DensePolynomial::<F>::rand(d - 1, rng).coeffs().to_vec();
+    let g = DensePolynomial::<F>::rand(d - 1, rng).coeffs().to_vec();
+    let twist = F::rand(rng);
+    let asserted_sum = ip(&hadamard(&powers(twist, d), &f), &g);
+    let ck = CommitterKey::<Bls12_381>::new(8, 1, rng);
+    let vk = VerifierKey::from(&ck);
+
+    let witness = Witness::new(&f, &g, &twist);
+    let proof = ZkSumcheckProof::prove(
+        &ck,
+        &mut Transcript::new(crate::PROTOCOL_NAME),
+        TimeProver::new(witness),
+        rng,
+    );
+    let subclaim = proof
+        .verify(
+            &vk,
+            &mut Transcript::new(crate::PROTOCOL_NAME),
+            asserted_sum,
+        )
+        .unwrap();
+
+    // the round messages are masked, while the sub-claims are unchanged.
+    let sumcheck = Sumcheck::new_time(&mut Transcript::new(crate::PROTOCOL_NAME), &f, &g, &twist);
+    assert_ne!(proof.prover_messages.messages()[0], sumcheck.messages[0]);
+    let rhos = tensor(&subclaim.challenges);
+    let twisted_rhos = hadamard(&rhos, &powers(twist, d));
+    assert_eq!(
+        subclaim.final_foldings[0],
+        [ip(&f, &twisted_rhos), ip(&g, &rhos)]
+    );
+
+    let wrong_sum = asserted_sum + F::one();
+    let subclaim = proof.verify(&vk, &mut Transcript::new(crate::PROTOCOL_NAME), wrong_sum);
+    assert!(subclaim.is_err());
+
+    let mut tampered = proof.clone();
+    tampered.mask_evaluations[0] += F::one();
+    tampered.mask_evaluations[1] -= F::one();
+    let subclaim = tampered.verify(
+        &vk,
+        &mut Transcript::new(crate::PROTOCOL_NAME),
+        asserted_sum,
+    );
+    assert!(subclaim.is_err());
+}
+
+#[test]
+fn test_messages_consistency_large() {
+    let rng = &mut ark_std::test_rng();
+    let twist = F::rand(rng);
+
+    // spanning more than one buffer of the parallel space prover.
+    let d = (1 << 17) + 11;
+    let f = (0..d).map(|_| F::rand(rng)).collect::<Vec<_>>();
+    let g = (0..d).map(|_| F::rand(rng)).collect::<Vec<_>>();
+    let mut time_prover = TimeProver::new(Witness::new(&f, &g, &twist));
+    let mut space_prover = SpaceProver::new(Reverse(f.as_slice()), Reverse(g.as_slice()), twist);
+
+    let mut verifier_message = None;
+    for _ in 0..2 {
+        assert_eq!(
+            space_prover.next_message(verifier_message),
+            time_prover.next_message(verifier_message)
+        );
+        verifier_message = Some(F::rand(rng));
+    }
+}
+
+#[test]
+fn test_subclaim_serialization() {
+    use crate::subprotocols::sumcheck::ProverMsgs;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+    let rng = &mut ark_std::test_rng();
+    let d = 1 << 5;
+    let f = (0..d).map(|_| F::rand(rng)).collect::<Vec<_>>();
+    let g = (0..d).map(|_| F::rand(rng)).collect::<Vec<_>>();
+    let asserted_sum = ip(&f, &g);
+
+    let sumcheck = Sumcheck::new_time(
+        &mut Transcript::new(crate::PROTOCOL_NAME),
+        &f,
+        &g,
+        &F::one(),
+    );
+    let subclaim = Subclaim::new(
+        &mut Transcript::new(crate::PROTOCOL_NAME),
+        &sumcheck.prover_messages(),
+        asserted_sum,
+    )
+    .unwrap();
+
+    // the messages and the subclaim are sent to another process...
+    let mut messages_bytes = Vec::new();
+    sumcheck
+        .prover_messages()
+        .serialize_compressed(&mut messages_bytes)
+        .unwrap();
+    let mut subclaim_bytes = Vec::new();
+    subclaim.serialize_compressed(&mut subclaim_bytes).unwrap();
+
+    // ... which re-derives the subclaim from the messages.
+    let messages = ProverMsgs::<F>::deserialize_compressed(&messages_bytes[..]).unwrap();
+    let received = Subclaim::<F>::deserialize_compressed(&subclaim_bytes[..]).unwrap();
+    let rederived = Subclaim::from_messages(
+        &mut Transcript::new(crate::PROTOCOL_NAME),
+        &messages,
+        &[asserted_sum],
+    )
+    .unwrap();
+    assert_eq!(received, rederived);
+}
+
+#[test]
+fn test_elastic_crossover_round() {
+    use crate::subprotocols::sumcheck::{RoundMode, SumcheckProver};
+
+    let rng = &mut ark_std::test_rng();
+    let d = 1 << 6;
+    let f = DensePolynomial::<F>::rand(d - 1, rng).coeffs().to_vec();
+    let g = DensePolynomial::<F>::rand(d - 1, rng).coeffs().to_vec();
+    let expected = Sumcheck::new_time(
+        &mut Transcript::new(crate::PROTOCOL_NAME),
+        &f,
+        &g,
+        &F::one(),
+    );
+
+    for crossover in [0, 3, 6, 10] {
+        let prover = SumcheckProver::new(Reverse(f.as_slice()), Reverse(g.as_slice()))
+            .with_crossover_round(crossover);
+        let (messages, modes) = prover.prove_with_modes(&mut Transcript::new(crate::PROTOCOL_NAME));
+        assert_eq!(messages, expected.prover_messages());
+
+        let streaming_rounds = usize::min(usize::max(crossover, 1), 6);
+        let expected_modes = (0..6)
+            .map(|round| {
+                if round < streaming_rounds {
+                    RoundMode::Streaming
+                } else {
+                    RoundMode::InMemory
+                }
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(modes, expected_modes);
+    }
+}
@@ -5,9 +5,15 @@ use ark_ff::Field;
 use ark_std::log2;
 use ark_std::vec::Vec;
 
-use crate::misc::fold_polynomial;
+use crate::misc::{ceil_div, fold_polynomial_into, BufferPool};
 use crate::subprotocols::sumcheck::prover::{Prover, RoundMsg};
 
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// The number of pairs of coefficients summed sequentially in each round.
+const BLOCK_SIZE: usize = 1 << 12;
+
 /// The witness for the Twisted Scalar product relation.
 #[derive(Clone)]
 pub(crate) struct Witness<F: Field> {
@@ -19,6 +25,14 @@ pub(crate) struct Witness<F: Field> {
     twist: F,
 }
 
+#[cfg(feature = "zeroize")]
+impl<F: Field> Drop for Witness<F> {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.f);
+        zeroize::Zeroize::zeroize(&mut self.g);
+    }
+}
+
 /// The witness for the proving algorithm.
 impl<F: Field> Witness<F> {
     /// Instantiate a new Witness isntance from polynomials f, g.
@@ -49,6 +63,8 @@ pub struct TimeProver<F: Field> {
     pub twist: F,
     /// Total number of rounds.
     pub tot_rounds: usize,
+    /// The buffers of the previous foldings, reused for the next ones.
+    pub(crate) buffers: BufferPool<F>,
 }
 
 impl<F: Field> TimeProver<F> {
@@ -63,19 +79,34 @@ impl<F: Field> TimeProver<F> {
             round: 0usize,
             twist: witness.twist,
             tot_rounds: witness.required_rounds(),
+            buffers: BufferPool::default(),
         }
     }
 }
 
+#[cfg(feature = "zeroize")]
+impl<F: Field> Drop for TimeProver<F> {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.f);
+        zeroize::Zeroize::zeroize(&mut self.g);
+        zeroize::Zeroize::zeroize(&mut self.buffers);
+    }
+}
+
 impl<F> Prover<F> for TimeProver<F>
 where
     F: Field,
 {
     /// Fold the sumcheck instance (inplace).
     fn fold(&mut self, r: F) {
-        // Fold the polynonomials f, g in the scalar product.
-        self.f = fold_polynomial(&self.f, r * self.twist);
-        self.g = fold_polynomial(&self.g, r);
+        // Fold the polynonomials f, g in the scalar product,
+        // into the buffers freed by the previous round.
+        let mut f = self.buffers.take(ceil_div(self.f.len(), 2));
+        fold_polynomial_into(&self.f, r * self.twist, &mut f);
+        self.buffers.put(ark_std::mem::replace(&mut self.f, f));
+        let mut g = self.buffers.take(ceil_div(self.g.len(), 2));
+        fold_polynomial_into(&self.g, r, &mut g);
+        self.buffers.put(ark_std::mem::replace(&mut self.g, g));
         self.twist.square_in_place();
     }
 
@@ -95,27 +126,42 @@ where
 
         // Compute the polynomial of the partial sum q = a + bx + c x2,
         // For the evaluations, send only the coefficients a, b of the polynomial .
-        let mut a = F::zero();
-        let mut b = F::zero();
-        let zero = F::zero();
-        let twist2 = self.twist.square();
-
-        let mut twist_runner = F::one();
-
-        for (f_pair, g_pair) in self.f.chunks(2).zip(self.g.chunks(2)) {
-            // The even part of the polynomial must always be unwrapped.
-            let f_even = f_pair[0];
-            let g_even = g_pair[0];
-
-            // For the right part, we might obtain zero if the degree is not a multiple of 2.
-            let f_odd = f_pair.get(1).unwrap_or(&zero);
-            let g_odd = g_pair.get(1).unwrap_or(&zero);
-
-            // Add to the partial sum
-            a += f_even * g_even * twist_runner;
-            b += (f_even * g_odd + g_even * f_odd * self.twist) * twist_runner;
-            twist_runner *= twist2;
-        }
+        // The pairs of coefficients are split in blocks, summed independently.
+        let pairs = usize::min(ceil_div(self.f.len(), 2), ceil_div(self.g.len(), 2));
+        let f = &self.f[..usize::min(self.f.len(), 2 * pairs)];
+        let g = &self.g[..usize::min(self.g.len(), 2 * pairs)];
+        let twist = self.twist;
+        let twist2 = twist.square();
+
+        let partial_sums = cfg_chunks!(f, 2 * BLOCK_SIZE)
+            .zip(cfg_chunks!(g, 2 * BLOCK_SIZE))
+            .enumerate()
+            .map(|(block, (f_block, g_block))| {
+                let zero = F::zero();
+                let mut a = F::zero();
+                let mut b = F::zero();
+                let mut twist_runner = twist2.pow([(block * BLOCK_SIZE) as u64]);
+
+                for (f_pair, g_pair) in f_block.chunks(2).zip(g_block.chunks(2)) {
+                    // The even part of the polynomial must always be unwrapped.
+                    let f_even = f_pair[0];
+                    let g_even = g_pair[0];
+
+                    // For the right part, we might obtain zero if the degree is not a multiple of 2.
+                    let f_odd = f_pair.get(1).unwrap_or(&zero);
+                    let g_odd = g_pair.get(1).unwrap_or(&zero);
+
+                    // Add to the partial sum
+                    a += f_even * g_even * twist_runner;
+                    b += (f_even * g_odd + g_even * f_odd * twist) * twist_runner;
+                    twist_runner *= twist2;
+                }
+                (a, b)
+            })
+            .collect::<Vec<_>>();
+        let (a, b) = partial_sums
+            .into_iter()
+            .fold((F::zero(), F::zero()), |(a, b), (x, y)| (a + x, b + y));
         // Increment the round counter
         self.round += 1;
 
@@ -189,3 +235,60 @@ fn test_trivial_prover() {
     // an subsequent call to the next-message function should return None.
     assert!(prover.next_message(None).is_none());
 }
+
+#[test]
+fn test_round_message_blocks() {
+    use ark_ff::UniformRand;
+    use ark_test_curves::bls12_381::Fr;
+
+    let rng = &mut ark_std::test_rng();
+    // span multiple blocks, with polynomials of different (odd) lengths.
+    let f = (0..4 * BLOCK_SIZE + 3)
+        .map(|_| Fr::rand(rng))
+        .collect::<Vec<_>>();
+    let g = (0..2 * BLOCK_SIZE + 1)
+        .map(|_| Fr::rand(rng))
+        .collect::<Vec<_>>();
+    let twist = Fr::rand(rng);
+
+    let mut prover = TimeProver::new(Witness::new(&f, &g, &twist));
+    let RoundMsg(a, b) = prover.next_message(None).unwrap();
+
+    let zero = Fr::from(0u64);
+    let twist2 = twist.square();
+    let mut expected_a = zero;
+    let mut expected_b = zero;
+    let mut twist_runner = Fr::from(1u64);
+    for (f_pair, g_pair) in f.chunks(2).zip(g.chunks(2)) {
+        let f_odd = f_pair.get(1).unwrap_or(&zero);
+        let g_odd = g_pair.get(1).unwrap_or(&zero);
+        expected_a += f_pair[0] * g_pair[0] * twist_runner;
+        expected_b += (f_pair[0] * g_odd + g_pair[0] * f_odd * twist) * twist_runner;
+        twist_runner *= twist2;
+    }
+    assert_eq!(a, expected_a);
+    assert_eq!(b, expected_b);
+}
+
+#[test]
+fn test_fold_reuses_buffers() {
+    use ark_ff::UniformRand;
+    use ark_test_curves::bls12_381::Fr;
+
+    let rng = &mut ark_std::test_rng();
+    let f = (0..16).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+    let g = (0..16).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+    let twist = Fr::rand(rng);
+    let mut prover = TimeProver::new(Witness::new(&f, &g, &twist));
+
+    // after the first round, the foldings are written into the buffers of the previous rounds.
+    let mut buffers = vec![prover.f.as_ptr(), prover.g.as_ptr()];
+    prover.fold(Fr::rand(rng));
+    buffers.extend([prover.f.as_ptr(), prover.g.as_ptr()]);
+    for _ in 0..3 {
+        prover.fold(Fr::rand(rng));
+        assert!(buffers.contains(&prover.f.as_ptr()));
+        assert!(buffers.contains(&prover.g.as_ptr()));
+    }
+    assert_eq!(prover.f.len(), 1);
+}
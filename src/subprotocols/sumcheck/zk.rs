@@ -0,0 +1,183 @@
+//! Zero-knowledge sumcheck rounds.
+//!
+//! The messages of [`Sumcheck`](super::Sumcheck) are partial sums of the witness.
+//! [`ZkSumcheckProof`] masks them, following the approach of [Libra](https://eprint.iacr.org/2019/317):
+//! before the first round, the prover samples a random masking polynomial
+//! \\(p(x_0, \dots, x_{n-1}) = \sum_j p_j(x_j)\\), with each \\(p_j\\) of degree 2,
+//! sends hiding commitments to \\(p_0, \dots, p_{n-1}\\) together with their sum \\(\sigma\\),
+//! and receives a challenge \\(\eta\\).
+//! The sumcheck then proves the claim \\(u + \eta \sigma\\) over the instance plus \\(\eta p\\):
+//! each round message is masked by the (uniformly random) coefficients of \\(p_j\\).
+//! Eventually, the prover opens each \\(p_j\\) at the challenge \\(\rho_j\\),
+//! and the verifier checks that the final foldings satisfy
+//! \\(t_0 t_1 + \eta \sum_j p_j(\rho_j) = \\) the reduced claim.
+//!
+//! As in [`Sumcheck`](super::Sumcheck), the sum of a round polynomial \\(a + bx + cx^2\\) is \\(a + c\\),
+//! thus \\(\sigma = \sum_j p_j[0] + p_j[2]\\).
+//! Only the round messages are masked: the final foldings \\(t_0, t_1\\) are sent in the clear,
+//! and are sub-claims that must be proven in zero-knowledge by the caller, see [`ZkSumcheckProof`].
+use ark_ec::pairing::Pairing;
+use ark_ff::{Field, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::RngCore;
+use ark_std::vec::Vec;
+use ark_std::UniformRand;
+
+use crate::errors::VerificationError;
+use crate::kzg::{Commitment, CommitterKey, HidingEvaluationProof, VerifierKey};
+use crate::misc::wipe;
+use crate::subprotocols::sumcheck::prover::{Prover, ProverMsgs, RoundMsg};
+use crate::subprotocols::sumcheck::Subclaim;
+use crate::transcript::GeminiTranscript;
+
+/// Evaluate the round polynomial `p` of degree 2 at `x`.
+fn evaluate<F: Field>(p: &[F; 3], x: F) -> F {
+    p[0] + x * (p[1] + x * p[2])
+}
+
+/// A sumcheck proof with masked round messages.
+///
+/// # Leakage
+///
+/// This proof is **not** zero-knowledge on its own:
+/// the final foldings \\(t_0, t_1\\) in [`prover_messages`](Self::prover_messages)
+/// are sent in the clear, and each is an evaluation of the witness at the (public) challenges.
+/// Callers requiring zero-knowledge must not expose the proof as-is,
+/// and instead prove the sub-claim on \\(t_0, t_1\\) in zero-knowledge themselves.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ZkSumcheckProof<E: Pairing> {
+    /// The hiding commitments to the masking polynomials \\(p_0, \dots, p_{n-1}\\).
+    pub mask_commitments: Vec<Commitment<E>>,
+    /// The sum \\(\sigma\\) of the masking polynomial.
+    pub mask_sum: E::ScalarField,
+    /// The masked round messages, and the final foldings, which are **not** masked.
+    pub prover_messages: ProverMsgs<E::ScalarField>,
+    /// The evaluations \\(p_j(\rho_j)\\).
+    pub mask_evaluations: Vec<E::ScalarField>,
+    /// The evaluation proofs for \\(p_j(\rho_j)\\).
+    pub mask_proofs: Vec<HidingEvaluationProof<E>>,
+}
+
+impl<E: Pairing> ZkSumcheckProof<E> {
+    /// Run the sumcheck with `prover` over `transcript`,
+    /// masking its messages with a polynomial sampled from `rng` and committed with `ck`.
+    pub fn prove<P: Prover<E::ScalarField>>(
+        ck: &CommitterKey<E>,
+        transcript: &mut impl GeminiTranscript,
+        mut prover: P,
+        rng: &mut impl RngCore,
+    ) -> Self {
+        let rounds = prover.rounds();
+        let mut masks = (0..rounds)
+            .map(|_| {
+                [
+                    E::ScalarField::rand(rng),
+                    E::ScalarField::rand(rng),
+                    E::ScalarField::rand(rng),
+                ]
+            })
+            .collect::<Vec<_>>();
+        let (mask_commitments, mask_randomness): (Vec<_>, Vec<_>) =
+            masks.iter().map(|mask| ck.commit_hiding(mask, rng)).unzip();
+        let mask_sum = masks
+            .iter()
+            .map(|mask| mask[0] + mask[2])
+            .sum::<E::ScalarField>();
+        transcript.append_serializable(b"mask-commitments", &mask_commitments);
+        transcript.append_serializable(b"mask-sum", &mask_sum);
+        let eta = transcript.get_challenge::<E::ScalarField>(b"mask-challenge");
+
+        let mut messages = Vec::with_capacity(rounds);
+        let mut challenges = Vec::with_capacity(rounds);
+        // the masking polynomial, evaluated at the challenges so far and summed over the remaining variables.
+        let mut folded_mask = E::ScalarField::zero();
+        let mut remaining_mask = mask_sum;
+        let mut verifier_message = None;
+        while let Some(RoundMsg(a, b)) = prover.next_message(verifier_message) {
+            let mask = &masks[messages.len()];
+            remaining_mask -= mask[0] + mask[2];
+            let message = RoundMsg(
+                a + eta * (folded_mask + remaining_mask + mask[0]),
+                b + eta * mask[1],
+            );
+            transcript.append_serializable(b"evaluations", &message);
+            let challenge = transcript.get_challenge(b"challenge");
+            verifier_message = Some(challenge);
+            folded_mask += evaluate(mask, challenge);
+
+            messages.push(message);
+            challenges.push(challenge);
+        }
+
+        let final_foldings = prover.final_foldings().unwrap();
+        transcript.append_serializable(b"final-folding", &final_foldings[0]);
+        transcript.append_serializable(b"final-folding", &final_foldings[1]);
+
+        let (mask_evaluations, mask_proofs): (Vec<_>, Vec<_>) = masks
+            .iter()
+            .zip(&mask_randomness)
+            .zip(&challenges)
+            .map(|((mask, randomness), challenge)| ck.open_hiding(mask, randomness, challenge))
+            .unzip();
+        transcript.append_serializable(b"mask-evaluations", &mask_evaluations);
+        wipe(&mut masks);
+
+        ZkSumcheckProof {
+            mask_commitments,
+            mask_sum,
+            prover_messages: ProverMsgs(messages, ark_std::vec![final_foldings]),
+            mask_evaluations,
+            mask_proofs,
+        }
+    }
+
+    /// Verify the proof for the claim `asserted_sum`, checking the openings of the masking polynomial with `vk`,
+    /// and return the subclaim.
+    pub fn verify(
+        &self,
+        vk: &VerifierKey<E>,
+        transcript: &mut impl GeminiTranscript,
+        asserted_sum: E::ScalarField,
+    ) -> Result<Subclaim<E::ScalarField>, VerificationError> {
+        let ProverMsgs(messages, final_foldings) = &self.prover_messages;
+        let rounds = messages.len();
+        if final_foldings.len() != 1
+            || self.mask_commitments.len() != rounds
+            || self.mask_evaluations.len() != rounds
+            || self.mask_proofs.len() != rounds
+        {
+            return Err(VerificationError::MalformedProof);
+        }
+        let final_folding = final_foldings[0];
+
+        transcript.append_serializable(b"mask-commitments", &self.mask_commitments);
+        transcript.append_serializable(b"mask-sum", &self.mask_sum);
+        let eta = transcript.get_challenge::<E::ScalarField>(b"mask-challenge");
+
+        let (challenges, reduced_claim) =
+            Subclaim::reduce(transcript, messages, asserted_sum + eta * self.mask_sum);
+        transcript.append_serializable(b"final-folding", &final_folding[0]);
+        transcript.append_serializable(b"final-folding", &final_folding[1]);
+        transcript.append_serializable(b"mask-evaluations", &self.mask_evaluations);
+
+        let folded_mask = self.mask_evaluations.iter().sum::<E::ScalarField>();
+        if final_folding[0] * final_folding[1] + eta * folded_mask != reduced_claim {
+            return Err(VerificationError::SumcheckRound(rounds));
+        }
+        for (((commitment, challenge), evaluation), proof) in self
+            .mask_commitments
+            .iter()
+            .zip(&challenges)
+            .zip(&self.mask_evaluations)
+            .zip(&self.mask_proofs)
+        {
+            vk.verify_hiding(commitment, challenge, evaluation, proof)
+                .map_err(|_| VerificationError::PairingCheck)?;
+        }
+
+        Ok(Subclaim {
+            challenges,
+            final_foldings: final_foldings.clone(),
+        })
+    }
+}
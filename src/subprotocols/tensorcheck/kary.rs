@@ -0,0 +1,334 @@
+//! The tensorcheck with folding arity \\(k = 2^a\\).
+//!
+//! Instead of folding by a factor of 2 in each round,
+//! the prover folds the polynomial \\(f(x) = \sum_{i < k} x^i f_i(x^k)\\) by a factor of \\(k\\),
+//! consuming \\(a\\) challenges \\(\rho_0, \dots, \rho_{a-1}\\) at once:
+//!
+//! \\[
+//! f'(x) = \sum_{i < k} \left(\otimes_{j < a} (1, \rho_j)\right)_i f_i(x).
+//! \\]
+//!
+//! Let \\(\omega\\) be a primitive \\(k\\)-th root of unity.
+//! The verifier checks each folding on a random point \\(\beta\\), via the inverse Fourier transform of the evaluations
+//! \\(f(\beta \omega^l)\\):
+//!
+//! \\[
+//! f'(\beta^k) = \frac{1}{k} \sum_{l < k} f(\beta \omega^l) \prod_{j < a} \left(1 + \rho_j (\beta\omega^l)^{-2^j}\right).
+//! \\]
+//!
+//! For \\(n\\) challenges, the proof holds \\(\lceil n / a \rceil - 1\\) folded polynomials, each evaluated at \\(k\\) points,
+//! trading the multi-scalar multiplications of the prover (and the size of the proof) against the number of evaluations.
+//! The challenges are padded with zeros to a multiple of \\(a\\); for \\(k = 2\\) this is the usual tensorcheck.
+use ark_ec::pairing::Pairing;
+use ark_ff::{FftField, Field, One, Zero};
+use ark_serialize::*;
+use ark_std::log2;
+use ark_std::vec::Vec;
+
+use crate::errors::{VerificationError, VerificationResult};
+use crate::kzg::{Commitment, CommitterKey, EvaluationProof, MultiPointVerifier};
+use crate::misc::{batch_inverse, evaluate_le, ip, linear_combination, powers, tensor};
+use crate::transcript::GeminiTranscript;
+
+/// A tensorcheck proof with folding arity \\(k\\).
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct KaryTensorcheckProof<E: Pairing> {
+    /// The commitments to the folded polynomials.
+    pub folded_polynomials_commitments: Vec<Commitment<E>>,
+    /// The evaluations of each folded polynomial at \\(\beta\omega^l\\), for \\(l < k\\).
+    pub folded_polynomials_evaluations: Vec<Vec<E::ScalarField>>,
+    /// The evaluations of each base polynomial at \\(\beta^k\\) and \\(\beta\omega^l\\), for \\(l < k\\).
+    pub base_polynomials_evaluations: Vec<Vec<E::ScalarField>>,
+    /// The batched evaluation proof for both base polynomials and folded polynomials.
+    pub evaluation_proof: EvaluationProof<E>,
+}
+
+/// Split `challenges` in groups of `log2(arity)`, padding the last one with zeros.
+fn challenge_groups<F: Field>(challenges: &[F], arity: usize) -> Vec<Vec<F>> {
+    let group_size = log2(arity) as usize;
+    challenges
+        .chunks(group_size)
+        .map(|group| {
+            let mut group = group.to_vec();
+            group.resize(group_size, F::zero());
+            group
+        })
+        .collect()
+}
+
+/// Return the evaluation points \\(\beta^k, \beta, \beta\omega, \dots, \beta\omega^{k-1}\\).
+fn evaluation_points<F: FftField>(beta: F, arity: usize) -> Vec<F> {
+    let omega = F::get_root_of_unity(arity as u64).expect("arity unsupported by the field");
+    let mut points = Vec::with_capacity(arity + 1);
+    points.push(beta.pow([arity as u64]));
+    points.extend(powers(omega, arity).into_iter().map(|w| beta * w));
+    points
+}
+
+/// Fold `polynomial` by a factor of `tensor.len()`, using the tensor of the challenges.
+fn fold_kary<F: Field>(polynomial: &[F], tensor: &[F]) -> Vec<F> {
+    polynomial
+        .chunks(tensor.len())
+        .map(|chunk| chunk.iter().zip(tensor).map(|(x, t)| *x * t).sum())
+        .collect()
+}
+
+/// Return the evaluation of the folding at \\(\beta^k\\),
+/// given the `evaluations` of the polynomial at the `points` \\(\beta\omega^l\\).
+fn evaluate_folding<F: Field>(evaluations: &[F], points: &[F], challenges: &[F]) -> F {
+    let k_inv = F::from(points.len() as u64).inverse().unwrap();
+    let inverse_points = batch_inverse(points).unwrap();
+    evaluations
+        .iter()
+        .zip(inverse_points)
+        .map(|(evaluation, mut inverse_power)| {
+            let mut factor = F::one();
+            for challenge in challenges {
+                factor *= F::one() + *challenge * inverse_power;
+                inverse_power.square_in_place();
+            }
+            *evaluation * factor
+        })
+        .sum::<F>()
+        * k_inv
+}
+
+impl<E: Pairing> KaryTensorcheckProof<E> {
+    /// Prove that each polynomial \\(f_i\\) in `polynomials`, of length at most \\(2^n\\),
+    /// satisfies \\(\langle f_i, \otimes_j (1, \rho_j) \rangle = t_i\\)
+    /// for the \\(n\\) `challenges` \\(\rho_0, \dots, \rho_{n-1}\\), folding by a factor of `arity` in each round.
+    ///
+    /// The committer key must support `arity + 1` evaluation points,
+    /// and the scalar field must have a primitive root of unity of order `arity`.
+    pub fn new(
+        transcript: &mut impl GeminiTranscript,
+        ck: &CommitterKey<E>,
+        polynomials: &[Vec<E::ScalarField>],
+        challenges: &[E::ScalarField],
+        arity: usize,
+    ) -> Self {
+        assert!(
+            arity >= 2 && arity.is_power_of_two(),
+            "the arity must be a power of two"
+        );
+        assert!(
+            log2(arity) <= E::ScalarField::TWO_ADICITY,
+            "the arity exceeds the two-adicity of the field"
+        );
+        let groups = challenge_groups(challenges, arity);
+        assert!(
+            groups.len() >= 2,
+            "the tensorcheck requires more than log2(arity) challenges"
+        );
+
+        let batch_challenge = transcript.get_challenge::<E::ScalarField>(b"batch_challenge");
+        let batched_polynomial =
+            linear_combination(polynomials, &powers(batch_challenge, polynomials.len()));
+        let folded_polynomials = groups[..groups.len() - 1]
+            .iter()
+            .scan(batched_polynomial, |polynomial, group| {
+                *polynomial = fold_kary(polynomial, &tensor(group));
+                Some(polynomial.clone())
+            })
+            .collect::<Vec<_>>();
+        let folded_polynomials_commitments = ck.batch_commit(&folded_polynomials);
+        transcript.append_serializables(b"commitment", &folded_polynomials_commitments);
+        let eval_chal = transcript.get_challenge::<E::ScalarField>(b"evaluation-chal");
+        let points = evaluation_points(eval_chal, arity);
+
+        let base_polynomials_evaluations = polynomials
+            .iter()
+            .map(|polynomial| points.iter().map(|x| evaluate_le(polynomial, x)).collect())
+            .collect::<Vec<Vec<_>>>();
+        let folded_polynomials_evaluations = folded_polynomials
+            .iter()
+            .map(|polynomial| {
+                points[1..]
+                    .iter()
+                    .map(|x| evaluate_le(polynomial, x))
+                    .collect()
+            })
+            .collect::<Vec<Vec<_>>>();
+        base_polynomials_evaluations
+            .iter()
+            .chain(&folded_polynomials_evaluations)
+            .flatten()
+            .for_each(|e| transcript.append_serializable(b"eval", e));
+        let open_chal = transcript.get_challenge(b"open-chal");
+
+        let all_polynomials = polynomials
+            .iter()
+            .chain(&folded_polynomials)
+            .collect::<Vec<_>>();
+        let evaluation_proof = ck.batch_open_multi_points(&all_polynomials, &points, &open_chal);
+
+        KaryTensorcheckProof {
+            folded_polynomials_commitments,
+            folded_polynomials_evaluations,
+            base_polynomials_evaluations,
+            evaluation_proof,
+        }
+    }
+
+    /// Verify the proof for the polynomials committed in `commitments`,
+    /// with claimed tensor products `evaluations` at the tensor of `challenges`,
+    /// for the folding arity `arity`.
+    pub fn verify<V: MultiPointVerifier<E>>(
+        &self,
+        transcript: &mut impl GeminiTranscript,
+        vk: &V,
+        commitments: &[Commitment<E>],
+        challenges: &[E::ScalarField],
+        evaluations: &[E::ScalarField],
+        arity: usize,
+    ) -> VerificationResult {
+        // the field must have a primitive root of unity of order `arity`.
+        if arity < 2 || !arity.is_power_of_two() || log2(arity) > E::ScalarField::TWO_ADICITY {
+            return Err(VerificationError::InvalidInput);
+        }
+        let groups = challenge_groups(challenges, arity);
+        let rounds = groups.len().saturating_sub(1);
+        if rounds == 0
+            || commitments.is_empty()
+            || commitments.len() != evaluations.len()
+            || self.base_polynomials_evaluations.len() != commitments.len()
+            || self.folded_polynomials_commitments.len() != rounds
+            || self.folded_polynomials_evaluations.len() != rounds
+            || self
+                .base_polynomials_evaluations
+                .iter()
+                .any(|e| e.len() != arity + 1)
+            || self
+                .folded_polynomials_evaluations
+                .iter()
+                .any(|e| e.len() != arity)
+        {
+            return Err(VerificationError::MalformedProof);
+        }
+
+        let batch_challenge = transcript.get_challenge::<E::ScalarField>(b"batch_challenge");
+        transcript.append_serializables(b"commitment", &self.folded_polynomials_commitments);
+        let eval_chal = transcript.get_challenge::<E::ScalarField>(b"evaluation-chal");
+        let points = evaluation_points(eval_chal, arity);
+
+        // the evaluations of the batched polynomial at beta * omega^l.
+        let batch_challenges = powers(batch_challenge, commitments.len());
+        let mut parent_evaluations = (1..=arity)
+            .map(|l| {
+                let evaluations = self
+                    .base_polynomials_evaluations
+                    .iter()
+                    .map(|e| e[l])
+                    .collect::<Vec<_>>();
+                ip(&evaluations, &batch_challenges)
+            })
+            .collect::<Vec<_>>();
+
+        let mut all_evaluations = self.base_polynomials_evaluations.clone();
+        for (group, evaluations) in groups.iter().zip(&self.folded_polynomials_evaluations) {
+            let mut row = Vec::with_capacity(arity + 1);
+            row.push(evaluate_folding(&parent_evaluations, &points[1..], group));
+            row.extend_from_slice(evaluations);
+            all_evaluations.push(row);
+            parent_evaluations = evaluations.clone();
+        }
+        let subclaim = evaluate_folding(&parent_evaluations, &points[1..], &groups[rounds]);
+        if subclaim != ip(evaluations, &batch_challenges) {
+            return Err(VerificationError::TensorcheckConsistency);
+        }
+
+        self.base_polynomials_evaluations
+            .iter()
+            .chain(&self.folded_polynomials_evaluations)
+            .flatten()
+            .for_each(|e| transcript.append_serializable(b"eval", e));
+        let open_chal = transcript.get_challenge(b"open-chal");
+
+        let mut all_commitments = commitments.to_vec();
+        all_commitments.extend_from_slice(&self.folded_polynomials_commitments);
+        vk.verify_multi_points(
+            &all_commitments,
+            &points,
+            &all_evaluations,
+            &self.evaluation_proof,
+            &open_chal,
+        )
+        .map_err(|_| VerificationError::PairingCheck)
+    }
+}
+
+#[test]
+fn test_kary_tensorcheck() {
+    use crate::kzg::VerifierKey;
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+    use merlin::Transcript;
+
+    let rng = &mut ark_std::test_rng();
+    let ck = CommitterKey::<Bls12_381>::new(128, 9, rng);
+    let vk = VerifierKey::from(&ck);
+
+    let polynomials = [128, 50]
+        .iter()
+        .map(|&len| (0..len).map(|_| Fr::rand(rng)).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let challenges = (0..7).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+    let commitments = ck.batch_commit(&polynomials);
+    let evaluations = polynomials
+        .iter()
+        .map(|p| ip(p, &tensor(&challenges)[..p.len()]))
+        .collect::<Vec<_>>();
+
+    for arity in [2, 4, 8] {
+        let proof = KaryTensorcheckProof::new(
+            &mut Transcript::new(crate::PROTOCOL_NAME),
+            &ck,
+            &polynomials,
+            &challenges,
+            arity,
+        );
+        let group_size = log2(arity) as usize;
+        let rounds = (challenges.len() + group_size - 1) / group_size - 1;
+        assert_eq!(proof.folded_polynomials_commitments.len(), rounds);
+
+        let result = proof.verify(
+            &mut Transcript::new(crate::PROTOCOL_NAME),
+            &vk,
+            &commitments,
+            &challenges,
+            &evaluations,
+            arity,
+        );
+        assert!(result.is_ok());
+
+        let mut wrong_evaluations = evaluations.clone();
+        wrong_evaluations[0] += Fr::one();
+        let result = proof.verify(
+            &mut Transcript::new(crate::PROTOCOL_NAME),
+            &vk,
+            &commitments,
+            &challenges,
+            &wrong_evaluations,
+            arity,
+        );
+        assert!(result.is_err());
+    }
+
+    // no root of unity of order beyond the two-adicity of the field.
+    let proof = KaryTensorcheckProof::new(
+        &mut Transcript::new(crate::PROTOCOL_NAME),
+        &ck,
+        &polynomials,
+        &challenges,
+        2,
+    );
+    let result = proof.verify(
+        &mut Transcript::new(crate::PROTOCOL_NAME),
+        &vk,
+        &commitments,
+        &challenges,
+        &evaluations,
+        1 << (Fr::TWO_ADICITY + 1),
+    );
+    assert_eq!(result, Err(VerificationError::InvalidInput));
+}
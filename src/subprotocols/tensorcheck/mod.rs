@@ -45,23 +45,34 @@ use ark_std::borrow::Borrow;
 use ark_std::vec::Vec;
 use ark_std::One;
 
-use merlin::Transcript;
-
+use crate::errors::{VerificationError, VerificationResult};
 use crate::iterable::Iterable;
 use crate::kzg::Commitment;
 use crate::kzg::CommitterKey;
 use crate::kzg::EvaluationProof;
-use crate::kzg::VerificationError;
-use crate::kzg::VerificationResult;
-use crate::kzg::VerifierKey;
+use crate::kzg::MultiPointVerifier;
 use crate::misc::strip_last;
 use crate::misc::{evaluate_le, fold_polynomial, ip, linear_combination, powers};
 use crate::subprotocols::sumcheck::streams::FoldedPolynomialTree;
 use crate::transcript::GeminiTranscript;
 use crate::SPACE_TIME_THRESHOLD;
 
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+mod kary;
+mod multipoint;
+mod packed;
+mod small_field;
+mod standalone;
 pub mod streams;
 
+pub use kary::KaryTensorcheckProof;
+pub use multipoint::MultiPointTensorcheckProof;
+pub use packed::PackedTensorcheckProof;
+pub use small_field::SmallFieldTensorcheckProof;
+pub use standalone::Tensorcheck;
+
 #[cfg(test)]
 pub mod tests;
 
@@ -107,7 +118,7 @@ pub fn evaluate_sq_fp<F: Field>(
 }
 
 /// The struct for the tensor check proof.
-#[derive(CanonicalSerialize, PartialEq, Eq)]
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, PartialEq, Eq)]
 pub struct TensorcheckProof<E: Pairing> {
     /// The commitments for all the folded polynomials in the tensor check.
     pub folded_polynomials_commitments: Vec<Commitment<E>>,
@@ -119,6 +130,19 @@ pub struct TensorcheckProof<E: Pairing> {
     pub base_polynomials_evaluations: Vec<[E::ScalarField; 3]>,
 }
 
+/// The multi-point evaluation claim to which the verification of a tensorcheck proof reduces,
+/// to be checked against the batched evaluation proof.
+pub(crate) struct MultiPointClaim<E: Pairing> {
+    /// The commitments to the base and the folded polynomials.
+    pub(crate) commitments: Vec<Commitment<E>>,
+    /// The evaluation points, e.g. \\(\beta^2, \beta, -\beta\\).
+    pub(crate) eval_points: Vec<E::ScalarField>,
+    /// The evaluations of each polynomial at each evaluation point.
+    pub(crate) evaluations: Vec<Vec<E::ScalarField>>,
+    /// The challenge for batching the evaluation proofs.
+    pub(crate) open_chal: E::ScalarField,
+}
+
 /// The function for folding polynomials using given challenges for each round.
 /// It skips the last challenge since the result can be obtained from asserted results.
 pub fn foldings_polynomial<F: Field>(polynomial: &[F], challenges: &[F]) -> Vec<Vec<F>> {
@@ -188,10 +212,21 @@ impl<E: Pairing> TensorcheckProof<E> {
     /// The folded polynomials `body_polynomials` consist of multiple tensor check intance.
     /// Each instance contains a set of folded polynomials and folding randomnesses.
     pub fn new_time<const N: usize, const M: usize>(
-        transcript: &mut Transcript,
+        transcript: &mut impl GeminiTranscript,
         ck: &CommitterKey<E>,
         base_polynomials: [&Vec<E::ScalarField>; N],
         body_polynomials: [(&[&Vec<E::ScalarField>], &[E::ScalarField]); M],
+    ) -> TensorcheckProof<E> {
+        Self::new_time_batch(transcript, ck, &base_polynomials, &body_polynomials)
+    }
+
+    /// Same as [`TensorcheckProof::new_time`], for a number of base polynomials and instances known at runtime.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(base_polynomials = base_polynomials.len(), instances = body_polynomials.len())))]
+    pub(crate) fn new_time_batch(
+        transcript: &mut impl GeminiTranscript,
+        ck: &CommitterKey<E>,
+        base_polynomials: &[&Vec<E::ScalarField>],
+        body_polynomials: &[(&[&Vec<E::ScalarField>], &[E::ScalarField])],
     ) -> TensorcheckProof<E> {
         let max_len = body_polynomials
             .iter()
@@ -218,9 +253,7 @@ impl<E: Pairing> TensorcheckProof<E> {
         let folded_polynomials_commitments = ck.batch_commit(&foldings_body_polynomials);
 
         // add commitments to transcript
-        folded_polynomials_commitments
-            .iter()
-            .for_each(|c| transcript.append_serializable(b"commitment", c));
+        transcript.append_serializables(b"commitment", &folded_polynomials_commitments);
         let eval_chal = transcript.get_challenge::<E::ScalarField>(b"evaluation-chal");
         let minus_eval_chal = -eval_chal;
         let eval_chal2 = eval_chal.square();
@@ -236,8 +269,7 @@ impl<E: Pairing> TensorcheckProof<E> {
             })
             .collect::<Vec<_>>();
 
-        let folded_polynomials_evaluations = foldings_body_polynomials
-            .iter()
+        let folded_polynomials_evaluations = cfg_iter!(foldings_body_polynomials)
             .map(|polynomial| {
                 [
                     evaluate_le(polynomial.borrow(), &eval_chal),
@@ -283,10 +315,10 @@ impl<E: Pairing> TensorcheckProof<E> {
     /// folding randomnesses `fold_randomness`,
     /// the evaluation challenge point `eval_chal`,
     /// and the random challenge `batch_challenge` for batching tensor check instances with the same folding randomnesses.
-    pub fn verify(
+    pub fn verify<V: MultiPointVerifier<E>>(
         &self,
-        transcript: &mut Transcript,
-        vk: &VerifierKey<E>,
+        transcript: &mut impl GeminiTranscript,
+        vk: &V,
         asserted_res_vec: &[Vec<E::ScalarField>],
         base_polynomials_commitments: &[Commitment<E>],
         direct_base_polynomials_evaluations: &[[E::ScalarField; 2]],
@@ -297,6 +329,40 @@ impl<E: Pairing> TensorcheckProof<E> {
     where
         E: Pairing,
     {
+        let claim = self.reduce(
+            transcript,
+            asserted_res_vec,
+            base_polynomials_commitments,
+            direct_base_polynomials_evaluations,
+            fold_randomness,
+            eval_chal,
+            batch_challenge,
+        )?;
+        vk.verify_multi_points(
+            &claim.commitments,
+            &claim.eval_points,
+            &claim.evaluations,
+            &self.evaluation_proof,
+            &claim.open_chal,
+        )
+        .map_err(|_| VerificationError::PairingCheck)
+    }
+
+    /// Check the consistency of the folded polynomials evaluations,
+    /// and return the multi-point evaluation claim left to check against the batched evaluation proof.
+    ///
+    /// The arguments are the same as in [`TensorcheckProof::verify`].
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(instances = fold_randomness.len())))]
+    pub(crate) fn reduce(
+        &self,
+        transcript: &mut impl GeminiTranscript,
+        asserted_res_vec: &[Vec<E::ScalarField>],
+        base_polynomials_commitments: &[Commitment<E>],
+        direct_base_polynomials_evaluations: &[[E::ScalarField; 2]],
+        fold_randomness: &[Vec<E::ScalarField>],
+        eval_chal: E::ScalarField,
+        batch_challenge: E::ScalarField,
+    ) -> Result<MultiPointClaim<E>, VerificationError> {
         let minus_eval_chal = -eval_chal;
         let eval_chal2 = eval_chal.square();
 
@@ -358,7 +424,7 @@ impl<E: Pairing> TensorcheckProof<E> {
             let lc_asserted_res = ip(asserted_res, &batch_challenges);
 
             if subclaim != lc_asserted_res {
-                return Err(VerificationError);
+                return Err(VerificationError::TensorcheckConsistency);
             }
         }
 
@@ -375,13 +441,12 @@ impl<E: Pairing> TensorcheckProof<E> {
             .for_each(|e| transcript.append_serializable(b"eval", e));
         let open_chal = transcript.get_challenge(b"open-chal");
 
-        vk.verify_multi_points(
-            &all_commitments,
-            &[eval_chal2, eval_chal, minus_eval_chal],
-            &evaluations,
-            &self.evaluation_proof,
-            &open_chal,
-        )
+        Ok(MultiPointClaim {
+            commitments: all_commitments,
+            eval_points: vec![eval_chal2, eval_chal, minus_eval_chal],
+            evaluations,
+            open_chal,
+        })
     }
 }
 #[test]
@@ -0,0 +1,349 @@
+//! The tensorcheck over distinct evaluation challenges.
+//!
+//! [`TensorcheckProof`](super::TensorcheckProof) checks the foldings of all instances on the same point \\(\beta\\),
+//! and its verifier accepts the evaluations of the base polynomials at \\(\pm\beta\\) only.
+//! [`MultiPointTensorcheckProof`] checks the \\(i\\)-th instance on its own challenge \\(\beta_i\\),
+//! and opens all polynomials at \\(\beta_i^2, \beta_i, -\beta_i\\) for each \\(i\\) with a single batched evaluation proof.
+//! Its verifier thus accepts, for each instance, the evaluations of the base polynomials at \\(\pm\beta_i\\),
+//! allowing to batch tensorcheck instances arising from different sub-protocols.
+//!
+//! For \\(m\\) instances the committer key must support \\(3m\\) evaluation points.
+use ark_ec::pairing::Pairing;
+use ark_ff::Field;
+use ark_serialize::*;
+use ark_std::vec::Vec;
+use ark_std::One;
+
+use super::{evaluate_sq_fp, foldings_polynomial, MultiPointClaim};
+use crate::errors::{VerificationError, VerificationResult};
+use crate::kzg::{Commitment, CommitterKey, EvaluationProof, MultiPointVerifier};
+use crate::misc::{batch_inverse, evaluate_le, ip, linear_combination, powers};
+use crate::transcript::GeminiTranscript;
+
+/// A tensorcheck proof where each instance is checked on its own evaluation challenge.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MultiPointTensorcheckProof<E: Pairing> {
+    /// The commitments for all the folded polynomials in the tensor check.
+    pub folded_polynomials_commitments: Vec<Commitment<E>>,
+    /// The evaluations of each folded polynomial at \\(\beta_i^2, \beta_i, -\beta_i\\), for each instance \\(i\\).
+    pub folded_polynomials_evaluations: Vec<Vec<E::ScalarField>>,
+    /// The batched evaluation proof for both base polynomials and folded polynomials.
+    pub evaluation_proof: EvaluationProof<E>,
+    /// The evaluations of each base polynomial at \\(\beta_i^2, \beta_i, -\beta_i\\), for each instance \\(i\\).
+    pub base_polynomials_evaluations: Vec<Vec<E::ScalarField>>,
+}
+
+/// Return the evaluation points \\(\beta_i^2, \beta_i, -\beta_i\\) for each challenge in `eval_chals`.
+pub(crate) fn evaluation_points<F: Field>(eval_chals: &[F]) -> Vec<F> {
+    eval_chals
+        .iter()
+        .flat_map(|&eval_chal| [eval_chal.square(), eval_chal, -eval_chal])
+        .collect()
+}
+
+impl<E: Pairing> MultiPointTensorcheckProof<E> {
+    /// Construct the tensorcheck proof, sampling an evaluation challenge for each instance.
+    ///
+    /// The arguments are the same as in [`TensorcheckProof::new_time`](super::TensorcheckProof::new_time).
+    pub fn new(
+        transcript: &mut impl GeminiTranscript,
+        ck: &CommitterKey<E>,
+        base_polynomials: &[&Vec<E::ScalarField>],
+        body_polynomials: &[(&[&Vec<E::ScalarField>], &[E::ScalarField])],
+    ) -> Self {
+        let max_len = body_polynomials
+            .iter()
+            .map(|x| x.0.len())
+            .fold(0, usize::max);
+        assert_ne!(max_len, 0);
+
+        let batch_challenge = transcript.get_challenge::<E::ScalarField>(b"batch_challenge");
+        let batch_challenges = powers(batch_challenge, max_len);
+        let foldings_body_polynomials = body_polynomials
+            .iter()
+            .flat_map(|(polynomials, challenges)| {
+                let polynomial = linear_combination(polynomials, &batch_challenges);
+                foldings_polynomial(&polynomial, challenges)
+            })
+            .collect::<Vec<_>>();
+        let folded_polynomials_commitments = ck.batch_commit(&foldings_body_polynomials);
+
+        transcript.append_serializables(b"commitment", &folded_polynomials_commitments);
+        let eval_chals = (0..body_polynomials.len())
+            .map(|_| transcript.get_challenge::<E::ScalarField>(b"evaluation-chal"))
+            .collect::<Vec<_>>();
+        let eval_points = evaluation_points(&eval_chals);
+
+        let evaluate = |polynomial: &Vec<E::ScalarField>| {
+            eval_points
+                .iter()
+                .map(|x| evaluate_le(polynomial, x))
+                .collect::<Vec<_>>()
+        };
+        let base_polynomials_evaluations = base_polynomials
+            .iter()
+            .map(|polynomial| evaluate(polynomial))
+            .collect::<Vec<_>>();
+        let folded_polynomials_evaluations = foldings_body_polynomials
+            .iter()
+            .map(evaluate)
+            .collect::<Vec<_>>();
+
+        base_polynomials_evaluations
+            .iter()
+            .chain(&folded_polynomials_evaluations)
+            .flatten()
+            .for_each(|e| transcript.append_serializable(b"eval", e));
+        let open_chal = transcript.get_challenge(b"open-chal");
+
+        let mut all_polynomials = base_polynomials.to_vec();
+        all_polynomials.extend(foldings_body_polynomials.iter());
+        let evaluation_proof =
+            ck.batch_open_multi_points(&all_polynomials, &eval_points, &open_chal);
+
+        Self {
+            folded_polynomials_commitments,
+            folded_polynomials_evaluations,
+            evaluation_proof,
+            base_polynomials_evaluations,
+        }
+    }
+
+    /// Verify the tensorcheck proof.
+    ///
+    /// The arguments are the same as in [`TensorcheckProof::verify`](super::TensorcheckProof::verify),
+    /// except that `direct_base_polynomials_evaluations` holds the evaluations at \\(\pm\beta_i\\)
+    /// for the evaluation challenge \\(\beta_i\\) of the \\(i\\)-th instance in `eval_chals`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify<V: MultiPointVerifier<E>>(
+        &self,
+        transcript: &mut impl GeminiTranscript,
+        vk: &V,
+        asserted_res_vec: &[Vec<E::ScalarField>],
+        base_polynomials_commitments: &[Commitment<E>],
+        direct_base_polynomials_evaluations: &[[E::ScalarField; 2]],
+        fold_randomness: &[Vec<E::ScalarField>],
+        eval_chals: &[E::ScalarField],
+        batch_challenge: E::ScalarField,
+    ) -> VerificationResult {
+        let claim = self.reduce(
+            transcript,
+            asserted_res_vec,
+            base_polynomials_commitments,
+            direct_base_polynomials_evaluations,
+            fold_randomness,
+            eval_chals,
+            batch_challenge,
+        )?;
+        vk.verify_multi_points(
+            &claim.commitments,
+            &claim.eval_points,
+            &claim.evaluations,
+            &self.evaluation_proof,
+            &claim.open_chal,
+        )
+        .map_err(|_| VerificationError::PairingCheck)
+    }
+
+    /// Check the consistency of the folded polynomials evaluations,
+    /// and return the multi-point evaluation claim left to check against the batched evaluation proof.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn reduce(
+        &self,
+        transcript: &mut impl GeminiTranscript,
+        asserted_res_vec: &[Vec<E::ScalarField>],
+        base_polynomials_commitments: &[Commitment<E>],
+        direct_base_polynomials_evaluations: &[[E::ScalarField; 2]],
+        fold_randomness: &[Vec<E::ScalarField>],
+        eval_chals: &[E::ScalarField],
+        batch_challenge: E::ScalarField,
+    ) -> Result<MultiPointClaim<E>, VerificationError> {
+        let instances = fold_randomness.len();
+        let eval_points = evaluation_points(eval_chals);
+        let total_rounds = fold_randomness
+            .iter()
+            .map(|randomness| randomness.len().saturating_sub(1))
+            .sum::<usize>();
+        if instances == 0
+            || eval_chals.len() != instances
+            || asserted_res_vec.len() != instances
+            || direct_base_polynomials_evaluations.len() != instances
+            || fold_randomness
+                .iter()
+                .any(|randomness| randomness.len() < 2)
+            || self.base_polynomials_evaluations.len() != base_polynomials_commitments.len()
+            || self.folded_polynomials_commitments.len() != total_rounds
+            || self.folded_polynomials_evaluations.len() != total_rounds
+            || self
+                .base_polynomials_evaluations
+                .iter()
+                .chain(&self.folded_polynomials_evaluations)
+                .any(|evaluations| evaluations.len() != eval_points.len())
+        {
+            return Err(VerificationError::MalformedProof);
+        }
+
+        let two_inv = E::ScalarField::one().double().inverse().unwrap();
+        let two_beta = eval_chals
+            .iter()
+            .map(|beta| beta.double())
+            .collect::<Vec<_>>();
+        // a zero evaluation challenge would make the folding checks undefined.
+        let two_beta_invs = batch_inverse(&two_beta).ok_or(VerificationError::InvalidInput)?;
+        let mut offset = 0;
+        for (instance, randomness) in fold_randomness.iter().enumerate() {
+            let rounds = randomness.len() - 1;
+            let two_beta_inv = two_beta_invs[instance];
+            let folded_polynomials_evaluations =
+                &self.folded_polynomials_evaluations[offset..offset + rounds];
+            offset += rounds;
+
+            // each folding is checked on the evaluations of its parent at the points of its own instance.
+            let mut parent_evaluations = direct_base_polynomials_evaluations[instance];
+            for (evaluations, challenge) in folded_polynomials_evaluations.iter().zip(randomness) {
+                let [eval_sq, eval_pos, eval_neg] =
+                    [0, 1, 2].map(|i| evaluations[3 * instance + i]);
+                let expected = evaluate_sq_fp(
+                    &parent_evaluations[0],
+                    &parent_evaluations[1],
+                    challenge,
+                    &two_inv,
+                    &two_beta_inv,
+                );
+                if eval_sq != expected {
+                    return Err(VerificationError::TensorcheckConsistency);
+                }
+                parent_evaluations = [eval_pos, eval_neg];
+            }
+
+            let subclaim = evaluate_sq_fp(
+                &parent_evaluations[0],
+                &parent_evaluations[1],
+                &randomness[rounds],
+                &two_inv,
+                &two_beta_inv,
+            );
+            let asserted_res = &asserted_res_vec[instance];
+            let batch_challenges = powers(batch_challenge, asserted_res.len());
+            if subclaim != ip(asserted_res, &batch_challenges) {
+                return Err(VerificationError::TensorcheckConsistency);
+            }
+        }
+
+        let mut all_commitments = base_polynomials_commitments.to_vec();
+        all_commitments.extend(self.folded_polynomials_commitments.iter());
+
+        self.base_polynomials_evaluations
+            .iter()
+            .chain(&self.folded_polynomials_evaluations)
+            .flatten()
+            .for_each(|e| transcript.append_serializable(b"eval", e));
+        let open_chal = transcript.get_challenge(b"open-chal");
+
+        let mut evaluations = self.base_polynomials_evaluations.clone();
+        evaluations.extend(self.folded_polynomials_evaluations.iter().cloned());
+        Ok(MultiPointClaim {
+            commitments: all_commitments,
+            eval_points,
+            evaluations,
+            open_chal,
+        })
+    }
+}
+
+#[test]
+fn test_multipoint_tensorcheck() {
+    use crate::kzg::VerifierKey;
+    use crate::misc::tensor;
+    use ark_ff::Zero;
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+    use merlin::Transcript;
+
+    let rng = &mut ark_std::test_rng();
+    let ck = CommitterKey::<Bls12_381>::new(16, 6, rng);
+    let vk = VerifierKey::from(&ck);
+
+    let polynomials = [16, 11, 8]
+        .iter()
+        .map(|&len| (0..len).map(|_| Fr::rand(rng)).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let base_polynomials = polynomials.iter().collect::<Vec<_>>();
+    let randomnesses = [
+        (0..4).map(|_| Fr::rand(rng)).collect::<Vec<_>>(),
+        (0..3).map(|_| Fr::rand(rng)).collect::<Vec<_>>(),
+    ];
+    // the first instance batches the first two polynomials, the second one the last.
+    let instances = [&base_polynomials[..2], &base_polynomials[2..]];
+    let body_polynomials = [
+        (instances[0], &randomnesses[0][..]),
+        (instances[1], &randomnesses[1][..]),
+    ];
+    let commitments = ck.batch_commit(&polynomials);
+    let asserted_res_vec = instances
+        .iter()
+        .zip(&randomnesses)
+        .map(|(polynomials, randomness)| {
+            polynomials
+                .iter()
+                .map(|p| ip(p, &tensor(randomness)[..p.len()]))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let proof = MultiPointTensorcheckProof::new(
+        &mut Transcript::new(crate::PROTOCOL_NAME),
+        &ck,
+        &base_polynomials,
+        &body_polynomials,
+    );
+
+    let verify = |asserted_res_vec: &[Vec<Fr>]| {
+        let mut transcript = Transcript::new(crate::PROTOCOL_NAME);
+        let batch_challenge = transcript.get_challenge::<Fr>(b"batch_challenge");
+        transcript.append_serializables(b"commitment", &proof.folded_polynomials_commitments);
+        let eval_chals = (0..2)
+            .map(|_| transcript.get_challenge::<Fr>(b"evaluation-chal"))
+            .collect::<Vec<_>>();
+        assert_ne!(eval_chals[0], eval_chals[1]);
+
+        let batch_challenges = powers(batch_challenge, 2);
+        let direct_base_polynomials_evaluations =
+            [(0, 0..2), (1, 2..3)].map(|(instance, range)| {
+                [1, 2].map(|i| {
+                    let evaluations = proof.base_polynomials_evaluations[range.clone()]
+                        .iter()
+                        .map(|e| e[3 * instance + i])
+                        .collect::<Vec<_>>();
+                    ip(&evaluations, &batch_challenges[..evaluations.len()])
+                })
+            });
+        proof.verify(
+            &mut transcript,
+            &vk,
+            asserted_res_vec,
+            &commitments,
+            &direct_base_polynomials_evaluations,
+            &randomnesses,
+            &eval_chals,
+            batch_challenge,
+        )
+    };
+    assert!(verify(&asserted_res_vec).is_ok());
+
+    let mut wrong_asserted_res_vec = asserted_res_vec.clone();
+    wrong_asserted_res_vec[1][0] += Fr::one();
+    assert!(verify(&wrong_asserted_res_vec).is_err());
+
+    let result = proof.verify(
+        &mut Transcript::new(crate::PROTOCOL_NAME),
+        &vk,
+        &asserted_res_vec,
+        &commitments,
+        &[[Fr::zero(); 2]; 2],
+        &randomnesses,
+        &[Fr::zero(); 2],
+        Fr::one(),
+    );
+    assert_eq!(result, Err(VerificationError::InvalidInput));
+}
@@ -0,0 +1,331 @@
+//! The tensorcheck with a single commitment to all folded polynomials.
+//!
+//! [`TensorcheckProof`](super::TensorcheckProof) commits to each folded polynomial separately,
+//! costing \\(\log n\\) multi-scalar multiplications of decreasing size, and as many group elements in the proof.
+//! [`PackedTensorcheckProof`] instead interleaves the folded polynomials \\(f_0, \dots, f_{r-1}\\) in
+//!
+//! \\[
+//! P(x) = \sum_{j < r} x^j f_j(x^R),
+//! \\]
+//!
+//! where \\(R\\) is the smallest power of two at least \\(r\\),
+//! and commits to it with a single multi-scalar multiplication over the \\(n\\) non-zero coefficients.
+//! For a primitive \\(R\\)-th root of unity \\(\omega\\), the evaluations \\(P(u \omega^k)\\) for \\(k < R\\)
+//! are the Fourier transform of \\(u^j f_j(u^R)\\):
+//! the verifier recovers all folded evaluations at \\(u^R\\) from a linear combination of them.
+//! Evaluating \\(P\\) at the cosets of \\(y, y\zeta, y^2\\), with \\(\zeta^R = -1\\),
+//! gives the evaluations of each \\(f_j\\) at \\(\beta, -\beta, \beta^2\\) for \\(\beta = y^R\\).
+//! Since \\(P\\) binds each \\(f_j\\), the proof is as sound as the original tensorcheck.
+//!
+//! The committer key must support polynomials of degree \\(Rn/2\\) and \\(3R\\) evaluation points.
+use ark_ec::pairing::Pairing;
+use ark_ec::VariableBaseMSM;
+use ark_ff::{FftField, Field, One, Zero};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_serialize::*;
+use ark_std::vec::Vec;
+
+use super::{evaluate_sq_fp, foldings_polynomial};
+use crate::errors::{VerificationError, VerificationResult};
+use crate::kzg::{Commitment, CommitterKey, EvaluationProof, MultiPointVerifier};
+use crate::misc::{evaluate_le, ip, linear_combination, powers};
+use crate::transcript::GeminiTranscript;
+
+/// A tensorcheck proof with a single commitment to all folded polynomials.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PackedTensorcheckProof<E: Pairing> {
+    /// The commitment to the interleaved folded polynomials \\(P\\).
+    pub packed_commitment: Commitment<E>,
+    /// The evaluations of \\(P\\) at \\(u\omega^k\\), for \\(u = y, y\zeta, y^2\\) and \\(k < R\\).
+    pub packed_evaluations: Vec<E::ScalarField>,
+    /// The evaluation proof for \\(P\\).
+    pub packed_proof: EvaluationProof<E>,
+    /// The evaluations of each base polynomial at \\(\beta, -\beta\\).
+    pub base_polynomials_evaluations: Vec<[E::ScalarField; 2]>,
+    /// The batched evaluation proof for the base polynomials.
+    pub evaluation_proof: EvaluationProof<E>,
+}
+
+/// Commit to \\(\sum_j x^j f_j(x^{\text{stride}})\\) for the `polynomials` \\(f_j\\),
+/// with a multi-scalar multiplication over their coefficients only.
+fn commit_packed<E: Pairing>(
+    ck: &CommitterKey<E>,
+    polynomials: &[Vec<E::ScalarField>],
+    stride: usize,
+) -> Commitment<E> {
+    let (bases, scalars): (Vec<_>, Vec<_>) = polynomials
+        .iter()
+        .enumerate()
+        .flat_map(|(j, polynomial)| {
+            polynomial
+                .iter()
+                .enumerate()
+                .map(move |(i, coefficient)| (i * stride + j, *coefficient))
+        })
+        .map(|(position, coefficient)| {
+            assert!(
+                position < ck.powers_of_g.len(),
+                "packed polynomial exceeds the committer key"
+            );
+            (ck.powers_of_g[position], coefficient)
+        })
+        .unzip();
+    Commitment(E::G1::msm_unchecked(&bases, &scalars))
+}
+
+/// Return the quotient of `polynomial` by the monic `divisor`, both in little-endian.
+fn divide_by_monic<F: Field>(polynomial: &[F], divisor: &[F]) -> Vec<F> {
+    let degree = divisor.len() - 1;
+    if polynomial.len() <= degree {
+        return Vec::new();
+    }
+    let mut remainder = polynomial.to_vec();
+    let mut quotient = vec![F::zero(); polynomial.len() - degree];
+    for i in (0..quotient.len()).rev() {
+        let coefficient = remainder[i + degree];
+        quotient[i] = coefficient;
+        for (k, d) in divisor[..degree].iter().enumerate() {
+            remainder[i + k] -= coefficient * d;
+        }
+    }
+    quotient
+}
+
+/// Return the cosets \\(u\omega^k\\), for \\(u = y, y\zeta, y^2\\) and \\(k < R\\).
+fn packed_points<F: FftField>(domain: &Radix2EvaluationDomain<F>, eval_chal: F) -> Vec<F> {
+    let size = domain.size();
+    let zeta = F::get_root_of_unity(2 * size as u64).unwrap();
+    [eval_chal, eval_chal * zeta, eval_chal.square()]
+        .iter()
+        .flat_map(|&u| domain.elements().map(move |w| u * w))
+        .collect()
+}
+
+impl<E: Pairing> PackedTensorcheckProof<E> {
+    /// Prove that each polynomial \\(f_i\\) in `polynomials`
+    /// satisfies \\(\langle f_i, \otimes_j (1, \rho_j) \rangle = t_i\\)
+    /// for the \\(n \geq 2\\) `challenges` \\(\rho_0, \dots, \rho_{n-1}\\).
+    pub fn new(
+        transcript: &mut impl GeminiTranscript,
+        ck: &CommitterKey<E>,
+        polynomials: &[Vec<E::ScalarField>],
+        challenges: &[E::ScalarField],
+    ) -> Self {
+        assert!(
+            challenges.len() >= 2,
+            "the tensorcheck requires at least two challenges"
+        );
+        let rounds = challenges.len() - 1;
+        let domain = Radix2EvaluationDomain::<E::ScalarField>::new(rounds).unwrap();
+        let stride = domain.size();
+
+        let batch_challenge = transcript.get_challenge::<E::ScalarField>(b"batch_challenge");
+        let batched_polynomial =
+            linear_combination(polynomials, &powers(batch_challenge, polynomials.len()));
+        let foldings = foldings_polynomial(&batched_polynomial, challenges);
+        let packed_commitment = commit_packed(ck, &foldings, stride);
+        transcript.append_serializable(b"commitment", &packed_commitment);
+
+        let eval_chal = transcript.get_challenge::<E::ScalarField>(b"evaluation-chal");
+        let beta = eval_chal.pow([stride as u64]);
+        let points = packed_points(&domain, eval_chal);
+
+        let base_polynomials_evaluations = polynomials
+            .iter()
+            .map(|polynomial| {
+                [
+                    evaluate_le(polynomial, &beta),
+                    evaluate_le(polynomial, &-beta),
+                ]
+            })
+            .collect::<Vec<_>>();
+        let packed_evaluations = points
+            .iter()
+            .map(|point| {
+                let point_stride = point.pow([stride as u64]);
+                foldings
+                    .iter()
+                    .zip(powers(*point, foldings.len()))
+                    .map(|(folding, shift)| shift * evaluate_le(folding, &point_stride))
+                    .sum::<E::ScalarField>()
+            })
+            .collect::<Vec<_>>();
+        base_polynomials_evaluations
+            .iter()
+            .flatten()
+            .chain(&packed_evaluations)
+            .for_each(|e| transcript.append_serializable(b"eval", e));
+        let open_chal = transcript.get_challenge(b"open-chal");
+
+        let polynomials = polynomials.iter().collect::<Vec<_>>();
+        let evaluation_proof = ck.batch_open_multi_points(&polynomials, &[beta, -beta], &open_chal);
+        // The vanishing polynomial over the points is z(x^R) for z(t) = (t^2 - beta^2)(t - beta^2),
+        // hence the quotient of P is interleaving the quotients of each folding by z.
+        let beta2 = beta.square();
+        let divisor = [beta2.square(), -beta2, -beta2, E::ScalarField::one()];
+        let quotients = foldings
+            .iter()
+            .map(|folding| divide_by_monic(folding, &divisor))
+            .collect::<Vec<_>>();
+        let packed_proof = EvaluationProof(commit_packed(ck, &quotients, stride).0);
+
+        PackedTensorcheckProof {
+            packed_commitment,
+            packed_evaluations,
+            packed_proof,
+            base_polynomials_evaluations,
+            evaluation_proof,
+        }
+    }
+
+    /// Verify the proof for the polynomials committed in `commitments`,
+    /// with claimed tensor products `evaluations` at the tensor of `challenges`.
+    pub fn verify<V: MultiPointVerifier<E>>(
+        &self,
+        transcript: &mut impl GeminiTranscript,
+        vk: &V,
+        commitments: &[Commitment<E>],
+        challenges: &[E::ScalarField],
+        evaluations: &[E::ScalarField],
+    ) -> VerificationResult {
+        let rounds = challenges.len().saturating_sub(1);
+        if rounds == 0
+            || commitments.is_empty()
+            || commitments.len() != evaluations.len()
+            || self.base_polynomials_evaluations.len() != commitments.len()
+            || self.packed_evaluations.len() != 3 * rounds.next_power_of_two()
+        {
+            return Err(VerificationError::MalformedProof);
+        }
+        let domain = Radix2EvaluationDomain::<E::ScalarField>::new(rounds).unwrap();
+        let stride = domain.size();
+
+        let batch_challenge = transcript.get_challenge::<E::ScalarField>(b"batch_challenge");
+        transcript.append_serializable(b"commitment", &self.packed_commitment);
+        let eval_chal = transcript.get_challenge::<E::ScalarField>(b"evaluation-chal");
+        let beta = eval_chal.pow([stride as u64]);
+        let points = packed_points(&domain, eval_chal);
+
+        // recover the evaluations of the foldings at beta, -beta, and beta^2.
+        let [pos, neg, sq] = [0, 1, 2].map(|coset| {
+            let offset = points[coset * stride];
+            let offset_inv = offset.inverse().unwrap();
+            domain
+                .ifft(&self.packed_evaluations[coset * stride..(coset + 1) * stride])
+                .into_iter()
+                .zip(powers(offset_inv, stride))
+                .map(|(e, shift)| e * shift)
+                .collect::<Vec<_>>()
+        });
+
+        let two_inv = E::ScalarField::one().double().inverse().unwrap();
+        let two_beta_inv = beta.double().inverse().unwrap();
+        let batch_challenges = powers(batch_challenge, commitments.len());
+        let mut parent = [0, 1].map(|point| {
+            let evaluations = self
+                .base_polynomials_evaluations
+                .iter()
+                .map(|e| e[point])
+                .collect::<Vec<_>>();
+            ip(&evaluations, &batch_challenges)
+        });
+        for (j, challenge) in challenges[..rounds].iter().enumerate() {
+            let expected =
+                evaluate_sq_fp(&parent[0], &parent[1], challenge, &two_inv, &two_beta_inv);
+            if sq[j] != expected {
+                return Err(VerificationError::TensorcheckConsistency);
+            }
+            parent = [pos[j], neg[j]];
+        }
+        let subclaim = evaluate_sq_fp(
+            &parent[0],
+            &parent[1],
+            &challenges[rounds],
+            &two_inv,
+            &two_beta_inv,
+        );
+        if subclaim != ip(evaluations, &batch_challenges) {
+            return Err(VerificationError::TensorcheckConsistency);
+        }
+
+        self.base_polynomials_evaluations
+            .iter()
+            .flatten()
+            .chain(&self.packed_evaluations)
+            .for_each(|e| transcript.append_serializable(b"eval", e));
+        let open_chal = transcript.get_challenge(b"open-chal");
+
+        let base_evaluations = self
+            .base_polynomials_evaluations
+            .iter()
+            .map(|e| e.to_vec())
+            .collect::<Vec<_>>();
+        vk.verify_multi_points(
+            commitments,
+            &[beta, -beta],
+            &base_evaluations,
+            &self.evaluation_proof,
+            &open_chal,
+        )
+        .and_then(|_| {
+            vk.verify_multi_points(
+                &[self.packed_commitment],
+                &points,
+                &[self.packed_evaluations.clone()],
+                &self.packed_proof,
+                &E::ScalarField::one(),
+            )
+        })
+        .map_err(|_| VerificationError::PairingCheck)
+    }
+}
+
+#[test]
+fn test_packed_tensorcheck() {
+    use crate::kzg::VerifierKey;
+    use crate::misc::tensor;
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+    use merlin::Transcript;
+
+    let rng = &mut ark_std::test_rng();
+    let ck = CommitterKey::<Bls12_381>::new(64, 12, rng);
+    let vk = VerifierKey::from(&ck);
+
+    let polynomials = [32, 20]
+        .iter()
+        .map(|&len| (0..len).map(|_| Fr::rand(rng)).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let challenges = (0..5).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+    let commitments = ck.batch_commit(&polynomials);
+    let evaluations = polynomials
+        .iter()
+        .map(|p| ip(p, &tensor(&challenges)[..p.len()]))
+        .collect::<Vec<_>>();
+
+    let proof = PackedTensorcheckProof::new(
+        &mut Transcript::new(crate::PROTOCOL_NAME),
+        &ck,
+        &polynomials,
+        &challenges,
+    );
+    let result = proof.verify(
+        &mut Transcript::new(crate::PROTOCOL_NAME),
+        &vk,
+        &commitments,
+        &challenges,
+        &evaluations,
+    );
+    assert!(result.is_ok());
+
+    let mut wrong_proof = proof.clone();
+    wrong_proof.packed_evaluations[0] += Fr::one();
+    let result = wrong_proof.verify(
+        &mut Transcript::new(crate::PROTOCOL_NAME),
+        &vk,
+        &commitments,
+        &challenges,
+        &evaluations,
+    );
+    assert!(result.is_err());
+}
@@ -0,0 +1,318 @@
+//! The tensorcheck with challenges from an extension of the scalar field.
+//!
+//! The sumcheck of [`SmallFieldProver`](crate::subprotocols::sumcheck::SmallFieldProver)
+//! reduces to claims \\(\langle f_i, \otimes_j (1, \rho_j) \rangle = t_i\\)
+//! for polynomials \\(f_i\\) over the base field \\(\FF\\),
+//! and challenges \\(\rho_j\\) and evaluations \\(t_i\\) over a degree-\\(k\\) extension \\(\mathbb{E}\\).
+//! KZG commits to polynomials over the scalar field of the curve only:
+//! here \\(\FF\\) is the scalar field, and each folded polynomial, over \\(\mathbb{E}\\),
+//! is committed as its \\(k\\) coordinate polynomials over \\(\FF\\).
+//!
+//! The base polynomials are never lifted: the first folding lifts them to \\(\mathbb{E}\\) while halving their length,
+//! as in the sumcheck.
+//! The evaluation point \\(\beta\\) is drawn from \\(\FF\\), so that the evaluations of a folded polynomial
+//! at \\(\beta^2, \beta, -\beta\\) are given by the evaluations of its coordinates,
+//! and the folding checks run over \\(\mathbb{E}\\).
+//! All polynomials are opened at \\(\beta^2, \beta, -\beta\\) with a single batched evaluation proof,
+//! at the price of \\(k\\) commitments per folding instead of one.
+use ark_ec::pairing::Pairing;
+use ark_ff::Field;
+use ark_serialize::*;
+use ark_std::vec::Vec;
+use ark_std::One;
+
+use super::evaluate_sq_fp;
+use crate::errors::{VerificationError, VerificationResult};
+use crate::kzg::{Commitment, CommitterKey, EvaluationProof, MultiPointVerifier};
+use crate::misc::{evaluate_le, fold_polynomial, ip, linear_combination, powers};
+use crate::subprotocols::sumcheck::small_field::fold_lift;
+use crate::transcript::GeminiTranscript;
+
+/// A tensorcheck proof for polynomials over the scalar field, with challenges from an extension.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SmallFieldTensorcheckProof<E: Pairing> {
+    /// The commitments to the coordinates of each folded polynomial.
+    pub folded_polynomials_commitments: Vec<Vec<Commitment<E>>>,
+    /// The evaluations of the coordinates of each folded polynomial at \\(\beta^2, \beta, -\beta\\).
+    pub folded_polynomials_evaluations: Vec<Vec<[E::ScalarField; 3]>>,
+    /// The evaluations of each base polynomial at \\(\beta^2, \beta, -\beta\\).
+    pub base_polynomials_evaluations: Vec<[E::ScalarField; 3]>,
+    /// The batched evaluation proof for the base polynomials and the coordinates of the folded polynomials.
+    pub evaluation_proof: EvaluationProof<E>,
+}
+
+/// Split `polynomial`, over the extension `K`, into its coordinate polynomials over the base prime field.
+fn coordinates<K: Field>(polynomial: &[K]) -> Vec<Vec<K::BasePrimeField>> {
+    let degree = K::extension_degree() as usize;
+    let mut coordinates = vec![Vec::with_capacity(polynomial.len()); degree];
+    for element in polynomial {
+        coordinates
+            .iter_mut()
+            .zip(element.to_base_prime_field_elements())
+            .for_each(|(coordinate, value)| coordinate.push(value));
+    }
+    coordinates
+}
+
+impl<E: Pairing> SmallFieldTensorcheckProof<E> {
+    /// Prove that each polynomial \\(f_i\\) in `polynomials`, of length at most \\(2^n\\),
+    /// satisfies \\(\langle f_i, \otimes_j (1, \rho_j) \rangle = t_i\\)
+    /// for the \\(n \geq 2\\) `challenges` \\(\rho_0, \dots, \rho_{n-1}\\) over the extension `K`.
+    ///
+    /// The committer key must support 3 evaluation points.
+    pub fn new<K: Field<BasePrimeField = E::ScalarField>>(
+        transcript: &mut impl GeminiTranscript,
+        ck: &CommitterKey<E>,
+        polynomials: &[Vec<E::ScalarField>],
+        challenges: &[K],
+    ) -> Self {
+        assert!(
+            challenges.len() >= 2,
+            "the tensorcheck requires at least two challenges"
+        );
+
+        let batch_challenge = transcript.get_challenge::<E::ScalarField>(b"batch_challenge");
+        let batched_polynomial =
+            linear_combination(polynomials, &powers(batch_challenge, polynomials.len()));
+        // the last folding is a constant, given by the claimed evaluations.
+        let mut folded_polynomials = Vec::with_capacity(challenges.len() - 1);
+        folded_polynomials.push(fold_lift(&batched_polynomial, challenges[0]));
+        for &challenge in &challenges[1..challenges.len() - 1] {
+            let folded = fold_polynomial(folded_polynomials.last().unwrap(), challenge);
+            folded_polynomials.push(folded);
+        }
+        let coordinate_polynomials = folded_polynomials
+            .iter()
+            .map(|polynomial| coordinates(polynomial))
+            .collect::<Vec<_>>();
+        let folded_polynomials_commitments = coordinate_polynomials
+            .iter()
+            .map(|coordinates| ck.batch_commit(coordinates))
+            .collect::<Vec<_>>();
+        folded_polynomials_commitments
+            .iter()
+            .for_each(|commitments| transcript.append_serializables(b"commitment", commitments));
+        let eval_chal = transcript.get_challenge::<E::ScalarField>(b"evaluation-chal");
+        let points = [eval_chal.square(), eval_chal, -eval_chal];
+
+        let evaluate =
+            |polynomial: &Vec<E::ScalarField>| points.map(|x| evaluate_le(polynomial, &x));
+        let base_polynomials_evaluations = polynomials.iter().map(evaluate).collect::<Vec<_>>();
+        let folded_polynomials_evaluations = coordinate_polynomials
+            .iter()
+            .map(|coordinates| coordinates.iter().map(evaluate).collect())
+            .collect::<Vec<Vec<_>>>();
+        base_polynomials_evaluations
+            .iter()
+            .chain(folded_polynomials_evaluations.iter().flatten())
+            .flatten()
+            .for_each(|e| transcript.append_serializable(b"eval", e));
+        let open_chal = transcript.get_challenge(b"open-chal");
+
+        let all_polynomials = polynomials
+            .iter()
+            .chain(coordinate_polynomials.iter().flatten())
+            .collect::<Vec<_>>();
+        let evaluation_proof = ck.batch_open_multi_points(&all_polynomials, &points, &open_chal);
+
+        SmallFieldTensorcheckProof {
+            folded_polynomials_commitments,
+            folded_polynomials_evaluations,
+            base_polynomials_evaluations,
+            evaluation_proof,
+        }
+    }
+
+    /// Verify the proof for the polynomials committed in `commitments`,
+    /// with claimed tensor products `evaluations` at the tensor of `challenges`, over the extension `K`.
+    pub fn verify<K, V>(
+        &self,
+        transcript: &mut impl GeminiTranscript,
+        vk: &V,
+        commitments: &[Commitment<E>],
+        challenges: &[K],
+        evaluations: &[K],
+    ) -> VerificationResult
+    where
+        K: Field<BasePrimeField = E::ScalarField>,
+        V: MultiPointVerifier<E>,
+    {
+        let rounds = challenges.len().saturating_sub(1);
+        let degree = K::extension_degree() as usize;
+        if rounds == 0
+            || commitments.is_empty()
+            || commitments.len() != evaluations.len()
+            || self.base_polynomials_evaluations.len() != commitments.len()
+            || self.folded_polynomials_commitments.len() != rounds
+            || self.folded_polynomials_evaluations.len() != rounds
+            || self
+                .folded_polynomials_commitments
+                .iter()
+                .any(|commitments| commitments.len() != degree)
+            || self
+                .folded_polynomials_evaluations
+                .iter()
+                .any(|evaluations| evaluations.len() != degree)
+        {
+            return Err(VerificationError::MalformedProof);
+        }
+
+        let batch_challenge = transcript.get_challenge::<E::ScalarField>(b"batch_challenge");
+        self.folded_polynomials_commitments
+            .iter()
+            .for_each(|commitments| transcript.append_serializables(b"commitment", commitments));
+        let eval_chal = transcript.get_challenge::<E::ScalarField>(b"evaluation-chal");
+        let points = [eval_chal.square(), eval_chal, -eval_chal];
+        let two_inv = K::from_base_prime_field(E::ScalarField::one().double().inverse().unwrap());
+        let two_beta_inv = K::from_base_prime_field(
+            eval_chal
+                .double()
+                .inverse()
+                .ok_or(VerificationError::InvalidInput)?,
+        );
+
+        // the evaluations of the batched polynomial at beta and -beta.
+        let batch_challenges = powers(batch_challenge, commitments.len());
+        let mut parent_evaluations = [1, 2].map(|point| {
+            let evaluations = self
+                .base_polynomials_evaluations
+                .iter()
+                .map(|evaluations| evaluations[point])
+                .collect::<Vec<_>>();
+            K::from_base_prime_field(ip(&evaluations, &batch_challenges))
+        });
+        for (challenge, evaluations) in challenges.iter().zip(&self.folded_polynomials_evaluations)
+        {
+            // the evaluations of the folded polynomial, from the ones of its coordinates.
+            let [eval_sq, eval_pos, eval_neg] = [0, 1, 2].map(|point| {
+                let coordinates = evaluations
+                    .iter()
+                    .map(|evaluations| evaluations[point])
+                    .collect::<Vec<_>>();
+                K::from_base_prime_field_elems(&coordinates)
+            });
+            let (eval_sq, eval_pos, eval_neg) = match (eval_sq, eval_pos, eval_neg) {
+                (Some(eval_sq), Some(eval_pos), Some(eval_neg)) => (eval_sq, eval_pos, eval_neg),
+                _ => return Err(VerificationError::MalformedProof),
+            };
+            let expected = evaluate_sq_fp(
+                &parent_evaluations[0],
+                &parent_evaluations[1],
+                challenge,
+                &two_inv,
+                &two_beta_inv,
+            );
+            if eval_sq != expected {
+                return Err(VerificationError::TensorcheckConsistency);
+            }
+            parent_evaluations = [eval_pos, eval_neg];
+        }
+        let subclaim = evaluate_sq_fp(
+            &parent_evaluations[0],
+            &parent_evaluations[1],
+            &challenges[rounds],
+            &two_inv,
+            &two_beta_inv,
+        );
+        let lifted_batch_challenges = batch_challenges
+            .iter()
+            .map(|&challenge| K::from_base_prime_field(challenge))
+            .collect::<Vec<_>>();
+        if subclaim != ip(evaluations, &lifted_batch_challenges) {
+            return Err(VerificationError::TensorcheckConsistency);
+        }
+
+        self.base_polynomials_evaluations
+            .iter()
+            .chain(self.folded_polynomials_evaluations.iter().flatten())
+            .flatten()
+            .for_each(|e| transcript.append_serializable(b"eval", e));
+        let open_chal = transcript.get_challenge(b"open-chal");
+
+        let all_commitments = commitments
+            .iter()
+            .chain(self.folded_polynomials_commitments.iter().flatten())
+            .copied()
+            .collect::<Vec<_>>();
+        let all_evaluations = self
+            .base_polynomials_evaluations
+            .iter()
+            .chain(self.folded_polynomials_evaluations.iter().flatten())
+            .map(|evaluations| evaluations.to_vec())
+            .collect::<Vec<_>>();
+        vk.verify_multi_points(
+            &all_commitments,
+            &points,
+            &all_evaluations,
+            &self.evaluation_proof,
+            &open_chal,
+        )
+        .map_err(|_| VerificationError::PairingCheck)
+    }
+}
+
+#[test]
+fn test_small_field_tensorcheck() {
+    use crate::kzg::VerifierKey;
+    use crate::subprotocols::sumcheck::small_field::evaluate_lifted;
+    use ark_ff::fields::{Fp2, Fp2Config, MontFp};
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+    use merlin::Transcript;
+
+    /// The quadratic extension of the scalar field of BLS12-381.
+    struct FrExtConfig;
+    impl Fp2Config for FrExtConfig {
+        type Fp = Fr;
+        // the multiplicative generator of the field, thus a quadratic non-residue.
+        const NONRESIDUE: Fr = MontFp!("7");
+        const FROBENIUS_COEFF_FP2_C1: &'static [Fr] = &[MontFp!("1"), MontFp!("-1")];
+    }
+    type FrExt = Fp2<FrExtConfig>;
+
+    let rng = &mut ark_std::test_rng();
+    let ck = CommitterKey::<Bls12_381>::new(32, 3, rng);
+    let vk = VerifierKey::from(&ck);
+
+    let polynomials = [32, 20, 7]
+        .iter()
+        .map(|&len| (0..len).map(|_| Fr::rand(rng)).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let challenges = (0..5).map(|_| FrExt::rand(rng)).collect::<Vec<_>>();
+    let commitments = ck.batch_commit(&polynomials);
+    let evaluations = polynomials
+        .iter()
+        .map(|p| evaluate_lifted(p, &challenges))
+        .collect::<Vec<_>>();
+
+    let proof = SmallFieldTensorcheckProof::new(
+        &mut Transcript::new(crate::PROTOCOL_NAME),
+        &ck,
+        &polynomials,
+        &challenges,
+    );
+    assert_eq!(proof.folded_polynomials_commitments.len(), 4);
+    assert!(proof
+        .folded_polynomials_commitments
+        .iter()
+        .all(|commitments| commitments.len() == 2));
+    let result = proof.verify(
+        &mut Transcript::new(crate::PROTOCOL_NAME),
+        &vk,
+        &commitments,
+        &challenges,
+        &evaluations,
+    );
+    assert!(result.is_ok());
+
+    let mut wrong_evaluations = evaluations.clone();
+    wrong_evaluations[1] += FrExt::one();
+    let result = proof.verify(
+        &mut Transcript::new(crate::PROTOCOL_NAME),
+        &vk,
+        &commitments,
+        &challenges,
+        &wrong_evaluations,
+    );
+    assert_eq!(result, Err(VerificationError::TensorcheckConsistency));
+}
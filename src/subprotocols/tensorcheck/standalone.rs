@@ -0,0 +1,143 @@
+//! The tensorcheck as a standalone protocol.
+//!
+//! [`TensorcheckProof::new_time`] and [`TensorcheckProof::verify`] expose the protocol
+//! as used within Gemini, where the verifier derives the evaluations of the base polynomials itself.
+//! [`Tensorcheck`] instead proves and verifies claims of the form
+//! \\(\langle f_i, \otimes_j (1, \rho_j) \rangle = t_i\\)
+//! for committed polynomials \\(f_i\\), replaying its own transcript,
+//! so that other split-and-fold protocols can reduce to it.
+use ark_ec::pairing::Pairing;
+use ark_std::vec::Vec;
+
+use super::TensorcheckProof;
+use crate::errors::{VerificationError, VerificationResult};
+use crate::kzg::{Commitment, CommitterKey, MultiPointVerifier};
+use crate::misc::{ip, powers};
+use crate::transcript::GeminiTranscript;
+
+/// Prover and verifier of the tensorcheck for polynomials sharing the same challenges.
+pub struct Tensorcheck;
+
+impl Tensorcheck {
+    /// Prove that each polynomial \\(f_i\\) in `polynomials`, of length at most \\(2^n\\),
+    /// satisfies \\(\langle f_i, \otimes_j (1, \rho_j) \rangle = t_i\\)
+    /// for the \\(n \geq 2\\) `challenges` \\(\rho_0, \dots, \rho_{n-1}\\).
+    pub fn prove<E: Pairing>(
+        transcript: &mut impl GeminiTranscript,
+        ck: &CommitterKey<E>,
+        polynomials: &[Vec<E::ScalarField>],
+        challenges: &[E::ScalarField],
+    ) -> TensorcheckProof<E> {
+        assert!(
+            challenges.len() >= 2,
+            "the tensorcheck requires at least two challenges"
+        );
+        let polynomials = polynomials.iter().collect::<Vec<_>>();
+        TensorcheckProof::new_time_batch(
+            transcript,
+            ck,
+            &polynomials,
+            &[(&polynomials[..], challenges)],
+        )
+    }
+
+    /// Verify `proof` for the polynomials committed in `commitments`,
+    /// with claimed tensor products `evaluations` at the tensor of `challenges`.
+    pub fn verify<E: Pairing, V: MultiPointVerifier<E>>(
+        proof: &TensorcheckProof<E>,
+        transcript: &mut impl GeminiTranscript,
+        vk: &V,
+        commitments: &[Commitment<E>],
+        challenges: &[E::ScalarField],
+        evaluations: &[E::ScalarField],
+    ) -> VerificationResult {
+        let rounds = challenges.len().saturating_sub(1);
+        if rounds == 0
+            || commitments.is_empty()
+            || commitments.len() != evaluations.len()
+            || proof.base_polynomials_evaluations.len() != commitments.len()
+            || proof.folded_polynomials_commitments.len() != rounds
+            || proof.folded_polynomials_evaluations.len() != rounds
+        {
+            return Err(VerificationError::MalformedProof);
+        }
+
+        let batch_challenge = transcript.get_challenge::<E::ScalarField>(b"batch_challenge");
+        transcript.append_serializables(b"commitment", &proof.folded_polynomials_commitments);
+        let eval_chal = transcript.get_challenge::<E::ScalarField>(b"evaluation-chal");
+
+        // the evaluations of the batched polynomial at beta and -beta.
+        let batch_challenges = powers(batch_challenge, commitments.len());
+        let [pos, neg] = [1, 2].map(|point| {
+            let evaluations = proof
+                .base_polynomials_evaluations
+                .iter()
+                .map(|evaluations| evaluations[point])
+                .collect::<Vec<_>>();
+            ip(&evaluations, &batch_challenges)
+        });
+
+        proof.verify(
+            transcript,
+            vk,
+            &[evaluations.to_vec()],
+            commitments,
+            &[[pos, neg]],
+            &[challenges.to_vec()],
+            eval_chal,
+            batch_challenge,
+        )
+    }
+}
+
+#[test]
+fn test_standalone_tensorcheck() {
+    use crate::kzg::VerifierKey;
+    use crate::misc::tensor;
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+    use merlin::Transcript;
+
+    let rng = &mut ark_std::test_rng();
+    let ck = CommitterKey::<Bls12_381>::new(32, 3, rng);
+    let vk = VerifierKey::from(&ck);
+
+    let polynomials = [32, 20, 7]
+        .iter()
+        .map(|&len| (0..len).map(|_| Fr::rand(rng)).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let challenges = (0..5).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+    let commitments = ck.batch_commit(&polynomials);
+    let evaluations = polynomials
+        .iter()
+        .map(|p| ip(p, &tensor(&challenges)[..p.len()]))
+        .collect::<Vec<_>>();
+
+    let proof = Tensorcheck::prove(
+        &mut Transcript::new(crate::PROTOCOL_NAME),
+        &ck,
+        &polynomials,
+        &challenges,
+    );
+    let result = Tensorcheck::verify(
+        &proof,
+        &mut Transcript::new(crate::PROTOCOL_NAME),
+        &vk,
+        &commitments,
+        &challenges,
+        &evaluations,
+    );
+    assert!(result.is_ok());
+
+    let mut wrong_evaluations = evaluations.clone();
+    wrong_evaluations[1] += Fr::from(1u64);
+    let result = Tensorcheck::verify(
+        &proof,
+        &mut Transcript::new(crate::PROTOCOL_NAME),
+        &vk,
+        &commitments,
+        &challenges,
+        &wrong_evaluations,
+    );
+    assert!(result.is_err());
+}
@@ -4,7 +4,7 @@ use ark_std::test_rng;
 use ark_std::vec::Vec;
 use ark_test_curves::bls12_381::{Bls12_381, Fr};
 
-use crate::kzg::CommitterKey;
+use crate::kzg::{CommitterKey, VerifierKey};
 use crate::misc::{ip, tensor};
 use crate::subprotocols::tensorcheck::TensorcheckProof;
 use crate::transcript::GeminiTranscript;
@@ -19,7 +19,7 @@ fn test_tensor_check() {
     let rounds = log2(d) as usize;
 
     let ck = CommitterKey::<Bls12_381>::new(d, 5, rng);
-    let vk = (&ck).into();
+    let vk = VerifierKey::from(&ck);
 
     let pp = [DensePolynomial::rand(d - 1, rng).coeffs];
     let base_polynomials = [&pp[0]];
@@ -54,10 +54,10 @@ fn test_tensor_check() {
     // ADD TO TRANSCRIPT ALL POLYNOMIALS
     let batch_challenge = transcript.get_challenge::<Fr>(b"batch_challenge");
     // add commitments to transcript
-    tensor_check_proof
-        .folded_polynomials_commitments
-        .iter()
-        .for_each(|c| transcript.append_serializable(b"commitment", c));
+    transcript.append_serializables(
+        b"commitment",
+        &tensor_check_proof.folded_polynomials_commitments,
+    );
     let eval_chal = transcript.get_challenge::<Fr>(b"evaluation-chal");
 
     let mut direct_base_polynomials_evaluations = Vec::new();
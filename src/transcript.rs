@@ -1,13 +1,41 @@
 //! Transcript utilities for the scalar product sub-protocol.
+//!
+//! The sub-protocols are generic over [`GeminiTranscript`],
+//! implemented by [`merlin::Transcript`] (hashing with Keccak)
+//! and by [`PoseidonTranscript`]
+//! (absorbing into a Poseidon sponge over the scalar field),
+//! whose challenges are cheap to re-derive within a circuit.
+//! [`RecordingTranscript`] wraps any of them, for debugging Fiat–Shamir divergences.
+//! [`TranscriptDomain`] binds a transcript to an application and a session.
+//! With the `fs-audit` feature, [`AuditTranscript`] checks that challenges are squeezed
+//! only after the prover messages they depend on.
 use ark_ff::Field;
 use ark_serialize::CanonicalSerialize;
+use ark_std::fmt;
 use ark_std::vec::Vec;
 use merlin::Transcript;
 
-/// A Transcript with some shorthands for feeding scalars, group elements, and obtaining challenges as field elements.
+/// A Transcript with some shorthands for feeding structured messages and obtaining challenges as field elements.
+///
+/// Messages are any [`CanonicalSerialize`] type:
+/// scalars and commitments, but also vectors, arrays, and tuples thereof,
+/// like the statement of the R1CS instance or the digest of the circuit.
 pub trait GeminiTranscript {
+    /// Append to the transcript the `label`ed message `msg`, in its uncompressed serialization.
     fn append_serializable<S: CanonicalSerialize>(&mut self, label: &'static [u8], msg: &S);
 
+    /// Append to the transcript each of the messages `msgs`, one by one, under the same `label`.
+    ///
+    /// Differently from appending a vector as a single message, the length is not serialized.
+    fn append_serializables<'a, S: CanonicalSerialize + 'a>(
+        &mut self,
+        label: &'static [u8],
+        msgs: impl IntoIterator<Item = &'a S>,
+    ) {
+        msgs.into_iter()
+            .for_each(|msg| self.append_serializable(label, msg))
+    }
+
     /// Compute a `label`ed challenge scalar from the given commitments and the choice bit.
     fn get_challenge<F: Field>(&mut self, label: &'static [u8]) -> F;
 }
@@ -33,3 +61,640 @@ impl GeminiTranscript for Transcript {
         }
     }
 }
+
+/// The domain separation of a Fiat–Shamir transcript.
+///
+/// Besides the protocol `label` (by default [`PROTOCOL_NAME`](crate::PROTOCOL_NAME)),
+/// callers can supply an application-level domain separator and a session identifier,
+/// appended to the transcript at its creation:
+/// proofs produced by deployments with different separators, or within different sessions,
+/// are not valid for one another.
+/// Without separator and session identifier, the transcript is the same as the one created with the label only.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TranscriptDomain {
+    label: &'static [u8],
+    application: Option<Vec<u8>>,
+    session: Option<Vec<u8>>,
+}
+
+impl TranscriptDomain {
+    /// Create a new domain with protocol label `label`.
+    pub fn new(label: &'static [u8]) -> Self {
+        Self {
+            label,
+            application: None,
+            session: None,
+        }
+    }
+
+    /// Use `label` as protocol label.
+    pub fn with_label(mut self, label: &'static [u8]) -> Self {
+        self.label = label;
+        self
+    }
+
+    /// Use `application` as application-level domain separator.
+    pub fn with_application(mut self, application: &[u8]) -> Self {
+        self.application = Some(application.to_vec());
+        self
+    }
+
+    /// Use `session` as session identifier.
+    pub fn with_session(mut self, session: &[u8]) -> Self {
+        self.session = Some(session.to_vec());
+        self
+    }
+
+    /// Return the protocol label.
+    pub fn label(&self) -> &'static [u8] {
+        self.label
+    }
+
+    /// Return a new transcript for the domain.
+    pub fn transcript(&self) -> Transcript {
+        let mut transcript = Transcript::new(self.label);
+        self.append_to(&mut transcript);
+        transcript
+    }
+
+    /// Append the application-level domain separator and the session identifier to `transcript`.
+    pub fn append_to(&self, transcript: &mut impl GeminiTranscript) {
+        if let Some(application) = &self.application {
+            transcript.append_serializable(b"application", application);
+        }
+        if let Some(session) = &self.session {
+            transcript.append_serializable(b"session", session);
+        }
+    }
+}
+
+impl Default for TranscriptDomain {
+    fn default() -> Self {
+        Self::new(crate::PROTOCOL_NAME)
+    }
+}
+
+/// An operation on a transcript, as recorded by [`RecordingTranscript`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TranscriptEvent {
+    /// A message appended with `label`, in its uncompressed serialization.
+    Append {
+        /// The label of the message.
+        label: &'static [u8],
+        /// The serialized message.
+        message: Vec<u8>,
+    },
+    /// A challenge squeezed with `label`, in its uncompressed serialization.
+    Challenge {
+        /// The label of the challenge.
+        label: &'static [u8],
+        /// The serialized challenge.
+        challenge: Vec<u8>,
+    },
+}
+
+impl TranscriptEvent {
+    /// Return the label of the event.
+    pub fn label(&self) -> &'static [u8] {
+        match self {
+            Self::Append { label, .. } | Self::Challenge { label, .. } => label,
+        }
+    }
+}
+
+/// The first event where two transcripts diverge.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Divergence {
+    /// The position of the event in the trace.
+    pub position: usize,
+    /// The event in the reference trace, if any.
+    pub expected: Option<TranscriptEvent>,
+    /// The event in the compared trace, if any.
+    pub found: Option<TranscriptEvent>,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn describe(f: &mut fmt::Formatter<'_>, event: &Option<TranscriptEvent>) -> fmt::Result {
+            fn label(label: &[u8]) -> &str {
+                ark_std::str::from_utf8(label).unwrap_or("<non-utf8 label>")
+            }
+            match event {
+                Some(TranscriptEvent::Append { label: l, .. }) => {
+                    write!(f, "append \"{}\"", label(l))
+                }
+                Some(TranscriptEvent::Challenge { label: l, .. }) => {
+                    write!(f, "challenge \"{}\"", label(l))
+                }
+                None => write!(f, "end of transcript"),
+            }
+        }
+        write!(
+            f,
+            "transcripts diverge at event {}: expected ",
+            self.position
+        )?;
+        describe(f, &self.expected)?;
+        write!(f, ", found ")?;
+        describe(f, &self.found)
+    }
+}
+
+/// Return the first divergence between the trace `found` and the reference trace `expected`, if any.
+pub fn compare_traces(
+    expected: &[TranscriptEvent],
+    found: &[TranscriptEvent],
+) -> Result<(), Divergence> {
+    let position = expected
+        .iter()
+        .zip(found)
+        .position(|(e, f)| e != f)
+        .unwrap_or_else(|| usize::min(expected.len(), found.len()));
+    if position == expected.len() && position == found.len() {
+        Ok(())
+    } else {
+        Err(Divergence {
+            position,
+            expected: expected.get(position).cloned(),
+            found: found.get(position).cloned(),
+        })
+    }
+}
+
+/// A transcript recording every message appended and every challenge squeezed from the transcript `T`.
+///
+/// Fiat–Shamir bugs typically manifest as a verifier rejecting an honest proof,
+/// because prover and verifier absorbed different messages, or the same messages in a different order.
+/// Recording both transcripts and comparing them with [`compare_traces`] pinpoints the first differing event.
+/// Alternatively, [`RecordingTranscript::replay`] checks each event against a reference trace as it happens,
+/// so that the divergence can be inspected (e.g., with a debugger) at the exact call site.
+/// With the `tracing` feature, each event is also logged at trace level.
+#[derive(Clone)]
+pub struct RecordingTranscript<T> {
+    inner: T,
+    trace: Vec<TranscriptEvent>,
+    reference: Option<Vec<TranscriptEvent>>,
+    divergence: Option<Divergence>,
+}
+
+impl<T> RecordingTranscript<T> {
+    /// Record the operations on the transcript `inner`.
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            trace: Vec::new(),
+            reference: None,
+            divergence: None,
+        }
+    }
+
+    /// Record the operations on the transcript `inner`, comparing them with the trace `reference`.
+    pub fn replay(inner: T, reference: Vec<TranscriptEvent>) -> Self {
+        Self {
+            reference: Some(reference),
+            ..Self::new(inner)
+        }
+    }
+
+    /// Return the events recorded so far.
+    pub fn trace(&self) -> &[TranscriptEvent] {
+        &self.trace
+    }
+
+    /// Return the first divergence from the reference trace, if any.
+    ///
+    /// If the recorded trace is a strict prefix of the reference, the divergence is reported as well.
+    pub fn divergence(&self) -> Option<Divergence> {
+        match (&self.divergence, &self.reference) {
+            (Some(divergence), _) => Some(divergence.clone()),
+            (None, Some(reference)) => compare_traces(reference, &self.trace).err(),
+            (None, None) => None,
+        }
+    }
+
+    /// Consume the transcript, returning the wrapped transcript and the recorded events.
+    pub fn into_inner(self) -> (T, Vec<TranscriptEvent>) {
+        (self.inner, self.trace)
+    }
+
+    fn record(&mut self, event: TranscriptEvent) {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(position = self.trace.len(), ?event, "transcript");
+        if let (None, Some(reference)) = (&self.divergence, &self.reference) {
+            let position = self.trace.len();
+            if reference.get(position) != Some(&event) {
+                self.divergence = Some(Divergence {
+                    position,
+                    expected: reference.get(position).cloned(),
+                    found: Some(event.clone()),
+                });
+            }
+        }
+        self.trace.push(event);
+    }
+}
+
+impl<T: GeminiTranscript> GeminiTranscript for RecordingTranscript<T> {
+    fn append_serializable<S: CanonicalSerialize>(
+        &mut self,
+        label: &'static [u8],
+        serializable: &S,
+    ) {
+        let mut message = Vec::new();
+        serializable.serialize_uncompressed(&mut message).unwrap();
+        self.inner.append_serializable(label, serializable);
+        self.record(TranscriptEvent::Append { label, message });
+    }
+
+    fn get_challenge<F: Field>(&mut self, label: &'static [u8]) -> F {
+        let challenge = self.inner.get_challenge::<F>(label);
+        let mut bytes = Vec::new();
+        challenge.serialize_uncompressed(&mut bytes).unwrap();
+        self.record(TranscriptEvent::Challenge {
+            label,
+            challenge: bytes,
+        });
+        challenge
+    }
+}
+
+#[cfg(feature = "fs-audit")]
+pub use audit::{AuditTranscript, ChallengeDerivation};
+
+#[cfg(feature = "fs-audit")]
+mod audit {
+    use ark_ff::Field;
+    use ark_serialize::CanonicalSerialize;
+    use ark_std::vec::Vec;
+
+    use super::GeminiTranscript;
+
+    /// The derivation of a challenge, as tracked by [`AuditTranscript`].
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct ChallengeDerivation {
+        /// The label of the challenge.
+        pub label: &'static [u8],
+        /// The labels of the messages absorbed since the previous challenge, in order.
+        pub absorbed: Vec<&'static [u8]>,
+    }
+
+    /// A transcript auditing the Fiat–Shamir transformation of the protocol run over it.
+    ///
+    /// Every challenge derivation is tracked, together with the messages absorbed before it.
+    /// Each dependency declared with [`AuditTranscript::with_dependency`]
+    /// is asserted when the challenge is squeezed:
+    /// a challenge squeezed before the prover message it depends on has been absorbed
+    /// is the symptom of a weak Fiat–Shamir transformation, and panics.
+    pub struct AuditTranscript<T: GeminiTranscript> {
+        inner: T,
+        dependencies: Vec<(&'static [u8], &'static [u8])>,
+        derivations: Vec<ChallengeDerivation>,
+        absorbed: Vec<&'static [u8]>,
+    }
+
+    impl<T: GeminiTranscript> AuditTranscript<T> {
+        /// Audit the transcript `inner`.
+        pub fn new(inner: T) -> Self {
+            Self {
+                inner,
+                dependencies: Vec::new(),
+                derivations: Vec::new(),
+                absorbed: Vec::new(),
+            }
+        }
+
+        /// Require that, before each challenge labeled `challenge` is squeezed,
+        /// a message labeled `message` is absorbed since the previous challenge.
+        pub fn with_dependency(mut self, challenge: &'static [u8], message: &'static [u8]) -> Self {
+            self.dependencies.push((challenge, message));
+            self
+        }
+
+        /// Return the challenges derived so far.
+        pub fn derivations(&self) -> &[ChallengeDerivation] {
+            &self.derivations
+        }
+
+        /// Consume the transcript, returning the wrapped transcript and the challenge derivations.
+        pub fn into_inner(self) -> (T, Vec<ChallengeDerivation>) {
+            (self.inner, self.derivations)
+        }
+    }
+
+    impl<T: GeminiTranscript> GeminiTranscript for AuditTranscript<T> {
+        fn append_serializable<S: CanonicalSerialize>(
+            &mut self,
+            label: &'static [u8],
+            serializable: &S,
+        ) {
+            self.inner.append_serializable(label, serializable);
+            self.absorbed.push(label);
+        }
+
+        fn get_challenge<F: Field>(&mut self, label: &'static [u8]) -> F {
+            for (challenge, message) in &self.dependencies {
+                assert!(
+                    *challenge != label || self.absorbed.contains(message),
+                    "weak Fiat–Shamir: challenge {:?} squeezed before absorbing {:?}, at derivation {}",
+                    display(label),
+                    display(message),
+                    self.derivations.len(),
+                );
+            }
+            let absorbed = ark_std::mem::take(&mut self.absorbed);
+            self.derivations
+                .push(ChallengeDerivation { label, absorbed });
+            self.inner.get_challenge(label)
+        }
+    }
+
+    fn display(label: &[u8]) -> &str {
+        ark_std::str::from_utf8(label).unwrap_or("<non-utf8 label>")
+    }
+}
+
+pub use poseidon::PoseidonTranscript;
+
+mod poseidon {
+    use ark_ff::{Field, PrimeField};
+    use ark_serialize::CanonicalSerialize;
+    use ark_std::vec::Vec;
+
+    use super::GeminiTranscript;
+    use crate::circuit::gadgets::{PoseidonParameters, PoseidonSponge};
+
+    /// A transcript over a Poseidon sponge on the prime field `F`.
+    ///
+    /// Labels and messages are absorbed as bytes, packed into field elements and prefixed with their length;
+    /// challenges in `F` are squeezed as native field elements,
+    /// so that a circuit over `F` re-derives them with the Poseidon sponge gadget
+    /// [`PoseidonSpongeVar`](crate::circuit::gadgets::PoseidonSpongeVar).
+    #[derive(Clone)]
+    pub struct PoseidonTranscript<F: PrimeField> {
+        sponge: PoseidonSponge<F>,
+    }
+
+    impl<F: PrimeField> PoseidonTranscript<F> {
+        /// Create a new transcript for the sponge parameters `parameters`, with domain separator `label`.
+        pub fn new(parameters: &PoseidonParameters<F>, label: &'static [u8]) -> Self {
+            let mut transcript = Self {
+                sponge: PoseidonSponge::new(parameters),
+            };
+            transcript.absorb_bytes(label);
+            transcript
+        }
+
+        /// Absorb the length of `bytes`, followed by `bytes` in chunks fitting a field element.
+        fn absorb_bytes(&mut self, bytes: &[u8]) {
+            let chunk_size = (F::MODULUS_BIT_SIZE as usize - 1) / 8;
+            let elements = bytes
+                .chunks(chunk_size)
+                .map(F::from_le_bytes_mod_order)
+                .collect::<Vec<_>>();
+            self.sponge.absorb(&[F::from(bytes.len() as u64)]);
+            self.sponge.absorb(&elements);
+        }
+    }
+
+    impl<F: PrimeField> GeminiTranscript for PoseidonTranscript<F> {
+        fn append_serializable<S: CanonicalSerialize>(
+            &mut self,
+            label: &'static [u8],
+            serializable: &S,
+        ) {
+            let mut message = Vec::new();
+            serializable.serialize_uncompressed(&mut message).unwrap();
+            self.absorb_bytes(label);
+            self.absorb_bytes(&message);
+        }
+
+        fn get_challenge<C: Field>(&mut self, label: &'static [u8]) -> C {
+            self.absorb_bytes(label);
+            // Squeeze enough native elements to cover the size of `C`.
+            // When `C` is `F`, the challenge is the squeezed element itself.
+            let size = C::zero().uncompressed_size();
+            let native_size = F::zero().uncompressed_size();
+            loop {
+                let mut bytes = Vec::with_capacity(size + native_size);
+                for _ in 0..(size + native_size - 1) / native_size {
+                    self.sponge
+                        .squeeze()
+                        .serialize_uncompressed(&mut bytes)
+                        .unwrap();
+                }
+                bytes.truncate(size);
+                if let Some(e) = C::from_random_bytes(&bytes) {
+                    return e;
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_poseidon_transcript() {
+    use ark_ff::PrimeField;
+    use ark_std::{One, UniformRand, Zero};
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+
+    use crate::circuit::gadgets::{PoseidonParameters, PoseidonSponge};
+    use crate::kzg::{CommitterKey, VerifierKey};
+    use crate::misc::{ip, tensor};
+    use crate::subprotocols::tensorcheck::Tensorcheck;
+
+    let mds = vec![
+        vec![Fr::one(), Fr::zero(), Fr::one()],
+        vec![Fr::one(), Fr::one(), Fr::zero()],
+        vec![Fr::zero(), Fr::one(), Fr::one()],
+    ];
+    let ark = vec![vec![Fr::one(); 3]; 39];
+    let parameters = PoseidonParameters::new(8, 31, 17, mds, ark, 2, 1);
+
+    // challenges in the scalar field are the native squeezed elements.
+    let mut transcript = PoseidonTranscript::new(&parameters, b"label");
+    let challenge = transcript.get_challenge::<Fr>(b"challenge");
+    let mut sponge = PoseidonSponge::new(&parameters);
+    sponge.absorb(&[Fr::from(5u64), Fr::from_le_bytes_mod_order(b"label")]);
+    sponge.absorb(&[Fr::from(9u64), Fr::from_le_bytes_mod_order(b"challenge")]);
+    assert_eq!(challenge, sponge.squeeze());
+
+    // the sub-protocols run over the Poseidon transcript.
+    let rng = &mut ark_std::test_rng();
+    let ck = CommitterKey::<Bls12_381>::new(16, 3, rng);
+    let vk = VerifierKey::from(&ck);
+    let polynomials = [(0..16).map(|_| Fr::rand(rng)).collect::<Vec<_>>()];
+    let challenges = (0..4).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+    let evaluations = [ip(&polynomials[0], &tensor(&challenges))];
+    let proof = Tensorcheck::prove(
+        &mut PoseidonTranscript::new(&parameters, crate::PROTOCOL_NAME),
+        &ck,
+        &polynomials,
+        &challenges,
+    );
+    let result = Tensorcheck::verify(
+        &proof,
+        &mut PoseidonTranscript::new(&parameters, crate::PROTOCOL_NAME),
+        &vk,
+        &ck.batch_commit(&polynomials),
+        &challenges,
+        &evaluations,
+    );
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_recording_transcript() {
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+
+    use crate::kzg::{CommitterKey, VerifierKey};
+    use crate::misc::{ip, tensor};
+    use crate::subprotocols::tensorcheck::Tensorcheck;
+
+    let rng = &mut ark_std::test_rng();
+    let ck = CommitterKey::<Bls12_381>::new(16, 3, rng);
+    let vk = VerifierKey::from(&ck);
+    let polynomials = [(0..16).map(|_| Fr::rand(rng)).collect::<Vec<_>>()];
+    let challenges = (0..4).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+    let evaluations = [ip(&polynomials[0], &tensor(&challenges))];
+
+    let mut prover_transcript = RecordingTranscript::new(Transcript::new(crate::PROTOCOL_NAME));
+    let proof = Tensorcheck::prove(&mut prover_transcript, &ck, &polynomials, &challenges);
+    let (_, prover_trace) = prover_transcript.into_inner();
+
+    let mut verifier_transcript =
+        RecordingTranscript::replay(Transcript::new(crate::PROTOCOL_NAME), prover_trace.clone());
+    let result = Tensorcheck::verify(
+        &proof,
+        &mut verifier_transcript,
+        &vk,
+        &ck.batch_commit(&polynomials),
+        &challenges,
+        &evaluations,
+    );
+    assert!(result.is_ok());
+    assert_eq!(verifier_transcript.divergence(), None);
+    assert_eq!(
+        compare_traces(&prover_trace, verifier_transcript.trace()),
+        Ok(())
+    );
+
+    // a verifier absorbing a different message diverges from the prover from then on.
+    let mut transcript =
+        RecordingTranscript::replay(Transcript::new(crate::PROTOCOL_NAME), prover_trace.clone());
+    transcript.get_challenge::<Fr>(b"batch_challenge");
+    transcript.append_serializable(b"commitment", &Fr::from(1u64));
+    let divergence = transcript.divergence().unwrap();
+    assert_eq!(divergence.position, 1);
+    assert_eq!(divergence.expected, Some(prover_trace[1].clone()));
+    assert_eq!(divergence.found.unwrap().label(), b"commitment");
+
+    // a truncated transcript diverges at its end.
+    let divergence = compare_traces(&prover_trace, &prover_trace[..3]).unwrap_err();
+    assert_eq!(divergence.position, 3);
+    assert_eq!(divergence.found, None);
+}
+
+#[test]
+fn test_append_serializable() {
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::Fr;
+
+    let rng = &mut ark_std::test_rng();
+    let elements = (0..4).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+
+    // byte arrays are absorbed as they are, with no length prefix.
+    let mut expected = Transcript::new(b"test");
+    expected.append_message(b"digest", &[7u8; 32]);
+    let mut transcript = Transcript::new(b"test");
+    transcript.append_serializable(b"digest", &[7u8; 32]);
+    assert_eq!(
+        expected.get_challenge::<Fr>(b"chal"),
+        transcript.get_challenge::<Fr>(b"chal")
+    );
+
+    // appending many messages at once is the same as appending each of them.
+    let mut expected = Transcript::new(b"test");
+    elements
+        .iter()
+        .for_each(|e| expected.append_serializable(b"eval", e));
+    let mut transcript = Transcript::new(b"test");
+    transcript.append_serializables(b"eval", &elements);
+    assert_eq!(
+        expected.get_challenge::<Fr>(b"chal"),
+        transcript.get_challenge::<Fr>(b"chal")
+    );
+
+    // .. but not as appending the vector, which carries its length.
+    let mut expected = Transcript::new(b"test");
+    expected.append_serializables(b"eval", &elements);
+    let mut transcript = Transcript::new(b"test");
+    transcript.append_serializable(b"eval", &elements);
+    assert_ne!(
+        expected.get_challenge::<Fr>(b"chal"),
+        transcript.get_challenge::<Fr>(b"chal")
+    );
+}
+
+#[cfg(feature = "fs-audit")]
+#[test]
+fn test_audit_transcript() {
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+
+    use crate::kzg::{CommitterKey, VerifierKey};
+    use crate::misc::{ip, tensor};
+    use crate::subprotocols::tensorcheck::Tensorcheck;
+
+    let rng = &mut ark_std::test_rng();
+    let ck = CommitterKey::<Bls12_381>::new(16, 3, rng);
+    let vk = VerifierKey::from(&ck);
+    let polynomials = [(0..16).map(|_| Fr::rand(rng)).collect::<Vec<_>>()];
+    let challenges = (0..4).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+    let evaluations = [ip(&polynomials[0], &tensor(&challenges))];
+
+    let audit = |transcript| {
+        AuditTranscript::new(transcript)
+            .with_dependency(b"evaluation-chal", b"commitment")
+            .with_dependency(b"open-chal", b"eval")
+    };
+    let mut prover_transcript = audit(Transcript::new(crate::PROTOCOL_NAME));
+    let proof = Tensorcheck::prove(&mut prover_transcript, &ck, &polynomials, &challenges);
+    let mut verifier_transcript = audit(Transcript::new(crate::PROTOCOL_NAME));
+    let result = Tensorcheck::verify(
+        &proof,
+        &mut verifier_transcript,
+        &vk,
+        &ck.batch_commit(&polynomials),
+        &challenges,
+        &evaluations,
+    );
+    assert!(result.is_ok());
+    assert_eq!(
+        prover_transcript.derivations(),
+        verifier_transcript.derivations()
+    );
+    let labels = prover_transcript
+        .derivations()
+        .iter()
+        .map(|derivation| derivation.label)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        labels,
+        [&b"batch_challenge"[..], b"evaluation-chal", b"open-chal"]
+    );
+}
+
+#[cfg(feature = "fs-audit")]
+#[test]
+#[should_panic(expected = "weak Fiat–Shamir")]
+fn test_audit_transcript_weak_fs() {
+    use ark_test_curves::bls12_381::Fr;
+
+    let mut transcript = AuditTranscript::new(Transcript::new(b"test"))
+        .with_dependency(b"evaluation-chal", b"commitment");
+    transcript.append_serializable(b"commitment", &Fr::from(1u64));
+    transcript.get_challenge::<Fr>(b"evaluation-chal");
+    // the commitment of a new round is absorbed only after squeezing the challenge.
+    transcript.get_challenge::<Fr>(b"evaluation-chal");
+}
@@ -0,0 +1,122 @@
+//! JavaScript bindings for verifying Gemini proofs from WebAssembly.
+//!
+//! The verifier and the provers compile to `wasm32-unknown-unknown`
+//! without the default features `asm` and `parallel`,
+//! and the bindings are built as a dynamic library:
+//!
+//! ```bash
+//! cargo rustc --lib --crate-type cdylib --release --target wasm32-unknown-unknown --no-default-features --features std,wasm-bindgen
+//! ```
+//!
+//! The bindings check preprocessing SNARK proofs over BLS12-381,
+//! against the [`IndexVerifierKey`] of the circuit, so that the matrices are never needed.
+//!
+//! ```js
+//! import { verify } from "ark_gemini";
+//!
+//! const valid = verify(proofBytes, vkBytes, ["3", "35"]);
+//! ```
+use ark_serialize::CanonicalDeserialize;
+use ark_std::str::FromStr;
+use ark_std::string::{String, ToString};
+use ark_std::vec::Vec;
+use ark_std::One;
+use ark_test_curves::bls12_381::{Bls12_381, Fr};
+use wasm_bindgen::prelude::*;
+
+use crate::psnark::{IndexVerifierKey, Proof};
+
+/// Verify the preprocessing SNARK proof `proof_bytes` for the public inputs `public_inputs`,
+/// against the indexed verifier key `vk_bytes`.
+///
+/// Both the proof and the verifier key are serialized with
+/// [`CanonicalSerialize`](ark_serialize::CanonicalSerialize) in compressed form.
+/// The public inputs are decimal strings, excluding the constant one,
+/// as in the `public.json` files of snarkjs.
+///
+/// Return `false` if the proof does not verify,
+/// and throw if any of the inputs is malformed.
+#[wasm_bindgen]
+pub fn verify(
+    proof_bytes: &[u8],
+    vk_bytes: &[u8],
+    public_inputs: Box<[JsValue]>,
+) -> Result<bool, JsValue> {
+    let public_inputs = public_inputs
+        .iter()
+        .map(|input| {
+            input
+                .as_string()
+                .ok_or_else(|| "public inputs must be strings".to_string())
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| JsValue::from_str(&e))?;
+    verify_bytes(proof_bytes, vk_bytes, &public_inputs).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Deserialize and verify the proof, see [`verify`].
+fn verify_bytes<S: AsRef<str>>(
+    proof_bytes: &[u8],
+    vk_bytes: &[u8],
+    public_inputs: &[S],
+) -> Result<bool, String> {
+    let proof = Proof::<Bls12_381>::deserialize_compressed(proof_bytes)
+        .map_err(|e| format!("invalid proof: {}", e))?;
+    let ivk = IndexVerifierKey::<Bls12_381>::deserialize_compressed(vk_bytes)
+        .map_err(|e| format!("invalid verifier key: {}", e))?;
+    let x = statement(public_inputs)?;
+    Ok(proof.verify_indexed(&ivk, &x).is_ok())
+}
+
+/// Return the statement for `public_inputs`: the constant one followed by the inputs,
+/// padded with zeros to a power of two.
+fn statement<S: AsRef<str>>(public_inputs: &[S]) -> Result<Vec<Fr>, String> {
+    let mut x = Vec::with_capacity((public_inputs.len() + 1).next_power_of_two());
+    x.push(Fr::one());
+    for input in public_inputs {
+        let input = input.as_ref();
+        x.push(Fr::from_str(input).map_err(|_| format!("invalid public input: {}", input))?);
+    }
+    x.resize(x.len().next_power_of_two(), Fr::from(0u64));
+    Ok(x)
+}
+
+#[test]
+fn test_wasm_verify() {
+    use ark_serialize::CanonicalSerialize;
+
+    use crate::circuit::{generate_relation, random_circuit};
+    use crate::kzg::CommitterKey;
+
+    let rng = &mut ark_std::test_rng();
+    let num_constraints = 64;
+    let num_variables = 64;
+    let r1cs = generate_relation(random_circuit(rng, num_constraints, num_variables));
+    let ck = CommitterKey::<Bls12_381>::new(5 * num_constraints + num_variables, 5, rng);
+    let ivk = Proof::index_verifier_key(&ck, &r1cs);
+    let proof = Proof::new_time(&ck, &r1cs, &ivk.index);
+
+    let mut proof_bytes = Vec::new();
+    proof.serialize_compressed(&mut proof_bytes).unwrap();
+    let mut vk_bytes = Vec::new();
+    ivk.serialize_compressed(&mut vk_bytes).unwrap();
+
+    let public_inputs = r1cs.x[1..]
+        .iter()
+        .map(|input| input.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(statement(&public_inputs).unwrap(), r1cs.x);
+    assert_eq!(
+        verify_bytes(&proof_bytes, &vk_bytes, &public_inputs),
+        Ok(true)
+    );
+
+    let mut wrong_inputs = public_inputs.clone();
+    wrong_inputs[0] = "1".to_string();
+    assert_eq!(
+        verify_bytes(&proof_bytes, &vk_bytes, &wrong_inputs),
+        Ok(false)
+    );
+    assert!(verify_bytes(&proof_bytes[1..], &vk_bytes, &public_inputs).is_err());
+    assert!(verify_bytes(&proof_bytes, &vk_bytes, &["x"]).is_err());
+}